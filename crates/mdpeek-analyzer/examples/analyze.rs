@@ -39,7 +39,10 @@ fn main() {
     println!("\n== todos ==");
     for t in &a.panel.todos {
         let mark = if t.done { "[x]" } else { "[ ]" };
-        println!("  {mark} {} ({}, L{})", t.text, t.marker, t.link.range.start_line);
+        println!(
+            "  {mark} {} ({}, L{})",
+            t.text, t.marker, t.link.range.start_line
+        );
     }
 
     println!("\n== risks ==");
@@ -5,36 +5,67 @@
 //! path — this module never guesses beyond documented heuristics and always
 //! reports a `confidence`.
 
+pub mod adr;
 pub mod block_class;
+pub mod changelog;
 pub mod code;
+pub mod contract;
 pub mod doctype;
+pub mod http;
+pub mod minutes;
+pub mod novel;
+pub mod procedure;
+pub mod recipe;
+pub mod snippet;
 pub mod table;
 pub mod tasks;
 
 use crate::links;
+use crate::locale::Locale;
 use crate::model::{DocumentModel, OutlineEntry};
+use crate::rules::ClassificationRules;
+use mdpeek_gfm::MarkdownOptions;
 use mdpeek_parser::{BlockKind, BlockTree};
 
 /// Run the full rules pipeline over a parsed tree, producing a [`DocumentModel`].
 ///
 /// `source` is the original markdown, needed to recover links (which the block
-/// tree does not carry).
-pub fn build_model(source: &str, tree: &BlockTree, filename: Option<&str>) -> DocumentModel {
+/// tree does not carry); `options` must be the ones `tree` was parsed with.
+/// `rules` extend the built-in section and document-type rules, whose
+/// keywords come from the document's [`Locale`].
+pub fn build_model(
+    source: &str,
+    tree: &BlockTree,
+    filename: Option<&str>,
+    options: &MarkdownOptions,
+    rules: &ClassificationRules,
+) -> DocumentModel {
     let outline = outline(tree);
     let tasks = tasks::extract(tree);
-    let doc_type = doctype::classify(filename, tree, &outline);
-    let blocks = block_class::classify(tree, &outline);
+    let locale = Locale::resolve(tree.frontmatter(), rules.lang.as_deref(), &prose(tree));
+    let doc_type = doctype::classify(filename, tree, &outline, rules, locale);
+    let blocks = block_class::classify(tree, &outline, rules, locale);
 
     DocumentModel {
+        locale,
         doc_type,
         blocks,
         frontmatter: tree.frontmatter().map(str::to_string),
         outline,
-        links: links::extract(source),
+        links: links::extract_with_options(source, options.parser_options()),
         tasks,
     }
 }
 
+/// The headings and paragraphs of `tree`, for language detection.
+fn prose(tree: &BlockTree) -> String {
+    tree.iter()
+        .filter(|b| matches!(b.kind, BlockKind::Heading { .. } | BlockKind::Paragraph))
+        .map(|b| b.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Extract the heading outline in document order.
 pub fn outline(tree: &BlockTree) -> Vec<OutlineEntry> {
     tree.iter()
@@ -50,6 +81,48 @@ pub fn outline(tree: &BlockTree) -> Vec<OutlineEntry> {
         .collect()
 }
 
+/// The names listed under frontmatter `key:`, as a flow list (`[A, B]`) or a
+/// block list of `- A` / `- name: A` items (`characters:`, `parties:`).
+pub(crate) fn frontmatter_list(frontmatter: &str, key: &str) -> Vec<String> {
+    let clean = |s: &str| s.trim().trim_matches(['"', '\'']).trim().to_string();
+    let mut names = Vec::new();
+    let mut in_list = false;
+    for line in frontmatter.lines() {
+        let trimmed = line.trim();
+        if in_list {
+            if let Some(item) = trimmed.strip_prefix("- ") {
+                let item = item.trim();
+                let item = item.strip_prefix("name:").unwrap_or(item);
+                names.push(clean(item));
+                continue;
+            }
+            // Keys of a `- name: A` mapping, or the end of the list.
+            if line.starts_with([' ', '\t']) && !trimmed.is_empty() {
+                continue;
+            }
+            in_list = false;
+        }
+        let Some((name, value)) = trimmed.split_once([':', '=']) else {
+            continue;
+        };
+        if name.trim().trim_matches('"').to_lowercase() != key {
+            continue;
+        }
+        let value = value.trim();
+        if let Some(inline) = value.strip_prefix('[') {
+            let inline = inline.trim_end_matches(']');
+            names.extend(inline.split(',').map(clean));
+        } else if value.is_empty() {
+            in_list = true;
+        } else {
+            names.extend(value.split(',').map(clean));
+        }
+    }
+    names.retain(|name| !name.is_empty());
+    names.dedup();
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,7 +138,13 @@ mod tests {
                   - [ ] install\n- [x] configure\n\n\
                   ```bash\nmake build\n```\n";
         let tree = BlockTree::parse(md);
-        let model = build_model(md, &tree, Some("README.md"));
+        let model = build_model(
+            md,
+            &tree,
+            Some("README.md"),
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+        );
 
         assert_eq!(model.doc_type.value, DocumentType::Readme);
         assert!(model.frontmatter.is_some());
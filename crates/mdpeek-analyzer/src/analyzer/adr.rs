@@ -0,0 +1,342 @@
+//! Architecture Decision Record extraction.
+//!
+//! [`doctype::classify`](super::doctype::classify) only says *that* a document
+//! is an ADR; this module reads the lifecycle fields the common templates
+//! (Nygard / adr-tools, MADR) put in it:
+//!
+//! * **number** — filename prefix (`0002-use-postgres.md`, `adr-0002-…`) or the
+//!   title prefix (`# 2. Use PostgreSQL`, `# ADR-0002: …`);
+//! * **title** — the first `#` heading without that prefix;
+//! * **status** — frontmatter `status:`, a `Status: …` line, or the first
+//!   paragraph of a `## Status` section;
+//! * **date** — frontmatter `date:` or a `Date: …` line;
+//! * **supersession** — `Supersedes …` / `Superseded by …` lines, whose links
+//!   (`[2. Use MySQL](0002-use-mysql.md)`) or bare `ADR-2` mentions name the
+//!   other records.
+//!
+//! Cross-document concerns (resolving references, validating the chain) belong
+//! to whoever sees the whole repository.
+
+use crate::links;
+use mdpeek_gfm::MarkdownOptions;
+use mdpeek_parser::{BlockKind, BlockTree, SourceRange};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+/// `0002-title`, `adr-0002-title`, `ADR_2_title` file stems.
+static FILE_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:adr[-_]?)?0*(\d+)(?:[-_.]|$)").expect("valid file number regex")
+});
+/// `2. Title`, `ADR-0002: Title`, `ADR 2 - Title` heading prefixes.
+static TITLE_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:adr[-\s#]*)?0*(\d+)\s*(?:[.:)\-–—]\s*|\s+)").expect("valid title regex")
+});
+/// A bare `ADR-2` / `ADR 0002` / `ADR #2` mention.
+static ADR_MENTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\badr[-\s#]*0*(\d+)\b").expect("valid mention regex"));
+
+/// Lifecycle state of an ADR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdrStatus {
+    Proposed,
+    Accepted,
+    Deprecated,
+    Superseded,
+}
+
+impl AdrStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdrStatus::Proposed => "proposed",
+            AdrStatus::Accepted => "accepted",
+            AdrStatus::Deprecated => "deprecated",
+            AdrStatus::Superseded => "superseded",
+        }
+    }
+
+    /// The status a free-form status text states. Later lifecycle states win
+    /// (`Accepted, superseded by ADR-5` is superseded).
+    pub fn parse(text: &str) -> Option<Self> {
+        let lower = text.to_lowercase();
+        [
+            ("supersede", AdrStatus::Superseded),
+            ("deprecate", AdrStatus::Deprecated),
+            ("accept", AdrStatus::Accepted),
+            ("approved", AdrStatus::Accepted),
+            ("propos", AdrStatus::Proposed),
+            ("draft", AdrStatus::Proposed),
+        ]
+        .into_iter()
+        .find(|(kw, _)| lower.contains(kw))
+        .map(|(_, status)| status)
+    }
+}
+
+/// A reference from one ADR to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AdrRef {
+    /// The referenced ADR's number, when the link text, target file or mention
+    /// states it.
+    pub number: Option<u32>,
+    /// The link target, for references written as links.
+    pub target: Option<String>,
+    pub range: SourceRange,
+}
+
+/// The lifecycle fields of one ADR.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Adr {
+    pub number: Option<u32>,
+    pub title: String,
+    pub status: Option<AdrStatus>,
+    /// Where the status is stated, for diagnostics.
+    pub status_range: Option<SourceRange>,
+    pub date: Option<String>,
+    pub supersedes: Vec<AdrRef>,
+    pub superseded_by: Vec<AdrRef>,
+}
+
+/// Extract the ADR fields of `source` (parsed as `tree` with `options`).
+/// `filename` supplies the number when the title does not.
+pub fn extract(
+    source: &str,
+    tree: &BlockTree,
+    filename: Option<&str>,
+    options: &MarkdownOptions,
+) -> Adr {
+    let links = links::extract_with_options(source, options.parser_options());
+    let stem = filename
+        .map(|f| f.rsplit(['/', '\\']).next().unwrap_or(f))
+        .map(|f| f.rsplit_once('.').map_or(f, |(stem, _)| stem));
+
+    let mut adr = Adr {
+        number: stem.and_then(number_in_file_name),
+        title: String::new(),
+        status: None,
+        status_range: None,
+        date: None,
+        supersedes: Vec::new(),
+        superseded_by: Vec::new(),
+    };
+    if let Some(frontmatter) = tree.frontmatter() {
+        let first = tree.iter().next().map(|b| b.range);
+        for (key, value) in frontmatter_fields(frontmatter) {
+            match key.as_str() {
+                "status" => {
+                    adr.status = AdrStatus::parse(&value);
+                    adr.status_range = first;
+                }
+                "date" => adr.date = Some(value),
+                _ => {}
+            }
+        }
+    }
+
+    let mut in_status_section = false;
+    for block in tree.iter() {
+        match &block.kind {
+            BlockKind::Heading { level } => {
+                let text = block.text.trim();
+                if *level == 1 && adr.title.is_empty() {
+                    adr.title = match TITLE_NUMBER.captures(text) {
+                        Some(caps) => {
+                            adr.number = adr.number.or_else(|| caps[1].parse().ok());
+                            text[caps[0].len()..].trim().to_string()
+                        }
+                        None => text.to_string(),
+                    };
+                }
+                in_status_section = matches!(
+                    text.to_lowercase().as_str(),
+                    "status" | "ステータス" | "状態"
+                );
+                continue;
+            }
+            BlockKind::Paragraph => {}
+            // Loose items repeat their paragraphs' text; read those instead.
+            BlockKind::Item { .. }
+                if !block
+                    .children
+                    .iter()
+                    .any(|c| c.kind == BlockKind::Paragraph) => {}
+            _ => continue,
+        }
+        let text = block.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let (field, value) = match text.split_once(':') {
+            Some((key, value)) if key.len() <= 20 => (key.trim().to_lowercase(), value.trim()),
+            _ => (String::new(), text),
+        };
+        if field == "date" && adr.date.is_none() {
+            adr.date = Some(value.to_string());
+        }
+        let states_status = field == "status" || (in_status_section && adr.status.is_none());
+        if states_status && let Some(status) = AdrStatus::parse(value) {
+            adr.status = Some(status);
+            adr.status_range = Some(block.range);
+        }
+        let lower = text.to_lowercase();
+        let refs = if lower.contains("superseded by") {
+            &mut adr.superseded_by
+        } else if lower.contains("supersedes") {
+            &mut adr.supersedes
+        } else {
+            continue;
+        };
+        refs.extend(references(text, block.range, &links));
+    }
+    if adr.title.is_empty() {
+        adr.title = stem.unwrap_or_default().to_string();
+    }
+    adr
+}
+
+/// The number an ADR file name carries, if any.
+pub fn number_in_file_name(stem: &str) -> Option<u32> {
+    FILE_NUMBER.captures(stem).and_then(|c| c[1].parse().ok())
+}
+
+/// References inside a supersession statement: its links, then bare `ADR-n`
+/// mentions outside link text.
+fn references(text: &str, range: SourceRange, links: &[links::Link]) -> Vec<AdrRef> {
+    let inside = |r: &SourceRange| {
+        (r.start_line, r.start_column) >= (range.start_line, range.start_column)
+            && (r.end_line, r.end_column) <= (range.end_line, range.end_column)
+    };
+    let mut out = Vec::new();
+    let mut link_texts = String::new();
+    for link in links.iter().filter(|l| inside(&l.range)) {
+        let file = link
+            .url
+            .split(['#', '?'])
+            .next()
+            .unwrap_or("")
+            .rsplit('/')
+            .next()
+            .unwrap_or("");
+        let number = number_in_file_name(file)
+            .or_else(|| {
+                TITLE_NUMBER
+                    .captures(&link.text)
+                    .and_then(|c| c[1].parse().ok())
+            })
+            .or_else(|| {
+                ADR_MENTION
+                    .captures(&link.text)
+                    .and_then(|c| c[1].parse().ok())
+            });
+        out.push(AdrRef {
+            number,
+            target: Some(link.url.clone()),
+            range: link.range,
+        });
+        link_texts.push_str(&link.text);
+        link_texts.push('\n');
+    }
+    for caps in ADR_MENTION.captures_iter(text) {
+        if link_texts.contains(&caps[0]) {
+            continue;
+        }
+        out.push(AdrRef {
+            number: caps[1].parse().ok(),
+            target: None,
+            range,
+        });
+    }
+    out
+}
+
+/// `key: value` pairs of simple YAML/TOML frontmatter, keys lowercased.
+fn frontmatter_fields(frontmatter: &str) -> Vec<(String, String)> {
+    frontmatter
+        .lines()
+        .filter_map(|line| line.split_once([':', '=']))
+        .map(|(key, value)| {
+            (
+                key.trim().trim_matches('"').to_lowercase(),
+                value.trim().trim_matches(['"', '\'']).to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adr(md: &str, filename: &str) -> Adr {
+        let options = MarkdownOptions::default();
+        extract(md, &BlockTree::parse(md), Some(filename), &options)
+    }
+
+    #[test]
+    fn nygard_template() {
+        let md = "# 3. Use PostgreSQL\n\nDate: 2024-03-01\n\n## Status\n\nAccepted\n\n\
+                  Supersedes [1. Use MySQL](0001-use-mysql.md)\n\n## Context\n\nWe need JSON.\n";
+        let a = adr(md, "docs/adr/0003-use-postgresql.md");
+        assert_eq!(a.number, Some(3));
+        assert_eq!(a.title, "Use PostgreSQL");
+        assert_eq!(a.status, Some(AdrStatus::Accepted));
+        assert_eq!(a.status_range.map(|r| r.start_line), Some(7));
+        assert_eq!(a.date.as_deref(), Some("2024-03-01"));
+        assert_eq!(a.supersedes.len(), 1);
+        assert_eq!(a.supersedes[0].number, Some(1));
+        assert_eq!(a.supersedes[0].target.as_deref(), Some("0001-use-mysql.md"));
+        assert_eq!(a.supersedes[0].range.start_line, 9);
+        assert!(a.superseded_by.is_empty());
+    }
+
+    #[test]
+    fn madr_frontmatter_and_bare_mentions() {
+        let md = "---\nstatus: superseded\ndate: 2023-11-02\n---\n\n# ADR-0001: Use MySQL\n\n\
+                  * Superseded by ADR-3\n";
+        let a = adr(md, "decision.md");
+        assert_eq!(a.number, Some(1));
+        assert_eq!(a.title, "Use MySQL");
+        assert_eq!(a.status, Some(AdrStatus::Superseded));
+        assert_eq!(a.date.as_deref(), Some("2023-11-02"));
+        assert_eq!(a.superseded_by.len(), 1);
+        assert_eq!(a.superseded_by[0].number, Some(3));
+        assert_eq!(a.superseded_by[0].target, None);
+    }
+
+    #[test]
+    fn status_section_can_name_the_successor() {
+        let md = "# Use MySQL\n\n## Status\n\nSuperseded by [the Postgres decision](0003-use-postgresql.md)\n";
+        let a = adr(md, "adr-0001-use-mysql.md");
+        assert_eq!(a.number, Some(1));
+        assert_eq!(a.status, Some(AdrStatus::Superseded));
+        assert_eq!(a.superseded_by[0].number, Some(3));
+
+        let loose = "# 4. Cache\n\n* Status: accepted\n\n* Supersedes ADR-2\n";
+        let a = adr(loose, "x.md");
+        assert_eq!(a.status, Some(AdrStatus::Accepted));
+        assert_eq!(a.supersedes.len(), 1);
+    }
+
+    #[test]
+    fn status_words() {
+        assert_eq!(AdrStatus::parse("Proposed"), Some(AdrStatus::Proposed));
+        assert_eq!(AdrStatus::parse("accepted"), Some(AdrStatus::Accepted));
+        assert_eq!(
+            AdrStatus::parse("Deprecated since 2.0"),
+            Some(AdrStatus::Deprecated)
+        );
+        assert_eq!(
+            AdrStatus::parse("Accepted, superseded by ADR-5"),
+            Some(AdrStatus::Superseded)
+        );
+        assert_eq!(AdrStatus::parse("We discussed it"), None);
+    }
+
+    #[test]
+    fn untitled_documents_fall_back_to_the_file_name() {
+        let a = adr("Some notes.\n", "0007-notes.md");
+        assert_eq!((a.number, a.title.as_str()), (Some(7), "0007-notes"));
+        assert_eq!(a.status, None);
+    }
+}
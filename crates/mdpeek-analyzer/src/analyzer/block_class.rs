@@ -4,27 +4,41 @@
 //! the section heading it sits under. Section membership is tracked by walking
 //! blocks in document order and remembering the most recent recognised heading.
 
-use crate::model::{BlockClass, ClassifiedBlock, OutlineEntry};
+use crate::locale::{EN, KeywordPack, Locale};
+use crate::model::{BlockClass, ClassifiedBlock, Origin, OutlineEntry};
+use crate::rules::ClassificationRules;
 use mdpeek_parser::{BlockKind, BlockTree};
 
-/// Classify every block in the tree.
-pub fn classify(tree: &BlockTree, _outline: &[OutlineEntry]) -> Vec<ClassifiedBlock> {
+/// Classify every block in the tree. Section keywords from `rules` are tried
+/// before the built-in ones of the document's `locale`.
+pub fn classify(
+    tree: &BlockTree,
+    _outline: &[OutlineEntry],
+    rules: &ClassificationRules,
+    locale: Locale,
+) -> Vec<ClassifiedBlock> {
     let mut out = Vec::new();
-    // The semantic class of the section we are currently inside.
+    // The semantic class of the section we are currently inside, and the rule
+    // that recognised its heading.
     let mut current_section = BlockClass::Generic;
+    let mut current_rule: Option<String> = None;
 
     for block in tree.iter() {
+        let mut rule = None;
         let (class, confidence) = match &block.kind {
             // Front matter is metadata, not content — leave it unclassified.
             BlockKind::MetadataBlock => continue,
-            BlockKind::Heading { .. } => match section_class(&block.text) {
+            BlockKind::Heading { .. } => match rules.section(&block.text, locale) {
                 Some(section) => {
-                    current_section = section;
-                    (section, 0.8)
+                    current_section = section.class;
+                    current_rule = Some(section.rule);
+                    rule = current_rule.clone();
+                    (section.class, section.confidence)
                 }
                 None => {
                     // Unrecognised heading opens a generic section.
                     current_section = BlockClass::Generic;
+                    current_rule = None;
                     (BlockClass::Heading, 0.6)
                 }
             },
@@ -33,6 +47,7 @@ pub fn classify(tree: &BlockTree, _outline: &[OutlineEntry]) -> Vec<ClassifiedBl
             BlockKind::Item { task: Some(_) } => (BlockClass::Task, 0.9),
             _ => {
                 // Inherit the enclosing section (or Generic).
+                rule = current_rule.clone();
                 let conf = if current_section == BlockClass::Generic {
                     0.3
                 } else {
@@ -46,57 +61,69 @@ pub fn classify(tree: &BlockTree, _outline: &[OutlineEntry]) -> Vec<ClassifiedBl
             block_id: block.id,
             class,
             confidence,
+            by: Origin::Rules,
+            rule,
             range: block.range,
         });
     }
     out
 }
 
-/// Map a heading title to the semantic class of the section it introduces.
+/// Map a heading title to the semantic class of the section it introduces,
+/// using the built-in keywords of every locale.
 pub fn section_class(title: &str) -> Option<BlockClass> {
-    let t = title.to_lowercase();
-    let has = |kws: &[&str]| kws.iter().any(|kw| t.contains(kw));
+    find_section(title, &Locale::ALL.map(Locale::pack)).map(|(class, _, _)| class)
+}
 
-    // Order matters: more specific classes are checked before broad ones.
-    if has(&["open question", "open issue", "未解決", "questions", "疑問"]) {
-        Some(BlockClass::OpenQuestion)
-    } else if has(&["risk", "リスク", "caveat", "warning", "注意", "落とし穴"]) {
-        Some(BlockClass::Risk)
-    } else if has(&["data model", "データモデル", "schema", "スキーマ", "型定義", "types"]) {
-        Some(BlockClass::DataModel)
-    } else if has(&["architecture", "アーキテクチャ", "構成", "design", "設計"]) {
-        Some(BlockClass::Architecture)
-    } else if has(&["consequence", "影響", "結果", "trade-off", "tradeoff"]) {
-        Some(BlockClass::Consequence)
-    } else if has(&["decision", "決定", "採用"]) {
-        Some(BlockClass::Decision)
-    } else if has(&["configuration", "config", "設定", "options"]) {
-        Some(BlockClass::Configuration)
-    } else if has(&["troubleshoot", "トラブル", "faq", "known issue"]) {
-        Some(BlockClass::Troubleshooting)
-    } else if has(&["usage", "使い方", "how to use", "getting started", "quick start", "使用方法"]) {
-        Some(BlockClass::Usage)
-    } else if has(&["step", "手順", "procedure", "instruction", "作り方", "how to"]) {
-        Some(BlockClass::Step)
-    } else if has(&["overview", "概要", "summary", "introduction", "はじめに", "要約"]) {
-        Some(BlockClass::Overview)
-    } else {
-        None
-    }
+/// The built-in section class of `title` in a `locale` document, with the
+/// keyword that matched and the pack it came from.
+pub fn builtin_section(title: &str, locale: Locale) -> Option<(BlockClass, &'static str, Locale)> {
+    let packs: Vec<&KeywordPack> = locale.packs().collect();
+    find_section(title, &packs)
+}
+
+/// Classes are tried in pack order (more specific ones first), each in every
+/// pack, so `Open questions and risks` is an open question in any language.
+fn find_section(
+    title: &str,
+    packs: &[&'static KeywordPack],
+) -> Option<(BlockClass, &'static str, Locale)> {
+    let t = title.to_lowercase();
+    (0..EN.sections.len()).find_map(|i| {
+        packs.iter().find_map(|pack| {
+            let (class, keywords) = pack.sections[i];
+            keywords
+                .iter()
+                .find(|kw| t.contains(*kw))
+                .map(|kw| (class, *kw, pack.locale))
+        })
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::analyzer::outline;
+    use crate::rules::SectionRule;
     use mdpeek_parser::{BlockKind, BlockTree};
 
     #[test]
     fn section_class_maps_common_headings() {
         assert_eq!(section_class("Overview"), Some(BlockClass::Overview));
-        assert_eq!(section_class("## Architecture"), Some(BlockClass::Architecture));
-        assert_eq!(section_class("Open Questions"), Some(BlockClass::OpenQuestion));
+        assert_eq!(
+            section_class("## Architecture"),
+            Some(BlockClass::Architecture)
+        );
+        assert_eq!(
+            section_class("Open Questions"),
+            Some(BlockClass::OpenQuestion)
+        );
         assert_eq!(section_class("リスク"), Some(BlockClass::Risk));
+        assert_eq!(section_class("Risiken"), Some(BlockClass::Risk));
+        assert_eq!(
+            builtin_section("Étapes", Locale::Fr),
+            Some((BlockClass::Step, "étape", Locale::Fr))
+        );
         assert_eq!(section_class("Random"), None);
     }
 
@@ -105,7 +132,7 @@ mod tests {
         let md = "# T\n\n## Risks\n\nThis is dangerous.\n\n## Usage\n\nRun it.\n";
         let tree = BlockTree::parse(md);
         let ol = outline(&tree);
-        let classes = classify(&tree, &ol);
+        let classes = classify(&tree, &ol, &ClassificationRules::default(), Locale::En);
 
         // Find the paragraph under Risks.
         let risk_para = tree
@@ -122,12 +149,46 @@ mod tests {
         assert_eq!(class_of(usage_para.id), BlockClass::Usage);
     }
 
+    #[test]
+    fn configured_keywords_override_built_in_ones() {
+        let rules = ClassificationRules {
+            sections: [
+                (
+                    "risk register".to_string(),
+                    SectionRule::Class(BlockClass::Decision),
+                ),
+                ("作業".to_string(), SectionRule::Class(BlockClass::Step)),
+            ]
+            .into(),
+            ..ClassificationRules::default()
+        };
+        let md = "## Risk register\n\nWe accept it.\n\n## Risks\n\nIt may break.\n\n## 作業\n";
+        let tree = BlockTree::parse(md);
+        let classes = classify(&tree, &outline(&tree), &rules, Locale::En);
+        let summary: Vec<(BlockClass, Option<&str>)> = classes
+            .iter()
+            .map(|c| (c.class, c.rule.as_deref()))
+            .collect();
+        let register = Some("analyzer.sections \"risk register\"");
+        let risk = Some("section keyword \"risk\" (en)");
+        assert_eq!(
+            summary,
+            vec![
+                (BlockClass::Decision, register),
+                (BlockClass::Decision, register),
+                (BlockClass::Risk, risk),
+                (BlockClass::Risk, risk),
+                (BlockClass::Step, Some("analyzer.sections \"作業\"")),
+            ]
+        );
+    }
+
     #[test]
     fn code_and_table_get_structural_classes() {
         let md = "## X\n\n```rust\nfn a(){}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n";
         let tree = BlockTree::parse(md);
         let ol = outline(&tree);
-        let classes = classify(&tree, &ol);
+        let classes = classify(&tree, &ol, &ClassificationRules::default(), Locale::En);
         assert!(classes.iter().any(|c| c.class == BlockClass::CodeExample));
         assert!(classes.iter().any(|c| c.class == BlockClass::Table));
     }
@@ -137,9 +198,12 @@ mod tests {
         let md = "## X\n\n- [ ] do a\n- [x] do b\n";
         let tree = BlockTree::parse(md);
         let ol = outline(&tree);
-        let classes = classify(&tree, &ol);
+        let classes = classify(&tree, &ol, &ClassificationRules::default(), Locale::En);
         assert_eq!(
-            classes.iter().filter(|c| c.class == BlockClass::Task).count(),
+            classes
+                .iter()
+                .filter(|c| c.class == BlockClass::Task)
+                .count(),
             2
         );
     }
@@ -0,0 +1,563 @@
+//! Release model of changelogs.
+//!
+//! For [`Changelog`](crate::model::DocumentType::Changelog) documents this
+//! module reads the [Keep a Changelog](https://keepachangelog.com) layout:
+//!
+//! * **releases** — `## [1.2.0] - 2024-03-01` headings (brackets, a `v`
+//!   prefix and a parenthesised date are accepted), `## [Unreleased]`, and a
+//!   trailing `[YANKED]` marker;
+//! * **sections** — the `### Added` / `Changed` / `Deprecated` / `Removed` /
+//!   `Fixed` / `Security` headings of a release, with their list items.
+//!
+//! The model is validated as it is read: versions must be semver and listed
+//! newest first, each at most once; released versions need an ISO
+//! `YYYY-MM-DD` date; section names must be one of the six above. Comparing
+//! releases against the repository's git tags needs the repository, so it is
+//! left to `mdpeek-check`.
+
+use crate::panel::SourceRangeLink;
+use mdpeek_parser::{Block, BlockKind, BlockTree, SourceRange};
+use regex::Regex;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::sync::LazyLock;
+
+/// `[1.2.0] - 2024-03-01 [YANKED]`, `v1.2.0 (2024-03-01)`, `[Unreleased]`.
+static RELEASE_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\[?v?(?P<version>unreleased|\d+\.[^\]\s]*)\]?(?:\s*[-–—]\s*|\s+\(?)?(?P<date>[^\s()\[\]]+)?\)?\s*(?P<yanked>\[yanked\])?\s*$",
+    )
+    .expect("valid release heading regex")
+});
+/// `MAJOR.MINOR.PATCH`, with optional `-pre.release` and `+build` parts.
+static SEMVER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?(?:\+[0-9A-Za-z.-]+)?$")
+        .expect("valid semver regex")
+});
+/// `YYYY-MM-DD`.
+static ISO_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").expect("valid date regex"));
+
+/// The releases of a changelog, newest first as written, and what is wrong
+/// with them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Changelog {
+    pub releases: Vec<Release>,
+    pub problems: Vec<Problem>,
+}
+
+/// One `## [x.y.z]` section.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Release {
+    /// The version as written without brackets or `v` prefix, or
+    /// `Unreleased`.
+    pub version: String,
+    pub unreleased: bool,
+    pub date: Option<String>,
+    pub yanked: bool,
+    pub sections: Vec<ChangeSection>,
+    /// The release heading.
+    pub link: SourceRangeLink,
+    /// Last source line of the release's notes (1-based, inclusive).
+    pub end_line: u32,
+}
+
+/// A `### Added` (…) section of a release.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangeSection {
+    /// `None` for a heading that is not one of the Keep a Changelog types.
+    pub kind: Option<ChangeKind>,
+    pub title: String,
+    pub entries: Vec<String>,
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+impl ChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Changed => "Changed",
+            ChangeKind::Deprecated => "Deprecated",
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Fixed => "Fixed",
+            ChangeKind::Security => "Security",
+        }
+    }
+
+    fn parse(title: &str) -> Option<Self> {
+        match title.trim().to_lowercase().as_str() {
+            "added" => Some(ChangeKind::Added),
+            "changed" => Some(ChangeKind::Changed),
+            "deprecated" => Some(ChangeKind::Deprecated),
+            "removed" => Some(ChangeKind::Removed),
+            "fixed" => Some(ChangeKind::Fixed),
+            "security" => Some(ChangeKind::Security),
+            _ => None,
+        }
+    }
+}
+
+/// A validation finding, located at the offending heading.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    pub message: String,
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProblemKind {
+    /// A release heading whose version is not semver.
+    InvalidVersion,
+    /// A released version without a `YYYY-MM-DD` date.
+    InvalidDate,
+    /// A release listed below an older one, or `Unreleased` below a release.
+    Unordered,
+    DuplicateVersion,
+    /// A section heading other than the six Keep a Changelog types.
+    UnknownSection,
+    /// A released version with no matching git tag (from `mdpeek-check`).
+    Untagged,
+}
+
+impl ProblemKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProblemKind::InvalidVersion => "invalid-version",
+            ProblemKind::InvalidDate => "invalid-date",
+            ProblemKind::Unordered => "unordered",
+            ProblemKind::DuplicateVersion => "duplicate-version",
+            ProblemKind::UnknownSection => "unknown-section",
+            ProblemKind::Untagged => "untagged",
+        }
+    }
+}
+
+/// A parsed semantic version, ordered by semver precedence (build metadata
+/// ignored, a pre-release below its release).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Option<String>,
+}
+
+impl Version {
+    pub fn parse(text: &str) -> Option<Self> {
+        let caps = SEMVER.captures(text)?;
+        Some(Self {
+            major: caps[1].parse().ok()?,
+            minor: caps[2].parse().ok()?,
+            patch: caps[3].parse().ok()?,
+            pre: caps.get(4).map(|m| m.as_str().to_string()),
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Pre-release identifiers compare numerically when both are numbers, and
+/// numbers sort below words.
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ord = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+impl Changelog {
+    /// The highest released version that was not yanked.
+    pub fn latest(&self) -> Option<&Release> {
+        self.releases
+            .iter()
+            .filter(|r| !r.unreleased && !r.yanked)
+            .filter_map(|r| Some((r.semver()?, r)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, r)| r)
+    }
+
+    /// The release named `version` (with or without a `v` prefix).
+    pub fn release(&self, version: &str) -> Option<&Release> {
+        let version = version.strip_prefix('v').unwrap_or(version);
+        self.releases
+            .iter()
+            .find(|r| r.version.eq_ignore_ascii_case(version))
+    }
+}
+
+impl Release {
+    pub fn semver(&self) -> Option<Version> {
+        Version::parse(&self.version)
+    }
+
+    /// The markdown of the release's notes in `source`, without its heading.
+    pub fn notes<'a>(&self, source: &'a str) -> &'a str {
+        let start = last_line(&self.link.range) as usize;
+        let last = self.end_line as usize;
+        let mut offset = 0;
+        let mut begin = None;
+        let mut end = source.len();
+        for (i, line) in source.split_inclusive('\n').enumerate() {
+            let number = i + 1;
+            if number == start + 1 {
+                begin = Some(offset);
+            }
+            offset += line.len();
+            if number == last {
+                end = offset;
+                break;
+            }
+        }
+        begin.map_or("", |b| source[b..end.max(b)].trim())
+    }
+}
+
+/// Extract the releases of the changelog parsed into `tree`.
+pub fn extract(tree: &BlockTree) -> Changelog {
+    let mut changelog = Changelog::default();
+    // Releases are the headings at the level of the first one that names a
+    // version; deeper headings are their sections.
+    let mut release_level: Option<u8> = None;
+    let mut in_release = false;
+
+    for block in &tree.blocks {
+        if let BlockKind::Heading { level } = block.kind {
+            let title = block.text.trim();
+            let starts_release = match release_level {
+                Some(l) => level <= l,
+                None => RELEASE_HEADING.is_match(title),
+            };
+            if starts_release {
+                in_release = false;
+                if release_level.is_some_and(|l| level < l) {
+                    continue;
+                }
+                release_level = Some(level);
+                in_release = true;
+                changelog.releases.push(read_release(title, block));
+            } else if in_release && let Some(release) = changelog.releases.last_mut() {
+                release.end_line = last_line(&block.range);
+                release.sections.push(ChangeSection {
+                    kind: ChangeKind::parse(title),
+                    title: title.to_string(),
+                    entries: Vec::new(),
+                    link: link(block),
+                });
+            }
+            continue;
+        }
+        if !in_release {
+            continue;
+        }
+        if let Some(release) = changelog.releases.last_mut() {
+            release.end_line = last_line(&block.range);
+            if let (Some(section), BlockKind::List { .. }) =
+                (release.sections.last_mut(), &block.kind)
+            {
+                section.entries.extend(block.children.iter().map(item_text));
+            }
+        }
+    }
+    validate(&mut changelog);
+    changelog
+}
+
+fn read_release(title: &str, block: &Block) -> Release {
+    let caps = RELEASE_HEADING.captures(title);
+    let version = caps
+        .as_ref()
+        .and_then(|c| c.name("version"))
+        .map_or(title, |m| m.as_str());
+    let unreleased = version.eq_ignore_ascii_case("unreleased");
+    Release {
+        version: if unreleased {
+            "Unreleased".to_string()
+        } else {
+            version.to_string()
+        },
+        unreleased,
+        date: caps
+            .as_ref()
+            .and_then(|c| c.name("date"))
+            .map(|m| m.as_str().to_string()),
+        yanked: caps.as_ref().is_some_and(|c| c.name("yanked").is_some())
+            || title.to_lowercase().contains("[yanked]"),
+        sections: Vec::new(),
+        link: link(block),
+        end_line: last_line(&block.range),
+    }
+}
+
+fn validate(changelog: &mut Changelog) {
+    let mut problems = Vec::new();
+    let mut seen = BTreeSet::new();
+    let mut previous: Option<(Version, &Release)> = None;
+    let mut released = false;
+    for release in &changelog.releases {
+        let problem = |kind, message: String| Problem {
+            kind,
+            message,
+            link: release.link.clone(),
+        };
+        for section in release.sections.iter().filter(|s| s.kind.is_none()) {
+            problems.push(Problem {
+                kind: ProblemKind::UnknownSection,
+                message: format!(
+                    "`{}` is not a change type (Added, Changed, Deprecated, Removed, Fixed, Security)",
+                    section.title
+                ),
+                link: section.link.clone(),
+            });
+        }
+        if release.unreleased {
+            if released {
+                problems.push(problem(
+                    ProblemKind::Unordered,
+                    "Unreleased should come before the releases".to_string(),
+                ));
+            }
+            continue;
+        }
+        released = true;
+        if !seen.insert(release.version.to_lowercase()) {
+            problems.push(problem(
+                ProblemKind::DuplicateVersion,
+                format!("{} is listed more than once", release.version),
+            ));
+        }
+        match &release.date {
+            None => problems.push(problem(
+                ProblemKind::InvalidDate,
+                format!("{} has no release date", release.version),
+            )),
+            Some(date) if !valid_date(date) => problems.push(problem(
+                ProblemKind::InvalidDate,
+                format!("{}: `{date}` is not a YYYY-MM-DD date", release.version),
+            )),
+            Some(_) => {}
+        }
+        let Some(version) = release.semver() else {
+            problems.push(problem(
+                ProblemKind::InvalidVersion,
+                format!("`{}` is not a semantic version", release.version),
+            ));
+            continue;
+        };
+        if let Some((newer, newer_release)) = &previous {
+            if version > *newer {
+                problems.push(problem(
+                    ProblemKind::Unordered,
+                    format!(
+                        "{} is listed below the older {}",
+                        release.version, newer_release.version
+                    ),
+                ));
+            } else if let (Some(date), Some(newer_date)) = (&release.date, &newer_release.date)
+                && version < *newer
+                && valid_date(date)
+                && valid_date(newer_date)
+                && date > newer_date
+            {
+                problems.push(problem(
+                    ProblemKind::Unordered,
+                    format!(
+                        "{} is dated {date}, after {} ({newer_date})",
+                        release.version, newer_release.version
+                    ),
+                ));
+            }
+        }
+        previous = Some((version, release));
+    }
+    problems.sort_by_key(|p| p.link.range.start_line);
+    changelog.problems = problems;
+}
+
+fn valid_date(date: &str) -> bool {
+    let Some(caps) = ISO_DATE.captures(date) else {
+        return false;
+    };
+    let (Ok(year), Ok(month), Ok(day)) = (
+        caps[1].parse::<u32>(),
+        caps[2].parse::<u32>(),
+        caps[3].parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// A list item's text, joined onto one line.
+fn item_text(item: &Block) -> String {
+    let own = item.text.trim();
+    let text = if own.is_empty() {
+        item.children
+            .iter()
+            .find(|c| c.kind == BlockKind::Paragraph)
+            .map_or("", |p| p.text.trim())
+    } else {
+        own
+    };
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The last line `range` covers: its exclusive end is the start of the next
+/// line when the block ends with a newline.
+fn last_line(range: &SourceRange) -> u32 {
+    if range.end_column == 1 && range.end_line > range.start_line {
+        range.end_line - 1
+    } else {
+        range.end_line
+    }
+}
+
+fn link(block: &Block) -> SourceRangeLink {
+    SourceRangeLink {
+        block_id: block.id,
+        range: block.range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog\n\n\
+                             ## [Unreleased]\n\n### Added\n\n- Dark mode\n\n\
+                             ## [1.1.0] - 2024-03-01\n\n### Fixed\n\n- Crash on start\n- Typo\n\n\
+                             ### Security\n\n- Bump openssl\n\n\
+                             ## [1.0.0] - 2024-01-15 [YANKED]\n\n### Added\n\n- First release\n\n\
+                             [1.1.0]: https://example.com/v1.1.0\n";
+
+    fn changelog(md: &str) -> Changelog {
+        extract(&BlockTree::parse(md))
+    }
+
+    #[test]
+    fn releases_with_sections_and_notes() {
+        let c = changelog(CHANGELOG);
+        let releases: Vec<(&str, Option<&str>, bool, bool)> = c
+            .releases
+            .iter()
+            .map(|r| {
+                (
+                    r.version.as_str(),
+                    r.date.as_deref(),
+                    r.unreleased,
+                    r.yanked,
+                )
+            })
+            .collect();
+        assert_eq!(
+            releases,
+            vec![
+                ("Unreleased", None, true, false),
+                ("1.1.0", Some("2024-03-01"), false, false),
+                ("1.0.0", Some("2024-01-15"), false, true),
+            ]
+        );
+        let fixed = &c.releases[1].sections[0];
+        assert_eq!(fixed.kind, Some(ChangeKind::Fixed));
+        assert_eq!(fixed.entries, vec!["Crash on start", "Typo"]);
+        assert!(c.problems.is_empty(), "{:?}", c.problems);
+
+        let latest = c.latest().expect("latest release");
+        assert_eq!(latest.version, "1.1.0");
+        assert_eq!(
+            latest.notes(CHANGELOG),
+            "### Fixed\n\n- Crash on start\n- Typo\n\n### Security\n\n- Bump openssl"
+        );
+        assert_eq!(c.release("v1.0.0").map(|r| r.yanked), Some(true));
+    }
+
+    #[test]
+    fn validation_problems() {
+        let md = "# Changelog\n\n\
+                  ## 1.0.0 - 2024-02-30\n\n### Improved\n\n- Things\n\n\
+                  ## [Unreleased]\n\n\
+                  ## v1.2.0 (2024-04-01)\n\n\
+                  ## 1.0.0 - 2024-01-01\n\n\
+                  ## next\n";
+        let problems: Vec<(ProblemKind, u32)> = changelog(md)
+            .problems
+            .iter()
+            .map(|p| (p.kind, p.link.range.start_line))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                (ProblemKind::InvalidDate, 3),
+                (ProblemKind::UnknownSection, 5),
+                (ProblemKind::Unordered, 9),
+                (ProblemKind::Unordered, 11),
+                (ProblemKind::DuplicateVersion, 13),
+                (ProblemKind::InvalidDate, 15),
+                (ProblemKind::InvalidVersion, 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn semver_precedence() {
+        let v = |s| Version::parse(s).expect(s);
+        assert!(v("1.0.0") > v("1.0.0-rc.1"));
+        assert!(v("1.0.0-rc.2") > v("1.0.0-rc.1"));
+        assert!(v("1.0.0-beta") > v("1.0.0-alpha.9"));
+        assert!(v("1.10.0") > v("1.9.3"));
+        assert_eq!(v("1.0.0+build.5"), v("1.0.0"));
+        assert!(Version::parse("1.0").is_none());
+    }
+}
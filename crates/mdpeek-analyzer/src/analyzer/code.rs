@@ -37,8 +37,8 @@ pub fn intent(lang: Option<&str>, content: &str) -> CodeIntent {
     sniff(content)
 }
 
-/// Map a fence language token to an intent.
-fn from_lang(lang: &str) -> Option<CodeIntent> {
+/// Map a (lowercase) fence language token to an intent.
+pub(crate) fn from_lang(lang: &str) -> Option<CodeIntent> {
     Some(match lang {
         "sh" | "bash" | "zsh" | "shell" | "console" | "shell-session" => CodeIntent::Shell,
         "json" | "jsonc" | "json5" => CodeIntent::Json,
@@ -110,9 +110,11 @@ fn sniff(content: &str) -> CodeIntent {
     }
     // SQL statement.
     let upper = first_line.to_uppercase();
-    if ["SELECT ", "INSERT ", "UPDATE ", "DELETE ", "CREATE ", "ALTER "]
-        .iter()
-        .any(|kw| upper.starts_with(kw))
+    if [
+        "SELECT ", "INSERT ", "UPDATE ", "DELETE ", "CREATE ", "ALTER ",
+    ]
+    .iter()
+    .any(|kw| upper.starts_with(kw))
     {
         return CodeIntent::Sql;
     }
@@ -142,13 +144,22 @@ mod tests {
         assert_eq!(intent(Some("bash"), "echo hi"), CodeIntent::Shell);
         assert_eq!(intent(Some("JSON"), "{}"), CodeIntent::Json);
         assert_eq!(intent(Some("mermaid"), "graph TD"), CodeIntent::Diagram);
-        assert_eq!(intent(Some("rust"), "fn a(){}"), CodeIntent::Language("rust".into()));
-        assert_eq!(intent(Some("py"), "x=1"), CodeIntent::Language("python".into()));
+        assert_eq!(
+            intent(Some("rust"), "fn a(){}"),
+            CodeIntent::Language("rust".into())
+        );
+        assert_eq!(
+            intent(Some("py"), "x=1"),
+            CodeIntent::Language("python".into())
+        );
     }
 
     #[test]
     fn sniff_http() {
-        assert_eq!(intent(None, "GET /users/1 HTTP/1.1\nHost: x"), CodeIntent::Http);
+        assert_eq!(
+            intent(None, "GET /users/1 HTTP/1.1\nHost: x"),
+            CodeIntent::Http
+        );
     }
 
     #[test]
@@ -165,8 +176,14 @@ mod tests {
 
     #[test]
     fn sniff_diff_and_diagram() {
-        assert_eq!(intent(None, "@@ -1,2 +1,3 @@\n-old\n+new"), CodeIntent::Diff);
-        assert_eq!(intent(None, "sequenceDiagram\nA->>B: hi"), CodeIntent::Diagram);
+        assert_eq!(
+            intent(None, "@@ -1,2 +1,3 @@\n-old\n+new"),
+            CodeIntent::Diff
+        );
+        assert_eq!(
+            intent(None, "sequenceDiagram\nA->>B: hi"),
+            CodeIntent::Diagram
+        );
     }
 
     #[test]
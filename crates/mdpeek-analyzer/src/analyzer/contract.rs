@@ -0,0 +1,655 @@
+//! Contract obligations.
+//!
+//! For documents [`doctype::classify`](super::doctype::classify) calls a
+//! [`Contract`](crate::model::DocumentType::Contract), this module finds the
+//! normative statements — `shall`, `must`, `may not`, `is responsible for`, and
+//! the Japanese `しなければならない`, `するものとする`, `してはならない`, … —
+//! and records for each (the phrases of other languages, `muss`, `doit`,
+//! `deberá`, `应当`, come from the document's [`Locale`] pack):
+//!
+//! * **party** — the subject it binds (`The Supplier shall …` → `Supplier`,
+//!   `… shall be paid by the Customer` → `Customer`, `乙は、…` → `乙`);
+//! * **action** — what the party must, must not or may do;
+//! * **deadline** — `within 30 days of …`, `no later than …`, `…以内`, `…までに`;
+//! * **clause** — the number from the heading path (`## 4. Payment` then
+//!   `### 4.2 Late fees` gives `4.2`; `## 第4条` then `### 2. 遅延` gives `4.2`).
+//!
+//! [`Contract::parties`] is the obligation matrix: one row per party (those
+//! listed under frontmatter `parties:` first), each pointing at the statements
+//! that bind it.
+
+use super::frontmatter_list;
+use crate::locale::{KeywordPack, Locale, Normative};
+use crate::panel::SourceRangeLink;
+use mdpeek_parser::{Block, BlockKind, BlockTree};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+/// A pack's normative phrases, compiled.
+struct Modals {
+    pack: &'static KeywordPack,
+    /// Every modal phrase, longest first so `shall not` wins over `shall`.
+    modal: Regex,
+    deadline: Regex,
+}
+
+static MODALS: LazyLock<Vec<Modals>> =
+    LazyLock::new(|| Locale::ALL.iter().map(|l| compile(l.pack())).collect());
+
+fn compile(pack: &'static KeywordPack) -> Modals {
+    let n = &pack.normative;
+    let mut phrases: Vec<&str> = [n.obligation, n.prohibition, n.permission].concat();
+    phrases.sort_by_key(|p| std::cmp::Reverse(p.chars().count()));
+    let alternatives = phrases
+        .iter()
+        .map(|p| regex::escape(p).replace(' ', r"\s+"))
+        .collect::<Vec<_>>()
+        .join("|");
+    let modal = if pack.spaced {
+        format!(r"(?i)\b(?:{alternatives})\b")
+    } else {
+        format!("(?:{alternatives})")
+    };
+    Modals {
+        pack,
+        modal: Regex::new(&modal).expect("valid modal regex"),
+        deadline: Regex::new(n.deadline).expect("valid deadline regex"),
+    }
+}
+
+fn modals(locale: Locale) -> &'static Modals {
+    MODALS
+        .iter()
+        .find(|m| m.pack.locale == locale)
+        .expect("every locale has a pack")
+}
+
+/// `… be paid by the Customer`: the agent of a passive obligation.
+static PASSIVE_AGENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^be\s+\w+\s+by\s+(?:the\s+)?(\p{Lu}[\p{L}-]*(?:\s+\p{Lu}[\p{L}-]*)*)")
+        .expect("valid passive regex")
+});
+/// A capitalised (defined) term such as `Service Provider`.
+static DEFINED_TERM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\p{Lu}[\p{L}-]*(?:\s+\p{Lu}[\p{L}-]*)*").expect("valid term regex")
+});
+/// `4`, `4.2`, `Article 4`, `Section 4.2`, `§ 4`, `第4条`, `第四条`.
+static CLAUSE_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:(?i:article|section|clause|§)\s*)?(\d+(?:\.\d+)*)(?:[.:)\s]|$)|^第\s*([0-9０-９〇一二三四五六七八九十百]+)\s*条",
+    )
+    .expect("valid clause regex")
+});
+
+/// How a statement binds its party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modality {
+    /// `shall`, `must`, `is responsible for`, `しなければならない`.
+    Obligation,
+    /// `shall not`, `may not`, `してはならない`.
+    Prohibition,
+    /// `may`, `することができる`.
+    Permission,
+}
+
+impl Modality {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Modality::Obligation => "must",
+            Modality::Prohibition => "must not",
+            Modality::Permission => "may",
+        }
+    }
+}
+
+/// One normative statement.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Obligation {
+    /// The party bound; `None` when the statement names none (`Notice must be
+    /// given in writing`).
+    pub party: Option<String>,
+    pub modality: Modality,
+    /// What the party must (not / may) do, without the modal (`deliver the
+    /// Goods within 30 days`); Japanese statements keep their predicate whole.
+    pub action: String,
+    pub deadline: Option<String>,
+    /// Clause number from the heading path, e.g. `4.2`.
+    pub clause: Option<String>,
+    /// Title of the innermost heading.
+    pub clause_title: Option<String>,
+    /// The whole sentence.
+    pub text: String,
+    /// The block the sentence is in.
+    pub link: SourceRangeLink,
+}
+
+/// One row of the obligation matrix.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Party {
+    pub name: String,
+    /// Listed under frontmatter `parties:`.
+    pub declared: bool,
+    /// Indices into [`Contract::obligations`].
+    pub obligations: Vec<usize>,
+}
+
+/// The obligation matrix of a contract.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Contract {
+    pub parties: Vec<Party>,
+    /// In document order.
+    pub obligations: Vec<Obligation>,
+}
+
+impl Contract {
+    /// The statements binding `party`.
+    pub fn obligations_of<'a>(&'a self, party: &'a Party) -> impl Iterator<Item = &'a Obligation> {
+        party.obligations.iter().map(|&i| &self.obligations[i])
+    }
+}
+
+/// A heading on the path to the current block.
+struct Heading {
+    level: u8,
+    number: Option<String>,
+    title: String,
+}
+
+/// Extract the normative statements of the contract parsed into `tree`,
+/// written in `locale`.
+pub fn extract(tree: &BlockTree, locale: Locale) -> Contract {
+    let mut path: Vec<Heading> = Vec::new();
+    let mut obligations = Vec::new();
+    for block in tree.iter() {
+        match block.kind {
+            BlockKind::Heading { level } => {
+                path.retain(|h| h.level < level);
+                path.push(Heading {
+                    level,
+                    number: clause_number(block.text.trim()),
+                    title: block.text.clone(),
+                });
+            }
+            BlockKind::Paragraph | BlockKind::Item { .. } => {
+                for sentence in sentences(&block.text) {
+                    if let Some(mut obligation) = statement(sentence, block, locale) {
+                        obligation.clause = clause_of(&path);
+                        obligation.clause_title = path.last().map(|h| h.title.clone());
+                        obligations.push(obligation);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let declared = tree
+        .frontmatter()
+        .map(|frontmatter| frontmatter_list(frontmatter, "parties"))
+        .unwrap_or_default();
+    // A `may` only grants something to a party the contract otherwise binds;
+    // elsewhere it is commentary (`Prices may change`).
+    let bound: Vec<String> = obligations
+        .iter()
+        .filter(|o| o.modality != Modality::Permission)
+        .filter_map(|o| o.party.clone())
+        .chain(declared.iter().cloned())
+        .collect();
+    obligations.retain(|o| {
+        o.modality != Modality::Permission
+            || o.party
+                .as_ref()
+                .is_some_and(|p| bound.iter().any(|b| b.eq_ignore_ascii_case(p)))
+    });
+
+    let mut parties: Vec<Party> = declared
+        .into_iter()
+        .map(|name| Party {
+            name,
+            declared: true,
+            obligations: Vec::new(),
+        })
+        .collect();
+    for (i, obligation) in obligations.iter().enumerate() {
+        let Some(name) = &obligation.party else {
+            continue;
+        };
+        match parties
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+        {
+            Some(party) => party.obligations.push(i),
+            None => parties.push(Party {
+                name: name.clone(),
+                declared: false,
+                obligations: vec![i],
+            }),
+        }
+    }
+    Contract {
+        parties,
+        obligations,
+    }
+}
+
+/// The clause number a heading starts with.
+fn clause_number(title: &str) -> Option<String> {
+    let caps = CLAUSE_NUMBER.captures(title)?;
+    let number = caps.get(1).or_else(|| caps.get(2))?.as_str();
+    Some(
+        number
+            .chars()
+            .map(|c| match c {
+                '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+                _ => c,
+            })
+            .collect(),
+    )
+}
+
+/// The clause number of the heading path: the innermost number, prefixed with
+/// its parent's when it is relative (`4` › `2.` → `4.2`; `4` › `4.2` → `4.2`).
+fn clause_of(path: &[Heading]) -> Option<String> {
+    let mut clause: Option<String> = None;
+    for number in path.iter().filter_map(|h| h.number.as_deref()) {
+        clause = Some(match clause {
+            Some(parent) if !number.starts_with(&format!("{parent}.")) => {
+                format!("{parent}.{number}")
+            }
+            _ => number.to_string(),
+        });
+    }
+    clause
+}
+
+/// Split block text into sentences at `.`, `!`, `?` followed by whitespace,
+/// and after `。`.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let boundary = match c {
+            '。' | '！' | '？' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            out.push(text[start..end].trim());
+            start = end;
+        }
+    }
+    out.push(text[start..].trim());
+    out.retain(|s| !s.is_empty());
+    out
+}
+
+/// The obligation a sentence states, if it is normative. The document's own
+/// phrases are tried before the English ones.
+fn statement(sentence: &str, block: &Block, locale: Locale) -> Option<Obligation> {
+    let (modals, m) = locale.packs().find_map(|pack| {
+        let modals = modals(pack.locale);
+        modals.modal.find(sentence).map(|m| (modals, m))
+    })?;
+    let n = &modals.pack.normative;
+    let link = SourceRangeLink {
+        block_id: block.id,
+        range: block.range,
+    };
+
+    // Topic-marked statements (`乙は、…しなければならない`) keep their
+    // predicate whole, modal included.
+    if let Some(topic) = n.topic {
+        let modality = modality(n, m.as_str(), "");
+        let (party, predicate) = match sentence[..m.start()].split_once(topic) {
+            Some((party, rest)) if party.chars().count() <= 12 => (
+                Some(party.trim().to_string()),
+                rest.trim_start_matches(['、', ' ']),
+            ),
+            _ => (None, &sentence[..m.start()]),
+        };
+        let action = format!("{predicate}{}", m.as_str());
+        let deadline = modals
+            .deadline
+            .find(predicate)
+            .map(|d| d.as_str().trim_end_matches('に').to_string());
+        return Some(Obligation {
+            party: party.filter(|p| !p.is_empty()),
+            modality,
+            deadline,
+            action,
+            clause: None,
+            clause_title: None,
+            text: sentence.to_string(),
+            link,
+        });
+    }
+
+    let action = sentence[m.end()..]
+        .trim()
+        .trim_end_matches(['.', '!', ';', '。'])
+        .to_string();
+    let modality = modality(n, m.as_str(), &action);
+    // A passive statement binds its agent (`be paid by the Customer`), if it
+    // names one; its subject is only what is acted on (`Notice must be given`).
+    let party = if action.starts_with("be ") {
+        PASSIVE_AGENT
+            .captures(&action)
+            .map(|caps| caps[1].to_string())
+    } else {
+        subject(&sentence[..m.start()], n.determiners)
+    };
+    let deadline = modals
+        .deadline
+        .find(&action)
+        .map(|d| d.as_str().trim().to_string());
+    Some(Obligation {
+        party,
+        modality,
+        action,
+        deadline,
+        clause: None,
+        clause_title: None,
+        text: sentence.to_string(),
+        link,
+    })
+}
+
+/// How `modal` binds: a prohibition or permission phrase of the pack, a
+/// permission negated later in the `action` (`darf … nicht`), else an
+/// obligation.
+fn modality(n: &Normative, modal: &str, action: &str) -> Modality {
+    let modal = modal
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if n.prohibition.contains(&modal.as_str()) {
+        Modality::Prohibition
+    } else if n.permission.contains(&modal.as_str()) {
+        let negated = action
+            .split(|c: char| !c.is_alphabetic())
+            .any(|w| n.negations.contains(&w.to_lowercase().as_str()));
+        if negated {
+            Modality::Prohibition
+        } else {
+            Modality::Permission
+        }
+    } else {
+        Modality::Obligation
+    }
+}
+
+/// The party named by the text before a modal: the last defined term after
+/// any introductory clause (`If payment is late, the Supplier …`), else a
+/// short lowercase noun phrase (`the tenant`).
+fn subject(before: &str, determiners: &[&str]) -> Option<String> {
+    let clause = before
+        .rsplit([',', ';', ':', '，', '；', '：'])
+        .next()
+        .unwrap_or(before)
+        .trim();
+    let term = DEFINED_TERM
+        .find_iter(clause)
+        .map(|m| strip_determiners(m.as_str(), determiners))
+        .filter(|t| !t.is_empty())
+        .last();
+    if term.is_some() {
+        return term;
+    }
+    let phrase = strip_determiners(clause, determiners);
+    let words = phrase.split_whitespace().count();
+    (1..=3).contains(&words).then_some(phrase)
+}
+
+fn strip_determiners(phrase: &str, determiners: &[&str]) -> String {
+    let mut words: Vec<&str> = phrase.split_whitespace().collect();
+    let skip = words
+        .iter()
+        .take_while(|w| determiners.contains(&w.to_lowercase().as_str()))
+        .count();
+    words.drain(..skip);
+    // Elided determiners are part of the next word (`l'acheteur`).
+    if let Some(first) = words.first_mut()
+        && let Some(d) = determiners
+            .iter()
+            .filter(|d| d.ends_with(['\'', '’']))
+            .find(|d| first.to_lowercase().starts_with(*d))
+    {
+        *first = &first[d.len()..];
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Party, modality, action, deadline, clause.
+    type Row<'a> = (
+        Option<&'a str>,
+        Modality,
+        &'a str,
+        Option<&'a str>,
+        Option<&'a str>,
+    );
+
+    fn contract(md: &str) -> Contract {
+        extract(&BlockTree::parse(md), Locale::detect(md))
+    }
+
+    #[test]
+    fn english_statements_with_parties_deadlines_and_clauses() {
+        let md = "# Supply Agreement\n\n## 4. Delivery\n\n\
+                  The Supplier shall deliver the Goods within 30 days of the Order. \
+                  Delivery is free.\n\n\
+                  ### 4.2 Acceptance\n\n\
+                  If the Goods are defective, the Customer may reject them. \
+                  The Customer must not resell samples.\n\n\
+                  ## Article 5 Payment\n\n\
+                  - Invoices shall be paid by the Customer no later than 31 March 2025.\n\
+                  - The Service Provider is responsible for all taxes.\n";
+        let c = contract(md);
+        let rows: Vec<Row> = c
+            .obligations
+            .iter()
+            .map(|o| {
+                (
+                    o.party.as_deref(),
+                    o.modality,
+                    o.action.as_str(),
+                    o.deadline.as_deref(),
+                    o.clause.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    Some("Supplier"),
+                    Modality::Obligation,
+                    "deliver the Goods within 30 days of the Order",
+                    Some("within 30 days of the Order"),
+                    Some("4"),
+                ),
+                (
+                    Some("Customer"),
+                    Modality::Permission,
+                    "reject them",
+                    None,
+                    Some("4.2"),
+                ),
+                (
+                    Some("Customer"),
+                    Modality::Prohibition,
+                    "resell samples",
+                    None,
+                    Some("4.2"),
+                ),
+                (
+                    Some("Customer"),
+                    Modality::Obligation,
+                    "be paid by the Customer no later than 31 March 2025",
+                    Some("no later than 31 March 2025"),
+                    Some("5"),
+                ),
+                (
+                    Some("Service Provider"),
+                    Modality::Obligation,
+                    "all taxes",
+                    None,
+                    Some("5"),
+                ),
+            ]
+        );
+        assert_eq!(
+            c.obligations[1].clause_title.as_deref(),
+            Some("4.2 Acceptance")
+        );
+        assert_eq!(c.obligations[3].link.range.start_line, 13);
+
+        let matrix: Vec<(&str, Vec<usize>)> = c
+            .parties
+            .iter()
+            .map(|p| (p.name.as_str(), p.obligations.clone()))
+            .collect();
+        assert_eq!(
+            matrix,
+            vec![
+                ("Supplier", vec![0]),
+                ("Customer", vec![1, 2, 3]),
+                ("Service Provider", vec![4])
+            ]
+        );
+    }
+
+    #[test]
+    fn japanese_statements() {
+        let md = "---\nparties: [甲, 乙]\n---\n# 業務委託契約書\n\n## 第3条（支払）\n\n\
+                  ### 2. 期限\n\n\
+                  乙は、納品後30日以内に代金を支払わなければならない。\
+                  甲は、本件業務を第三者に再委託してはならない。\n\n\
+                  ## 第4条（解除）\n\n乙は、本契約を解除することができる。\n";
+        let c = contract(md);
+        let rows: Vec<(&str, Modality, Option<&str>, Option<&str>)> = c
+            .obligations
+            .iter()
+            .map(|o| {
+                (
+                    o.party.as_deref().unwrap(),
+                    o.modality,
+                    o.deadline.as_deref(),
+                    o.clause.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "乙",
+                    Modality::Obligation,
+                    Some("納品後30日以内"),
+                    Some("3.2")
+                ),
+                ("甲", Modality::Prohibition, None, Some("3.2")),
+                ("乙", Modality::Permission, None, Some("4")),
+            ]
+        );
+        assert_eq!(
+            c.obligations[0].action,
+            "納品後30日以内に代金を支払わなければならない"
+        );
+        // Declared parties come first, in frontmatter order.
+        let parties: Vec<(&str, bool, usize)> = c
+            .parties
+            .iter()
+            .map(|p| (p.name.as_str(), p.declared, p.obligations.len()))
+            .collect();
+        assert_eq!(parties, vec![("甲", true, 1), ("乙", true, 2)]);
+    }
+
+    #[test]
+    fn european_and_chinese_statements() {
+        let rows = |md: &str| -> Vec<(Option<String>, Modality, Option<String>)> {
+            contract(md)
+                .obligations
+                .into_iter()
+                .map(|o| (o.party, o.modality, o.deadline))
+                .collect()
+        };
+        let row = |party: &str, modality, deadline: Option<&str>| {
+            (
+                Some(party.to_string()),
+                modality,
+                deadline.map(str::to_string),
+            )
+        };
+        assert_eq!(
+            rows(
+                "# Liefervertrag\n\nDer Lieferant muss die Ware innerhalb von 30 Tagen liefern. \
+                  Der Kunde darf die Muster nicht weiterverkaufen.\n"
+            ),
+            vec![
+                row(
+                    "Lieferant",
+                    Modality::Obligation,
+                    Some("innerhalb von 30 Tagen")
+                ),
+                row("Kunde", Modality::Prohibition, None),
+            ]
+        );
+        assert_eq!(
+            rows(
+                "# Contrat\n\nLe Fournisseur doit livrer les marchandises dans un délai de 30 jours. \
+                  L'Acheteur ne peut pas revendre les échantillons.\n"
+            ),
+            vec![
+                row(
+                    "Fournisseur",
+                    Modality::Obligation,
+                    Some("dans un délai de 30 jours")
+                ),
+                row("Acheteur", Modality::Prohibition, None),
+            ]
+        );
+        assert_eq!(
+            rows(
+                "# Contrato\n\nEl Proveedor deberá entregar los bienes dentro de 30 días. \
+                  El Cliente no podrá revender las muestras.\n"
+            ),
+            vec![
+                row("Proveedor", Modality::Obligation, Some("dentro de 30 días")),
+                row("Cliente", Modality::Prohibition, None),
+            ]
+        );
+        assert_eq!(
+            rows("# 采购合同\n\n甲方应当在30日内支付货款。乙方不得将本合同转让给第三方。\n"),
+            vec![
+                row("甲方", Modality::Obligation, Some("在30日内")),
+                row("乙方", Modality::Prohibition, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn statements_without_a_party() {
+        let c = contract("# Terms\n\nNotice must be given in writing. Prices may change.\n");
+        assert_eq!(c.obligations.len(), 1);
+        assert_eq!(c.obligations[0].party, None);
+        assert_eq!(c.obligations[0].action, "be given in writing");
+        assert!(c.parties.is_empty());
+    }
+
+    #[test]
+    fn clause_numbers() {
+        assert_eq!(clause_number("Section 4.2 Fees").as_deref(), Some("4.2"));
+        assert_eq!(clause_number("§ 7").as_deref(), Some("7"));
+        assert_eq!(clause_number("第１２条（秘密保持）").as_deref(), Some("12"));
+        assert_eq!(clause_number("Definitions"), None);
+    }
+}
@@ -3,8 +3,13 @@
 //! Priority: explicit `type:` frontmatter → filename convention → heading-set
 //! heuristics → content sniffing → `Generic`. Each rule reports a confidence so
 //! Layer 3 can decide whether to escalate a low-confidence guess to the LLM.
+//! `[analyzer.doctypes]` rules ([`ClassificationRules`]) are tried before the
+//! built-in rules of their tier, and the result names the rule that fired.
+//! Heading keywords come from the document's [`Locale`] packs.
 
+use crate::locale::{DocTypeKeywords, Locale};
 use crate::model::{Classified, DocumentType, OutlineEntry};
+use crate::rules::{ClassificationRules, DocFacts, Tier};
 use mdpeek_parser::BlockTree;
 
 /// Infer the document type from filename, frontmatter and structure.
@@ -12,68 +17,98 @@ pub fn classify(
     filename: Option<&str>,
     tree: &BlockTree,
     outline: &[OutlineEntry],
+    rules: &ClassificationRules,
+    locale: Locale,
 ) -> Classified<DocumentType> {
+    let titles: Vec<String> = outline.iter().map(|e| e.title.to_lowercase()).collect();
+    let facts = DocFacts {
+        filename,
+        frontmatter: tree.frontmatter(),
+        titles: &titles,
+    };
+
     // 1. Explicit frontmatter `type:` wins outright.
+    if let Some(c) = rules.doc_type(Tier::FrontMatter, &facts) {
+        return c;
+    }
     if let Some(t) = tree.frontmatter().and_then(frontmatter_type) {
-        return Classified::rules(t, 0.95);
+        return Classified::rules(t, 0.95).because("front matter type");
     }
 
     // 2. Filename conventions.
+    if let Some(c) = rules.doc_type(Tier::Filename, &facts) {
+        return c;
+    }
     if let Some(name) = filename.map(base_name)
-        && let Some(t) = filename_type(&name)
+        && let Some((t, convention)) = filename_type(&name)
     {
-        return Classified::rules(t, 0.9);
+        return Classified::rules(t, 0.9).because(format!("file name {convention}"));
     }
 
     // 3. Heading-set heuristics.
-    let titles: Vec<String> = outline.iter().map(|e| e.title.to_lowercase()).collect();
-    let has = |kw: &str| titles.iter().any(|t| t.contains(kw));
-    let has_any = |kws: &[&str]| kws.iter().any(|kw| has(kw));
+    if let Some(c) = rules.doc_type(Tier::Headings, &facts) {
+        return c;
+    }
+    // The first keyword of the document's packs a heading contains.
+    let hit = |pick: fn(&DocTypeKeywords) -> &'static [&'static str]| {
+        locale
+            .packs()
+            .flat_map(|pack| pick(&pack.doc_types))
+            .copied()
+            .find(|kw| titles.iter().any(|t| t.contains(kw)))
+    };
 
     // ADR: status + context + (decision | consequences).
-    if has("status") && has("context") && (has("decision") || has("consequence")) {
-        return Classified::rules(DocumentType::Adr, 0.85);
+    if let (Some(status), Some(context), Some(decision)) = (
+        hit(|k| k.adr_status),
+        hit(|k| k.adr_context),
+        hit(|k| k.adr_decision),
+    ) {
+        return Classified::rules(DocumentType::Adr, 0.85)
+            .because(format!("headings {status:?} + {context:?} + {decision:?}"));
     }
     // Recipe: ingredients + a preparation section.
-    if has_any(&["ingredient", "材料"]) && has_any(&["instruction", "作り方", "手順", "steps"]) {
-        return Classified::rules(DocumentType::Recipe, 0.8);
+    if let (Some(ingredients), Some(instructions)) =
+        (hit(|k| k.ingredients), hit(|k| k.instructions))
+    {
+        return Classified::rules(DocumentType::Recipe, 0.8)
+            .because(format!("headings {ingredients:?} + {instructions:?}"));
     }
     // Meeting minutes.
-    if has_any(&["agenda", "attendee", "action item", "議題", "出席", "決定事項"]) {
-        return Classified::rules(DocumentType::Minutes, 0.8);
+    if let Some(kw) = hit(|k| k.minutes) {
+        return Classified::rules(DocumentType::Minutes, 0.8).because(format!("heading {kw:?}"));
     }
     // Changelog.
-    if has_any(&["changelog", "unreleased", "変更履歴"]) {
-        return Classified::rules(DocumentType::Changelog, 0.8);
+    if let Some(kw) = hit(|k| k.changelog) {
+        return Classified::rules(DocumentType::Changelog, 0.8).because(format!("heading {kw:?}"));
     }
     // Runbook / procedure.
-    if has_any(&["prerequisite", "前提", "手順", "procedure", "runbook", "rollback"]) {
-        return Classified::rules(DocumentType::Runbook, 0.7);
+    if let Some(kw) = hit(|k| k.runbook) {
+        return Classified::rules(DocumentType::Runbook, 0.7).because(format!("heading {kw:?}"));
     }
     // Design doc.
-    if has_any(&["architecture", "アーキテクチャ", "設計", "data model", "データモデル"])
-        && has_any(&["overview", "概要", "design", "risk", "リスク"])
-    {
-        return Classified::rules(DocumentType::DesignDoc, 0.7);
+    if let (Some(architecture), Some(overview)) = (hit(|k| k.architecture), hit(|k| k.overview)) {
+        return Classified::rules(DocumentType::DesignDoc, 0.7)
+            .because(format!("headings {architecture:?} + {overview:?}"));
     }
     // FAQ: a majority of headings are questions.
     if !titles.is_empty() {
         let questions = titles
             .iter()
-            .filter(|t| t.trim_end().ends_with('?') || t.contains('？'))
+            .filter(|t| t.trim_end().ends_with('?') || t.contains('？') || t.starts_with('¿'))
             .count();
         if questions * 2 >= titles.len() && questions >= 2 {
-            return Classified::rules(DocumentType::Faq, 0.7);
+            return Classified::rules(DocumentType::Faq, 0.7).because("headings: mostly questions");
         }
     }
 
     // 4. Content sniffing: git log.
     if looks_like_git_log(tree) {
-        return Classified::rules(DocumentType::GitLog, 0.65);
+        return Classified::rules(DocumentType::GitLog, 0.65).because("content: commit hashes");
     }
 
     // 5. Fallback.
-    Classified::rules(DocumentType::Generic, 0.3)
+    Classified::rules(DocumentType::Generic, 0.3).because("fallback")
 }
 
 /// Strip directory components from a path, keeping the final segment.
@@ -81,21 +116,22 @@ fn base_name(path: &str) -> String {
     path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
 }
 
-fn filename_type(name: &str) -> Option<DocumentType> {
+/// The type a file name implies, with the convention it follows.
+fn filename_type(name: &str) -> Option<(DocumentType, &'static str)> {
     let lower = name.to_lowercase();
     let stem = lower.strip_suffix(".md").unwrap_or(&lower);
     if stem.starts_with("readme") {
-        return Some(DocumentType::Readme);
+        return Some((DocumentType::Readme, "README*"));
     }
     if stem.starts_with("changelog") {
-        return Some(DocumentType::Changelog);
+        return Some((DocumentType::Changelog, "CHANGELOG*"));
     }
     if stem.starts_with("contributing") {
-        return Some(DocumentType::Readme);
+        return Some((DocumentType::Readme, "CONTRIBUTING*"));
     }
     // ADR files: `adr-0001-*`, `0001-title`, or anything containing `adr`.
     if stem.starts_with("adr") || stem.contains("adr-") || stem.contains("-adr") {
-        return Some(DocumentType::Adr);
+        return Some((DocumentType::Adr, "adr-*"));
     }
     None
 }
@@ -169,7 +205,14 @@ mod tests {
     fn classify_md(md: &str, filename: Option<&str>) -> DocumentType {
         let tree = BlockTree::parse(md);
         let ol = outline(&tree);
-        classify(filename, &tree, &ol).value
+        classify(
+            filename,
+            &tree,
+            &ol,
+            &ClassificationRules::default(),
+            Locale::En,
+        )
+        .value
     }
 
     #[test]
@@ -181,12 +224,18 @@ mod tests {
 
     #[test]
     fn readme_by_filename() {
-        assert_eq!(classify_md("# Hi\n", Some("docs/README.md")), DocumentType::Readme);
+        assert_eq!(
+            classify_md("# Hi\n", Some("docs/README.md")),
+            DocumentType::Readme
+        );
     }
 
     #[test]
     fn changelog_by_filename() {
-        assert_eq!(classify_md("# 1.0\n", Some("CHANGELOG.md")), DocumentType::Changelog);
+        assert_eq!(
+            classify_md("# 1.0\n", Some("CHANGELOG.md")),
+            DocumentType::Changelog
+        );
     }
 
     #[test]
@@ -225,8 +274,90 @@ mod tests {
         assert_eq!(classify_md(md, None), DocumentType::GitLog);
     }
 
+    #[test]
+    fn configured_rules_precede_built_ins_of_their_tier() {
+        let rules: ClassificationRules = toml::from_str(
+            "[[doctypes]]\ntype = \"minutes\"\nfilename = \"meetings/*.md\"\n\n\
+             [[doctypes]]\ntype = \"investigation\"\nheadings = [\"timeline\", \"root cause\"]\n",
+        )
+        .unwrap();
+        let classify_with = |md: &str, filename| {
+            let tree = BlockTree::parse(md);
+            classify(filename, &tree, &outline(&tree), &rules, Locale::En)
+        };
+
+        let c = classify_with("# Sync\n", Some("docs/meetings/README.md"));
+        assert_eq!(c.value, DocumentType::Minutes);
+        assert_eq!(
+            c.rule.as_deref(),
+            Some("analyzer.doctypes[0] (filename \"meetings/*.md\")")
+        );
+        // Front matter still outranks a filename rule.
+        let c = classify_with("---\ntype: adr\n---\n# X\n", Some("meetings/a.md"));
+        assert_eq!(c.value, DocumentType::Adr);
+        assert_eq!(c.rule.as_deref(), Some("front matter type"));
+
+        let md = "# Outage\n\n## Timeline\n\n## Root cause\n\n## Rollback\n";
+        assert_eq!(classify_with(md, None).value, DocumentType::Investigation);
+        let c = classify_md_rule(md);
+        assert_eq!(c.value, DocumentType::Runbook);
+        assert_eq!(c.rule.as_deref(), Some("heading \"rollback\""));
+    }
+
+    fn classify_md_rule(md: &str) -> Classified<DocumentType> {
+        let tree = BlockTree::parse(md);
+        classify(
+            None,
+            &tree,
+            &outline(&tree),
+            &ClassificationRules::default(),
+            Locale::En,
+        )
+    }
+
+    #[test]
+    fn heading_sets_of_other_locales() {
+        let classify_in = |md: &str, locale| {
+            let tree = BlockTree::parse(md);
+            classify(
+                None,
+                &tree,
+                &outline(&tree),
+                &ClassificationRules::default(),
+                locale,
+            )
+        };
+        let minutes = classify_in(
+            "# Wochenrunde\n\n## Teilnehmer\n\n## Tagesordnung\n",
+            Locale::De,
+        );
+        assert_eq!(minutes.value, DocumentType::Minutes);
+        assert_eq!(minutes.rule.as_deref(), Some("heading \"tagesordnung\""));
+        let adr = classify_in(
+            "# Base de données\n\n## Statut\n\n## Contexte\n\n## Décision\n",
+            Locale::Fr,
+        );
+        assert_eq!(adr.value, DocumentType::Adr);
+        let recipe = classify_in(
+            "# Tortilla\n\n## Ingredientes\n\n## Preparación\n",
+            Locale::Es,
+        );
+        assert_eq!(recipe.value, DocumentType::Recipe);
+        let changelog = classify_in("# 项目\n\n## 更新日志\n", Locale::Zh);
+        assert_eq!(changelog.value, DocumentType::Changelog);
+        // English headings are recognised in any locale; German ones only in
+        // German documents.
+        let en = "# Sync\n\n## Agenda\n";
+        assert_eq!(classify_in(en, Locale::De).value, DocumentType::Minutes);
+        let de = "# Wochenrunde\n\n## Tagesordnung\n";
+        assert_eq!(classify_in(de, Locale::En).value, DocumentType::Generic);
+    }
+
     #[test]
     fn unknown_falls_back_to_generic() {
-        assert_eq!(classify_md("just some prose\n", None), DocumentType::Generic);
+        assert_eq!(
+            classify_md("just some prose\n", None),
+            DocumentType::Generic
+        );
     }
 }
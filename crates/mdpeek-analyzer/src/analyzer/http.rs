@@ -0,0 +1,281 @@
+//! HTTP code blocks (`http` fences, or blocks that open with a request line).
+//!
+//! Each block becomes an [`HttpExchange`]: the request's method, URL, headers
+//! and body, and/or the response's status line, headers and body. A block may
+//! hold either or both; the response starts at its `HTTP/1.1 200 OK` status
+//! line:
+//!
+//! ```text
+//! POST /users HTTP/1.1
+//! Content-Type: application/json
+//!
+//! {"name": "alice"}
+//!
+//! HTTP/1.1 201 Created
+//! Location: /users/7
+//! ```
+//!
+//! Headers end at the first blank line (or the first line that is not a
+//! `Name: value` header, so a body may follow the request line directly).
+//! Leading `#` / `//` comment lines, as in `.http` files, are skipped. The
+//! browser renders each exchange as a request card whose "send" button goes
+//! through the server's API explorer proxy.
+
+use super::code::{self, CodeIntent};
+use crate::panel::SourceRangeLink;
+use mdpeek_parser::{BlockKind, BlockTree};
+use serde::Serialize;
+use std::iter::Peekable;
+
+/// An HTTP code block: a request, a response, or a request and its response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HttpExchange {
+    pub request: Option<HttpRequest>,
+    pub response: Option<HttpResponse>,
+    /// The code block.
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HttpRequest {
+    /// `GET`, `POST`, … as written.
+    pub method: String,
+    /// The request target as written: a path (`/users?page=2`) or an
+    /// absolute URL.
+    pub url: String,
+    /// `HTTP/1.1`, when the request line has one.
+    pub version: Option<String>,
+    pub headers: Vec<HttpHeader>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HttpResponse {
+    /// `HTTP/1.1`, `HTTP/2`, …
+    pub version: String,
+    pub status: u16,
+    /// The reason phrase (`Created`); empty when the status line has none.
+    pub reason: String,
+    pub headers: Vec<HttpHeader>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl HttpRequest {
+    /// The path and query of the target, without the scheme and host of an
+    /// absolute URL: what is appended to a base URL to replay the request.
+    pub fn path(&self) -> &str {
+        match self.url.split_once("://") {
+            Some((_, rest)) => rest.find(['/', '?']).map_or("/", |i| &rest[i..]),
+            None => &self.url,
+        }
+    }
+}
+
+/// Every HTTP code block of `tree` that holds a request or a response.
+pub fn extract(tree: &BlockTree) -> Vec<HttpExchange> {
+    tree.iter()
+        .filter_map(|block| {
+            let BlockKind::CodeBlock { language } = &block.kind else {
+                return None;
+            };
+            if code::intent(language.as_deref(), &block.text) != CodeIntent::Http {
+                return None;
+            }
+            let (request, response) = parse(&block.text);
+            (request.is_some() || response.is_some()).then_some(HttpExchange {
+                request,
+                response,
+                link: SourceRangeLink {
+                    block_id: block.id,
+                    range: block.range,
+                },
+            })
+        })
+        .collect()
+}
+
+fn parse(text: &str) -> (Option<HttpRequest>, Option<HttpResponse>) {
+    let mut lines = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .skip_while(|line| line.trim().is_empty() || is_comment(line))
+        .peekable();
+    let request = lines
+        .peek()
+        .copied()
+        .and_then(request_line)
+        .map(|(method, url, version)| {
+            lines.next();
+            let (headers, body) = message(&mut lines);
+            HttpRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                version: version.map(str::to_string),
+                headers,
+                body,
+            }
+        });
+    while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+    let response = lines
+        .peek()
+        .copied()
+        .and_then(status_line)
+        .map(|(version, status, reason)| {
+            lines.next();
+            let (headers, body) = message(&mut lines);
+            HttpResponse {
+                version: version.to_string(),
+                status,
+                reason: reason.to_string(),
+                headers,
+                body,
+            }
+        });
+    (request, response)
+}
+
+/// The headers and body after a request or status line; the body runs up to
+/// the status line of a response that follows.
+fn message<'a>(
+    lines: &mut Peekable<impl Iterator<Item = &'a str>>,
+) -> (Vec<HttpHeader>, Option<String>) {
+    let mut headers = Vec::new();
+    while let Some(h) = lines.peek().copied().and_then(header) {
+        headers.push(h);
+        lines.next();
+    }
+    while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+    let mut body = Vec::new();
+    while let Some(line) = lines.next_if(|line| status_line(line).is_none()) {
+        body.push(line);
+    }
+    let body = body.join("\n");
+    let body = body.trim_end();
+    (headers, (!body.is_empty()).then(|| body.to_string()))
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('#') || line.starts_with("//")
+}
+
+/// `METHOD target [HTTP/x.y]`.
+fn request_line(line: &str) -> Option<(&str, &str, Option<&str>)> {
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let url = parts.next()?;
+    let version = parts.next();
+    let valid = method.len() >= 3
+        && method.bytes().all(|b| b.is_ascii_uppercase())
+        && version.is_none_or(|v| v.starts_with("HTTP/"))
+        && parts.next().is_none();
+    valid.then_some((method, url, version))
+}
+
+/// `HTTP/x.y 200 [Reason]`.
+fn status_line(line: &str) -> Option<(&str, u16, &str)> {
+    if !line.starts_with("HTTP/") {
+        return None;
+    }
+    let mut parts = line.splitn(3, ' ');
+    let version = parts.next()?;
+    let status = parts.next()?;
+    if status.len() != 3 {
+        return None;
+    }
+    let status = status.parse().ok()?;
+    Some((version, status, parts.next().unwrap_or("").trim()))
+}
+
+/// `Name: value`, the name being an HTTP token.
+fn header(line: &str) -> Option<HttpHeader> {
+    let (name, value) = line.split_once(':')?;
+    let token = |b: u8| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b);
+    (!name.is_empty() && name.bytes().all(token)).then(|| HttpHeader {
+        name: name.to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchanges(md: &str) -> Vec<HttpExchange> {
+        extract(&BlockTree::parse(md))
+    }
+
+    #[test]
+    fn request_and_response_are_split() {
+        let md = "```http\n# Create a user\nPOST https://api.example.com/users?notify=1 HTTP/1.1\n\
+                  Content-Type: application/json\nAuthorization: Bearer token\n\n\
+                  {\n  \"name\": \"alice\"\n}\n\n\
+                  HTTP/1.1 201 Created\nLocation: /users/7\n\n{\"id\": 7}\n```\n";
+        let found = exchanges(md);
+        assert_eq!(found.len(), 1);
+        let request = found[0].request.as_ref().expect("request");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://api.example.com/users?notify=1");
+        assert_eq!(request.path(), "/users?notify=1");
+        assert_eq!(request.version.as_deref(), Some("HTTP/1.1"));
+        assert_eq!(
+            request.headers,
+            [
+                HttpHeader {
+                    name: "Content-Type".into(),
+                    value: "application/json".into()
+                },
+                HttpHeader {
+                    name: "Authorization".into(),
+                    value: "Bearer token".into()
+                },
+            ]
+        );
+        assert_eq!(request.body.as_deref(), Some("{\n  \"name\": \"alice\"\n}"));
+        let response = found[0].response.as_ref().expect("response");
+        assert_eq!(
+            (
+                response.version.as_str(),
+                response.status,
+                response.reason.as_str()
+            ),
+            ("HTTP/1.1", 201, "Created")
+        );
+        assert_eq!(response.headers[0].value, "/users/7");
+        assert_eq!(response.body.as_deref(), Some("{\"id\": 7}"));
+    }
+
+    #[test]
+    fn sniffed_requests_and_bare_responses() {
+        let found = exchanges(
+            "```\nGET /users/1\nAccept: application/json\n```\n\n\
+             ```http\nHTTP/2 204\n```\n\n```http\nnot a request\n```\n",
+        );
+        assert_eq!(found.len(), 2);
+        let request = found[0].request.as_ref().unwrap();
+        assert_eq!(
+            (request.method.as_str(), request.path()),
+            ("GET", "/users/1")
+        );
+        assert_eq!(request.version, None);
+        assert_eq!(request.body, None);
+        assert!(found[0].response.is_none());
+        assert!(found[1].request.is_none());
+        let response = found[1].response.as_ref().unwrap();
+        assert_eq!((response.status, response.reason.as_str()), (204, ""));
+    }
+
+    #[test]
+    fn a_body_may_follow_the_request_line() {
+        let found = exchanges("```http\nPUT /items/3\n{\"done\": true}\n```\n");
+        let request = found[0].request.as_ref().unwrap();
+        assert!(request.headers.is_empty());
+        assert_eq!(request.body.as_deref(), Some("{\"done\": true}"));
+    }
+}
@@ -0,0 +1,653 @@
+//! Action items and decisions of meeting minutes.
+//!
+//! For [`Minutes`](crate::model::DocumentType::Minutes) documents this module
+//! collects:
+//!
+//! * **action items** — every task-list item, and the items of the lists under
+//!   an `Action items` / `TODO` / `宿題` heading, with their owners (`@alice`,
+//!   `Owner: Alice`, `担当: 田中`) and due date;
+//! * **decisions** — the list items and paragraphs under a `Decisions` /
+//!   `決定事項` heading.
+//!
+//! Due dates may be written as dates (`2024-10-31`, `10/31`, `10月31日`) or
+//! relative to the meeting (`by Friday`, `next week`, `来週`, `月末`). Relative
+//! ones are resolved against the meeting date — front matter `date:`, or the
+//! first full date in the title or a `Date:` / `日時:` line — and left
+//! unresolved when there is none.
+//!
+//! [`Minutes::to_ical`] exports the action items as iCalendar `VTODO`s.
+
+use super::frontmatter_list;
+use crate::panel::SourceRangeLink;
+use mdpeek_parser::{Block, BlockKind, BlockTree};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A full date: `2024-10-31`, `2024/10/31`, `2024年10月31日`.
+static FULL_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(\d{4})[-/](\d{1,2})[-/](\d{1,2})\b|(\d{4})年\s*(\d{1,2})月\s*(\d{1,2})日")
+        .expect("valid full date regex")
+});
+/// A date without its year: `10/31`, `10月31日`.
+static SHORT_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\d/])(\d{1,2})/(\d{1,2})\b|(?:^|[^\d年])(\d{1,2})月\s*(\d{1,2})日")
+        .expect("valid short date regex")
+});
+/// `by Friday`, `due next Monday`, `until end of month`, `(eow)`.
+static RELATIVE_DUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:by|due(?: date)?:?|until|before|deadline:?)\s+(?:the\s+)?(?P<next>next\s+)?(?P<day>monday|tuesday|wednesday|thursday|friday|saturday|sunday|mon|tue|wed|thu|fri|sat|sun|today|tomorrow|week|end of (?:the )?week|end of (?:the )?month|eow|eom)\b|\((?P<abbr>eow|eom)\)",
+    )
+    .expect("valid relative due regex")
+});
+/// `来週`, `来週の金曜`, `今週中`, `金曜日まで`, `明日`, `月末`.
+static RELATIVE_DUE_JA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<next>来週)(?:の?(?P<nday>[月火水木金土日])曜日?)?(?:中|末)?|今週(?:中|末)?|(?P<day>[月火水木金土日])曜日?|明日|今日|本日|月末",
+    )
+    .expect("valid Japanese relative due regex")
+});
+/// `@alice`, not the domain of an e-mail address.
+static MENTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[^\w@])@([\w][\w.-]*)").expect("valid mention regex"));
+/// `Owner: Alice`, `担当：田中` (one word).
+static OWNER_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:owner|assignee|担当者?)\s*[:：]\s*([^\s,、;；()（）\[\]]+)")
+        .expect("valid owner label regex")
+});
+/// `Date: …` / `日時：…` lines naming the meeting date.
+static DATE_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^(?:date|when|日時|日付|開催日)\s*[:：]").expect("valid date label regex")
+});
+
+/// Headings of the action item section.
+const ACTION_HEADINGS: &[&str] = &[
+    "action",
+    "todo",
+    "to do",
+    "to-do",
+    "next step",
+    "follow-up",
+    "follow up",
+    "宿題",
+    "タスク",
+    "対応事項",
+    "アクション",
+];
+/// Headings of the decisions section.
+const DECISION_HEADINGS: &[&str] = &["decision", "decided", "resolution", "決定", "決議"];
+
+/// The action items and decisions of a meeting.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Minutes {
+    /// The meeting date (`YYYY-MM-DD`) relative due dates are resolved from.
+    pub date: Option<String>,
+    pub actions: Vec<ActionItem>,
+    pub decisions: Vec<Decision>,
+}
+
+/// One action item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActionItem {
+    pub text: String,
+    /// `@mentions` and labelled owners, in order of appearance.
+    pub owners: Vec<String>,
+    pub due: Option<Due>,
+    /// Checked off (`- [x]`).
+    pub done: bool,
+    pub link: SourceRangeLink,
+}
+
+/// A due date as written, and the date it resolves to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Due {
+    pub text: String,
+    /// `YYYY-MM-DD`; `None` when it is relative and the meeting date unknown.
+    pub date: Option<String>,
+}
+
+/// One decision.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Decision {
+    pub text: String,
+    pub link: SourceRangeLink,
+}
+
+impl Minutes {
+    /// The action items grouped by owner, owners in order of first
+    /// appearance and the unassigned items (`None`) last. An item with
+    /// several owners is listed under each.
+    pub fn by_owner(&self) -> Vec<(Option<&str>, Vec<&ActionItem>)> {
+        let mut groups: Vec<(Option<&str>, Vec<&ActionItem>)> = Vec::new();
+        let mut unassigned = Vec::new();
+        for item in &self.actions {
+            if item.owners.is_empty() {
+                unassigned.push(item);
+            }
+            for owner in &item.owners {
+                match groups.iter_mut().find(|(o, _)| *o == Some(owner.as_str())) {
+                    Some((_, items)) => items.push(item),
+                    None => groups.push((Some(owner), vec![item])),
+                }
+            }
+        }
+        if !unassigned.is_empty() {
+            groups.push((None, unassigned));
+        }
+        groups
+    }
+
+    /// The action items as an iCalendar (RFC 5545) calendar of `VTODO`s,
+    /// stamped `now`. UIDs come from the items' block ids, so re-importing
+    /// an edited document updates the existing entries.
+    pub fn to_ical(&self, now: SystemTime) -> String {
+        let secs = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let (y, m, d) = civil_from_days(secs.div_euclid(86_400));
+        let t = secs.rem_euclid(86_400);
+        let stamp = format!(
+            "{y:04}{m:02}{d:02}T{:02}{:02}{:02}Z",
+            t / 3600,
+            t / 60 % 60,
+            t % 60
+        );
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//mdpeek//minutes//EN".to_string(),
+        ];
+        for item in &self.actions {
+            lines.push("BEGIN:VTODO".to_string());
+            lines.push(format!("UID:{:016x}@mdpeek", item.link.block_id.0));
+            lines.push(format!("DTSTAMP:{stamp}"));
+            lines.push(format!("SUMMARY:{}", ical_escape(&item.text)));
+            if !item.owners.is_empty() {
+                lines.push(format!(
+                    "DESCRIPTION:{}",
+                    ical_escape(&format!("Owner: {}", item.owners.join(", ")))
+                ));
+            }
+            if let Some(date) = item.due.as_ref().and_then(|d| d.date.as_deref()) {
+                lines.push(format!("DUE;VALUE=DATE:{}", date.replace('-', "")));
+            }
+            lines.push(
+                if item.done {
+                    "STATUS:COMPLETED"
+                } else {
+                    "STATUS:NEEDS-ACTION"
+                }
+                .to_string(),
+            );
+            lines.push("END:VTODO".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|l| fold(l) + "\r\n").collect()
+    }
+}
+
+/// Which kind of section the walk is in, and the level of its heading.
+#[derive(Clone, Copy, PartialEq)]
+enum Region {
+    Other,
+    Actions(u8),
+    Decisions(u8),
+}
+
+/// Extract the action items and decisions of the minutes parsed into `tree`.
+pub fn extract(tree: &BlockTree) -> Minutes {
+    let base = meeting_date(tree);
+    let mut minutes = Minutes {
+        date: base.map(format_date),
+        ..Minutes::default()
+    };
+    let mut region = Region::Other;
+    for block in &tree.blocks {
+        if let BlockKind::Heading { level } = block.kind {
+            if matches!(region, Region::Actions(l) | Region::Decisions(l) if l >= level) {
+                region = Region::Other;
+            }
+            let lower = block.text.to_lowercase();
+            if DECISION_HEADINGS.iter().any(|h| lower.contains(h)) {
+                region = Region::Decisions(level);
+            } else if ACTION_HEADINGS.iter().any(|h| lower.contains(h)) {
+                region = Region::Actions(level);
+            }
+            continue;
+        }
+        match (region, &block.kind) {
+            (Region::Actions(_), BlockKind::List { .. }) => {
+                for item in &block.children {
+                    minutes.actions.push(action(item, base));
+                }
+            }
+            (Region::Decisions(_), BlockKind::List { .. }) => {
+                for item in &block.children {
+                    minutes.decisions.push(Decision {
+                        text: item_title(item),
+                        link: link(item),
+                    });
+                }
+            }
+            (Region::Decisions(_), BlockKind::Paragraph) => {
+                minutes.decisions.push(Decision {
+                    text: block.text.split_whitespace().collect::<Vec<_>>().join(" "),
+                    link: link(block),
+                });
+            }
+            _ => collect_tasks(block, base, &mut minutes.actions),
+        }
+    }
+    minutes
+}
+
+/// Task-list items outside the action item section, wherever they nest.
+fn collect_tasks(block: &Block, base: Option<i64>, actions: &mut Vec<ActionItem>) {
+    if matches!(block.kind, BlockKind::Item { task: Some(_) }) {
+        actions.push(action(block, base));
+    }
+    for child in &block.children {
+        collect_tasks(child, base, actions);
+    }
+}
+
+fn action(item: &Block, base: Option<i64>) -> ActionItem {
+    let text = item_title(item);
+    ActionItem {
+        owners: owners(&text),
+        due: due(&text, base),
+        done: matches!(item.kind, BlockKind::Item { task: Some(true) }),
+        text,
+        link: link(item),
+    }
+}
+
+fn owners(text: &str) -> Vec<String> {
+    let mut owners: Vec<String> = Vec::new();
+    let mentions = MENTION
+        .captures_iter(text)
+        .map(|c| c[1].trim_end_matches(['.', '-']).to_string());
+    let labels = OWNER_LABEL
+        .captures_iter(text)
+        .map(|c| c[1].trim_start_matches('@').to_string());
+    for owner in mentions.chain(labels) {
+        if !owner.is_empty() && !owners.contains(&owner) {
+            owners.push(owner);
+        }
+    }
+    owners
+}
+
+/// The first due date in `text`, resolved against the meeting date `base`
+/// (days since the Unix epoch).
+fn due(text: &str, base: Option<i64>) -> Option<Due> {
+    if let Some(caps) = FULL_DATE.captures(text) {
+        let date = full_date(&caps);
+        return Some(Due {
+            text: caps[0].to_string(),
+            date: date.map(format_date),
+        });
+    }
+    if let Some(caps) = SHORT_DATE.captures(text) {
+        let n = |a: usize, b: usize| -> Option<u32> {
+            caps.get(a)
+                .or_else(|| caps.get(b))
+                .and_then(|m| m.as_str().parse().ok())
+        };
+        let (m, d) = (n(1, 3)?, n(2, 4)?);
+        // The next such date on or after the meeting.
+        let date = base.and_then(|base| {
+            let (year, _, _) = civil_from_days(base);
+            let this_year = days_from_civil(year, m, d)?;
+            if this_year >= base {
+                Some(this_year)
+            } else {
+                days_from_civil(year + 1, m, d)
+            }
+        });
+        let matched = caps.get(1).or_else(|| caps.get(3)).map_or(0, |m| m.start());
+        let end = caps.get(0).map_or(0, |m| m.end());
+        return Some(Due {
+            text: text[matched..end].to_string(),
+            date: date.map(format_date),
+        });
+    }
+    if let Some(caps) = RELATIVE_DUE.captures(text) {
+        let day = caps
+            .name("day")
+            .or_else(|| caps.name("abbr"))
+            .map_or("", |m| m.as_str())
+            .to_lowercase();
+        let next = caps.name("next").is_some();
+        let date = base.and_then(|base| {
+            let monday = base - weekday(base);
+            match day.as_str() {
+                "today" => Some(base),
+                "tomorrow" => Some(base + 1),
+                "week" if next => Some(monday + 7 + 4),
+                "end of week" | "end of the week" | "eow" | "week" => Some((monday + 4).max(base)),
+                "end of month" | "end of the month" | "eom" => Some(end_of_month(base)),
+                name => {
+                    let target = weekday_named(name)?;
+                    Some(if next {
+                        monday + 7 + target
+                    } else {
+                        following(base, target)
+                    })
+                }
+            }
+        });
+        return Some(Due {
+            text: caps[0].trim_matches(['(', ')']).to_string(),
+            date: date.map(format_date),
+        });
+    }
+    if let Some(caps) = RELATIVE_DUE_JA.captures(text) {
+        let matched = &caps[0];
+        let date = base.and_then(|base| {
+            let monday = base - weekday(base);
+            if caps.name("next").is_some() {
+                let target = caps
+                    .name("nday")
+                    .and_then(|d| weekday_ja(d.as_str()))
+                    .unwrap_or(4);
+                Some(monday + 7 + target)
+            } else if let Some(day) = caps.name("day") {
+                Some(following(base, weekday_ja(day.as_str())?))
+            } else if matched.starts_with("今週") {
+                Some((monday + 4).max(base))
+            } else if matched == "明日" {
+                Some(base + 1)
+            } else if matched == "月末" {
+                Some(end_of_month(base))
+            } else {
+                Some(base)
+            }
+        });
+        return Some(Due {
+            text: matched.to_string(),
+            date: date.map(format_date),
+        });
+    }
+    None
+}
+
+/// The meeting date: front matter `date:`, else the first full date in the
+/// first heading or on a `Date:` line.
+fn meeting_date(tree: &BlockTree) -> Option<i64> {
+    let parse = |text: &str| full_date(&FULL_DATE.captures(text)?);
+    if let Some(fm) = tree.frontmatter()
+        && let Some(date) = frontmatter_list(fm, "date").first()
+        && let Some(days) = parse(date)
+    {
+        return Some(days);
+    }
+    let title = tree
+        .blocks
+        .iter()
+        .find(|b| matches!(b.kind, BlockKind::Heading { .. }));
+    if let Some(days) = title.and_then(|t| parse(&t.text)) {
+        return Some(days);
+    }
+    tree.iter()
+        .filter(|b| matches!(b.kind, BlockKind::Paragraph | BlockKind::Item { .. }))
+        .flat_map(|b| b.text.lines())
+        .filter(|line| DATE_LABEL.is_match(line.trim()))
+        .find_map(parse)
+}
+
+/// The date a [`FULL_DATE`] match names, if it exists.
+fn full_date(caps: &Captures) -> Option<i64> {
+    let n = |a, b| caps.get(a).or_else(|| caps.get(b)).map(|m| m.as_str());
+    days_from_civil(
+        n(1, 4)?.parse().ok()?,
+        n(2, 5)?.parse().ok()?,
+        n(3, 6)?.parse().ok()?,
+    )
+}
+
+/// The first day strictly after `base` falling on `target` (0 = Monday).
+fn following(base: i64, target: i64) -> i64 {
+    let ahead = (target - weekday(base)).rem_euclid(7);
+    base + if ahead == 0 { 7 } else { ahead }
+}
+
+/// Day of the week, 0 = Monday.
+fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday.
+    (days + 3).rem_euclid(7)
+}
+
+fn weekday_named(name: &str) -> Option<i64> {
+    let days = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    days.iter()
+        .position(|d| name.starts_with(d))
+        .map(|i| i as i64)
+}
+
+fn weekday_ja(name: &str) -> Option<i64> {
+    "月火水木金土日"
+        .chars()
+        .position(|c| name.starts_with(c))
+        .map(|i| i as i64)
+}
+
+fn end_of_month(days: i64) -> i64 {
+    let (y, m, _) = civil_from_days(days);
+    let (ny, nm) = if m == 12 { (y + 1, 1) } else { (y, m + 1) };
+    days_from_civil(ny, nm, 1).map_or(days, |first| first - 1)
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date; `None` for a date
+/// that does not exist.
+fn days_from_civil(y: i64, m: u32, d: u32) -> Option<i64> {
+    let leap = y % 4 == 0 && (y % 100 != 0 || y % 400 == 0);
+    let days_in_month = match m {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if d == 0 || d > days_in_month {
+        return None;
+    }
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = i64::from((m + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+fn format_date(days: i64) -> String {
+    let (y, m, d) = civil_from_days(days);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11).
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets (RFC 5545 §3.1), never inside a UTF-8
+/// sequence.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+/// A list item's own text, or that of its first paragraph in a loose list.
+fn item_title(item: &Block) -> String {
+    let own = item.text.trim();
+    if !own.is_empty() {
+        return own.lines().next().unwrap_or(own).to_string();
+    }
+    item.children
+        .iter()
+        .find(|c| c.kind == BlockKind::Paragraph)
+        .map_or(String::new(), |p| {
+            p.text.lines().next().unwrap_or("").trim().to_string()
+        })
+}
+
+fn link(block: &Block) -> SourceRangeLink {
+    SourceRangeLink {
+        block_id: block.id,
+        range: block.range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(md: &str) -> Minutes {
+        extract(&BlockTree::parse(md))
+    }
+
+    #[test]
+    fn action_items_with_owners_and_due_dates() {
+        // 2024-10-16 is a Wednesday.
+        let md = "---\ndate: 2024-10-16\n---\n# Weekly sync\n\n## Notes\n\n\
+                  - [x] @bob shared the draft\n- plain note\n\n\
+                  ## Action items\n\n\
+                  - @alice send the notes by Friday\n\
+                  - Update the roadmap (Owner: Carol) due 2024-11-01\n\
+                  - @alice @bob review 10/31\n\
+                  - 資料を作成する 担当：田中 来週\n\
+                  - Check mail from dev@example.com next week\n\n\
+                  ## Decisions\n\n- Ship on Monday\n\nWe keep the weekly cadence.\n";
+        let m = minutes(md);
+        assert_eq!(m.date.as_deref(), Some("2024-10-16"));
+        let actions: Vec<(&str, Vec<&str>, Option<&str>, bool)> = m
+            .actions
+            .iter()
+            .map(|a| {
+                (
+                    a.text.as_str(),
+                    a.owners.iter().map(String::as_str).collect(),
+                    a.due.as_ref().and_then(|d| d.date.as_deref()),
+                    a.done,
+                )
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("@bob shared the draft", vec!["bob"], None, true),
+                (
+                    "@alice send the notes by Friday",
+                    vec!["alice"],
+                    Some("2024-10-18"),
+                    false
+                ),
+                (
+                    "Update the roadmap (Owner: Carol) due 2024-11-01",
+                    vec!["Carol"],
+                    Some("2024-11-01"),
+                    false
+                ),
+                (
+                    "@alice @bob review 10/31",
+                    vec!["alice", "bob"],
+                    Some("2024-10-31"),
+                    false
+                ),
+                (
+                    "資料を作成する 担当：田中 来週",
+                    vec!["田中"],
+                    Some("2024-10-25"),
+                    false
+                ),
+                (
+                    "Check mail from dev@example.com next week",
+                    vec![],
+                    None,
+                    false
+                ),
+            ]
+        );
+        let decisions: Vec<&str> = m.decisions.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(
+            decisions,
+            vec!["Ship on Monday", "We keep the weekly cadence."]
+        );
+
+        let groups: Vec<(Option<&str>, usize)> = m
+            .by_owner()
+            .iter()
+            .map(|(owner, items)| (*owner, items.len()))
+            .collect();
+        assert_eq!(groups[0], (Some("bob"), 2));
+        assert_eq!(groups[1], (Some("alice"), 2));
+        assert_eq!(groups.last(), Some(&(None, 1)));
+    }
+
+    #[test]
+    fn relative_due_dates() {
+        let base = days_from_civil(2024, 10, 16); // Wednesday
+        let resolve = |text: &str| due(text, base).and_then(|d| d.date);
+        assert_eq!(resolve("by tomorrow").as_deref(), Some("2024-10-17"));
+        assert_eq!(resolve("by next Monday").as_deref(), Some("2024-10-21"));
+        assert_eq!(resolve("by Wednesday").as_deref(), Some("2024-10-23"));
+        assert_eq!(resolve("until end of month").as_deref(), Some("2024-10-31"));
+        assert_eq!(resolve("(eow)").as_deref(), Some("2024-10-18"));
+        assert_eq!(resolve("今週中").as_deref(), Some("2024-10-18"));
+        assert_eq!(resolve("来週の月曜").as_deref(), Some("2024-10-21"));
+        assert_eq!(resolve("金曜日まで").as_deref(), Some("2024-10-18"));
+        assert_eq!(resolve("月末").as_deref(), Some("2024-10-31"));
+        assert_eq!(resolve("3/1").as_deref(), Some("2025-03-01"));
+        assert_eq!(resolve("12月24日").as_deref(), Some("2024-12-24"));
+        // Without a meeting date, only full dates resolve.
+        assert_eq!(
+            due("by Friday", None).map(|d| d.text).as_deref(),
+            Some("by Friday")
+        );
+        assert_eq!(due("by Friday", None).and_then(|d| d.date), None);
+    }
+
+    #[test]
+    fn exports_vtodos() {
+        let md = "# Sync 2024-10-16\n\n## Action items\n\n\
+                  - [ ] @alice write the summary, with links; by Friday\n- [x] Book a room\n";
+        let ical = minutes(md).to_ical(UNIX_EPOCH);
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VTODO").count(), 2);
+        assert!(ical.contains("DTSTAMP:19700101T000000Z\r\n"));
+        assert!(ical.contains("SUMMARY:@alice write the summary\\, with links\\; by Friday\r\n"));
+        assert!(ical.contains("DESCRIPTION:Owner: alice\r\n"));
+        assert!(ical.contains("DUE;VALUE=DATE:20241018\r\n"));
+        assert!(ical.contains("STATUS:COMPLETED\r\n"));
+        assert!(fold(&"x".repeat(80)).contains("\r\n x"));
+    }
+}
@@ -0,0 +1,507 @@
+//! Novel structure: chapters, character roster and first appearances.
+//!
+//! Used for documents [`doctype::classify`](super::doctype::classify) calls a
+//! [`Novel`](crate::model::DocumentType::Novel):
+//!
+//! * **chapters** — the headings at the chapter level: the level of the first
+//!   `Chapter 3` / `Ch. 3` / `第三章` / `Prologue` heading, otherwise the
+//!   shallowest level used more than once (so a lone `# Book title` stays out);
+//! * **characters** — names listed under frontmatter `characters:`, plus
+//!   capitalised names (`Elizabeth`, `Mr. Darcy`) and short quoted names
+//!   (`「ハル」`) that recur in the prose;
+//! * **first appearance** — the chapter and block of each name's first mention.
+//!
+//! Chapters are numbered from 1 in document order; text before the first
+//! chapter belongs to chapter 0. Viewers use the first appearances for their
+//! "read up to chapter N" spoiler mode.
+
+use super::frontmatter_list;
+use crate::panel::SourceRangeLink;
+use crate::stats;
+use mdpeek_parser::{Block, BlockKind, BlockTree};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// `Chapter 3`, `Ch. 3`, `Prologue`, `第三章`, `第3話`, `序章` headings.
+static CHAPTER_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:chapter\b|ch\.\s*\d|prologue\b|epilogue\b|interlude\b|第[0-9０-９〇零一二三四五六七八九十百千]+[章話回]|序章|終章|プロローグ|エピローグ)",
+    )
+    .expect("valid chapter regex")
+});
+/// A run of capitalised words, each optionally after a title (`Mr. Darcy`).
+static CAPITALISED: LazyLock<Regex> = LazyLock::new(|| {
+    let word = format!(r"(?:(?:{})\.?[ \t]+)?\p{{Lu}}\p{{Ll}}+", TITLES.join("|"));
+    Regex::new(&format!(r"\b{word}(?:[ \t]+{word})*")).expect("valid name regex")
+});
+/// A short quoted span: `「ハル」`, `『Mole』`, `“Rat”`, `"Toad"`.
+static QUOTED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"「([^」\n]{1,12})」|『([^』\n]{1,12})』|“([^”\n]{1,24})”|"([^"\n]{1,24})""#)
+        .expect("valid quote regex")
+});
+
+/// A capitalised name needs this many mentions, one of them mid-sentence (so
+/// `Suddenly` at the start of sentences is not a character)…
+const MIN_NAME_MENTIONS: usize = 3;
+/// …and a quoted name this many.
+const MIN_QUOTED_MENTIONS: usize = 2;
+
+/// Titles that start a name (`Mr. Darcy`), with or without the dot.
+const TITLES: &[&str] = &[
+    "Mr",
+    "Mrs",
+    "Ms",
+    "Dr",
+    "Miss",
+    "Sir",
+    "Lady",
+    "Lord",
+    "Captain",
+    "Professor",
+];
+
+/// Capitalised words that are not names on their own.
+const NOT_NAMES: &[&str] = &[
+    "A",
+    "After",
+    "All",
+    "Also",
+    "An",
+    "And",
+    "As",
+    "At",
+    "Before",
+    "But",
+    "By",
+    "Chapter",
+    "Did",
+    "Do",
+    "Even",
+    "Every",
+    "For",
+    "From",
+    "He",
+    "Her",
+    "Here",
+    "His",
+    "How",
+    "I",
+    "If",
+    "In",
+    "It",
+    "Its",
+    "Just",
+    "Let",
+    "My",
+    "No",
+    "Not",
+    "Now",
+    "Of",
+    "Oh",
+    "On",
+    "One",
+    "Or",
+    "Our",
+    "Part",
+    "She",
+    "So",
+    "Some",
+    "That",
+    "The",
+    "Their",
+    "Then",
+    "There",
+    "These",
+    "They",
+    "This",
+    "Those",
+    "To",
+    "Too",
+    "Was",
+    "We",
+    "Well",
+    "What",
+    "When",
+    "Where",
+    "Which",
+    "While",
+    "Who",
+    "Why",
+    "With",
+    "Yes",
+    "Yet",
+    "You",
+    "Your",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Chapters and characters of a novel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Novel {
+    pub chapters: Vec<Chapter>,
+    /// In order of first appearance; declared characters never mentioned last.
+    pub characters: Vec<Character>,
+}
+
+/// One chapter heading.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Chapter {
+    /// 1-based position in the document.
+    pub number: usize,
+    pub title: String,
+    /// Prose words up to the next chapter (CJK-aware, as in [`crate::stats`]).
+    pub words: usize,
+    pub link: SourceRangeLink,
+}
+
+/// One roster entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Character {
+    pub name: String,
+    /// Listed under frontmatter `characters:`.
+    pub declared: bool,
+    pub mentions: usize,
+    /// Chapter of the first mention (0 before the first chapter); `None` for a
+    /// declared character the text never mentions.
+    pub first_chapter: Option<usize>,
+    /// The block of the first mention.
+    pub first_seen: Option<SourceRangeLink>,
+}
+
+impl Novel {
+    /// The source line where reading past chapter `upto` starts — the heading
+    /// of chapter `upto + 1` — or `None` when `upto` covers the whole book.
+    pub fn spoiler_line(&self, upto: usize) -> Option<u32> {
+        self.chapters
+            .get(upto)
+            .map(|chapter| chapter.link.range.start_line)
+    }
+
+    /// The characters a reader who has finished chapter `upto` has met.
+    pub fn revealed(&self, upto: usize) -> impl Iterator<Item = &Character> {
+        self.characters.iter().filter(move |c| c.revealed_by(upto))
+    }
+}
+
+impl Character {
+    /// Whether the character appears no later than chapter `upto`.
+    pub fn revealed_by(&self, upto: usize) -> bool {
+        self.first_chapter.is_none_or(|chapter| chapter <= upto)
+    }
+}
+
+/// Read the chapters and characters of the novel parsed into `tree`.
+pub fn extract(tree: &BlockTree) -> Novel {
+    let level = chapter_level(tree);
+    // Prose blocks with the chapter each belongs to.
+    let mut prose: Vec<(usize, &Block)> = Vec::new();
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for block in tree.iter() {
+        match block.kind {
+            BlockKind::Heading { level: l } if Some(l) == level => {
+                chapters.push(Chapter {
+                    number: chapters.len() + 1,
+                    title: block.text.clone(),
+                    words: 0,
+                    link: SourceRangeLink {
+                        block_id: block.id,
+                        range: block.range,
+                    },
+                });
+            }
+            BlockKind::CodeBlock { .. } | BlockKind::MetadataBlock | BlockKind::HtmlBlock => {
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(chapter) = chapters.last_mut() {
+            chapter.words += stats::count(&block.text).words;
+        }
+        prose.push((chapters.len(), block));
+    }
+
+    let declared = tree
+        .frontmatter()
+        .map(|frontmatter| frontmatter_list(frontmatter, "characters"))
+        .unwrap_or_default();
+    let mut names = declared.clone();
+    for name in recurring_names(&prose) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut characters: Vec<Character> = names
+        .into_iter()
+        .map(|name| {
+            let mut character = Character {
+                declared: declared.contains(&name),
+                name,
+                mentions: 0,
+                first_chapter: None,
+                first_seen: None,
+            };
+            for (chapter, block) in &prose {
+                let n = mentions(&block.text, &character.name);
+                if n > 0 && character.first_seen.is_none() {
+                    character.first_chapter = Some(*chapter);
+                    character.first_seen = Some(SourceRangeLink {
+                        block_id: block.id,
+                        range: block.range,
+                    });
+                }
+                character.mentions += n;
+            }
+            character
+        })
+        .collect();
+    characters.sort_by_key(|c| {
+        c.first_seen
+            .as_ref()
+            .map_or((1, 0, 0), |l| (0, l.range.start_line, l.range.start_column))
+    });
+    Novel {
+        chapters,
+        characters,
+    }
+}
+
+/// The heading level chapters use, or `None` for a document without headings.
+fn chapter_level(tree: &BlockTree) -> Option<u8> {
+    let mut counts: HashMap<u8, usize> = HashMap::new();
+    for block in tree.iter() {
+        if let BlockKind::Heading { level } = block.kind {
+            if CHAPTER_HEADING.is_match(block.text.trim()) {
+                return Some(level);
+            }
+            *counts.entry(level).or_default() += 1;
+        }
+    }
+    let repeated = counts
+        .iter()
+        .filter(|(_, n)| **n > 1)
+        .map(|(l, _)| *l)
+        .min();
+    repeated.or_else(|| counts.keys().min().copied())
+}
+
+/// Capitalised and quoted names that recur often enough to be characters, in
+/// order of first occurrence.
+fn recurring_names(prose: &[(usize, &Block)]) -> Vec<String> {
+    // name → (mentions, mentioned mid-sentence)
+    let mut capitalised: HashMap<String, (usize, bool)> = HashMap::new();
+    let mut quoted: HashMap<String, usize> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+    for (_, block) in prose {
+        if matches!(block.kind, BlockKind::Heading { .. }) {
+            continue;
+        }
+        let text = &block.text;
+        for m in CAPITALISED.find_iter(text) {
+            let words: Vec<&str> = m.as_str().split_whitespace().collect();
+            // Drop leading non-names, anything before a title (`Suddenly
+            // Mr. Darcy`) and, at the start of a sentence, the opening word.
+            let title = words
+                .iter()
+                .rposition(|w| TITLES.contains(&w.trim_end_matches('.')));
+            let mut skip = words
+                .iter()
+                .take_while(|w| NOT_NAMES.contains(w))
+                .count()
+                .max(title.unwrap_or(0));
+            if skip == words.len() {
+                continue;
+            }
+            if title.is_none()
+                && skip == 0
+                && words.len() > 1
+                && starts_sentence(&text[..m.start()])
+            {
+                // `Later Toad waved`: more likely an adverb than a first name.
+                skip = 1;
+            }
+            let name = words[skip..].join(" ");
+            let mid_sentence = skip > 0 || !starts_sentence(&text[..m.start()]);
+            let entry = capitalised.entry(name.clone()).or_default();
+            entry.0 += 1;
+            entry.1 |= mid_sentence;
+            order.push(name);
+        }
+        for caps in QUOTED.captures_iter(text) {
+            let inner = caps
+                .iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .map_or("", |m| m.as_str().trim());
+            if looks_like_name(inner) {
+                *quoted.entry(inner.to_string()).or_default() += 1;
+                order.push(inner.to_string());
+            }
+        }
+    }
+    let mut names: Vec<String> = Vec::new();
+    for name in order {
+        let recurring = capitalised
+            .get(&name)
+            .is_some_and(|(n, mid)| *n >= MIN_NAME_MENTIONS && *mid)
+            || quoted.get(&name).is_some_and(|n| *n >= MIN_QUOTED_MENTIONS);
+        if recurring && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Whether a match preceded by `before` opens a sentence (so its capital
+/// letter says nothing about it being a name).
+fn starts_sentence(before: &str) -> bool {
+    let last = before
+        .chars()
+        .rev()
+        .find(|c| !c.is_whitespace() && !matches!(c, '"' | '“' | '‘' | '\'' | '(' | '「' | '—'));
+    last.is_none_or(|c| matches!(c, '.' | '!' | '?' | '…' | ':' | '。' | '！' | '？'))
+}
+
+/// A quoted span short and plain enough to be a name rather than dialogue.
+fn looks_like_name(inner: &str) -> bool {
+    !inner.is_empty()
+        && inner.split_whitespace().count() <= 3
+        && inner.chars().count() <= 12
+        && !inner.chars().any(|c| {
+            matches!(
+                c,
+                '.' | ',' | '!' | '?' | ';' | '…' | '。' | '、' | '！' | '？' | '—'
+            )
+        })
+}
+
+/// Occurrences of `name` in `text`, as a whole word where the name starts or
+/// ends with a letter of a spaced script.
+fn mentions(text: &str, name: &str) -> usize {
+    let is_word = |c: char| c.is_alphanumeric() && !mdpeek_gfm::cjk::is_east_asian_wide(c);
+    let bounded_start = name.chars().next().is_some_and(is_word);
+    let bounded_end = name.chars().last().is_some_and(is_word);
+    text.match_indices(name)
+        .filter(|(i, _)| {
+            let before = text[..*i].chars().next_back();
+            let after = text[i + name.len()..].chars().next();
+            let joined_before = bounded_start && before.is_some_and(is_word);
+            let joined_after = bounded_end && after.is_some_and(is_word);
+            !joined_before && !joined_after
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn novel(md: &str) -> Novel {
+        extract(&BlockTree::parse(md))
+    }
+
+    fn names(novel: &Novel) -> Vec<(&str, Option<usize>)> {
+        novel
+            .characters
+            .iter()
+            .map(|c| (c.name.as_str(), c.first_chapter))
+            .collect()
+    }
+
+    #[test]
+    fn chapters_from_chapter_headings_below_the_title() {
+        let md = "# The Wind\n\nA book.\n\n## Prologue\n\nDark.\n\n### Scene\n\nx\n\n\
+                  ## Chapter 1\n\nOne two three.\n\n## Chapter 2\n\nFour.\n";
+        let n = novel(md);
+        let chapters: Vec<(usize, &str, usize)> = n
+            .chapters
+            .iter()
+            .map(|c| (c.number, c.title.as_str(), c.words))
+            .collect();
+        assert_eq!(
+            chapters,
+            vec![(1, "Prologue", 4), (2, "Chapter 1", 5), (3, "Chapter 2", 3)]
+        );
+        assert_eq!(n.chapters[1].link.range.start_line, 13);
+        assert_eq!(n.spoiler_line(1), Some(13));
+        assert_eq!(n.spoiler_line(3), None);
+    }
+
+    #[test]
+    fn chapters_fall_back_to_the_repeated_heading_level() {
+        let n = novel("# Title\n\n## The Sea\n\nx\n\n## The Shore\n\ny\n");
+        let titles: Vec<&str> = n.chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["The Sea", "The Shore"]);
+        let n = novel("# 第一章 出会い\n\n本文。\n\n# 第二章 別れ\n\n本文。\n");
+        assert_eq!(n.chapters.len(), 2);
+    }
+
+    #[test]
+    fn roster_from_capitalised_names_with_first_appearance() {
+        let md = "## Chapter 1\n\nAlice met the Queen. Then Alice ran. \
+                  Suddenly it rained.\n\nThe Queen frowned at Alice.\n\n\
+                  ## Chapter 2\n\nMr. Darcy arrived. Alice bowed to Mr. Darcy. \
+                  Everyone saw Mr. Darcy. Suddenly it rained.\n";
+        let n = novel(md);
+        // `Queen` has two mentions; `Suddenly` only opens sentences.
+        assert_eq!(names(&n), vec![("Alice", Some(1)), ("Mr. Darcy", Some(2))]);
+        assert_eq!(n.characters[0].mentions, 4);
+        assert_eq!(
+            n.characters[1]
+                .first_seen
+                .as_ref()
+                .unwrap()
+                .range
+                .start_line,
+            9
+        );
+        let revealed: Vec<&str> = n.revealed(1).map(|c| c.name.as_str()).collect();
+        assert_eq!(revealed, vec!["Alice"]);
+    }
+
+    #[test]
+    fn roster_from_frontmatter_and_quoted_names() {
+        let md = "---\ntype: novel\ncharacters:\n  - name: 春子\n    role: lead\n  - 秋人\n\
+                  title: x\n---\n# 第1章\n\n春子は「ハル」と呼ばれた。\n\n\
+                  # 第2章\n\n「ハル」、と秋人が言った。\n";
+        let n = novel(md);
+        assert_eq!(
+            names(&n),
+            vec![("春子", Some(1)), ("ハル", Some(1)), ("秋人", Some(2))]
+        );
+        assert!(n.characters[0].declared && !n.characters[1].declared);
+        assert_eq!(n.characters[1].mentions, 2);
+
+        let n = novel("---\ncharacters: [Mole, \"Ratty\"]\n---\n## One\n\nMole.\n\n## Two\n\nx\n");
+        assert_eq!(names(&n), vec![("Mole", Some(1)), ("Ratty", None)]);
+        // Never mentioned: nothing to spoil.
+        assert!(n.characters[1].revealed_by(0));
+    }
+
+    #[test]
+    fn mentions_respect_word_boundaries() {
+        assert_eq!(mentions("Al and Alice, Al's cat", "Al"), 2);
+        assert_eq!(mentions("ハルとハルカ", "ハル"), 2);
+    }
+}
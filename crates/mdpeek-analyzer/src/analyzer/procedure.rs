@@ -0,0 +1,396 @@
+//! Step model of runbooks and procedures.
+//!
+//! For [`Runbook`](crate::model::DocumentType::Runbook) and
+//! [`Procedure`](crate::model::DocumentType::Procedure) documents this module
+//! turns the numbered instructions into steps a viewer can walk through:
+//!
+//! * **steps** — `Step 1` / `手順1` headings, and the items of ordered lists in
+//!   sections classified as [`BlockClass::Step`] (`## Procedure`, `## 手順`,
+//!   or a `[analyzer.sections]` keyword);
+//! * **commands** — shell code blocks in a step ([`code::intent`]);
+//! * **expected output** — an `Expected: …` line, or a code block introduced
+//!   as the expected output (`You should see:`), or a plain `text` block after
+//!   a command;
+//! * **prerequisites** — the list under a `Prerequisites` / `前提条件` heading;
+//! * **rollback** — the steps under a `Rollback` / `切り戻し` heading.
+//!
+//! Each step has a [`Step::key`] derived from its title, so completion stored
+//! against it (see [`crate::progress`]) survives steps being added or moved.
+
+use super::code::{self, CodeIntent};
+use crate::locale::Locale;
+use crate::model::BlockClass;
+use crate::panel::SourceRangeLink;
+use crate::rules::ClassificationRules;
+use mdpeek_parser::{Block, BlockKind, BlockTree};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::LazyLock;
+
+/// `Step 3`, `Step 3: Drain`, `手順3`, `手順 3.`.
+static STEP_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:step|手順)\s*(\d+)\s*[.:)\-–—]?\s*").expect("valid step regex")
+});
+/// Text announcing that the next code block is the expected output.
+static EXPECTS_OUTPUT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)expected (?:output|result)|you should see|should (?:print|output|return|show)|出力例|期待される|次のように表示",
+    )
+    .expect("valid expected-output regex")
+});
+/// `Expected: ok` written inline.
+static EXPECTED_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:expected(?: output| result)?|期待値|期待される結果)\s*[:：]\s*(.+)")
+        .expect("valid expected line regex")
+});
+
+/// Headings of the prerequisites section.
+const PREREQUISITE_HEADINGS: &[&str] = &[
+    "prerequisite",
+    "requirement",
+    "before you begin",
+    "前提",
+    "準備",
+    "必要なもの",
+];
+/// Headings of the rollback section.
+const ROLLBACK_HEADINGS: &[&str] = &[
+    "rollback",
+    "roll back",
+    "revert",
+    "undo",
+    "切り戻し",
+    "ロールバック",
+];
+
+/// The steps of a runbook or procedure.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Procedure {
+    pub prerequisites: Vec<Prerequisite>,
+    pub steps: Vec<Step>,
+    pub rollback: Vec<Step>,
+}
+
+/// One prerequisite item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prerequisite {
+    pub text: String,
+    pub link: SourceRangeLink,
+}
+
+/// One step.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Step {
+    /// 1-based position among the steps (or among the rollback steps).
+    pub number: usize,
+    /// Stable identity for stored progress: the title, prefixed `rollback:`
+    /// for rollback steps and suffixed `#2`, `#3` … when titles repeat.
+    pub key: String,
+    pub title: String,
+    /// Shell commands to run, in order.
+    pub commands: Vec<String>,
+    /// What a successful step prints.
+    pub expected: Vec<String>,
+    /// Completed, according to the stored progress.
+    pub done: bool,
+    pub link: SourceRangeLink,
+}
+
+impl Procedure {
+    /// Mark the steps whose keys are in `done` as completed.
+    pub fn mark_done(&mut self, done: &BTreeSet<String>) {
+        for step in self.steps.iter_mut().chain(&mut self.rollback) {
+            step.done = done.contains(&step.key);
+        }
+    }
+
+    /// The step `key` names, among the steps and the rollback steps.
+    pub fn step_mut(&mut self, key: &str) -> Option<&mut Step> {
+        self.steps
+            .iter_mut()
+            .chain(&mut self.rollback)
+            .find(|step| step.key == key)
+    }
+
+    /// The first step not yet done.
+    pub fn next_step(&self) -> Option<&Step> {
+        self.steps.iter().find(|step| !step.done)
+    }
+}
+
+/// Which kind of section the walk is in, and the level of its heading.
+#[derive(Clone, Copy, PartialEq)]
+enum Region {
+    Other,
+    Steps(u8),
+    Prerequisites(u8),
+    Rollback(u8),
+}
+
+impl Region {
+    fn level(self) -> Option<u8> {
+        match self {
+            Region::Other => None,
+            Region::Steps(l) | Region::Prerequisites(l) | Region::Rollback(l) => Some(l),
+        }
+    }
+}
+
+/// Extract the procedure in the document parsed into `tree`; `rules` and the
+/// document's `locale` decide which section headings introduce steps.
+pub fn extract(tree: &BlockTree, rules: &ClassificationRules, locale: Locale) -> Procedure {
+    let mut procedure = Procedure::default();
+    let mut region = Region::Other;
+    // The `Step N` heading whose content we are reading, and its level.
+    let mut heading_step: Option<(bool, u8)> = None;
+    let mut expect_output = false;
+
+    for block in &tree.blocks {
+        if let BlockKind::Heading { level } = block.kind {
+            if region.level().is_some_and(|l| l >= level) {
+                region = Region::Other;
+            }
+            if heading_step.is_some_and(|(_, l)| l >= level) {
+                heading_step = None;
+            }
+            expect_output = false;
+            let title = block.text.trim();
+            let lower = title.to_lowercase();
+            let in_rollback = matches!(region, Region::Rollback(_));
+            if let Some(m) = STEP_HEADING.find(title) {
+                let name = title[m.end()..].trim();
+                let name = if name.is_empty() { title } else { name };
+                push_step(&mut procedure, in_rollback, name, block);
+                heading_step = Some((in_rollback, level));
+            } else if PREREQUISITE_HEADINGS.iter().any(|h| lower.contains(h)) {
+                region = Region::Prerequisites(level);
+            } else if ROLLBACK_HEADINGS.iter().any(|h| lower.contains(h)) {
+                region = Region::Rollback(level);
+            } else if rules.section(title, locale).map(|s| s.class) == Some(BlockClass::Step) {
+                region = Region::Steps(level);
+            }
+            continue;
+        }
+
+        if let Some((in_rollback, _)) = heading_step {
+            let steps = steps_of(&mut procedure, in_rollback);
+            if let Some(step) = steps.last_mut() {
+                read_content(block, step, &mut expect_output);
+            }
+            continue;
+        }
+
+        match (region, &block.kind) {
+            (Region::Prerequisites(_), BlockKind::List { .. }) => {
+                for item in &block.children {
+                    procedure.prerequisites.push(Prerequisite {
+                        text: item_title(item),
+                        link: link(item),
+                    });
+                }
+            }
+            (Region::Steps(_), BlockKind::List { ordered: true, .. })
+            | (Region::Rollback(_), BlockKind::List { .. }) => {
+                let in_rollback = matches!(region, Region::Rollback(_));
+                for item in &block.children {
+                    push_step(&mut procedure, in_rollback, &item_title(item), item);
+                    let step = steps_of(&mut procedure, in_rollback)
+                        .last_mut()
+                        .expect("step just pushed");
+                    let mut expect_output = false;
+                    for child in &item.children {
+                        read_content(child, step, &mut expect_output);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    procedure
+}
+
+fn steps_of(procedure: &mut Procedure, rollback: bool) -> &mut Vec<Step> {
+    if rollback {
+        &mut procedure.rollback
+    } else {
+        &mut procedure.steps
+    }
+}
+
+fn push_step(procedure: &mut Procedure, rollback: bool, title: &str, block: &Block) {
+    let prefix = if rollback { "rollback:" } else { "" };
+    let base = format!("{prefix}{title}");
+    let steps = steps_of(procedure, rollback);
+    let repeats = steps
+        .iter()
+        .filter(|s| s.key == base || s.key.starts_with(&format!("{base}#")))
+        .count();
+    let key = if repeats == 0 {
+        base
+    } else {
+        format!("{base}#{}", repeats + 1)
+    };
+    steps.push(Step {
+        number: steps.len() + 1,
+        key,
+        title: title.to_string(),
+        commands: Vec::new(),
+        expected: Vec::new(),
+        done: false,
+        link: link(block),
+    });
+}
+
+/// Collect the commands and expected output in `block` (and what it nests)
+/// into `step`. `expect_output` carries an `Expected output:` announcement
+/// over to the next code block.
+fn read_content(block: &Block, step: &mut Step, expect_output: &mut bool) {
+    match &block.kind {
+        BlockKind::CodeBlock { language } => {
+            let lang = language.as_deref();
+            let code = block.text.trim_end().to_string();
+            let plain = lang.is_none_or(|l| matches!(l, "text" | "txt" | "output" | "plaintext"));
+            if *expect_output {
+                step.expected.push(code);
+            } else if code::intent(lang, &code) == CodeIntent::Shell {
+                step.commands.push(code);
+            } else if plain && !step.commands.is_empty() {
+                step.expected.push(code);
+            }
+            *expect_output = false;
+        }
+        BlockKind::Paragraph | BlockKind::Item { .. } => {
+            let text = block.text.trim();
+            if let Some(caps) = EXPECTED_LINE.captures(text) {
+                step.expected.push(caps[1].trim().to_string());
+            } else if EXPECTS_OUTPUT.is_match(text) {
+                *expect_output = true;
+            }
+        }
+        _ => {}
+    }
+    if !matches!(block.kind, BlockKind::Paragraph) {
+        for child in &block.children {
+            read_content(child, step, expect_output);
+        }
+    }
+}
+
+/// A list item's own text, or that of its first paragraph in a loose list.
+fn item_title(item: &Block) -> String {
+    let own = item.text.trim();
+    if !own.is_empty() {
+        return own.lines().next().unwrap_or(own).to_string();
+    }
+    item.children
+        .iter()
+        .find(|c| c.kind == BlockKind::Paragraph)
+        .map_or(String::new(), |p| {
+            p.text.lines().next().unwrap_or("").trim().to_string()
+        })
+}
+
+fn link(block: &Block) -> SourceRangeLink {
+    SourceRangeLink {
+        block_id: block.id,
+        range: block.range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn procedure(md: &str) -> Procedure {
+        // The Japanese packs include the English ones.
+        extract(
+            &BlockTree::parse(md),
+            &ClassificationRules::default(),
+            Locale::Ja,
+        )
+    }
+
+    #[test]
+    fn ordered_list_steps_with_commands_and_expected_output() {
+        let md = "# Rotate certificates\n\n## Prerequisites\n\n- kubectl access\n- A maintenance window\n\n\
+                  ## Procedure\n\n\
+                  1. Drain the node\n\n   ```sh\n   kubectl drain node-1\n   ```\n\n   \
+                  You should see:\n\n   ```\n   node/node-1 drained\n   ```\n\n\
+                  2. Renew the certificate\n\n   ```bash\n   kubeadm certs renew all\n   ```\n\n   \
+                  Expected: certificates renewed\n\n\
+                  - not a step\n\n\
+                  ## Rollback\n\n- Restore the old certificate\n";
+        let p = procedure(md);
+        let prereqs: Vec<&str> = p.prerequisites.iter().map(|x| x.text.as_str()).collect();
+        assert_eq!(prereqs, vec!["kubectl access", "A maintenance window"]);
+
+        let steps: Vec<(usize, &str, Vec<&str>, Vec<&str>)> = p
+            .steps
+            .iter()
+            .map(|s| {
+                (
+                    s.number,
+                    s.title.as_str(),
+                    s.commands.iter().map(String::as_str).collect(),
+                    s.expected.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (
+                    1,
+                    "Drain the node",
+                    vec!["kubectl drain node-1"],
+                    vec!["node/node-1 drained"]
+                ),
+                (
+                    2,
+                    "Renew the certificate",
+                    vec!["kubeadm certs renew all"],
+                    vec!["certificates renewed"]
+                ),
+            ]
+        );
+        assert_eq!(p.steps[1].link.range.start_line, 22);
+        assert_eq!(p.rollback.len(), 1);
+        assert_eq!(p.rollback[0].key, "rollback:Restore the old certificate");
+    }
+
+    #[test]
+    fn step_headings_and_japanese() {
+        let md = "# デプロイ手順\n\n## 手順1: ビルド\n\n```sh\n$ make\n```\n\n\
+                  ### 補足\n\nnote\n\n```sh\nmake check\n```\n\n\
+                  ## 手順2: 配布\n\n出力例:\n\n```\nok\n```\n\n## 参考\n\n```sh\nls\n```\n";
+        let p = procedure(md);
+        let steps: Vec<(&str, usize, Vec<&str>)> = p
+            .steps
+            .iter()
+            .map(|s| {
+                (
+                    s.title.as_str(),
+                    s.commands.len(),
+                    s.expected.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        // The `###` subsection still belongs to step 1; `## 参考` ends step 2.
+        assert_eq!(steps, vec![("ビルド", 2, vec![]), ("配布", 0, vec!["ok"])]);
+    }
+
+    #[test]
+    fn keys_are_unique_and_progress_marks_steps() {
+        let md = "## Steps\n\n1. Restart\n2. Check\n3. Restart\n";
+        let mut p = procedure(md);
+        let keys: Vec<&str> = p.steps.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(keys, vec!["Restart", "Check", "Restart#2"]);
+
+        let done: BTreeSet<String> = ["Restart".to_string()].into();
+        p.mark_done(&done);
+        assert_eq!(p.next_step().map(|s| s.title.as_str()), Some("Check"));
+        p.step_mut("Check").unwrap().done = true;
+        assert_eq!(p.next_step().map(|s| s.key.as_str()), Some("Restart#2"));
+    }
+}
@@ -0,0 +1,798 @@
+//! Recipe ingredients: quantities, units and scaling.
+//!
+//! Used for documents [`doctype::classify`](super::doctype::classify) calls a
+//! [`Recipe`](crate::model::DocumentType::Recipe):
+//!
+//! * **ingredients** — the items of the lists under an `Ingredients` / `材料`
+//!   heading, each split into a [`Measure`] (`1 1/2 cups`, `200g`, `2-3`,
+//!   `大さじ2`) and the item (`flour`). The measure may lead (`2 cups flour`)
+//!   or trail (`薄力粉 100g`, `Salt: 1 tsp`);
+//! * **servings** — frontmatter `servings:` / `serves:` / `yield:`, or the
+//!   first `Serves 4`, `4 servings`, `4人分` in the text.
+//!
+//! [`Measure::scaled`] re-renders a measure for another number of servings
+//! and, for weights and volumes, in metric or US units. Each measure keeps
+//! the text it was parsed from, so viewers can replace it in place.
+
+use super::frontmatter_list;
+use crate::panel::SourceRangeLink;
+use mdpeek_parser::{Block, BlockKind, BlockTree};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// One amount: `2`, `1.5`, `1/2`, `1 1/2`, `½`, `1½`.
+const AMOUNT: &str = r"(?:\d+\s*[½⅓⅔¼¾⅛⅜⅝⅞]|[½⅓⅔¼¾⅛⅜⅝⅞]|\d+\s+\d+/\d+|\d+/\d+|\d+(?:\.\d+)?)";
+/// A range separator: `2-3`, `2–3`, `2 to 3`, `2〜3`.
+const RANGE_SEPARATOR: &str = r"\s*(?:-|–|~|〜|～|to)\s*";
+
+/// `2 cups flour`, `200g butter`, `2-3 cloves garlic`.
+static LEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^(?P<measure>(?P<amount>{AMOUNT})(?:(?P<sep>{RANGE_SEPARATOR})(?P<max>{AMOUNT}))?(?:(?P<space>\s*)(?P<unit>{units}))?)\.?\s+(?:of\s+)?(?P<item>\S.*)$",
+        units = unit_pattern(false),
+    ))
+    .expect("valid leading measure regex")
+});
+/// `薄力粉 100g`, `砂糖 大さじ2`, `Salt: 1 tsp`.
+static TRAILING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^(?P<item>.*?\S)(?:\s*[:：、,]\s*|\s+)(?P<measure>(?P<first>{first})?(?P<amount>{AMOUNT})(?:(?P<sep>{RANGE_SEPARATOR})(?P<max>{AMOUNT}))?(?:(?P<space>\s*)(?P<unit>{units}))?)$",
+        first = unit_pattern(true),
+        units = unit_pattern(false),
+    ))
+    .expect("valid trailing measure regex")
+});
+/// `Serves 4`, `Servings: 4`, `Makes 12`, `4 servings`, `4人分`, `4人前`.
+static SERVINGS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:serves|servings?|yield|makes)\s*[:：]?\s*(\d+)|\b(\d+)\s*(?:servings|portions|people)\b|(\d+)\s*人(?:分|前)",
+    )
+    .expect("valid servings regex")
+});
+
+/// What a unit measures; only weights and volumes convert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Mass,
+    Volume,
+    Count,
+}
+
+/// Metric or US customary units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Metric,
+    #[serde(alias = "us")]
+    Imperial,
+}
+
+struct UnitDef {
+    /// Canonical symbol, as serialised and as printed after a conversion.
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    /// `(singular, plural)` forms of the English unit words among the
+    /// aliases; only these are inflected when the amount changes.
+    words: &'static [(&'static str, &'static str)],
+    dimension: Dimension,
+    /// Grams or millilitres per unit.
+    base: f64,
+    system: UnitSystem,
+    /// Written before the amount (`大さじ2`).
+    first: bool,
+}
+
+const fn unit(
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    words: &'static [(&'static str, &'static str)],
+    dimension: Dimension,
+    base: f64,
+    system: UnitSystem,
+) -> UnitDef {
+    UnitDef {
+        symbol,
+        aliases,
+        words,
+        dimension,
+        base,
+        system,
+        first: false,
+    }
+}
+
+use Dimension::{Count, Mass, Volume};
+use UnitSystem::{Imperial, Metric};
+
+const UNITS: &[UnitDef] = &[
+    unit(
+        "g",
+        &["grams", "gram", "gr", "g", "グラム"],
+        &[("gram", "grams")],
+        Mass,
+        1.0,
+        Metric,
+    ),
+    unit(
+        "kg",
+        &["kilograms", "kilogram", "kg", "キロ"],
+        &[("kilogram", "kilograms")],
+        Mass,
+        1000.0,
+        Metric,
+    ),
+    unit(
+        "ml",
+        &[
+            "millilitres",
+            "milliliters",
+            "millilitre",
+            "milliliter",
+            "ml",
+            "cc",
+        ],
+        &[("millilitre", "millilitres"), ("milliliter", "milliliters")],
+        Volume,
+        1.0,
+        Metric,
+    ),
+    unit(
+        "l",
+        &["litres", "liters", "litre", "liter", "l", "リットル"],
+        &[("litre", "litres"), ("liter", "liters")],
+        Volume,
+        1000.0,
+        Metric,
+    ),
+    unit(
+        "tsp",
+        &["teaspoons", "teaspoon", "tsp"],
+        &[("teaspoon", "teaspoons")],
+        Volume,
+        4.929,
+        Imperial,
+    ),
+    unit(
+        "tbsp",
+        &["tablespoons", "tablespoon", "tbsp", "tbs"],
+        &[("tablespoon", "tablespoons")],
+        Volume,
+        14.787,
+        Imperial,
+    ),
+    unit(
+        "cup",
+        &["cups", "cup"],
+        &[("cup", "cups")],
+        Volume,
+        236.6,
+        Imperial,
+    ),
+    unit(
+        "fl oz",
+        &["fluid ounces", "fluid ounce", "fl. oz", "fl oz"],
+        &[("fluid ounce", "fluid ounces")],
+        Volume,
+        29.57,
+        Imperial,
+    ),
+    unit(
+        "pint",
+        &["pints", "pint", "pt"],
+        &[("pint", "pints")],
+        Volume,
+        473.2,
+        Imperial,
+    ),
+    unit(
+        "quart",
+        &["quarts", "quart", "qt"],
+        &[("quart", "quarts")],
+        Volume,
+        946.4,
+        Imperial,
+    ),
+    unit(
+        "oz",
+        &["ounces", "ounce", "oz"],
+        &[("ounce", "ounces")],
+        Mass,
+        28.35,
+        Imperial,
+    ),
+    unit(
+        "lb",
+        &["pounds", "pound", "lbs", "lb"],
+        &[("pound", "pounds")],
+        Mass,
+        453.6,
+        Imperial,
+    ),
+    UnitDef {
+        first: true,
+        ..unit("大さじ", &["大さじ"], &[], Volume, 15.0, Metric)
+    },
+    UnitDef {
+        first: true,
+        ..unit("小さじ", &["小さじ"], &[], Volume, 5.0, Metric)
+    },
+    UnitDef {
+        first: true,
+        ..unit("カップ", &["カップ"], &[], Volume, 200.0, Metric)
+    },
+    unit(
+        "clove",
+        &["cloves", "clove"],
+        &[("clove", "cloves")],
+        Count,
+        1.0,
+        Metric,
+    ),
+    unit(
+        "slice",
+        &["slices", "slice"],
+        &[("slice", "slices")],
+        Count,
+        1.0,
+        Metric,
+    ),
+    unit(
+        "can",
+        &["cans", "can"],
+        &[("can", "cans")],
+        Count,
+        1.0,
+        Metric,
+    ),
+    unit(
+        "piece",
+        &["pieces", "piece"],
+        &[("piece", "pieces")],
+        Count,
+        1.0,
+        Metric,
+    ),
+    unit(
+        "pinch",
+        &["pinches", "pinch"],
+        &[("pinch", "pinches")],
+        Count,
+        1.0,
+        Metric,
+    ),
+    unit("個", &["個"], &[], Count, 1.0, Metric),
+    unit("本", &["本"], &[], Count, 1.0, Metric),
+    unit("枚", &["枚"], &[], Count, 1.0, Metric),
+    unit("片", &["片"], &[], Count, 1.0, Metric),
+    unit("束", &["束"], &[], Count, 1.0, Metric),
+    unit("袋", &["袋"], &[], Count, 1.0, Metric),
+];
+
+/// Alternation of the unit aliases written after (or, with `first`, before)
+/// the amount, longest first so `fl oz` wins over `oz`. ASCII aliases must end
+/// at a word boundary (`1 lemon` is not `1 l`).
+fn unit_pattern(first: bool) -> String {
+    let mut aliases: Vec<&str> = UNITS
+        .iter()
+        .filter(|u| u.first == first)
+        .flat_map(|u| u.aliases.iter().copied())
+        .collect();
+    aliases.sort_by_key(|a| std::cmp::Reverse(a.len()));
+    aliases
+        .iter()
+        .map(|a| {
+            let escaped = regex::escape(a);
+            if a.is_ascii() {
+                format!(r"{escaped}\b")
+            } else {
+                escaped
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn unit_def(written: &str) -> Option<&'static UnitDef> {
+    let lower = written.to_lowercase();
+    UNITS.iter().find(|u| u.aliases.contains(&lower.as_str()))
+}
+
+/// The ingredients of a recipe.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Recipe {
+    /// How many servings the quantities are for, when the recipe says.
+    pub servings: Option<f64>,
+    pub ingredients: Vec<Ingredient>,
+}
+
+/// One ingredient line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ingredient {
+    /// The line as written.
+    pub text: String,
+    /// `None` for `Salt to taste`.
+    pub measure: Option<Measure>,
+    /// The ingredient without its measure: `flour`, `薄力粉`.
+    pub item: String,
+    pub link: SourceRangeLink,
+}
+
+impl Recipe {
+    /// The multiplier from the recipe's quantities to `servings` servings.
+    /// Without a known serving count, `servings` is the multiplier itself.
+    pub fn factor(&self, servings: f64) -> f64 {
+        servings / self.servings.unwrap_or(1.0)
+    }
+}
+
+impl Ingredient {
+    /// The line with its measure scaled by `factor` (see [`Measure::scaled`]).
+    pub fn scaled(&self, factor: f64, system: Option<UnitSystem>) -> String {
+        match &self.measure {
+            Some(m) => self.text.replacen(&m.text, &m.scaled(factor, system), 1),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// A parsed quantity and unit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Measure {
+    pub amount: f64,
+    /// Upper end of a range (`2-3`).
+    pub max: Option<f64>,
+    /// Canonical unit symbol (`g`, `cup`, `大さじ`); `None` for plain counts.
+    pub unit: Option<String>,
+    /// The unit as written (`cups`, `Tablespoons`).
+    pub unit_text: Option<String>,
+    /// The measure as written in [`Ingredient::text`], to replace in place.
+    pub text: String,
+    /// Metric or US units, for weights and volumes.
+    pub system: Option<UnitSystem>,
+    /// Written unit first (`大さじ2`).
+    pub unit_first: bool,
+    /// Written with a space between amount and unit (`200 g`, not `200g`).
+    pub spaced: bool,
+    /// Range separator as written (`-`, ` to `, `〜`).
+    pub separator: Option<String>,
+}
+
+impl Measure {
+    /// The measure for `factor` times the servings, converted to `system`
+    /// when it is a weight or volume in the other system.
+    pub fn scaled(&self, factor: f64, system: Option<UnitSystem>) -> String {
+        let def = self.unit.as_deref().and_then(unit_def);
+        let conversion = def.zip(system).and_then(|(from, system)| {
+            (from.dimension != Count && from.system != system)
+                .then(|| (from, convert(from, self.amount * factor, system)))
+        });
+        if let Some((from, to)) = conversion {
+            // Converted: both ends in the target unit, written conventionally.
+            let ratio = factor * from.base / to.base;
+            let amount = format_amount(self.amount * ratio, Some(to));
+            let max = self.max.map(|max| format_amount(max * ratio, Some(to)));
+            let quantity = join_range(&amount, max.as_deref(), self.separator.as_deref());
+            let plural = self.max.unwrap_or(self.amount) * ratio > 1.0;
+            let symbol = if plural && to.symbol == "cup" {
+                "cups"
+            } else {
+                to.symbol
+            };
+            return if to.first {
+                format!("{symbol}{quantity}")
+            } else {
+                format!("{quantity} {symbol}")
+            };
+        }
+
+        let amount = format_amount(self.amount * factor, def);
+        let max = self.max.map(|max| format_amount(max * factor, def));
+        let quantity = join_range(&amount, max.as_deref(), self.separator.as_deref());
+        let Some(written) = &self.unit_text else {
+            return quantity;
+        };
+        // Unscaled, the unit stays exactly as written (`1 cups`).
+        let written = if factor == 1.0 {
+            written.clone()
+        } else {
+            inflect(written, self.max.unwrap_or(self.amount) * factor > 1.0)
+        };
+        if self.unit_first {
+            format!("{written}{quantity}")
+        } else if self.spaced {
+            format!("{quantity} {written}")
+        } else {
+            format!("{quantity}{written}")
+        }
+    }
+}
+
+/// The unit of `system` to show `amount` of `from` in: the largest one that
+/// keeps the number at 1 or more (¼ for cups).
+fn convert(from: &'static UnitDef, amount: f64, system: UnitSystem) -> &'static UnitDef {
+    let base = amount * from.base;
+    let pick = |candidates: &[&str], min: f64| {
+        candidates
+            .iter()
+            .filter_map(|s| UNITS.iter().find(|u| u.symbol == *s))
+            .find(|u| base / u.base >= min)
+            .unwrap_or_else(|| {
+                let last = candidates.last().expect("candidates");
+                UNITS
+                    .iter()
+                    .find(|u| u.symbol == *last)
+                    .expect("known unit")
+            })
+    };
+    match (from.dimension, system) {
+        (Mass, Metric) => pick(&["kg", "g"], 1.0),
+        (Mass, Imperial) => pick(&["lb", "oz"], 1.0),
+        (Volume, Metric) => pick(&["l", "ml"], 1.0),
+        (_, Imperial) => {
+            if base >= 0.25 * 236.6 {
+                pick(&["cup"], 0.25)
+            } else {
+                pick(&["tbsp", "tsp"], 1.0)
+            }
+        }
+        _ => from,
+    }
+}
+
+/// Metric weights and volumes as decimals (`1.5 kg`, `250 g`); US units,
+/// counts and bare numbers as fractions (`1 1/2 cups`, `1 1/2 eggs`).
+fn format_amount(amount: f64, unit: Option<&UnitDef>) -> String {
+    if !unit.is_some_and(|u| u.system == Metric && u.dimension != Count) {
+        return fraction(amount);
+    }
+    if amount >= 10.0 {
+        format!("{}", amount.round())
+    } else {
+        let rounded = (amount * 10.0).round() / 10.0;
+        format!("{rounded}")
+    }
+}
+
+/// `1.5` → `1 1/2`, to the nearest eighth (or third).
+fn fraction(amount: f64) -> String {
+    if amount >= 10.0 {
+        return format!("{}", amount.round());
+    }
+    let whole = amount.trunc();
+    let part = amount - whole;
+    let (numerator, denominator) = [(1, 3), (2, 3)]
+        .into_iter()
+        .find(|(n, d)| (part - f64::from(*n) / f64::from(*d)).abs() < 0.02)
+        .unwrap_or_else(|| {
+            let eighths = (part * 8.0).round() as i32;
+            let gcd = [4, 2, 1]
+                .into_iter()
+                .find(|g| eighths % g == 0)
+                .unwrap_or(1);
+            (eighths / gcd, 8 / gcd)
+        });
+    let (whole, numerator) = if numerator == denominator {
+        (whole + 1.0, 0)
+    } else {
+        (whole, numerator)
+    };
+    match (whole as i64, numerator) {
+        (0, 0) => "0".to_string(),
+        (w, 0) => w.to_string(),
+        (0, n) => format!("{n}/{denominator}"),
+        (w, n) => format!("{w} {n}/{denominator}"),
+    }
+}
+
+fn join_range(amount: &str, max: Option<&str>, separator: Option<&str>) -> String {
+    match max {
+        Some(max) => format!("{amount}{}{max}", separator.unwrap_or("-")),
+        None => amount.to_string(),
+    }
+}
+
+/// `cups` ↔ `cup` for the unit's English words; symbols (`tbsp`, `g`) and
+/// Japanese stay as written.
+fn inflect(written: &str, plural: bool) -> String {
+    let lower = written.to_lowercase();
+    let form = unit_def(written).and_then(|def| {
+        def.words
+            .iter()
+            .find(|(one, many)| lower == *one || lower == *many)
+            .map(|(one, many)| if plural { many } else { one })
+    });
+    form.map_or_else(|| written.to_string(), |form| match_case(written, form))
+}
+
+/// `word` with the first letter capitalised like `like`.
+fn match_case(like: &str, word: &str) -> String {
+    if like.starts_with(char::is_uppercase) {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+    } else {
+        word.to_string()
+    }
+}
+
+/// `1 1/2` → 1.5, `½` → 0.5, `1½` → 1.5.
+fn parse_amount(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let vulgar = |c: char| {
+        Some(match c {
+            '½' => 0.5,
+            '⅓' => 1.0 / 3.0,
+            '⅔' => 2.0 / 3.0,
+            '¼' => 0.25,
+            '¾' => 0.75,
+            '⅛' => 0.125,
+            '⅜' => 0.375,
+            '⅝' => 0.625,
+            '⅞' => 0.875,
+            _ => return None,
+        })
+    };
+    if let Some(last) = text.chars().last()
+        && let Some(part) = vulgar(last)
+    {
+        let whole = text[..text.len() - last.len_utf8()].trim();
+        let whole = if whole.is_empty() {
+            0.0
+        } else {
+            whole.parse::<f64>().ok()?
+        };
+        return Some(whole + part);
+    }
+    let (whole, part) = match text.split_once(char::is_whitespace) {
+        Some((whole, part)) => (whole.parse::<f64>().ok()?, part.trim()),
+        None if text.contains('/') => (0.0, text),
+        None => return text.parse().ok(),
+    };
+    let (n, d) = part.split_once('/')?;
+    let (n, d) = (n.parse::<f64>().ok()?, d.parse::<f64>().ok()?);
+    (d != 0.0).then(|| whole + n / d)
+}
+
+/// Split an ingredient line into its measure and item.
+fn parse_ingredient(text: &str) -> (Option<Measure>, String) {
+    let Some(caps) = LEADING.captures(text).or_else(|| TRAILING.captures(text)) else {
+        return (None, text.to_string());
+    };
+    let Some(amount) = parse_amount(&caps["amount"]) else {
+        return (None, text.to_string());
+    };
+    let first = caps.name("first").map(|m| m.as_str().to_string());
+    let after = caps.name("unit").map(|m| m.as_str().to_string());
+    let unit_text = first.clone().or(after);
+    let def = unit_text.as_deref().and_then(unit_def);
+    let measure = Measure {
+        amount,
+        max: caps.name("max").and_then(|m| parse_amount(m.as_str())),
+        unit: def.map(|d| d.symbol.to_string()),
+        unit_text,
+        text: caps["measure"].to_string(),
+        system: def.filter(|d| d.dimension != Count).map(|d| d.system),
+        unit_first: first.is_some(),
+        spaced: caps.name("space").is_some_and(|m| !m.as_str().is_empty()),
+        separator: caps.name("sep").map(|m| m.as_str().to_string()),
+    };
+    (Some(measure), caps["item"].trim().to_string())
+}
+
+/// Extract the ingredients (and servings) of the recipe parsed into `tree`.
+pub fn extract(tree: &BlockTree) -> Recipe {
+    let mut recipe = Recipe {
+        servings: tree
+            .frontmatter()
+            .and_then(|fm| {
+                ["servings", "serves", "yield", "portions"]
+                    .iter()
+                    .find_map(|key| frontmatter_list(fm, key).into_iter().next())
+            })
+            .and_then(|value| leading_number(&value)),
+        ingredients: Vec::new(),
+    };
+    // The level of the `Ingredients` heading we are under.
+    let mut section: Option<u8> = None;
+    for block in &tree.blocks {
+        match &block.kind {
+            BlockKind::Heading { level } => {
+                if section.is_some_and(|l| l >= *level) {
+                    section = None;
+                }
+                let lower = block.text.to_lowercase();
+                if lower.contains("ingredient") || lower.contains("材料") {
+                    section = Some(*level);
+                }
+            }
+            BlockKind::List { .. } if section.is_some() => {
+                collect_items(block, &mut recipe.ingredients);
+            }
+            _ => {}
+        }
+        if recipe.servings.is_none()
+            && matches!(block.kind, BlockKind::Heading { .. } | BlockKind::Paragraph)
+            && let Some(caps) = SERVINGS.captures(&block.text)
+        {
+            let number = caps.iter().skip(1).flatten().next();
+            recipe.servings = number.and_then(|m| m.as_str().parse().ok());
+        }
+    }
+    recipe
+}
+
+fn collect_items(list: &Block, ingredients: &mut Vec<Ingredient>) {
+    for item in &list.children {
+        let own = item.text.trim();
+        let text = if own.is_empty() {
+            item.children
+                .iter()
+                .find(|c| c.kind == BlockKind::Paragraph)
+                .map_or("", |p| p.text.trim())
+        } else {
+            own
+        };
+        let text = text.lines().next().unwrap_or("").trim().to_string();
+        if !text.is_empty() {
+            let (measure, name) = parse_ingredient(&text);
+            ingredients.push(Ingredient {
+                text,
+                measure,
+                item: name,
+                link: SourceRangeLink {
+                    block_id: item.id,
+                    range: item.range,
+                },
+            });
+        }
+        for nested in item
+            .children
+            .iter()
+            .filter(|c| matches!(c.kind, BlockKind::List { .. }))
+        {
+            collect_items(nested, ingredients);
+        }
+    }
+}
+
+fn leading_number(value: &str) -> Option<f64> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    value[..end].parse().ok().filter(|n| *n > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line, amount, max, unit, measure as written, item.
+    type Case<'a> = (&'a str, f64, Option<f64>, Option<&'a str>, &'a str, &'a str);
+
+    fn measure(text: &str) -> (Option<Measure>, String) {
+        parse_ingredient(text)
+    }
+
+    #[test]
+    fn parses_leading_and_trailing_measures() {
+        let cases: Vec<Case> = vec![
+            ("2 cups flour", 2.0, None, Some("cup"), "2 cups", "flour"),
+            (
+                "1 1/2 tsp baking soda",
+                1.5,
+                None,
+                Some("tsp"),
+                "1 1/2 tsp",
+                "baking soda",
+            ),
+            ("½ cup milk", 0.5, None, Some("cup"), "½ cup", "milk"),
+            ("200g butter", 200.0, None, Some("g"), "200g", "butter"),
+            (
+                "2-3 cloves garlic",
+                2.0,
+                Some(3.0),
+                Some("clove"),
+                "2-3 cloves",
+                "garlic",
+            ),
+            ("3 large eggs", 3.0, None, None, "3", "large eggs"),
+            ("1 lemon", 1.0, None, None, "1", "lemon"),
+            (
+                "8 oz of cream cheese",
+                8.0,
+                None,
+                Some("oz"),
+                "8 oz",
+                "cream cheese",
+            ),
+            ("薄力粉 100g", 100.0, None, Some("g"), "100g", "薄力粉"),
+            (
+                "砂糖 大さじ1.5",
+                1.5,
+                None,
+                Some("大さじ"),
+                "大さじ1.5",
+                "砂糖",
+            ),
+            ("卵 2個", 2.0, None, Some("個"), "2個", "卵"),
+            ("Salt: 1 tsp", 1.0, None, Some("tsp"), "1 tsp", "Salt"),
+        ];
+        for (text, amount, max, unit, written, item) in cases {
+            let (m, name) = measure(text);
+            let m = m.unwrap_or_else(|| panic!("no measure in {text:?}"));
+            assert_eq!(
+                (
+                    m.amount,
+                    m.max,
+                    m.unit.as_deref(),
+                    m.text.as_str(),
+                    name.as_str()
+                ),
+                (amount, max, unit, written, item),
+                "{text}"
+            );
+        }
+        assert_eq!(
+            measure("Salt to taste"),
+            (None, "Salt to taste".to_string())
+        );
+        assert_eq!(measure("塩 少々").0, None);
+    }
+
+    #[test]
+    fn scales_in_the_written_units() {
+        let scaled = |text: &str, factor: f64| measure(text).0.unwrap().scaled(factor, None);
+        assert_eq!(scaled("1 cup sugar", 2.0), "2 cups");
+        assert_eq!(scaled("2 cups flour", 0.5), "1 cup");
+        assert_eq!(scaled("1 1/2 tsp salt", 1.5), "2 1/4 tsp");
+        assert_eq!(scaled("3 eggs", 0.5), "1 1/2");
+        assert_eq!(scaled("2-3 cloves garlic", 2.0), "4-6 cloves");
+        assert_eq!(scaled("200g butter", 1.5), "300g");
+        assert_eq!(scaled("砂糖 大さじ1", 1.5), "大さじ1.5");
+        assert_eq!(scaled("1 Tablespoon oil", 3.0), "3 Tablespoons");
+        assert_eq!(scaled("2 tbsp oil", 2.0), "4 tbsp");
+        assert_eq!(scaled("1 tbsp oil", 0.5), "1/2 tbsp");
+        assert_eq!(scaled("1 pinch salt", 2.0), "2 pinches");
+        assert_eq!(scaled("2 pinches salt", 0.5), "1 pinch");
+        // Unscaled, the measure is left as written.
+        assert_eq!(scaled("1 cups milk", 1.0), "1 cups");
+        assert_eq!(scaled("1 pinch salt", 1.0), "1 pinch");
+    }
+
+    #[test]
+    fn converts_between_metric_and_us_units() {
+        let converted = |text: &str, system| measure(text).0.unwrap().scaled(1.0, Some(system));
+        assert_eq!(converted("2 cups flour", Metric), "473 ml");
+        assert_eq!(converted("1 tsp oil", Metric), "4.9 ml");
+        assert_eq!(converted("1 lb beef", Metric), "454 g");
+        assert_eq!(converted("2.5 lb beef", Metric), "1.1 kg");
+        assert_eq!(converted("250 g butter", Imperial), "8 7/8 oz");
+        assert_eq!(converted("500 ml stock", Imperial), "2 1/8 cups");
+        assert_eq!(converted("砂糖 大さじ2", Imperial), "2 tbsp");
+        assert_eq!(converted("10 ml vanilla", Imperial), "2 tsp");
+        // Same system, or counts: unchanged.
+        assert_eq!(converted("200g butter", Metric), "200g");
+        assert_eq!(converted("2 cloves garlic", Imperial), "2 cloves");
+    }
+
+    #[test]
+    fn extracts_ingredients_and_servings() {
+        let md = "# Pancakes\n\nServes 4.\n\n## Ingredients\n\n- 2 cups flour\n- Salt to taste\n\
+                  \n### Topping\n\n- 100 ml maple syrup\n\n## Instructions\n\n1. Mix 2 cups water\n";
+        let recipe = extract(&BlockTree::parse(md));
+        assert_eq!(recipe.servings, Some(4.0));
+        let items: Vec<&str> = recipe.ingredients.iter().map(|i| i.item.as_str()).collect();
+        assert_eq!(items, vec!["flour", "Salt to taste", "maple syrup"]);
+        assert_eq!(recipe.ingredients[2].link.range.start_line, 12);
+        let factor = recipe.factor(6.0);
+        assert_eq!(recipe.ingredients[0].scaled(factor, None), "3 cups flour");
+        assert_eq!(recipe.ingredients[1].scaled(factor, None), "Salt to taste");
+
+        let md = "---\ntype: recipe\nservings: 2 people\n---\n# 肉じゃが（4人分）\n\n## 材料\n\n- じゃがいも 3個\n";
+        let recipe = extract(&BlockTree::parse(md));
+        assert_eq!(recipe.servings, Some(2.0));
+        assert_eq!(recipe.ingredients[0].measure.as_ref().unwrap().amount, 3.0);
+    }
+}
@@ -0,0 +1,700 @@
+//! Syntax checks for config snippets (JSON / YAML / TOML code blocks).
+//!
+//! Code blocks fenced as `json`, `yaml` / `yml` or `toml` are parsed and the
+//! first syntax error of each is reported, with its position
+//! mapped from the snippet back to the document: the block's lines are re-read
+//! from the source (the tree's text is trimmed and has lost container prefixes
+//! such as `> ` or list indentation), so a `SyntaxError` at snippet line 2 lands
+//! on the right document line and column.
+//!
+//! JSON and TOML go through `serde_json` and `toml`. YAML has no parser in the
+//! dependency tree, so [`yaml`] is a structural checker for the mistakes that
+//! break real snippets: tab indentation, a key nested under a scalar, a
+//! dedent to no enclosing level, a line that is neither key nor item, and
+//! unclosed quotes and brackets.
+//!
+//! `jsonc` / `json5` blocks are skipped (they allow comments and trailing
+//! commas), and so are blocks whose format [`code::intent`] only sniffs from
+//! their content: an unlabelled `[build]` table passes for a JSON array.
+
+use super::code::{self, CodeIntent};
+use crate::panel::SourceRangeLink;
+use mdpeek_parser::{Block, BlockKind, BlockTree, SourceRange};
+use serde::Serialize;
+use std::ops::Range;
+
+/// The first syntax error of a config snippet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnippetError {
+    /// `json`, `yaml` or `toml`.
+    pub format: &'static str,
+    pub message: String,
+    /// Where the error is, in document coordinates.
+    pub range: SourceRange,
+    /// The code block.
+    pub link: SourceRangeLink,
+}
+
+/// A syntax error within a snippet: the message and the offending bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+/// Check every JSON, YAML and TOML code block of `tree`, parsed from `source`.
+pub fn check(source: &str, tree: &BlockTree) -> Vec<SnippetError> {
+    let lines = line_starts(source);
+    tree.iter()
+        .filter_map(|block| {
+            let BlockKind::CodeBlock { language } = &block.kind else {
+                return None;
+            };
+            let format = format(language.as_deref())?;
+            let (content, offsets) = content(source, &lines, block);
+            let err = validate(format, &content).err()?;
+            let doc = |at: usize| {
+                // The content line holding `at`, and its offset in `source`.
+                let i = offsets
+                    .partition_point(|&(start, _)| start <= at)
+                    .saturating_sub(1);
+                offsets.get(i).map_or(0, |&(start, doc)| doc + at - start)
+            };
+            Some(SnippetError {
+                format,
+                message: err.message,
+                range: tree
+                    .line_index
+                    .source_range(doc(err.span.start)..doc(err.span.end)),
+                link: SourceRangeLink {
+                    block_id: block.id,
+                    range: block.range,
+                },
+            })
+        })
+        .collect()
+}
+
+/// The config format a fence language names: `json`, `yaml` or `toml`.
+pub fn format(language: Option<&str>) -> Option<&'static str> {
+    let language = language?.to_lowercase();
+    match code::from_lang(&language)? {
+        CodeIntent::Json if language == "json" => Some("json"),
+        CodeIntent::Yaml => Some("yaml"),
+        CodeIntent::Toml => Some("toml"),
+        _ => None,
+    }
+}
+
+/// Parse `text` as `format` (one of [`format`]'s names).
+pub fn validate(format: &str, text: &str) -> Result<(), SyntaxError> {
+    match format {
+        "json" => json(text),
+        "yaml" => yaml(text),
+        _ => toml(text),
+    }
+}
+
+fn json(text: &str) -> Result<(), SyntaxError> {
+    let Err(e) = serde_json::from_str::<serde::de::IgnoredAny>(text) else {
+        return Ok(());
+    };
+    let message = e.to_string();
+    let message = match message.rsplit_once(" at line ") {
+        Some((message, _)) => message.to_string(),
+        None => message,
+    };
+    let at = byte_at(text, e.line(), e.column());
+    Err(SyntaxError {
+        message,
+        span: at..next_char(text, at),
+    })
+}
+
+fn toml(text: &str) -> Result<(), SyntaxError> {
+    let Err(e) = text.parse::<toml::Table>() else {
+        return Ok(());
+    };
+    let span = e.span().unwrap_or(0..0);
+    let span = if span.is_empty() {
+        span.start..next_char(text, span.start)
+    } else {
+        span
+    };
+    Err(SyntaxError {
+        message: e.message().trim().to_string(),
+        span,
+    })
+}
+
+/// What a YAML block-collection level holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    /// `empty_key`: the last key had no value, so a sequence may follow at
+    /// the key's own indent (`key:\n- a`).
+    Mapping {
+        empty_key: bool,
+    },
+    Sequence,
+}
+
+/// What the previous content line leaves open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Open {
+    Nothing,
+    /// `key:` or `-` with nothing after it: a deeper block may follow.
+    Child,
+    /// `key: value` or `- value` at this indent: deeper lines continue the
+    /// scalar, so they cannot be keys.
+    Scalar(usize),
+    /// `key: |` at this indent: deeper lines are literal text.
+    BlockScalar(usize),
+}
+
+/// Check `text` as YAML block structure (see the module docs).
+pub fn yaml(text: &str) -> Result<(), SyntaxError> {
+    let err = |message: &str, at: usize| SyntaxError {
+        message: message.to_string(),
+        span: at..next_char(text, at),
+    };
+    // Open block collections, innermost last: indent and kind.
+    let mut levels: Vec<(usize, Level)> = Vec::new();
+    let mut open = Open::Nothing;
+    let mut flow = Flow::default();
+    // A document that is one plain scalar has no structure to check; one with
+    // explicit `? key` entries is beyond this checker.
+    let mut unchecked = false;
+
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = start;
+        start += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let content = &line[indent..];
+
+        if let Open::BlockScalar(parent) = open {
+            if content.is_empty() || indent > parent {
+                continue;
+            }
+            open = Open::Nothing;
+        }
+        if flow.is_open() {
+            flow.scan(line, line_start).map_err(|(m, at)| err(m, at))?;
+            if !flow.is_open() {
+                open = Open::Scalar(levels.last().map_or(0, |l| l.0));
+            }
+            continue;
+        }
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if indent == 0
+            && (line.starts_with("---") || line.starts_with("...") || line.starts_with('%'))
+        {
+            levels.clear();
+            open = Open::Nothing;
+            unchecked = false;
+            continue;
+        }
+        if content.starts_with('\t') {
+            return Err(err(
+                "found a tab character where an indentation space is expected",
+                line_start + indent,
+            ));
+        }
+        if content.starts_with("? ") || content.starts_with(": ") {
+            unchecked = true;
+        }
+        if unchecked {
+            continue;
+        }
+        // An anchor or tag on a line of its own belongs to the next line.
+        if strip_properties(strip_comment(content).trim()).is_empty() {
+            continue;
+        }
+
+        let item = content == "-" || content.starts_with("- ");
+        // The part after any `- ` indicator.
+        let body_at = if item {
+            indent + 1 + (content.len() - 1 - content[1..].trim_start().len())
+        } else {
+            indent
+        };
+        let body = &line[body_at..];
+        let colon = key_colon(body).map(|c| body_at + c);
+
+        // A deeper line under a scalar continues it — unless it is a key.
+        if let Open::Scalar(parent) = open
+            && indent > parent
+        {
+            if let Some(colon) = colon.filter(|_| !item) {
+                return Err(err(
+                    "mapping values are not allowed in this context",
+                    line_start + colon,
+                ));
+            }
+            continue;
+        }
+
+        match levels.last().copied() {
+            None => {
+                if !item && colon.is_none() {
+                    // A flow collection or quoted scalar is checked below.
+                    if !content.starts_with(['[', '{', '"', '\'']) {
+                        unchecked = true;
+                        continue;
+                    }
+                } else {
+                    levels.push((
+                        indent,
+                        if item {
+                            Level::Sequence
+                        } else {
+                            Level::Mapping { empty_key: false }
+                        },
+                    ));
+                }
+            }
+            Some((top, _)) if indent > top => {
+                if open != Open::Child {
+                    return Err(err(
+                        "bad indentation of a mapping entry",
+                        line_start + indent,
+                    ));
+                }
+                // A deeper plain line is the value of `key:` itself.
+                if !item && colon.is_none() {
+                    open = Open::Scalar(top);
+                    continue;
+                }
+                // The empty key got its value: this nested block.
+                if let Some((_, Level::Mapping { empty_key })) = levels.last_mut() {
+                    *empty_key = false;
+                }
+                levels.push((
+                    indent,
+                    if item {
+                        Level::Sequence
+                    } else {
+                        Level::Mapping { empty_key: false }
+                    },
+                ));
+            }
+            Some(_) => {
+                while levels.last().is_some_and(|&(i, _)| i > indent) {
+                    levels.pop();
+                }
+                // A key ends the sequence that was the value of the key
+                // before it (`key:\n- a\nnext: 1`).
+                if !item
+                    && let [.., (outer, Level::Mapping { .. }), (inner, Level::Sequence)] =
+                        levels[..]
+                    && outer == inner
+                {
+                    levels.pop();
+                }
+                let Some(&(top, level)) = levels.last() else {
+                    return Err(err(
+                        "bad indentation of a mapping entry",
+                        line_start + indent,
+                    ));
+                };
+                if top != indent {
+                    return Err(err(
+                        "bad indentation: the line matches no enclosing level",
+                        line_start + indent,
+                    ));
+                }
+                match level {
+                    Level::Mapping { empty_key: true } if item => {
+                        // `key:\n- a`: the sequence is the key's value.
+                        levels.push((indent, Level::Sequence));
+                    }
+                    Level::Mapping { .. } if item => {
+                        return Err(err(
+                            "did not find expected key: a sequence item inside a mapping",
+                            line_start + indent,
+                        ));
+                    }
+                    Level::Mapping { .. } if !item && colon.is_none() => {
+                        return Err(err(
+                            "could not find expected ':'",
+                            line_start + line.len().max(indent + 1) - 1,
+                        ));
+                    }
+                    Level::Sequence if !item => {
+                        return Err(err(
+                            "did not find expected '-' indicator",
+                            line_start + indent,
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // What this line leaves open.
+        if item && colon.is_some() {
+            // `- key: value` starts a mapping nested in the item.
+            levels.push((body_at, Level::Mapping { empty_key: false }));
+        }
+        let value_at = colon.map_or(body_at, |c| c + 1);
+        let value = strip_comment(&line[value_at..]);
+        let value_start = value_at + (value.len() - value.trim_start().len());
+        let value = value.trim();
+        let value = strip_properties(value);
+        if colon.is_some()
+            && let Some((_, Level::Mapping { empty_key })) = levels.last_mut()
+        {
+            *empty_key = value.is_empty();
+        }
+        open = if value.is_empty() {
+            Open::Child
+        } else if value.starts_with(['|', '>']) {
+            Open::BlockScalar(if colon.is_some() { body_at } else { indent })
+        } else {
+            flow.scan(&line[value_start..], line_start + value_start)
+                .map_err(|(m, at)| err(m, at))?;
+            if flow.is_open() {
+                Open::Nothing
+            } else {
+                Open::Scalar(if colon.is_some() { body_at } else { indent })
+            }
+        };
+    }
+    if let Some((message, at)) = flow.unclosed() {
+        return Err(err(message, at));
+    }
+    Ok(())
+}
+
+/// Open quotes and flow brackets (`[`, `{`) of a value, across lines.
+#[derive(Debug, Default)]
+struct Flow {
+    /// Open brackets and their offsets.
+    brackets: Vec<(char, usize)>,
+    /// An open quote and its offset.
+    quote: Option<(char, usize)>,
+}
+
+impl Flow {
+    fn is_open(&self) -> bool {
+        !self.brackets.is_empty() || self.quote.is_some()
+    }
+
+    /// Track the quotes and brackets of `text`, found at `offset`. Outside a
+    /// flow collection only a leading quote or bracket opens one: `it's` and
+    /// `a]` are plain scalars.
+    fn scan(&mut self, text: &str, offset: usize) -> Result<(), (&'static str, usize)> {
+        let mut chars = text.char_indices().peekable();
+        let mut token_start = self.brackets.is_empty() && self.quote.is_none();
+        while let Some((i, c)) = chars.next() {
+            let at = offset + i;
+            if let Some((q, _)) = self.quote {
+                match c {
+                    '\\' if q == '"' => {
+                        chars.next();
+                    }
+                    '\'' if q == '\'' && chars.peek().is_some_and(|&(_, n)| n == '\'') => {
+                        chars.next();
+                    }
+                    c if c == q => {
+                        self.quote = None;
+                        token_start = false;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            let in_flow = !self.brackets.is_empty();
+            match c {
+                '"' | '\'' if token_start => self.quote = Some((c, at)),
+                '[' | '{' if token_start || in_flow => {
+                    self.brackets.push((c, at));
+                    token_start = true;
+                    continue;
+                }
+                ']' | '}' if in_flow => {
+                    let (open, _) = self.brackets.pop().expect("in a flow collection");
+                    if (open, c) != ('[', ']') && (open, c) != ('{', '}') {
+                        return Err(if c == ']' {
+                            ("found an unexpected ']' inside a '{' mapping", at)
+                        } else {
+                            ("found an unexpected '}' inside a '[' sequence", at)
+                        });
+                    }
+                }
+                '#' if text[..i].ends_with([' ', '\t']) || i == 0 => break,
+                _ => {}
+            }
+            // In a flow collection, a new token starts after `,` `:` or space.
+            token_start = in_flow && matches!(c, ',' | ':' | ' ');
+            if !self.is_open() {
+                // The value ended; anything after a closed collection or
+                // quote other than a comment is the caller's concern.
+                token_start = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn unclosed(&self) -> Option<(&'static str, usize)> {
+        if let Some((_, at)) = self.quote {
+            return Some(("unterminated quoted scalar", at));
+        }
+        self.brackets.last().map(|&(c, at)| {
+            if c == '[' {
+                ("did not find the ']' closing this sequence", at)
+            } else {
+                ("did not find the '}' closing this mapping", at)
+            }
+        })
+    }
+}
+
+/// The offset of the `:` that makes `text` a `key: value` line: the first
+/// colon followed by a space or the line end, outside quotes and brackets.
+fn key_colon(text: &str) -> Option<usize> {
+    let text = strip_comment(text);
+    let bytes = text.as_bytes();
+    let mut quote = None;
+    let mut depth = 0usize;
+    for (i, &b) in bytes.iter().enumerate() {
+        match (quote, b) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') if i == 0 => quote = Some(b),
+            (None, b'[' | b'{') if i == 0 || depth > 0 => depth += 1,
+            (None, b']' | b'}') if depth > 0 => depth -= 1,
+            (None, b':') if depth == 0 && bytes.get(i + 1).is_none_or(|&n| n == b' ') => {
+                return Some(i);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `text` without a trailing `# comment`.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if text[..i].trim_end().is_empty() => quote = Some(c),
+            (None, '#') if i == 0 || text[..i].ends_with([' ', '\t']) => return &text[..i],
+            _ => {}
+        }
+    }
+    text
+}
+
+/// A value without its leading `&anchor` and `!tag` properties.
+fn strip_properties(mut value: &str) -> &str {
+    while value.starts_with(['&', '!']) {
+        value = value
+            .split_once(' ')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+    value
+}
+
+/// The byte offset in `text` of 1-based `line` and `column`, clamped.
+fn byte_at(text: &str, line: usize, column: usize) -> usize {
+    let start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let line_len = text[start..].find('\n').unwrap_or(text.len() - start);
+    start + column.saturating_sub(1).min(line_len)
+}
+
+/// The offset after the character at `at` (or `at` itself at the end).
+fn next_char(text: &str, at: usize) -> usize {
+    text[at.min(text.len())..]
+        .chars()
+        .next()
+        .map_or(at, |c| at + c.len_utf8())
+}
+
+/// The byte offset of every line of `source`.
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// The code of `block` as written, with container prefixes removed, and for
+/// each of its lines `(offset in the content, offset in source)`.
+fn content(source: &str, lines: &[usize], block: &Block) -> (String, Vec<(usize, usize)>) {
+    let r = block.range;
+    let line = |n: u32| -> &str {
+        let Some(&start) = lines.get(n as usize - 1) else {
+            return "";
+        };
+        let end = lines.get(n as usize).map_or(source.len(), |&e| e);
+        source[start..end].trim_end_matches(['\n', '\r'])
+    };
+    // The container prefix (`> `, list indentation) is everything before the
+    // block's start column.
+    let prefix = r.start_column as usize - 1;
+    let first = line(r.start_line);
+    let fence = first
+        .get(prefix..)
+        .map(str::trim_start)
+        .filter(|f| f.starts_with("```") || f.starts_with("~~~"))
+        .map(|f| &f[..3]);
+    let mut first_line = r.start_line;
+    let mut last_line = if r.end_column == 1 {
+        r.end_line - 1
+    } else {
+        r.end_line
+    };
+    if let Some(fence) = fence {
+        first_line += 1;
+        let closing = line(last_line);
+        if last_line >= first_line
+            && closing
+                .get(prefix.min(closing.len())..)
+                .is_some_and(|l| l.trim_start().starts_with(fence))
+        {
+            last_line -= 1;
+        }
+    }
+
+    let mut content = String::new();
+    let mut offsets = Vec::new();
+    for n in first_line..=last_line {
+        let text = line(n);
+        // Strip the prefix only where it is there (lazy continuation lines of
+        // a block quote may lack it).
+        let cut = (0..=prefix.min(text.len()))
+            .rev()
+            .find(|&c| text.is_char_boundary(c))
+            .unwrap_or(0);
+        let start = lines[n as usize - 1];
+        offsets.push((content.len(), start + cut));
+        content.push_str(&text[cut..]);
+        content.push('\n');
+    }
+    (content, offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_md(md: &str) -> Vec<(String, u32, u32)> {
+        check(md, &BlockTree::parse(md))
+            .into_iter()
+            .map(|e| (e.message, e.range.start_line, e.range.start_column))
+            .collect()
+    }
+
+    fn yaml_error(text: &str) -> Option<(String, usize)> {
+        yaml(text).err().map(|e| (e.message, e.span.start))
+    }
+
+    #[test]
+    fn json_and_toml_errors_map_to_document_positions() {
+        let md = "# Config\n\n```json\n{\n  \"port\": 8080,\n}\n```\n\n\
+                  - In a list:\n\n  ```toml\n  [server]\n  port = \n  ```\n\n\
+                  > ```json\n> {\"ok\": true}\n> ```\n";
+        assert_eq!(
+            check_md(md),
+            vec![
+                ("key must be a string".to_string(), 6, 1),
+                (
+                    "string values must be quoted, expected literal string".to_string(),
+                    13,
+                    10
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_config_blocks_are_checked() {
+        let md = "```jsonc\n{ \"a\": 1, // note\n}\n```\n\n```rust\nfn x( {\n```\n\n```yaml\na: [1, 2\n```\n\n\
+                  ```\n[build]\ntarget = wasm\n```\n";
+        assert_eq!(
+            check_md(md),
+            vec![(
+                "did not find the ']' closing this sequence".to_string(),
+                11,
+                4
+            )]
+        );
+    }
+
+    #[test]
+    fn valid_yaml_passes() {
+        let docs = [
+            "name: mdpeek\nversion: 1\n",
+            "server:\n  host: example.com\n  ports:\n    - 80\n    - 443\n",
+            "items:\n- a\n- b\nnext: 1\n",
+            "- name: a\n  value: 1\n- name: b\n  tags: [x, y]\n",
+            "script: |\n  echo hi\n    indented: fine\nafter: true\n",
+            "desc: a long\n  plain scalar\n# comment\nurl: http://x:80 # trailing\n",
+            "quoted: \"a: b # not a comment\"\nit: it's fine\nflow: {a: 1,\n  b: [2, 3]}\n",
+            "base: &base\n  a: 1\nderived:\n  <<: *base\n---\nsecond: doc\n",
+            "just a plain scalar\nspanning lines\n",
+            "steps:\n- uses: checkout\n- name: build\n  with:\n    a: 1\n- run: make\n",
+            "on:\n  push:\n    branches: [main]\njobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n      - run: cargo test\n",
+        ];
+        for doc in docs {
+            assert_eq!(yaml_error(doc), None, "{doc}");
+        }
+    }
+
+    #[test]
+    fn yaml_structure_errors() {
+        let cases = [
+            (
+                "a:\n\tb: 1\n",
+                "found a tab character where an indentation space is expected",
+                3,
+            ),
+            (
+                "a: 1\n  b: 2\n",
+                "mapping values are not allowed in this context",
+                8,
+            ),
+            (
+                "a:\n    b: 1\n  c: 2\n",
+                "bad indentation: the line matches no enclosing level",
+                14,
+            ),
+            ("a: 1\nb\nc: 2\n", "could not find expected ':'", 5),
+            ("- a\nb: 1\n", "did not find expected '-' indicator", 4),
+            (
+                "a: 1\n- b\n",
+                "did not find expected key: a sequence item inside a mapping",
+                5,
+            ),
+            (
+                "a:\n  b:\n- c\n",
+                "did not find expected key: a sequence item inside a mapping",
+                8,
+            ),
+            (
+                "a: 1\n b: 2\n",
+                "mapping values are not allowed in this context",
+                7,
+            ),
+            ("a: \"open\nb: 1\n", "unterminated quoted scalar", 3),
+            (
+                "a: [1, 2}\n",
+                "found an unexpected '}' inside a '[' sequence",
+                8,
+            ),
+        ];
+        for (doc, message, at) in cases {
+            assert_eq!(yaml_error(doc), Some((message.to_string(), at)), "{doc:?}");
+        }
+    }
+}
@@ -14,6 +14,16 @@ pub struct TableInfo {
     /// Index of a column that looks like a status/state column, if any.
     pub status_column: Option<usize>,
     pub row_count: usize,
+    /// Body rows, so later layers can lay rows out by status.
+    pub rows: Vec<TableRow>,
+}
+
+/// One body row of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRow {
+    pub cells: Vec<String>,
+    /// 1-based source line of the row.
+    pub line: u32,
 }
 
 /// Analyse a table block by re-reading its source lines. Returns `None` if the
@@ -30,12 +40,13 @@ pub fn analyze(source: &str, block: &Block) -> Option<TableInfo> {
     let count = end.saturating_sub(start);
     let mut lines = source
         .lines()
+        .enumerate()
         .skip(start)
         .take(count)
-        .map(str::trim)
-        .filter(|l| l.contains('|') && !l.is_empty());
+        .map(|(i, l)| (i as u32 + 1, l.trim()))
+        .filter(|(_, l)| l.contains('|') && !l.is_empty());
 
-    let header = lines.next()?;
+    let (_, header) = lines.next()?;
     let columns = split_row(header);
     if columns.is_empty() {
         return None;
@@ -43,10 +54,16 @@ pub fn analyze(source: &str, block: &Block) -> Option<TableInfo> {
 
     // The line after the header is the `|---|---|` delimiter; the rest are rows.
     let mut remaining = lines.peekable();
-    if remaining.peek().is_some_and(|l| is_delimiter_row(l)) {
+    if remaining.peek().is_some_and(|(_, l)| is_delimiter_row(l)) {
         remaining.next();
     }
-    let row_count = remaining.count();
+    let rows: Vec<TableRow> = remaining
+        .map(|(line, l)| TableRow {
+            cells: split_row(l),
+            line,
+        })
+        .collect();
+    let row_count = rows.len();
 
     let status_column = columns.iter().position(|c| is_status_label(c));
 
@@ -54,6 +71,7 @@ pub fn analyze(source: &str, block: &Block) -> Option<TableInfo> {
         columns,
         status_column,
         row_count,
+        rows,
     })
 }
 
@@ -111,6 +129,8 @@ mod tests {
         assert_eq!(info.columns, vec!["Name", "Age"]);
         assert_eq!(info.row_count, 2);
         assert_eq!(info.status_column, None);
+        assert_eq!(info.rows[1].cells, vec!["Bob", "25"]);
+        assert_eq!(info.rows[1].line, 4);
     }
 
     #[test]
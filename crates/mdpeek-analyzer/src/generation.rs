@@ -3,7 +3,7 @@
 //!
 //! Layer 2 is rules-only, but the policy type lives here as the single source of
 //! truth: the `mdpeek` binary loads it from `config.toml` at startup and Layer 3's
-//! generator (`RulesGenerator` / `ClaudeGenerator`) consults [`GenerationConfig`]
+//! generator (`RulesGenerator` / `LlmGenerator`) consults [`GenerationConfig`]
 //! per node via [`GenerationConfig::should_use_llm`].
 
 use serde::{Deserialize, Serialize};
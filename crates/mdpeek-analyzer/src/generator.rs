@@ -0,0 +1,42 @@
+//! Layer 3 generator — refines the rules-stage [`DocumentModel`].
+//!
+//! The rules analyser always runs first and always produces a complete model.
+//! A [`Generator`] may then revisit the judgements it is unsure about:
+//!
+//! - [`RulesGenerator`] keeps the rules result as is (offline, reproducible).
+//! - [`LlmGenerator`] asks an LLM to re-classify the document type and the
+//!   blocks that [`crate::GenerationConfig::should_use_llm`] selects, marking
+//!   what it changed with [`Origin::Llm`](crate::model::Origin::Llm).
+//!
+//! Generators never fail: when the LLM is unreachable, slow or answers
+//! nonsense, the rules result simply stands.
+
+mod cache;
+pub mod llm;
+
+pub use llm::{LlmGenerator, LlmSettings};
+
+use crate::model::DocumentModel;
+use mdpeek_parser::BlockTree;
+
+/// Refines a rules-stage model in place.
+pub trait Generator: Send + Sync {
+    /// Short name for logs (`"rules"`, `"llm"`).
+    fn name(&self) -> &'static str;
+
+    /// Revisit `model`, built by the rules analyser from `source` / `tree`.
+    /// Anything the generator does not improve must be left untouched.
+    fn refine(&self, source: &str, tree: &BlockTree, model: &mut DocumentModel);
+}
+
+/// The rules-only generator: the analyser's result is final.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RulesGenerator;
+
+impl Generator for RulesGenerator {
+    fn name(&self) -> &'static str {
+        "rules"
+    }
+
+    fn refine(&self, _source: &str, _tree: &BlockTree, _model: &mut DocumentModel) {}
+}
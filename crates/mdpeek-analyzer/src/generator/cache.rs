@@ -0,0 +1,59 @@
+//! On-disk cache of LLM replies, keyed by a hash of the model and prompt.
+//!
+//! The prompt embeds the document source, so an unchanged document never costs
+//! a second request — not even across restarts — while any edit misses.
+
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ReplyCache {
+    dir: PathBuf,
+}
+
+impl ReplyCache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Cache key for a request. FNV-1a rather than `DefaultHasher`, whose
+    /// output may change between Rust releases and orphan the cache.
+    pub(crate) fn key(model: &str, prompt: &str) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in model.bytes().chain([0]).chain(prompt.bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        format!("{hash:016x}")
+    }
+
+    pub(crate) fn load(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.path(key)).ok()
+    }
+
+    /// Best effort: a read-only or full disk only costs a repeated request.
+    pub(crate) fn store(&self, key: &str, reply: &str) {
+        let result = fs::create_dir_all(&self.dir).and_then(|()| fs::write(self.path(key), reply));
+        if let Err(err) = result {
+            tracing::debug!("llm cache write to {} failed: {err}", self.dir.display());
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_is_stable_and_content_sensitive() {
+        let a = ReplyCache::key("m", "prompt");
+        assert_eq!(a, ReplyCache::key("m", "prompt"));
+        assert_ne!(a, ReplyCache::key("m", "prompt!"));
+        assert_ne!(a, ReplyCache::key("other", "prompt"));
+        assert_eq!(a.len(), 16);
+    }
+}
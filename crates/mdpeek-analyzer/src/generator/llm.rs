@@ -0,0 +1,578 @@
+//! [`LlmGenerator`]: re-classifies low-confidence rules results with an LLM.
+//!
+//! Speaks the Anthropic Messages API (`POST {base_url}/v1/messages`). The base
+//! URL is configurable so tests (or a proxy) can stand in for the real API. One
+//! request per document covers the document type and every selected block; the
+//! reply is a small JSON object, cached on disk by content hash.
+
+use super::Generator;
+use super::cache::ReplyCache;
+use crate::generation::GenerationConfig;
+use crate::model::{BlockClass, Classified, DocumentModel, DocumentType, Origin};
+use mdpeek_parser::{BlockId, BlockKind, BlockTree};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default API endpoint.
+pub const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+/// Default model: classification needs speed more than depth.
+pub const DEFAULT_MODEL: &str = "claude-haiku-4-5";
+/// Environment variable holding the API key unless configured otherwise.
+pub const DEFAULT_API_KEY_ENV: &str = "ANTHROPIC_API_KEY";
+/// How long to wait for the LLM before keeping the rules result.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Longest document excerpt sent in a prompt (characters).
+const MAX_SOURCE_CHARS: usize = 24_000;
+/// Longest per-block excerpt listed in a prompt (characters).
+const MAX_BLOCK_CHARS: usize = 160;
+/// Confidence recorded when the reply omits one.
+const DEFAULT_LLM_CONFIDENCE: f32 = 0.8;
+
+/// Connection settings for [`LlmGenerator`].
+#[derive(Debug, Clone)]
+pub struct LlmSettings {
+    pub base_url: String,
+    pub model: String,
+    pub api_key: String,
+    pub timeout: Duration,
+    /// Directory for cached replies; `None` disables the cache.
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl LlmSettings {
+    /// Default settings with the given API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            api_key: api_key.into(),
+            timeout: DEFAULT_TIMEOUT,
+            cache_dir: None,
+        }
+    }
+}
+
+/// Generator that escalates uncertain rules results to an LLM.
+pub struct LlmGenerator {
+    policy: GenerationConfig,
+    settings: LlmSettings,
+    agent: ureq::Agent,
+    cache: Option<ReplyCache>,
+}
+
+impl LlmGenerator {
+    /// `policy` decides which judgements are escalated (see
+    /// [`GenerationConfig::should_use_llm`]).
+    pub fn new(policy: GenerationConfig, settings: LlmSettings) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(settings.timeout))
+            .build()
+            .into();
+        let cache = settings.cache_dir.clone().map(ReplyCache::new);
+        Self {
+            policy,
+            settings,
+            agent,
+            cache,
+        }
+    }
+
+    /// The raw reply text for `prompt`, from the cache or the API.
+    fn reply_text(&self, prompt: &str) -> Result<String, LlmError> {
+        let key = ReplyCache::key(&self.settings.model, prompt);
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.load(&key)) {
+            return Ok(cached);
+        }
+        let text = self.request(prompt)?;
+        // Only cache replies that parse, so a garbled answer is retried.
+        parse_reply(&text)?;
+        if let Some(cache) = &self.cache {
+            cache.store(&key, &text);
+        }
+        Ok(text)
+    }
+
+    fn request(&self, prompt: &str) -> Result<String, LlmError> {
+        let url = format!(
+            "{}/v1/messages",
+            self.settings.base_url.trim_end_matches('/')
+        );
+        let body = MessagesRequest {
+            model: &self.settings.model,
+            max_tokens: 2048,
+            messages: [Message {
+                role: "user",
+                content: prompt,
+            }],
+        };
+        let response: MessagesResponse = self
+            .agent
+            .post(&url)
+            .header("x-api-key", &self.settings.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .send_json(&body)?
+            .body_mut()
+            .read_json()?;
+        Ok(response
+            .content
+            .into_iter()
+            .map(|block| block.text)
+            .collect())
+    }
+}
+
+impl Generator for LlmGenerator {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+
+    fn refine(&self, source: &str, tree: &BlockTree, model: &mut DocumentModel) {
+        let wanted = Wanted::select(&self.policy, model);
+        if wanted.is_empty() {
+            return;
+        }
+        let prompt = prompt(source, tree, model, &wanted);
+        match self.reply_text(&prompt).and_then(|text| parse_reply(&text)) {
+            Ok(reply) => apply(reply, &wanted, model),
+            Err(err) => {
+                tracing::warn!("llm generation failed ({err}); keeping the rules result");
+            }
+        }
+    }
+}
+
+/// The judgements to escalate.
+struct Wanted {
+    doc_type: bool,
+    blocks: HashSet<BlockId>,
+}
+
+impl Wanted {
+    fn select(policy: &GenerationConfig, model: &DocumentModel) -> Self {
+        Self {
+            doc_type: policy.should_use_llm(model.doc_type.confidence),
+            blocks: model
+                .blocks
+                .iter()
+                .filter(|b| policy.should_use_llm(b.confidence))
+                .map(|b| b.block_id)
+                .collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.doc_type && self.blocks.is_empty()
+    }
+}
+
+const DOCUMENT_TYPES: &[&str] = &[
+    "design_doc",
+    "readme",
+    "adr",
+    "minutes",
+    "runbook",
+    "investigation",
+    "changelog",
+    "git_log",
+    "novel",
+    "production_order",
+    "procedure",
+    "recipe",
+    "contract",
+    "paper",
+    "faq",
+    "generic",
+];
+
+const BLOCK_CLASSES: &[&str] = &[
+    "overview",
+    "architecture",
+    "data_model",
+    "risk",
+    "open_question",
+    "decision",
+    "consequence",
+    "step",
+    "usage",
+    "configuration",
+    "troubleshooting",
+    "task",
+    "code_example",
+    "table",
+    "heading",
+    "generic",
+];
+
+fn prompt(source: &str, tree: &BlockTree, model: &DocumentModel, wanted: &Wanted) -> String {
+    let mut out = String::from(
+        "You classify Markdown documents for a document viewer.\n\
+         Reply with a single JSON object and nothing else:\n\
+         {\"doc_type\": {\"value\": TYPE, \"confidence\": 0-1} or null, \
+         \"blocks\": [{\"id\": ID, \"class\": CLASS, \"confidence\": 0-1}]}\n",
+    );
+    out.push_str(&format!("TYPE is one of: {}\n", DOCUMENT_TYPES.join(", ")));
+    out.push_str(&format!(
+        "CLASS is one of: {}\n\n",
+        BLOCK_CLASSES.join(", ")
+    ));
+    if wanted.doc_type {
+        out.push_str(&format!(
+            "Classify the document type (rules guessed {} at {:.2}).\n",
+            serde_name(&model.doc_type.value),
+            model.doc_type.confidence
+        ));
+    } else {
+        out.push_str("Set doc_type to null.\n");
+    }
+    if wanted.blocks.is_empty() {
+        out.push_str("Leave blocks empty.\n");
+    } else {
+        out.push_str("Classify only these blocks:\n");
+        for block in model
+            .blocks
+            .iter()
+            .filter(|b| wanted.blocks.contains(&b.block_id))
+        {
+            let Some(node) = tree.find(block.block_id) else {
+                continue;
+            };
+            let excerpt: String = node.text.chars().take(MAX_BLOCK_CHARS).collect();
+            out.push_str(&format!(
+                "- id {} ({}, line {}, rules guessed {} at {:.2}): {}\n",
+                block.block_id.0,
+                kind_name(&node.kind),
+                block.range.start_line,
+                serde_name(&block.class),
+                block.confidence,
+                excerpt.replace('\n', " ")
+            ));
+        }
+    }
+    let document: String = source.chars().take(MAX_SOURCE_CHARS).collect();
+    out.push_str("\nDocument:\n<<<\n");
+    out.push_str(&document);
+    out.push_str("\n>>>\n");
+    out
+}
+
+/// The snake_case spelling serde uses for a unit enum variant.
+fn serde_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn kind_name(kind: &BlockKind) -> &'static str {
+    match kind {
+        BlockKind::Heading { .. } => "heading",
+        BlockKind::Paragraph => "paragraph",
+        BlockKind::CodeBlock { .. } => "code block",
+        BlockKind::BlockQuote => "block quote",
+        BlockKind::List { .. } => "list",
+        BlockKind::Item { .. } => "list item",
+        BlockKind::Table => "table",
+        _ => "block",
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Reply {
+    #[serde(default)]
+    doc_type: Option<Judgement<DocumentType>>,
+    #[serde(default)]
+    blocks: Vec<BlockJudgement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Judgement<T> {
+    value: T,
+    #[serde(default = "default_confidence")]
+    confidence: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BlockJudgement {
+    id: u64,
+    class: BlockClass,
+    #[serde(default = "default_confidence")]
+    confidence: f32,
+}
+
+fn default_confidence() -> f32 {
+    DEFAULT_LLM_CONFIDENCE
+}
+
+/// Parse the model's answer, tolerating a surrounding ```json fence or prose.
+fn parse_reply(text: &str) -> Result<Reply, LlmError> {
+    let start = text.find('{');
+    let end = text.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => return Err(LlmError::Reply("no JSON object in reply".into())),
+    };
+    serde_json::from_str(json).map_err(|err| LlmError::Reply(err.to_string()))
+}
+
+/// Write the reply into `model`, ignoring anything that was not asked for.
+fn apply(reply: Reply, wanted: &Wanted, model: &mut DocumentModel) {
+    if wanted.doc_type
+        && let Some(doc_type) = reply.doc_type
+    {
+        model.doc_type = Classified::llm(doc_type.value, doc_type.confidence.clamp(0.0, 1.0));
+    }
+    for judgement in reply.blocks {
+        let id = BlockId(judgement.id);
+        if !wanted.blocks.contains(&id) {
+            continue;
+        }
+        if let Some(block) = model.blocks.iter_mut().find(|b| b.block_id == id) {
+            block.class = judgement.class;
+            block.confidence = judgement.confidence.clamp(0.0, 1.0);
+            block.by = Origin::Llm;
+            block.rule = None;
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    messages: [Message<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct ContentBlock {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug)]
+enum LlmError {
+    Http(ureq::Error),
+    Reply(String),
+}
+
+impl fmt::Display for LlmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LlmError::Http(err) => write!(f, "request failed: {err}"),
+            LlmError::Reply(msg) => write!(f, "unusable reply: {msg}"),
+        }
+    }
+}
+
+impl From<ureq::Error> for LlmError {
+    fn from(err: ureq::Error) -> Self {
+        LlmError::Http(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::GenerationStrategy;
+    use crate::{MarkdownOptions, analyze, analyze_with_generator};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    const DOC: &str = "# Notes\n\nSome loosely related thoughts.\n\n## Usage\n\nRun it.\n";
+
+    /// A local stand-in for the Messages API. Answers every request with
+    /// `reply` wrapped in a Messages response, or stalls when `reply` is
+    /// `None`. Returns the base URL, a request counter and the last request.
+    fn mock_server(reply: Option<String>) -> (String, Arc<AtomicUsize>, Arc<Mutex<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let last = Arc::new(Mutex::new(String::new()));
+        let (thread_hits, thread_last) = (hits.clone(), last.clone());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                thread_hits.fetch_add(1, Ordering::SeqCst);
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap_or(0);
+                    }
+                    request.push_str(&line);
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).ok();
+                request.push_str(&String::from_utf8_lossy(&body));
+                *thread_last.lock().unwrap() = request;
+                let Some(reply) = &reply else {
+                    std::thread::sleep(Duration::from_secs(2));
+                    continue;
+                };
+                let body =
+                    serde_json::json!({"content": [{"type": "text", "text": reply}]}).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).ok();
+            }
+        });
+        (base_url, hits, last)
+    }
+
+    fn generator(base_url: String, cache_dir: Option<PathBuf>) -> LlmGenerator {
+        let policy = GenerationConfig {
+            llm_enabled: true,
+            strategy: GenerationStrategy::RulesFirst,
+            confidence_threshold: 0.6,
+        };
+        LlmGenerator::new(
+            policy,
+            LlmSettings {
+                base_url,
+                timeout: Duration::from_millis(500),
+                cache_dir,
+                ..LlmSettings::new("test-key")
+            },
+        )
+    }
+
+    /// The id of the low-confidence paragraph in [`DOC`].
+    fn uncertain_block() -> u64 {
+        let rules = analyze(DOC, None);
+        let block = rules
+            .model
+            .blocks
+            .iter()
+            .find(|b| b.confidence < 0.6 && b.class == BlockClass::Generic)
+            .expect("an uncertain block");
+        block.block_id.0
+    }
+
+    fn run(generator: &LlmGenerator) -> crate::Analysis {
+        analyze_with_generator(DOC, None, &MarkdownOptions::default(), generator)
+    }
+
+    #[test]
+    fn reclassifies_low_confidence_results() {
+        let id = uncertain_block();
+        let reply = format!(
+            r#"```json
+{{"doc_type": {{"value": "investigation", "confidence": 0.7}}, "blocks": [{{"id": {id}, "class": "overview", "confidence": 0.75}}]}}
+```"#
+        );
+        let (base_url, hits, last) = mock_server(Some(reply));
+        let analysis = run(&generator(base_url, None));
+
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        let request = last.lock().unwrap().to_ascii_lowercase();
+        assert!(request.contains("x-api-key: test-key"), "{request}");
+        assert!(request.contains("/v1/messages"), "{request}");
+
+        assert_eq!(analysis.model.doc_type.value, DocumentType::Investigation);
+        assert_eq!(analysis.model.doc_type.by, Origin::Llm);
+        let block = analysis
+            .model
+            .blocks
+            .iter()
+            .find(|b| b.block_id.0 == id)
+            .unwrap();
+        assert_eq!(block.class, BlockClass::Overview);
+        assert_eq!(block.by, Origin::Llm);
+        // Confident rules results are not sent and stay as they were.
+        assert!(
+            analysis
+                .model
+                .blocks
+                .iter()
+                .filter(|b| b.block_id.0 != id)
+                .all(|b| b.by == Origin::Rules)
+        );
+    }
+
+    #[test]
+    fn replies_are_cached_on_disk_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let (base_url, hits, _) = mock_server(Some(
+            r#"{"doc_type": {"value": "faq"}, "blocks": []}"#.into(),
+        ));
+
+        let first = run(&generator(base_url.clone(), Some(dir.path().to_path_buf())));
+        // A fresh generator (as after a restart) reads the same cache.
+        let second = run(&generator(base_url, Some(dir.path().to_path_buf())));
+        assert_eq!(
+            hits.load(Ordering::SeqCst),
+            1,
+            "second run should hit the cache"
+        );
+        assert_eq!(first.model.doc_type, second.model.doc_type);
+        assert_eq!(second.model.doc_type.value, DocumentType::Faq);
+        assert!((second.model.doc_type.confidence - DEFAULT_LLM_CONFIDENCE).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn timeout_falls_back_to_rules() {
+        let (base_url, _, _) = mock_server(None);
+        let analysis = run(&generator(base_url, None));
+        assert_eq!(analysis.model, analyze(DOC, None).model);
+    }
+
+    #[test]
+    fn unreachable_or_garbled_llm_falls_back_to_rules() {
+        let rules = analyze(DOC, None).model;
+        // Nothing listens on port 9 (discard) on a test machine.
+        let analysis = run(&generator("http://127.0.0.1:9".into(), None));
+        assert_eq!(analysis.model, rules);
+
+        let (base_url, _, _) = mock_server(Some("I think it is a README.".into()));
+        let analysis = run(&generator(base_url, None));
+        assert_eq!(analysis.model, rules);
+    }
+
+    #[test]
+    fn confident_documents_make_no_request() {
+        let (base_url, hits, _) = mock_server(Some("{}".into()));
+        let src = "---\ntype: readme\n---\n\n```sh\nmake\n```\n";
+        analyze_with_generator(
+            src,
+            None,
+            &MarkdownOptions::default(),
+            &generator(base_url, None),
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn prompt_vocabulary_matches_the_model() {
+        for name in DOCUMENT_TYPES {
+            let parsed: Result<DocumentType, _> = serde_json::from_value((*name).into());
+            assert!(parsed.is_ok(), "{name}");
+        }
+        for name in BLOCK_CLASSES {
+            let parsed: Result<BlockClass, _> = serde_json::from_value((*name).into());
+            assert!(parsed.is_ok(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Local issue exports.
+//!
+//! Documents reference issues as `TODO(#123)`; [`panel`](crate::panel) parses
+//! those references, and an [`IssueList`] loaded from a JSON export lets them
+//! be joined against the tracker's state without network access. Accepted
+//! shapes:
+//!
+//! * GitHub REST (`GET /repos/{o}/{r}/issues`): `[{"number", "title", "state": "open"}]`
+//! * `gh issue list --json number,title,state,url`: `"state": "OPEN"`
+//! * GitLab (`GET /projects/:id/issues`): `[{"iid", "title", "state": "opened"}]`
+//!
+//! The array may also be wrapped in an object under `issues`, `items` or `data`.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// State of a referenced issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueState {
+    Open,
+    Closed,
+    /// Referenced, but absent from the export.
+    Missing,
+}
+
+impl IssueState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IssueState::Open => "open",
+            IssueState::Closed => "closed",
+            IssueState::Missing => "missing",
+        }
+    }
+}
+
+/// One exported issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    /// `None` when the export gives no state, or one this module does not
+    /// know (GitLab's `locked`); such issues are neither open nor closed.
+    pub state: Option<IssueState>,
+    pub url: Option<String>,
+}
+
+/// Exported issues by number.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IssueList {
+    issues: BTreeMap<u64, Issue>,
+}
+
+impl IssueList {
+    /// Read and parse an export file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Parse an export (see the module docs for the accepted shapes). Entries
+    /// without a number are skipped.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let items = match &value {
+            Value::Array(items) => items,
+            Value::Object(map) => ["issues", "items", "data"]
+                .iter()
+                .find_map(|k| map.get(*k).and_then(Value::as_array))
+                .ok_or("expected an array of issues")?,
+            _ => return Err("expected an array of issues".to_string()),
+        };
+        let issues = items
+            .iter()
+            .filter_map(|item| {
+                let number = item
+                    .get("iid")
+                    .or_else(|| item.get("number"))
+                    .and_then(Value::as_u64)?;
+                let state = match item.get("state").and_then(Value::as_str) {
+                    Some(s) if s.eq_ignore_ascii_case("open") || s == "opened" => {
+                        Some(IssueState::Open)
+                    }
+                    Some(s) if s.eq_ignore_ascii_case("closed") => Some(IssueState::Closed),
+                    _ => None,
+                };
+                let text = |key: &str| item.get(key).and_then(Value::as_str).map(str::to_string);
+                let issue = Issue {
+                    number,
+                    title: text("title").unwrap_or_default(),
+                    state,
+                    url: text("html_url")
+                        .or_else(|| text("web_url"))
+                        .or_else(|| text("url")),
+                };
+                Some((number, issue))
+            })
+            .collect();
+        Ok(IssueList { issues })
+    }
+
+    pub fn get(&self, number: u64) -> Option<&Issue> {
+        self.issues.get(&number)
+    }
+
+    /// State of issue `number`: [`IssueState::Missing`] when not exported,
+    /// `None` when exported without a known state.
+    pub fn state(&self, number: u64) -> Option<IssueState> {
+        self.get(number)
+            .map_or(Some(IssueState::Missing), |i| i.state)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_github_rest_and_gh_cli_exports() {
+        let rest = r#"[
+            {"number": 12, "title": "Crash on start", "state": "open", "html_url": "https://github.com/o/r/issues/12"},
+            {"number": 7, "title": "Old bug", "state": "closed"}
+        ]"#;
+        let list = IssueList::parse(rest).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.state(12), Some(IssueState::Open));
+        assert_eq!(list.state(7), Some(IssueState::Closed));
+        assert_eq!(list.state(99), Some(IssueState::Missing));
+        assert_eq!(
+            list.get(12).unwrap().url.as_deref(),
+            Some("https://github.com/o/r/issues/12")
+        );
+
+        let gh = r#"[{"number": 3, "title": "x", "state": "OPEN", "url": "u"}]"#;
+        assert_eq!(
+            IssueList::parse(gh).unwrap().state(3),
+            Some(IssueState::Open)
+        );
+    }
+
+    #[test]
+    fn parses_gitlab_exports_by_iid() {
+        let gitlab = r#"{"issues": [
+            {"id": 90001, "iid": 4, "title": "Docs", "state": "opened", "web_url": "w"},
+            {"id": 90002, "iid": 5, "title": "Done", "state": "closed"}
+        ]}"#;
+        let list = IssueList::parse(gitlab).unwrap();
+        assert_eq!(list.state(4), Some(IssueState::Open));
+        assert_eq!(list.state(5), Some(IssueState::Closed));
+        assert!(list.get(90001).is_none());
+    }
+
+    #[test]
+    fn unknown_states_are_not_guessed() {
+        let list = IssueList::parse(
+            r#"[
+                {"number": 1, "title": "No state"},
+                {"number": 2, "title": "Locked", "state": "locked"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.state(1), None);
+        assert_eq!(list.state(2), None);
+    }
+
+    #[test]
+    fn rejects_other_shapes() {
+        assert!(IssueList::parse("{\"count\": 1}").is_err());
+        assert!(IssueList::parse("not json").is_err());
+    }
+}
@@ -12,27 +12,114 @@
 //! (Layer 3). The public entry point is [`analyze`], returning an [`Analysis`]
 //! that bundles the tree, model and side panel.
 //!
+//! The one Layer 3 piece hosted here is the [`generator`]:
+//! [`analyze_with_generator`] lets an [`LlmGenerator`] re-classify the
+//! low-confidence rules results before the side panel is built.
+//!
 //! Block parsing and `SourceRange`s come from Layer 1's `mdpeek-parser`
 //! (`BlockTree`); this crate adds the semantic layer on top.
 
 pub mod analyzer;
 pub mod generation;
+pub mod generator;
+pub mod issues;
 pub mod links;
+pub mod lint;
+pub mod locale;
 pub mod model;
 pub mod panel;
+pub mod progress;
+pub mod rules;
+pub mod stats;
 
 pub use generation::{GenerationConfig, GenerationStrategy};
+pub use generator::{Generator, LlmGenerator, LlmSettings, RulesGenerator};
+pub use mdpeek_gfm::MarkdownOptions;
 pub use mdpeek_parser::{Block, BlockId, BlockKind, BlockTree, SourceRange};
 
-use self::model::DocumentModel;
+use self::analyzer::changelog::{self, Changelog};
+use self::analyzer::contract::{self, Contract};
+use self::analyzer::http::{self, HttpExchange};
+use self::analyzer::minutes::{self, Minutes};
+use self::analyzer::novel::{self, Novel};
+use self::analyzer::procedure::{self, Procedure};
+use self::analyzer::recipe::{self, Recipe};
+use self::analyzer::snippet::{self, SnippetError};
+use self::issues::IssueList;
+use self::lint::{Diagnostic, Linter};
+use self::model::{DocumentModel, DocumentType};
 use self::panel::SemanticPanel;
+use self::progress::ProgressStore;
+use self::rules::ClassificationRules;
+use self::stats::DocumentStats;
+use serde::Serialize;
+use std::path::Path;
 
-/// The complete Layer 2 analysis of a document.
-#[derive(Debug, Clone)]
+/// The complete Layer 2 analysis of a document. Serialises as
+/// `{"tree": …, "model": …, "panel": …, "stats": …, "diagnostics": …,
+/// "snippet_errors": …, "http": …, "novel": …, "contract": …, "procedure": …,
+/// "recipe": …, "changelog": …, "minutes": …}` for the browser side panel.
+#[derive(Debug, Clone, Serialize)]
 pub struct Analysis {
     pub tree: BlockTree,
     pub model: DocumentModel,
     pub panel: SemanticPanel,
+    pub stats: DocumentStats,
+    /// Prose linter findings; empty unless [`AnalysisExtras`] has a linter.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Syntax errors of the JSON / YAML / TOML code blocks.
+    pub snippet_errors: Vec<SnippetError>,
+    /// Requests and responses of the HTTP code blocks.
+    pub http: Vec<HttpExchange>,
+    /// Chapters and character roster, for documents classified as novels.
+    pub novel: Option<Novel>,
+    /// Obligation matrix, for documents classified as contracts.
+    pub contract: Option<Contract>,
+    /// Step model, for runbooks and procedures.
+    pub procedure: Option<Procedure>,
+    /// Parsed ingredients, for recipes.
+    pub recipe: Option<Recipe>,
+    /// Releases and their validation problems, for changelogs.
+    pub changelog: Option<Changelog>,
+    /// Action items and decisions, for meeting minutes.
+    pub minutes: Option<Minutes>,
+}
+
+/// Project-level inputs to an [`Analysis`]: the classification rules it is
+/// built with ([`analyze_with_rules`]) and, applied after it is built, the
+/// issue export `TODO(#123)` markers are joined against, the prose linter,
+/// and the store of completed procedure steps.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisExtras {
+    pub rules: ClassificationRules,
+    pub issues: Option<IssueList>,
+    pub linter: Option<Linter>,
+    pub progress: Option<ProgressStore>,
+}
+
+impl AnalysisExtras {
+    /// Annotate `analysis` of `markdown` (analysed with `options`), read from
+    /// `path` when it is a file.
+    pub fn apply(
+        &self,
+        markdown: &str,
+        path: Option<&Path>,
+        options: &MarkdownOptions,
+        analysis: &mut Analysis,
+    ) {
+        if let Some(issues) = &self.issues {
+            analysis.panel.link_issues(issues);
+        }
+        if let Some(linter) = &self.linter {
+            let options = options.for_document(markdown);
+            analysis.diagnostics = linter.check(markdown, &analysis.tree, &options);
+        }
+        if let (Some(store), Some(path), Some(procedure)) =
+            (&self.progress, path, &mut analysis.procedure)
+        {
+            procedure.mark_done(&store.load(path));
+        }
+    }
 }
 
 /// Analyse a markdown document end-to-end (parse → rules model → side panel).
@@ -40,16 +127,86 @@ pub struct Analysis {
 /// `filename` (when known) sharpens document-type inference; pass `None` if
 /// analysing an in-memory buffer.
 pub fn analyze(markdown: &str, filename: Option<&str>) -> Analysis {
-    let tree = BlockTree::parse(markdown);
-    let model = analyzer::build_model(markdown, &tree, filename);
+    analyze_with_options(markdown, filename, &MarkdownOptions::default())
+}
+
+/// [`analyze`] with the project's markdown feature set (`[markdown]` config).
+/// Front matter overrides are applied on top, exactly as the renderers do.
+pub fn analyze_with_options(
+    markdown: &str,
+    filename: Option<&str>,
+    options: &MarkdownOptions,
+) -> Analysis {
+    analyze_with_generator(markdown, filename, options, &RulesGenerator)
+}
+
+/// [`analyze_with_options`] followed by Layer 3's `generator`, which may
+/// re-classify low-confidence rules results before the side panel is built.
+pub fn analyze_with_generator(
+    markdown: &str,
+    filename: Option<&str>,
+    options: &MarkdownOptions,
+    generator: &dyn Generator,
+) -> Analysis {
+    analyze_with_rules(
+        markdown,
+        filename,
+        options,
+        &ClassificationRules::default(),
+        generator,
+    )
+}
+
+/// [`analyze_with_generator`] with the project's `[analyzer]` rules merged
+/// into the built-in classification. Pass the document's path as `filename`
+/// so directory globs can match.
+pub fn analyze_with_rules(
+    markdown: &str,
+    filename: Option<&str>,
+    options: &MarkdownOptions,
+    rules: &ClassificationRules,
+    generator: &dyn Generator,
+) -> Analysis {
+    let options = options.for_document(markdown);
+    let tree = BlockTree::parse_with_options(markdown, options.parser_options());
+    let mut model = analyzer::build_model(markdown, &tree, filename, &options, rules);
+    generator.refine(markdown, &tree, &mut model);
     let panel = panel::build(&model, &tree);
-    Analysis { tree, model, panel }
+    let stats = stats::compute(markdown, &tree, &options);
+    let snippet_errors = snippet::check(markdown, &tree);
+    let http = http::extract(&tree);
+    let novel = (model.doc_type.value == DocumentType::Novel).then(|| novel::extract(&tree));
+    let contract = (model.doc_type.value == DocumentType::Contract)
+        .then(|| contract::extract(&tree, model.locale));
+    let procedure = matches!(
+        model.doc_type.value,
+        DocumentType::Runbook | DocumentType::Procedure
+    )
+    .then(|| procedure::extract(&tree, rules, model.locale));
+    let recipe = (model.doc_type.value == DocumentType::Recipe).then(|| recipe::extract(&tree));
+    let changelog =
+        (model.doc_type.value == DocumentType::Changelog).then(|| changelog::extract(&tree));
+    let minutes = (model.doc_type.value == DocumentType::Minutes).then(|| minutes::extract(&tree));
+    Analysis {
+        tree,
+        model,
+        panel,
+        stats,
+        diagnostics: Vec::new(),
+        snippet_errors,
+        http,
+        novel,
+        contract,
+        procedure,
+        recipe,
+        changelog,
+        minutes,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::DocumentType;
 
     #[test]
     fn end_to_end_readme_analysis() {
@@ -80,4 +237,143 @@ mod tests {
         assert_eq!(a.model, b.model);
         assert_eq!(a.panel, b.panel);
     }
+
+    #[test]
+    fn front_matter_can_disable_math() {
+        let md = "---\nmarkdown:\n  math: false\n---\n\nCosts $5 and $10.\n";
+        let a = analyze(md, None);
+        let para = a
+            .tree
+            .iter()
+            .find(|b| b.kind == BlockKind::Paragraph)
+            .expect("paragraph");
+        assert_eq!(para.text, "Costs $5 and $10.");
+    }
+
+    #[test]
+    fn config_snippets_are_checked() {
+        let md = "# Setup\n\n```yaml\nserver:\n  port: 80\n   host: x\n```\n";
+        let a = analyze(md, None);
+        assert_eq!(a.snippet_errors.len(), 1);
+        assert_eq!(a.snippet_errors[0].format, "yaml");
+        assert_eq!(a.snippet_errors[0].range.start_line, 6);
+    }
+
+    #[test]
+    fn novels_get_chapters_and_roster() {
+        let md = "---\ntype: novel\ncharacters: [Mole]\n---\n# Chapter 1\n\nMole woke.\n";
+        let a = analyze(md, None);
+        let novel = a.novel.expect("novel analysis");
+        assert_eq!(novel.chapters.len(), 1);
+        assert_eq!(novel.characters[0].name, "Mole");
+        assert!(analyze("# Chapter 1\n\nMole woke.\n", None).novel.is_none());
+    }
+
+    #[test]
+    fn runbooks_get_steps_marked_from_stored_progress() {
+        let md = "---\ntype: runbook\n---\n# Restart\n\n## Steps\n\n1. Stop\n2. Start\n";
+        let dir = tempfile::tempdir().unwrap();
+        let doc = dir.path().join("restart.md");
+        let store = ProgressStore::new(dir.path().join("progress.json"));
+        store.set(&doc, "Stop", true).unwrap();
+        let extras = AnalysisExtras {
+            progress: Some(store),
+            ..AnalysisExtras::default()
+        };
+
+        let mut a = analyze(md, None);
+        extras.apply(md, Some(&doc), &MarkdownOptions::default(), &mut a);
+        let done: Vec<bool> = a
+            .procedure
+            .expect("procedure")
+            .steps
+            .iter()
+            .map(|s| s.done)
+            .collect();
+        assert_eq!(done, vec![true, false]);
+        assert!(analyze("# Restart\n\n1. Stop\n", None).procedure.is_none());
+    }
+
+    #[test]
+    fn recipes_get_parsed_ingredients() {
+        let md = "# Pancakes\n\n## Ingredients\n\n- 2 cups flour\n\n## Instructions\n\n1. Mix\n";
+        let recipe = analyze(md, None).recipe.expect("recipe analysis");
+        assert_eq!(recipe.ingredients[0].item, "flour");
+        assert!(
+            analyze("# Pancakes\n\n- 2 cups flour\n", None)
+                .recipe
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn changelogs_get_releases() {
+        let md =
+            "# Changelog\n\n## [Unreleased]\n\n## [0.2.0] - 2024-05-01\n\n### Fixed\n\n- Bug\n";
+        let changelog = analyze(md, Some("CHANGELOG.md"))
+            .changelog
+            .expect("changelog analysis");
+        assert_eq!(
+            changelog.latest().map(|r| r.version.as_str()),
+            Some("0.2.0")
+        );
+        assert!(analyze(md, Some("README.md")).changelog.is_none());
+    }
+
+    #[test]
+    fn minutes_get_action_items() {
+        let md = "# Sync 2024-10-16
+
+## Attendees
+
+- Alice
+
+## Action items
+
+- @alice draft the plan by Friday
+";
+        let minutes = analyze(md, None).minutes.expect("minutes analysis");
+        assert_eq!(minutes.actions[0].owners, vec!["alice"]);
+        assert_eq!(
+            minutes.actions[0]
+                .due
+                .as_ref()
+                .and_then(|d| d.date.as_deref()),
+            Some("2024-10-18")
+        );
+    }
+
+    #[test]
+    fn configured_rules_drive_classification_and_extraction() {
+        let rules = ClassificationRules {
+            sections: [(
+                "作業".to_string(),
+                rules::SectionRule::Class(model::BlockClass::Step),
+            )]
+            .into(),
+            doctypes: vec![rules::DocTypeRule {
+                doc_type: DocumentType::Procedure,
+                filename: Some("sop/*.md".to_string()),
+                headings: Vec::new(),
+                frontmatter: None,
+                confidence: 0.95,
+            }],
+            ..ClassificationRules::default()
+        };
+        let md = "# 棚卸し\n\n## 作業\n\n1. 在庫を数える\n2. 記録する\n";
+        let a = analyze_with_rules(
+            md,
+            Some("ops/sop/stocktake.md"),
+            &MarkdownOptions::default(),
+            &rules,
+            &RulesGenerator,
+        );
+        assert_eq!(a.model.doc_type.value, DocumentType::Procedure);
+        assert_eq!(a.model.doc_type.confidence, 0.95);
+        assert_eq!(
+            a.model.doc_type.rule.as_deref(),
+            Some("analyzer.doctypes[0] (filename \"sop/*.md\")")
+        );
+        assert_eq!(a.procedure.expect("procedure").steps.len(), 2);
+    }
 }
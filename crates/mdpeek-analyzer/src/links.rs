@@ -3,11 +3,11 @@
 //! `mdpeek-parser`'s `BlockTree` folds inline formatting into plain text and
 //! does not surface hyperlinks, so Layer 2 re-parses the source once to collect
 //! links with their source ranges (for the model's `links` list and, later,
-//! link-aware UI). Uses the same GFM options as the block parser so autolinks
-//! and reference links are seen identically.
+//! link-aware UI). Takes the same parser options as the block parser so
+//! autolinks, reference links and wiki links are seen identically.
 
 use mdpeek_parser::{LineIndex, SourceRange};
-use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 use serde::Serialize;
 
 /// A hyperlink discovered in the source, with its span.
@@ -19,15 +19,20 @@ pub struct Link {
 }
 
 /// Extract every link (`[text](url)`, autolinks, reference links) in document
-/// order.
+/// order, using the default feature set.
 pub fn extract(markdown: &str) -> Vec<Link> {
+    extract_with_options(markdown, mdpeek_gfm::parser_options())
+}
+
+/// Like [`extract`], with explicit `pulldown_cmark` options (e.g. to pick up
+/// `[[wiki links]]` when they are enabled).
+pub fn extract_with_options(markdown: &str, options: Options) -> Vec<Link> {
     let line_index = LineIndex::new(markdown);
     let mut links = Vec::new();
     // Stack of in-progress links: (url, accumulated text, byte range).
     let mut open: Vec<(String, String, std::ops::Range<usize>)> = Vec::new();
 
-    for (event, range) in Parser::new_ext(markdown, mdpeek_gfm::parser_options()).into_offset_iter()
-    {
+    for (event, range) in Parser::new_ext(markdown, options).into_offset_iter() {
         match event {
             Event::Start(Tag::Link { dest_url, .. }) => {
                 open.push((dest_url.to_string(), String::new(), range));
@@ -76,4 +81,17 @@ mod tests {
     fn no_links_yields_empty() {
         assert!(extract("plain text only\n").is_empty());
     }
+
+    #[test]
+    fn wiki_links_only_with_the_option() {
+        let md = "See [[Home Page]].\n";
+        assert!(extract(md).is_empty());
+        let options = mdpeek_gfm::MarkdownOptions {
+            wiki_links: true,
+            ..Default::default()
+        };
+        let links = extract_with_options(md, options.parser_options());
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "Home Page");
+    }
 }
@@ -0,0 +1,833 @@
+//! Prose linter.
+//!
+//! A small Vale-style rule engine over block text. Each block's prose (inline
+//! formatting flattened, code spans masked) is collected together with a map
+//! back to the source bytes, so every [`Diagnostic`] carries the exact
+//! [`SourceRange`] of the offending words. Rules:
+//!
+//! * `substitute` — regex → preferred replacement (`utilize` → `use`);
+//! * `banned-word` — words or phrases to avoid;
+//! * `terminology` — a preferred term and its unwanted variants;
+//! * `sentence-length` — sentences over a word limit (a character limit for
+//!   Japanese/Chinese sentences, which have no spaces);
+//! * `passive-voice` — an English "be + past participle" heuristic;
+//! * `repeated-word` — `the the`;
+//! * `hyphenation` — a word spelled both hyphenated and closed in the same
+//!   document (`e-mail` / `email`); the less common spelling is flagged.
+//!
+//! Rules come from a [`LintConfig`] (the `[lint]` table of `config.toml`),
+//! compiled once into a [`Linter`].
+
+use crate::stats;
+use mdpeek_gfm::MarkdownOptions;
+use mdpeek_parser::{BlockId, BlockTree, SourceRange};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// How serious a [`Diagnostic`] is. Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Suggestion,
+    #[default]
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Suggestion => "suggestion",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// One rule violation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Rule name, e.g. `sentence-length` (see the module docs).
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Suggested replacement for the text at `range`, when there is one.
+    pub fix: Option<String>,
+    pub range: SourceRange,
+    /// Innermost block containing the diagnostic, for jump-to-source.
+    pub block_id: Option<BlockId>,
+}
+
+/// The `[lint]` configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Lint documents at all (off by default).
+    pub enabled: bool,
+    /// Maximum words per sentence; 0 disables the check.
+    pub sentence_length: usize,
+    /// Maximum characters per Japanese/Chinese sentence; 0 disables it.
+    pub sentence_length_cjk: usize,
+    pub passive_voice: bool,
+    pub repeated_words: bool,
+    pub hyphenation: bool,
+    /// Severity overrides for the built-in rules, by rule name.
+    pub severity: BTreeMap<String, Severity>,
+    #[serde(rename = "substitute")]
+    pub substitutions: Vec<Substitution>,
+    #[serde(rename = "ban")]
+    pub bans: Vec<Ban>,
+    pub terms: Vec<Term>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            enabled: false,
+            sentence_length: 35,
+            sentence_length_cjk: 100,
+            passive_voice: true,
+            repeated_words: true,
+            hyphenation: true,
+            severity: BTreeMap::new(),
+            substitutions: Vec::new(),
+            bans: Vec::new(),
+            terms: Vec::new(),
+        }
+    }
+}
+
+/// `[[lint.substitute]]`: text matching `pattern` should read `replacement`
+/// (which may use `$1`-style capture references).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Substitution {
+    pub pattern: String,
+    pub replacement: String,
+    /// Custom message; `{match}` is replaced with the matched text.
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// `[[lint.ban]]`: words or phrases to avoid (matched case-insensitively on
+/// word boundaries).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ban {
+    pub words: Vec<String>,
+    /// Custom message; `{match}` is replaced with the matched text.
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// `[[lint.terms]]`: use `preferred` instead of any of `variants` (matched
+/// case-sensitively, so `Javascript` can be flagged while `JavaScript` is not).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Term {
+    pub preferred: String,
+    pub variants: Vec<String>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// Built-in rules whose severity `[lint.severity]` may override, with their
+/// defaults.
+const BUILTIN_RULES: &[(&str, Severity)] = &[
+    ("sentence-length", Severity::Warning),
+    ("passive-voice", Severity::Suggestion),
+    ("repeated-word", Severity::Warning),
+    ("hyphenation", Severity::Suggestion),
+];
+
+static WORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").expect("valid word regex"));
+
+static HYPHENATED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\w+(?:-\w+)+").expect("valid hyphenated regex"));
+
+/// "be" followed by a past participle, optionally with an adverb in between.
+static PASSIVE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:am|is|are|was|were|be|been|being)\s+(?:\w+ly\s+)?(?:\w+ed|arisen|awoken|begun|bitten|blown|born|bought|brought|built|caught|chosen|done|drawn|driven|eaten|fallen|forgiven|forgotten|found|frozen|given|grown|held|hidden|kept|known|laid|led|lost|made|meant|paid|put|read|said|seen|sent|shown|shut|sold|spent|spoken|stolen|taken|taught|thought|thrown|told|torn|understood|won|worn|written)\b",
+    )
+    .expect("valid passive voice regex")
+});
+
+/// A compiled [`LintConfig`].
+#[derive(Debug, Clone)]
+pub struct Linter {
+    sentence_length: usize,
+    sentence_length_cjk: usize,
+    passive_voice: bool,
+    repeated_words: bool,
+    hyphenation: bool,
+    severity: BTreeMap<&'static str, Severity>,
+    substitutions: Vec<(Regex, Substitution)>,
+    bans: Vec<(Regex, Ban)>,
+    terms: Vec<(Regex, Term)>,
+}
+
+impl Linter {
+    /// Compile `config`, failing on an invalid pattern or an unknown rule in
+    /// `[lint.severity]`.
+    pub fn new(config: &LintConfig) -> Result<Self, String> {
+        let mut severity: BTreeMap<&'static str, Severity> =
+            BUILTIN_RULES.iter().copied().collect();
+        for (rule, level) in &config.severity {
+            let Some((name, _)) = BUILTIN_RULES.iter().find(|(name, _)| name == rule) else {
+                return Err(format!("lint.severity: unknown rule `{rule}`"));
+            };
+            severity.insert(name, *level);
+        }
+        let substitutions = config
+            .substitutions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                Regex::new(&s.pattern)
+                    .map(|re| (re, s.clone()))
+                    .map_err(|e| format!("lint.substitute[{i}]: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        let bans = config
+            .bans
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| !b.words.is_empty())
+            .map(|(i, b)| {
+                Regex::new(&format!(r"(?i)\b(?:{})\b", alternation(&b.words)))
+                    .map(|re| (re, b.clone()))
+                    .map_err(|e| format!("lint.ban[{i}]: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        let terms = config
+            .terms
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.variants.is_empty())
+            .map(|(i, t)| {
+                Regex::new(&format!(r"\b(?:{})\b", alternation(&t.variants)))
+                    .map(|re| (re, t.clone()))
+                    .map_err(|e| format!("lint.terms[{i}]: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Linter {
+            sentence_length: config.sentence_length,
+            sentence_length_cjk: config.sentence_length_cjk,
+            passive_voice: config.passive_voice,
+            repeated_words: config.repeated_words,
+            hyphenation: config.hyphenation,
+            severity,
+            substitutions,
+            bans,
+            terms,
+        })
+    }
+
+    /// Lint `markdown`, parsed into `tree` with the document's effective
+    /// `options`. Diagnostics are returned in document order.
+    pub fn check(
+        &self,
+        markdown: &str,
+        tree: &BlockTree,
+        options: &MarkdownOptions,
+    ) -> Vec<Diagnostic> {
+        let segments = segments(markdown, options);
+        let mut found: Vec<(usize, Range<usize>, Found)> = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            let mut push = |range: Range<usize>, f: Found| found.push((i, range, f));
+            self.substitute(&segment.text, &mut push);
+            self.ban(&segment.text, &mut push);
+            self.terminology(&segment.text, &mut push);
+            self.sentences(&segment.text, &mut push);
+            self.passive(&segment.text, &mut push);
+            self.repeated(&segment.text, &mut push);
+        }
+        if self.hyphenation {
+            found.extend(self.hyphenation(&segments));
+        }
+
+        let line_index = &tree.line_index;
+        let mut diagnostics: Vec<Diagnostic> = found
+            .into_iter()
+            .map(|(i, range, f)| {
+                let range = line_index.source_range(segments[i].source(range));
+                Diagnostic {
+                    rule: f.rule.to_string(),
+                    severity: f.severity,
+                    message: f.message,
+                    fix: f.fix,
+                    range,
+                    block_id: block_at(tree, range),
+                }
+            })
+            .collect();
+        diagnostics.sort_by_key(|d| (d.range.start_line, d.range.start_column));
+        diagnostics
+    }
+
+    fn builtin(&self, rule: &'static str) -> Severity {
+        self.severity.get(rule).copied().unwrap_or_default()
+    }
+
+    fn substitute(&self, text: &str, push: &mut impl FnMut(Range<usize>, Found)) {
+        for (re, rule) in &self.substitutions {
+            for caps in re.captures_iter(text) {
+                let m = caps.get(0).expect("group 0 always matches");
+                if m.is_empty() {
+                    continue;
+                }
+                let mut fix = String::new();
+                caps.expand(&rule.replacement, &mut fix);
+                if fix == m.as_str() {
+                    continue;
+                }
+                let message = match &rule.message {
+                    Some(message) => message.replace("{match}", m.as_str()),
+                    None => format!("Use '{fix}' instead of '{}'", m.as_str()),
+                };
+                push(
+                    m.range(),
+                    Found::new("substitute", rule.severity, message).with_fix(fix),
+                );
+            }
+        }
+    }
+
+    fn ban(&self, text: &str, push: &mut impl FnMut(Range<usize>, Found)) {
+        for (re, rule) in &self.bans {
+            for m in re.find_iter(text) {
+                let message = match &rule.message {
+                    Some(message) => message.replace("{match}", m.as_str()),
+                    None => format!("Avoid '{}'", m.as_str()),
+                };
+                push(m.range(), Found::new("banned-word", rule.severity, message));
+            }
+        }
+    }
+
+    fn terminology(&self, text: &str, push: &mut impl FnMut(Range<usize>, Found)) {
+        for (re, term) in &self.terms {
+            for m in re.find_iter(text) {
+                let message = format!("Use '{}' instead of '{}'", term.preferred, m.as_str());
+                push(
+                    m.range(),
+                    Found::new("terminology", term.severity, message)
+                        .with_fix(term.preferred.clone()),
+                );
+            }
+        }
+    }
+
+    fn sentences(&self, text: &str, push: &mut impl FnMut(Range<usize>, Found)) {
+        if self.sentence_length == 0 && self.sentence_length_cjk == 0 {
+            return;
+        }
+        for range in sentences(text) {
+            let counts = stats::count(&text[range.clone()]);
+            // Mostly Japanese/Chinese: limit characters rather than words.
+            let (size, limit, unit) = if counts.cjk * 2 > counts.words {
+                (counts.characters, self.sentence_length_cjk, "characters")
+            } else {
+                (counts.words, self.sentence_length, "words")
+            };
+            if limit > 0 && size > limit {
+                let message = format!("Sentence has {size} {unit} (limit {limit})");
+                push(
+                    range,
+                    Found::new("sentence-length", self.builtin("sentence-length"), message),
+                );
+            }
+        }
+    }
+
+    fn passive(&self, text: &str, push: &mut impl FnMut(Range<usize>, Found)) {
+        if !self.passive_voice {
+            return;
+        }
+        for m in PASSIVE_RE.find_iter(text) {
+            let message = format!("Passive voice: '{}'", m.as_str());
+            push(
+                m.range(),
+                Found::new("passive-voice", self.builtin("passive-voice"), message),
+            );
+        }
+    }
+
+    fn repeated(&self, text: &str, push: &mut impl FnMut(Range<usize>, Found)) {
+        if !self.repeated_words {
+            return;
+        }
+        let mut prev: Option<regex::Match> = None;
+        for m in WORD_RE.find_iter(text) {
+            if let Some(p) = prev {
+                let gap = &text[p.end()..m.start()];
+                if !gap.is_empty()
+                    && gap.chars().all(char::is_whitespace)
+                    && m.as_str().chars().any(char::is_alphabetic)
+                    && p.as_str().to_lowercase() == m.as_str().to_lowercase()
+                {
+                    let message = format!("Repeated word '{}'", m.as_str());
+                    push(
+                        p.start()..m.end(),
+                        Found::new("repeated-word", self.builtin("repeated-word"), message)
+                            .with_fix(p.as_str().to_string()),
+                    );
+                }
+            }
+            prev = Some(m);
+        }
+    }
+
+    /// Document-wide: words written both hyphenated and closed.
+    fn hyphenation(&self, segments: &[Segment]) -> Vec<(usize, Range<usize>, Found)> {
+        type Spots = Vec<(usize, Range<usize>, String)>;
+        // Closed, lower-cased spelling → hyphenated / closed occurrences.
+        let mut hyphenated: BTreeMap<String, Spots> = BTreeMap::new();
+        for (i, segment) in segments.iter().enumerate() {
+            for m in HYPHENATED_RE.find_iter(&segment.text) {
+                if m.as_str().chars().any(char::is_alphabetic) {
+                    let key = m.as_str().replace('-', "").to_lowercase();
+                    let spot = (i, m.range(), m.as_str().to_string());
+                    hyphenated.entry(key).or_default().push(spot);
+                }
+            }
+        }
+        let mut closed: BTreeMap<String, Spots> = BTreeMap::new();
+        for (i, segment) in segments.iter().enumerate() {
+            for m in WORD_RE.find_iter(&segment.text) {
+                let key = m.as_str().to_lowercase();
+                if hyphenated.contains_key(&key) {
+                    let spot = (i, m.range(), m.as_str().to_string());
+                    closed.entry(key).or_default().push(spot);
+                }
+            }
+        }
+        let severity = self.builtin("hyphenation");
+        let mut found = Vec::new();
+        for (key, closed) in closed {
+            let hyphenated = &hyphenated[&key];
+            // Flag the less common spelling; on a tie, the hyphenated one.
+            let (flagged, kept) = if closed.len() >= hyphenated.len() {
+                (hyphenated, &closed)
+            } else {
+                (&closed, hyphenated)
+            };
+            let preferred = &kept[0].2;
+            for (i, range, text) in flagged {
+                let message = format!(
+                    "'{text}' is also spelled '{preferred}' in this document ({}×)",
+                    kept.len()
+                );
+                found.push((
+                    *i,
+                    range.clone(),
+                    Found::new("hyphenation", severity, message).with_fix(preferred.clone()),
+                ));
+            }
+        }
+        found
+    }
+}
+
+/// A diagnostic before its range is mapped back to the source.
+struct Found {
+    rule: &'static str,
+    severity: Severity,
+    message: String,
+    fix: Option<String>,
+}
+
+impl Found {
+    fn new(rule: &'static str, severity: Severity, message: String) -> Self {
+        Found {
+            rule,
+            severity,
+            message,
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: String) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// `a|b|c` with every alternative escaped.
+fn alternation(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|w| regex::escape(w))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// The prose of one block (a paragraph, heading, list item or table cell)
+/// and where each piece of it came from.
+#[derive(Debug, Default)]
+struct Segment {
+    text: String,
+    pieces: Vec<Piece>,
+}
+
+/// A run of segment text starting at `start`, taken from the source bytes
+/// `source`. `exact` runs are verbatim, so offsets inside them map one to
+/// one; others (escapes, masked code, smart quotes) map to their whole span.
+#[derive(Debug)]
+struct Piece {
+    start: usize,
+    source: Range<usize>,
+    exact: bool,
+}
+
+impl Segment {
+    fn push(&mut self, text: &str, source: Range<usize>, markdown: &str) {
+        let exact = markdown.get(source.clone()) == Some(text);
+        self.pieces.push(Piece {
+            start: self.text.len(),
+            source,
+            exact,
+        });
+        self.text.push_str(text);
+    }
+
+    /// Source byte range of the segment text `range`.
+    fn source(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.piece_at(range.start, false).map_or(0, |p| {
+            if p.exact {
+                p.source.start + (range.start - p.start)
+            } else {
+                p.source.start
+            }
+        });
+        let end = self.piece_at(range.end, true).map_or(start, |p| {
+            if p.exact {
+                p.source.start + (range.end - p.start)
+            } else {
+                p.source.end
+            }
+        });
+        start..end.max(start)
+    }
+
+    /// The piece containing text offset `at` (for an end offset, the piece
+    /// the preceding byte belongs to).
+    fn piece_at(&self, at: usize, end: bool) -> Option<&Piece> {
+        self.pieces
+            .iter()
+            .rev()
+            .find(|p| if end { p.start < at } else { p.start <= at })
+    }
+}
+
+/// Split `markdown` into block prose segments. Code blocks, HTML and front
+/// matter are skipped; inline code and math become U+FFFC so they neither
+/// match rules nor join the words around them.
+fn segments(markdown: &str, options: &MarkdownOptions) -> Vec<Segment> {
+    const MASK: &str = "\u{FFFC}";
+    let mut segments = Vec::new();
+    let mut current = Segment::default();
+    let mut skip = 0usize;
+    let mut flush = |current: &mut Segment| {
+        let segment = std::mem::take(current);
+        if !segment.text.trim().is_empty() {
+            segments.push(segment);
+        }
+    };
+    for (event, range) in Parser::new_ext(markdown, options.parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::MetadataBlock(_) | Tag::HtmlBlock) => {
+                flush(&mut current);
+                skip += 1;
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::MetadataBlock(_) | TagEnd::HtmlBlock) => {
+                skip = skip.saturating_sub(1);
+            }
+            Event::Start(tag) if !is_inline(&tag) => flush(&mut current),
+            Event::End(tag) if !is_inline_end(&tag) => flush(&mut current),
+            _ if skip > 0 => {}
+            Event::Text(text) => current.push(&text, range, markdown),
+            Event::Code(_) | Event::InlineMath(_) | Event::DisplayMath(_) => {
+                current.push(MASK, range, markdown)
+            }
+            Event::SoftBreak | Event::HardBreak => current.push(" ", range, markdown),
+            _ => {}
+        }
+    }
+    flush(&mut current);
+    segments
+}
+
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+fn is_inline_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
+
+/// Sentence spans of `text`: ending at `.`/`!`/`?` before whitespace, or at
+/// `。`/`！`/`？`; surrounding whitespace is trimmed.
+fn sentences(text: &str) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let boundary = match c {
+            '。' | '！' | '？' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|(_, n)| n.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            let end = i + c.len_utf8();
+            push_trimmed(text, start..end, &mut out);
+            start = end;
+        }
+    }
+    push_trimmed(text, start..text.len(), &mut out);
+    out
+}
+
+fn push_trimmed(text: &str, range: Range<usize>, out: &mut Vec<Range<usize>>) {
+    let s = &text[range.clone()];
+    let lead = s.len() - s.trim_start().len();
+    let trail = s.len() - s.trim_end().len();
+    if lead + trail < s.len() {
+        out.push(range.start + lead..range.end - trail);
+    }
+}
+
+/// The innermost block whose range contains the start of `range`.
+fn block_at(tree: &BlockTree, range: SourceRange) -> Option<BlockId> {
+    let at = (range.start_line, range.start_column);
+    tree.iter()
+        .filter(|b| {
+            (b.range.start_line, b.range.start_column) <= at
+                && at < (b.range.end_line, b.range.end_column)
+        })
+        .last()
+        .map(|b| b.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(config: &LintConfig, md: &str) -> Vec<Diagnostic> {
+        let options = MarkdownOptions::default();
+        let tree = BlockTree::parse(md);
+        Linter::new(config).unwrap().check(md, &tree, &options)
+    }
+
+    fn quiet() -> LintConfig {
+        LintConfig {
+            enabled: true,
+            sentence_length: 0,
+            sentence_length_cjk: 0,
+            passive_voice: false,
+            repeated_words: false,
+            hyphenation: false,
+            ..LintConfig::default()
+        }
+    }
+
+    fn spans(diags: &[Diagnostic]) -> Vec<(&str, u32, u32, u32, Option<&str>)> {
+        diags
+            .iter()
+            .map(|d| {
+                (
+                    d.rule.as_str(),
+                    d.range.start_line,
+                    d.range.start_column,
+                    d.range.end_column,
+                    d.fix.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn substitutions_and_bans_have_exact_ranges() {
+        let config: LintConfig = toml::from_str(
+            r#"
+            [[substitute]]
+            pattern = "\\butili[sz]e\\b"
+            replacement = "use"
+
+            [[ban]]
+            words = ["simply", "obviously"]
+            message = "Don't say '{match}'"
+            severity = "error"
+            "#,
+        )
+        .unwrap();
+        let md = "# Setup\n\nYou can *simply* utilize `utilize` to\nutilise it.\n";
+        let diags = lint(
+            &LintConfig {
+                enabled: true,
+                ..config
+            },
+            md,
+        );
+        assert_eq!(
+            spans(&diags),
+            vec![
+                ("banned-word", 3, 10, 16, None),
+                ("substitute", 3, 18, 25, Some("use")),
+                ("substitute", 4, 1, 8, Some("use")),
+            ]
+        );
+        assert_eq!(diags[0].message, "Don't say 'simply'");
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert_eq!(diags[1].message, "Use 'use' instead of 'utilize'");
+        assert_eq!(diags[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn terminology_is_case_sensitive() {
+        let config = LintConfig {
+            terms: vec![Term {
+                preferred: "JavaScript".into(),
+                variants: vec!["Javascript".into(), "JS".into()],
+                severity: Severity::Warning,
+            }],
+            ..quiet()
+        };
+        let diags = lint(&config, "JavaScript, Javascript and JS.\n");
+        assert_eq!(
+            spans(&diags),
+            vec![
+                ("terminology", 1, 13, 23, Some("JavaScript")),
+                ("terminology", 1, 28, 30, Some("JavaScript")),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_sentences_in_english_and_japanese() {
+        let config = LintConfig {
+            sentence_length: 5,
+            sentence_length_cjk: 10,
+            ..quiet()
+        };
+        let md = "Short one. This sentence has far too many words in it.\n\n\
+                  短い文。これはとても長い日本語の文章になっています。\n";
+        let diags = lint(&config, md);
+        assert_eq!(
+            spans(&diags),
+            vec![
+                ("sentence-length", 1, 12, 55, None),
+                ("sentence-length", 3, 13, 79, None)
+            ]
+        );
+        assert_eq!(diags[0].message, "Sentence has 9 words (limit 5)");
+        assert_eq!(diags[1].message, "Sentence has 22 characters (limit 10)");
+    }
+
+    #[test]
+    fn passive_voice_and_repeated_words() {
+        let config = LintConfig {
+            passive_voice: true,
+            repeated_words: true,
+            ..quiet()
+        };
+        let diags = lint(&config, "The file was written by the the tool.\n");
+        assert_eq!(
+            spans(&diags),
+            vec![
+                ("passive-voice", 1, 10, 21, None),
+                ("repeated-word", 1, 25, 32, Some("the")),
+            ]
+        );
+        assert_eq!(diags[0].severity, Severity::Suggestion);
+    }
+
+    #[test]
+    fn hyphenation_flags_the_minority_spelling() {
+        let config = LintConfig {
+            hyphenation: true,
+            ..quiet()
+        };
+        let md = "# E-mail\n\nSend an email.\n\n- Check your email\n";
+        let diags = lint(&config, md);
+        assert_eq!(spans(&diags), vec![("hyphenation", 1, 3, 9, Some("email"))]);
+        assert_eq!(
+            diags[0].message,
+            "'E-mail' is also spelled 'email' in this document (2×)"
+        );
+    }
+
+    #[test]
+    fn code_is_not_linted_and_blocks_are_linked() {
+        let config = LintConfig {
+            repeated_words: true,
+            ..quiet()
+        };
+        let md = "```\nthe the\n```\n\n- item with with words\n";
+        let diags = lint(&config, md);
+        assert_eq!(
+            spans(&diags),
+            vec![("repeated-word", 5, 8, 17, Some("with"))]
+        );
+        let tree = BlockTree::parse(md);
+        let block = tree.find(diags[0].block_id.unwrap()).unwrap();
+        assert!(matches!(block.kind, mdpeek_parser::BlockKind::Item { .. }));
+    }
+
+    #[test]
+    fn severity_overrides_and_bad_config() {
+        let config = LintConfig {
+            repeated_words: true,
+            severity: BTreeMap::from([("repeated-word".to_string(), Severity::Error)]),
+            ..quiet()
+        };
+        assert_eq!(lint(&config, "a a\n")[0].severity, Severity::Error);
+
+        let unknown = LintConfig {
+            severity: BTreeMap::from([("nope".to_string(), Severity::Error)]),
+            ..quiet()
+        };
+        assert!(Linter::new(&unknown).unwrap_err().contains("nope"));
+        let bad = LintConfig {
+            substitutions: vec![Substitution {
+                pattern: "(".into(),
+                replacement: "x".into(),
+                message: None,
+                severity: Severity::Warning,
+            }],
+            ..quiet()
+        };
+        assert!(
+            Linter::new(&bad)
+                .unwrap_err()
+                .starts_with("lint.substitute[0]")
+        );
+    }
+}
@@ -0,0 +1,796 @@
+//! Locale keyword packs.
+//!
+//! The analyser's heuristics key off words: section headings (`## Risks`),
+//! the heading sets that identify a document type (`Agenda` + `Attendees`),
+//! TODO markers and the normative phrases of contracts (`shall`, `muss`).
+//! Each supported language has a [`KeywordPack`] of them: English, Japanese,
+//! Chinese, German, French and Spanish.
+//!
+//! A document's [`Locale`] comes from its front matter `lang:`, else the
+//! configured language (`[analyzer] lang`, falling back to `[markdown] lang`),
+//! else [`Locale::detect`] on its prose. English is always consulted after the
+//! document's own pack, since English headings (`TODO`, `API`, `FAQ`) turn up
+//! in documents of every language.
+
+use crate::model::BlockClass;
+use serde::{Deserialize, Serialize};
+
+/// A supported analyser language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+    Zh,
+    De,
+    Fr,
+    Es,
+}
+
+/// The words of one language.
+#[derive(Debug)]
+pub struct KeywordPack {
+    pub locale: Locale,
+    /// Whether words are separated by spaces, so keywords match at word
+    /// boundaries (Japanese and Chinese match anywhere).
+    pub spaced: bool,
+    /// Section heading keywords (lowercase substrings) by the class of the
+    /// section they introduce.
+    pub sections: &'static [(BlockClass, &'static [&'static str])],
+    pub doc_types: DocTypeKeywords,
+    /// Inline TODO markers besides the universal `TODO` / `FIXME` / `XXX` /
+    /// `HACK`, with the marker kind each one counts as (`todo`, `fixme`).
+    /// They are matched case-sensitively and need a trailing colon.
+    pub todo_markers: &'static [(&'static str, &'static str)],
+    pub normative: Normative,
+}
+
+/// Heading keywords of the built-in document-type heuristics.
+#[derive(Debug)]
+pub struct DocTypeKeywords {
+    /// An ADR has a status, a context and a decision (or consequences).
+    pub adr_status: &'static [&'static str],
+    pub adr_context: &'static [&'static str],
+    pub adr_decision: &'static [&'static str],
+    /// A recipe has ingredients and instructions.
+    pub ingredients: &'static [&'static str],
+    pub instructions: &'static [&'static str],
+    pub minutes: &'static [&'static str],
+    pub changelog: &'static [&'static str],
+    pub runbook: &'static [&'static str],
+    /// A design doc has an architecture and an overview (or design, risks).
+    pub architecture: &'static [&'static str],
+    pub overview: &'static [&'static str],
+}
+
+/// The normative phrases of contracts.
+#[derive(Debug)]
+pub struct Normative {
+    pub obligation: &'static [&'static str],
+    pub prohibition: &'static [&'static str],
+    pub permission: &'static [&'static str],
+    /// Words that turn a permission into a prohibition when they follow it
+    /// (`darf … nicht`).
+    pub negations: &'static [&'static str],
+    /// The particle ending the party of a statement (`乙は、…`); `None` where
+    /// the party is the subject in front of the modal.
+    pub topic: Option<char>,
+    /// Words in front of a party name that are not part of it.
+    pub determiners: &'static [&'static str],
+    /// Regex of a deadline phrase (`within 30 days`, `30日以内`).
+    pub deadline: &'static str,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 6] = [
+        Locale::En,
+        Locale::Ja,
+        Locale::Zh,
+        Locale::De,
+        Locale::Fr,
+        Locale::Es,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+            Locale::Zh => "zh",
+            Locale::De => "de",
+            Locale::Fr => "fr",
+            Locale::Es => "es",
+        }
+    }
+
+    /// The locale of a BCP 47-ish language tag (`de`, `fr-CA`, `zh_TW`);
+    /// `None` for languages without a pack.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        Locale::ALL.into_iter().find(|l| l.as_str() == primary)
+    }
+
+    /// The document's locale: front matter `lang:`, else `configured`, else
+    /// detected from `prose`.
+    pub fn resolve(frontmatter: Option<&str>, configured: Option<&str>, prose: &str) -> Self {
+        frontmatter
+            .and_then(frontmatter_lang)
+            .or_else(|| configured.and_then(Locale::from_tag))
+            .unwrap_or_else(|| Locale::detect(prose))
+    }
+
+    /// Guess the language of `text`: Japanese when it has kana, Chinese when
+    /// it is mostly Han characters without kana, else the Latin-script
+    /// language whose common words occur most (English on a tie).
+    pub fn detect(text: &str) -> Self {
+        let (mut kana, mut han, mut letters) = (0usize, 0usize, 0usize);
+        for c in text.chars() {
+            match c {
+                '\u{3040}'..='\u{30ff}' => kana += 1,
+                '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' => han += 1,
+                c if c.is_alphabetic() => letters += 1,
+                _ => {}
+            }
+        }
+        // One CJK character carries about as much as a five-letter word.
+        let cjk = kana + han;
+        if cjk > 0 && cjk * 5 >= letters {
+            return if kana * 20 >= cjk {
+                Locale::Ja
+            } else {
+                Locale::Zh
+            };
+        }
+        let mut scores = [
+            (Locale::En, 0usize),
+            (Locale::De, 0),
+            (Locale::Fr, 0),
+            (Locale::Es, 0),
+        ];
+        for word in text
+            .split(|c: char| !c.is_alphabetic() && c != '\'')
+            .filter(|w| !w.is_empty())
+        {
+            let word = word.to_lowercase();
+            for (locale, score) in &mut scores {
+                if stopwords(*locale).contains(&word.as_str()) {
+                    *score += 1;
+                }
+            }
+        }
+        // `max_by_key` keeps the last maximum; English goes last to win ties.
+        scores.rotate_left(1);
+        scores
+            .into_iter()
+            .max_by_key(|(_, score)| *score)
+            .map_or(Locale::En, |(locale, _)| locale)
+    }
+
+    /// This locale's keyword pack.
+    pub fn pack(self) -> &'static KeywordPack {
+        match self {
+            Locale::En => &EN,
+            Locale::Ja => &JA,
+            Locale::Zh => &ZH,
+            Locale::De => &DE,
+            Locale::Fr => &FR,
+            Locale::Es => &ES,
+        }
+    }
+
+    /// The packs consulted for a document in this locale: its own, then
+    /// English.
+    pub fn packs(self) -> impl Iterator<Item = &'static KeywordPack> {
+        std::iter::once(self.pack()).chain((self != Locale::En).then_some(&EN))
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A top-level `lang:` in raw front matter, if it names a supported locale.
+fn frontmatter_lang(frontmatter: &str) -> Option<Locale> {
+    frontmatter.lines().find_map(|line| {
+        let value = line.strip_prefix("lang:")?;
+        Locale::from_tag(value.trim().trim_matches(['"', '\'']))
+    })
+}
+
+/// Frequent function words that tell the Latin-script languages apart.
+fn stopwords(locale: Locale) -> &'static [&'static str] {
+    match locale {
+        Locale::En => &[
+            "the", "and", "of", "to", "is", "with", "for", "this", "that",
+        ],
+        Locale::De => &[
+            "der", "die", "das", "und", "ist", "nicht", "mit", "für", "ein", "eine",
+        ],
+        Locale::Fr => &[
+            "le", "les", "et", "est", "des", "une", "pour", "dans", "avec", "du",
+        ],
+        Locale::Es => &[
+            "el", "los", "las", "y", "es", "del", "una", "para", "con", "por",
+        ],
+        Locale::Ja | Locale::Zh => &[],
+    }
+}
+
+pub static EN: KeywordPack = KeywordPack {
+    locale: Locale::En,
+    spaced: true,
+    sections: &[
+        (
+            BlockClass::OpenQuestion,
+            &["open question", "open issue", "questions"],
+        ),
+        (BlockClass::Risk, &["risk", "caveat", "warning"]),
+        (BlockClass::DataModel, &["data model", "schema", "types"]),
+        (BlockClass::Architecture, &["architecture", "design"]),
+        (
+            BlockClass::Consequence,
+            &["consequence", "trade-off", "tradeoff"],
+        ),
+        (BlockClass::Decision, &["decision"]),
+        (
+            BlockClass::Configuration,
+            &["configuration", "config", "options"],
+        ),
+        (
+            BlockClass::Troubleshooting,
+            &["troubleshoot", "faq", "known issue"],
+        ),
+        (
+            BlockClass::Usage,
+            &["usage", "how to use", "getting started", "quick start"],
+        ),
+        (
+            BlockClass::Step,
+            &["step", "procedure", "instruction", "how to"],
+        ),
+        (
+            BlockClass::Overview,
+            &["overview", "summary", "introduction"],
+        ),
+    ],
+    doc_types: DocTypeKeywords {
+        adr_status: &["status"],
+        adr_context: &["context"],
+        adr_decision: &["decision", "consequence"],
+        ingredients: &["ingredient"],
+        instructions: &["instruction", "steps"],
+        minutes: &["agenda", "attendee", "action item"],
+        changelog: &["changelog", "unreleased"],
+        runbook: &["prerequisite", "procedure", "runbook", "rollback"],
+        architecture: &["architecture", "data model"],
+        overview: &["overview", "design", "risk"],
+    },
+    todo_markers: &[],
+    normative: Normative {
+        obligation: &[
+            "shall",
+            "must",
+            "agree to",
+            "agrees to",
+            "undertake to",
+            "undertakes to",
+            "is responsible for",
+            "are responsible for",
+            "is required to",
+            "are required to",
+            "is obliged to",
+            "are obliged to",
+        ],
+        prohibition: &["shall not", "must not", "may not", "will not", "cannot"],
+        permission: &["may"],
+        negations: &[],
+        topic: None,
+        determiners: &[
+            "the", "each", "either", "any", "a", "an", "both", "neither", "such", "every",
+        ],
+        deadline: r"(?i)\b(?:within\s+\S+\s+(?:business\s+|calendar\s+|working\s+)?(?:days?|weeks?|months?|years?|hours?)|(?:no|not)\s+later\s+than|on\s+or\s+before|prior\s+to|before|by\s+(?:the\s+end\b|\d|(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\b))[^,;]*",
+    },
+};
+
+pub static JA: KeywordPack = KeywordPack {
+    locale: Locale::Ja,
+    spaced: false,
+    sections: &[
+        (BlockClass::OpenQuestion, &["未解決", "疑問"]),
+        (BlockClass::Risk, &["リスク", "注意", "落とし穴"]),
+        (
+            BlockClass::DataModel,
+            &["データモデル", "スキーマ", "型定義"],
+        ),
+        (
+            BlockClass::Architecture,
+            &["アーキテクチャ", "構成", "設計"],
+        ),
+        (BlockClass::Consequence, &["影響", "結果"]),
+        (BlockClass::Decision, &["決定", "採用"]),
+        (BlockClass::Configuration, &["設定"]),
+        (BlockClass::Troubleshooting, &["トラブル"]),
+        (BlockClass::Usage, &["使い方", "使用方法"]),
+        (BlockClass::Step, &["手順", "作り方"]),
+        (BlockClass::Overview, &["概要", "はじめに", "要約"]),
+    ],
+    doc_types: DocTypeKeywords {
+        adr_status: &["ステータス"],
+        adr_context: &["背景", "コンテキスト"],
+        adr_decision: &["決定", "結果"],
+        ingredients: &["材料"],
+        instructions: &["作り方", "手順"],
+        minutes: &["議題", "出席", "決定事項"],
+        changelog: &["変更履歴"],
+        runbook: &["前提", "手順"],
+        architecture: &["アーキテクチャ", "設計", "データモデル"],
+        overview: &["概要", "リスク"],
+    },
+    todo_markers: &[
+        ("要対応", "todo"),
+        ("未対応", "todo"),
+        ("要確認", "todo"),
+        ("要修正", "fixme"),
+    ],
+    normative: Normative {
+        obligation: &[
+            "なければならない",
+            "なければならず",
+            "ものとする",
+            "責任を負う",
+            "義務を負う",
+        ],
+        prohibition: &["てはならない", "ことができない", "ないものとする"],
+        permission: &["ことができる"],
+        negations: &[],
+        topic: Some('は'),
+        determiners: &[],
+        deadline: r"[^、。はがを]{1,30}?(?:以内|までに)",
+    },
+};
+
+pub static ZH: KeywordPack = KeywordPack {
+    locale: Locale::Zh,
+    spaced: false,
+    sections: &[
+        (
+            BlockClass::OpenQuestion,
+            &["未决问题", "待定问题", "开放问题", "疑问"],
+        ),
+        (BlockClass::Risk, &["风险", "注意事项", "警告"]),
+        (BlockClass::DataModel, &["数据模型", "类型定义"]),
+        (BlockClass::Architecture, &["架构", "设计", "结构"]),
+        (BlockClass::Consequence, &["影响", "后果", "权衡"]),
+        (BlockClass::Decision, &["决定", "决策"]),
+        (BlockClass::Configuration, &["配置", "设置", "选项"]),
+        (
+            BlockClass::Troubleshooting,
+            &["故障排除", "常见问题", "已知问题"],
+        ),
+        (BlockClass::Usage, &["用法", "使用方法", "快速开始", "入门"]),
+        (BlockClass::Step, &["步骤", "操作流程"]),
+        (BlockClass::Overview, &["概述", "简介", "摘要", "总结"]),
+    ],
+    doc_types: DocTypeKeywords {
+        adr_status: &["状态"],
+        adr_context: &["背景", "上下文"],
+        adr_decision: &["决定", "决策", "后果"],
+        ingredients: &["材料", "配料", "食材"],
+        instructions: &["做法", "步骤"],
+        minutes: &["议程", "与会", "参会", "行动项", "会议纪要"],
+        changelog: &["变更日志", "更新日志", "版本历史"],
+        runbook: &["前提条件", "操作步骤", "回滚", "运维手册"],
+        architecture: &["架构", "数据模型"],
+        overview: &["概述", "设计", "风险"],
+    },
+    todo_markers: &[("待办", "todo"), ("待辦", "todo"), ("待修复", "fixme")],
+    normative: Normative {
+        obligation: &["应当", "应该", "必须", "负责"],
+        prohibition: &["不得", "不应", "禁止"],
+        permission: &["可以", "有权"],
+        negations: &[],
+        topic: None,
+        determiners: &[],
+        deadline: r"[^，。、]{0,20}?(?:以内|之内|之前|日内|天内)",
+    },
+};
+
+pub static DE: KeywordPack = KeywordPack {
+    locale: Locale::De,
+    spaced: true,
+    sections: &[
+        (
+            BlockClass::OpenQuestion,
+            &["offene fragen", "offene punkte", "ungeklärt"],
+        ),
+        (
+            BlockClass::Risk,
+            &["risik", "warnung", "achtung", "vorsicht"],
+        ),
+        (BlockClass::DataModel, &["datenmodell", "schema", "typen"]),
+        (
+            BlockClass::Architecture,
+            &["architektur", "entwurf", "aufbau"],
+        ),
+        (
+            BlockClass::Consequence,
+            &["konsequenz", "auswirkung", "folgen", "abwägung"],
+        ),
+        (BlockClass::Decision, &["entscheidung", "beschluss"]),
+        (
+            BlockClass::Configuration,
+            &["konfiguration", "einstellungen", "optionen"],
+        ),
+        (
+            BlockClass::Troubleshooting,
+            &[
+                "fehlerbehebung",
+                "problembehandlung",
+                "bekannte probleme",
+                "häufige fragen",
+            ],
+        ),
+        (
+            BlockClass::Usage,
+            &[
+                "verwendung",
+                "benutzung",
+                "nutzung",
+                "erste schritte",
+                "schnellstart",
+            ],
+        ),
+        (
+            BlockClass::Step,
+            &["schritt", "anleitung", "vorgehen", "ablauf"],
+        ),
+        (
+            BlockClass::Overview,
+            &[
+                "überblick",
+                "übersicht",
+                "zusammenfassung",
+                "einleitung",
+                "einführung",
+            ],
+        ),
+    ],
+    doc_types: DocTypeKeywords {
+        adr_status: &["status"],
+        adr_context: &["kontext"],
+        adr_decision: &["entscheidung", "konsequenz"],
+        ingredients: &["zutaten"],
+        instructions: &["zubereitung", "anleitung"],
+        minutes: &["tagesordnung", "teilnehmer", "anwesende", "beschlüsse"],
+        changelog: &["änderungsprotokoll", "versionshistorie", "unveröffentlicht"],
+        runbook: &[
+            "voraussetzung",
+            "durchführung",
+            "rückabwicklung",
+            "betriebshandbuch",
+        ],
+        architecture: &["architektur", "datenmodell"],
+        overview: &["überblick", "übersicht", "entwurf", "risik"],
+    },
+    todo_markers: &[("ZU ERLEDIGEN", "todo"), ("OFFEN", "todo")],
+    normative: Normative {
+        obligation: &[
+            "muss",
+            "müssen",
+            "hat sicherzustellen",
+            "ist verpflichtet",
+            "sind verpflichtet",
+            "verpflichtet sich",
+            "verpflichten sich",
+        ],
+        prohibition: &["darf nicht", "dürfen nicht", "darf kein", "dürfen kein"],
+        permission: &["darf", "dürfen", "kann", "können"],
+        negations: &["nicht", "kein", "keine", "keinen", "keinem", "keiner"],
+        topic: None,
+        determiners: &[
+            "der", "die", "das", "den", "dem", "des", "ein", "eine", "jeder", "jede", "jedes",
+        ],
+        deadline: r"(?i)\b(?:innerhalb\s+(?:von\s+)?\S+\s+(?:Werktagen|Arbeitstagen|Kalendertagen|Tagen|Wochen|Monaten|Jahren|Stunden)|spätestens\s+(?:bis\s+)?(?:zum\s+)?[^,;]*|bis\s+(?:zum\s+)?\d[^,;]*)",
+    },
+};
+
+pub static FR: KeywordPack = KeywordPack {
+    locale: Locale::Fr,
+    spaced: true,
+    sections: &[
+        (
+            BlockClass::OpenQuestion,
+            &[
+                "questions ouvertes",
+                "points ouverts",
+                "questions en suspens",
+            ],
+        ),
+        (
+            BlockClass::Risk,
+            &["risque", "mise en garde", "avertissement", "attention"],
+        ),
+        (
+            BlockClass::DataModel,
+            &["modèle de données", "schéma", "types"],
+        ),
+        (BlockClass::Architecture, &["architecture", "conception"]),
+        (
+            BlockClass::Consequence,
+            &["conséquence", "impact", "compromis"],
+        ),
+        (BlockClass::Decision, &["décision"]),
+        (
+            BlockClass::Configuration,
+            &["configuration", "paramètres", "options"],
+        ),
+        (
+            BlockClass::Troubleshooting,
+            &[
+                "dépannage",
+                "résolution des problèmes",
+                "problèmes connus",
+                "faq",
+            ],
+        ),
+        (
+            BlockClass::Usage,
+            &[
+                "utilisation",
+                "mode d'emploi",
+                "démarrage rapide",
+                "prise en main",
+                "premiers pas",
+            ],
+        ),
+        (
+            BlockClass::Step,
+            &["étape", "procédure", "instructions", "marche à suivre"],
+        ),
+        (
+            BlockClass::Overview,
+            &[
+                "aperçu",
+                "vue d'ensemble",
+                "résumé",
+                "introduction",
+                "présentation",
+            ],
+        ),
+    ],
+    doc_types: DocTypeKeywords {
+        adr_status: &["statut"],
+        adr_context: &["contexte"],
+        adr_decision: &["décision", "conséquence"],
+        ingredients: &["ingrédient"],
+        instructions: &["préparation", "instructions", "étapes"],
+        minutes: &[
+            "ordre du jour",
+            "participants",
+            "présents",
+            "actions à mener",
+        ],
+        changelog: &[
+            "journal des modifications",
+            "historique des versions",
+            "non publié",
+        ],
+        runbook: &["prérequis", "procédure", "retour arrière"],
+        architecture: &["architecture", "modèle de données"],
+        overview: &["aperçu", "vue d'ensemble", "conception", "risque"],
+    },
+    todo_markers: &[
+        ("À FAIRE", "todo"),
+        ("A FAIRE", "todo"),
+        ("À CORRIGER", "fixme"),
+    ],
+    normative: Normative {
+        obligation: &[
+            "doit",
+            "doivent",
+            "devra",
+            "devront",
+            "s'engage à",
+            "s'engagent à",
+            "s’engage à",
+            "s’engagent à",
+            "est tenu de",
+            "est tenue de",
+            "sont tenus de",
+        ],
+        prohibition: &[
+            "ne doit pas",
+            "ne doivent pas",
+            "ne devra pas",
+            "ne peut pas",
+            "ne peuvent pas",
+            "ne pourra pas",
+        ],
+        permission: &["peut", "peuvent", "pourra", "pourront"],
+        negations: &[],
+        topic: None,
+        determiners: &["le", "la", "les", "l'", "l’", "un", "une", "chaque"],
+        deadline: r"(?i)\b(?:dans\s+un\s+délai\s+de\s+\S+\s+(?:jours|semaines|mois|ans|heures)(?:\s+ouvrés|\s+ouvrables)?|au\s+plus\s+tard\s+le\s+[^,;]*|avant\s+le\s+[^,;]*)",
+    },
+};
+
+pub static ES: KeywordPack = KeywordPack {
+    locale: Locale::Es,
+    spaced: true,
+    sections: &[
+        (
+            BlockClass::OpenQuestion,
+            &[
+                "preguntas abiertas",
+                "cuestiones abiertas",
+                "temas pendientes",
+            ],
+        ),
+        (
+            BlockClass::Risk,
+            &["riesgo", "advertencia", "precaución", "atención"],
+        ),
+        (
+            BlockClass::DataModel,
+            &["modelo de datos", "esquema", "tipos"],
+        ),
+        (BlockClass::Architecture, &["arquitectura", "diseño"]),
+        (
+            BlockClass::Consequence,
+            &["consecuencia", "impacto", "compensaciones"],
+        ),
+        (BlockClass::Decision, &["decisión"]),
+        (
+            BlockClass::Configuration,
+            &["configuración", "ajustes", "opciones"],
+        ),
+        (
+            BlockClass::Troubleshooting,
+            &[
+                "solución de problemas",
+                "problemas conocidos",
+                "preguntas frecuentes",
+            ],
+        ),
+        (
+            BlockClass::Usage,
+            &[
+                "modo de uso",
+                "cómo usar",
+                "utilización",
+                "primeros pasos",
+                "inicio rápido",
+            ],
+        ),
+        (
+            BlockClass::Step,
+            &["paso", "procedimiento", "instrucciones"],
+        ),
+        (
+            BlockClass::Overview,
+            &[
+                "resumen",
+                "descripción general",
+                "introducción",
+                "visión general",
+            ],
+        ),
+    ],
+    doc_types: DocTypeKeywords {
+        adr_status: &["estado"],
+        adr_context: &["contexto"],
+        adr_decision: &["decisión", "consecuencia"],
+        ingredients: &["ingrediente"],
+        instructions: &["preparación", "instrucciones", "pasos"],
+        minutes: &["orden del día", "asistentes", "participantes", "acuerdos"],
+        changelog: &[
+            "registro de cambios",
+            "historial de versiones",
+            "sin publicar",
+        ],
+        runbook: &["requisitos previos", "procedimiento", "reversión"],
+        architecture: &["arquitectura", "modelo de datos"],
+        overview: &["resumen", "visión general", "diseño", "riesgo"],
+    },
+    todo_markers: &[
+        ("POR HACER", "todo"),
+        ("PENDIENTE", "todo"),
+        ("CORREGIR", "fixme"),
+    ],
+    normative: Normative {
+        obligation: &[
+            "deberá",
+            "deberán",
+            "debe",
+            "deben",
+            "se obliga a",
+            "se obligan a",
+            "está obligado a",
+            "están obligados a",
+        ],
+        prohibition: &[
+            "no deberá",
+            "no deberán",
+            "no debe",
+            "no deben",
+            "no podrá",
+            "no podrán",
+            "no puede",
+            "no pueden",
+        ],
+        permission: &["podrá", "podrán", "puede", "pueden"],
+        negations: &[],
+        topic: None,
+        determiners: &["el", "la", "los", "las", "un", "una", "cada"],
+        deadline: r"(?i)\b(?:dentro\s+de(?:l\s+plazo\s+de)?\s+\S+\s+(?:días|semanas|meses|años|horas)(?:\s+hábiles|\s+naturales)?|a\s+más\s+tardar\s+el\s+[^,;]*|antes\s+del?\s+[^,;]*)",
+    },
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_map_to_locales() {
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("zh_TW"), Some(Locale::Zh));
+        assert_eq!(Locale::from_tag("pt"), None);
+    }
+
+    #[test]
+    fn detection_by_script_and_common_words() {
+        assert_eq!(Locale::detect("設計の概要をまとめます。"), Locale::Ja);
+        assert_eq!(Locale::detect("本文档介绍系统的架构设计。"), Locale::Zh);
+        assert_eq!(
+            Locale::detect("Der Dienst ist nicht erreichbar und die Logs sind leer."),
+            Locale::De
+        );
+        assert_eq!(
+            Locale::detect("Le service est en panne depuis une heure et les journaux sont vides."),
+            Locale::Fr
+        );
+        assert_eq!(
+            Locale::detect("El servicio no responde y los registros están vacíos para el equipo."),
+            Locale::Es
+        );
+        assert_eq!(Locale::detect("The service is down."), Locale::En);
+        assert_eq!(Locale::detect(""), Locale::En);
+        // A stray Japanese word does not make an English document Japanese.
+        assert_eq!(
+            Locale::detect("We call this step the 手順 in the runbook for the team."),
+            Locale::En
+        );
+    }
+
+    #[test]
+    fn front_matter_wins_over_configuration_and_detection() {
+        let prose = "Le service est en panne et les journaux sont vides.";
+        assert_eq!(
+            Locale::resolve(Some("lang: es-MX"), Some("de"), prose),
+            Locale::Es
+        );
+        assert_eq!(
+            Locale::resolve(Some("title: x"), Some("de"), prose),
+            Locale::De
+        );
+        assert_eq!(Locale::resolve(None, Some("pt"), prose), Locale::Fr);
+    }
+
+    #[test]
+    fn every_pack_lists_the_section_classes_in_the_same_order() {
+        let order: Vec<BlockClass> = EN.sections.iter().map(|(c, _)| *c).collect();
+        for locale in Locale::ALL {
+            let pack = locale.pack();
+            assert_eq!(pack.locale, locale);
+            let classes: Vec<BlockClass> = pack.sections.iter().map(|(c, _)| *c).collect();
+            assert_eq!(classes, order, "{locale}");
+        }
+    }
+}
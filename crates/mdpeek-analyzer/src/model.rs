@@ -6,11 +6,12 @@
 //! source ranges are re-exported from Layer 1's `mdpeek-parser`.
 
 use crate::links::Link;
+use crate::locale::Locale;
 use mdpeek_parser::{BlockId, SourceRange};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-/// Where a piece of information came from. Rules today; `Llm` reserved for
-/// Layer 3's `ClaudeGenerator`.
+/// Where a piece of information came from: the rules analyser, or Layer 3's
+/// [`crate::generator::LlmGenerator`] re-classifying a low-confidence result.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum Origin {
@@ -25,6 +26,10 @@ pub struct Classified<T> {
     pub value: T,
     pub confidence: f32,
     pub by: Origin,
+    /// Which rule decided a rules result, e.g. `heading set: agenda /
+    /// attendees / action items` or `analyzer.doctypes[0] (filename
+    /// "meetings/*.md")`. `None` for LLM results.
+    pub rule: Option<String>,
 }
 
 impl<T> Classified<T> {
@@ -33,12 +38,28 @@ impl<T> Classified<T> {
             value,
             confidence,
             by: Origin::Rules,
+            rule: None,
         }
     }
+
+    pub fn llm(value: T, confidence: f32) -> Self {
+        Classified {
+            value,
+            confidence,
+            by: Origin::Llm,
+            rule: None,
+        }
+    }
+
+    /// Record the rule that produced this result.
+    pub fn because(mut self, rule: impl Into<String>) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
 }
 
 /// Document type. Development docs first, then the non-dev domains of §9.2.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DocumentType {
     // 開発文書
@@ -62,7 +83,7 @@ pub enum DocumentType {
 }
 
 /// Semantic role of a block, derived by rules from its section heading / kind.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockClass {
     Overview,
@@ -89,6 +110,10 @@ pub struct ClassifiedBlock {
     pub block_id: BlockId,
     pub class: BlockClass,
     pub confidence: f32,
+    pub by: Origin,
+    /// The section keyword rule behind a heading's class (inherited by the
+    /// blocks of its section).
+    pub rule: Option<String>,
     pub range: SourceRange,
 }
 
@@ -113,6 +138,8 @@ pub struct Task {
 /// The full semantic model produced by the rules analyser.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct DocumentModel {
+    /// The language whose keyword packs the rules used.
+    pub locale: Locale,
     pub doc_type: Classified<DocumentType>,
     pub blocks: Vec<ClassifiedBlock>,
     pub frontmatter: Option<String>,
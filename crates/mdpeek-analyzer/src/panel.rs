@@ -6,6 +6,8 @@
 //! panels can render directly, with every entry carrying a [`SourceRangeLink`]
 //! back to the original document (design思想「全 UI は sourceRange に紐づく」).
 
+use crate::issues::{IssueList, IssueState};
+use crate::locale::Locale;
 use crate::model::{BlockClass, DocumentModel};
 use mdpeek_parser::{BlockId, BlockKind, BlockTree, SourceRange};
 use regex::Regex;
@@ -33,9 +35,31 @@ pub struct TodoItem {
     pub done: bool,
     /// Marker kind: `"task"`, `"todo"`, `"fixme"`, etc.
     pub marker: String,
+    /// The issue a `TODO(#123)` marker references.
+    pub issue: Option<IssueRef>,
     pub link: SourceRangeLink,
 }
 
+/// An issue referenced by a TODO. `state` and `title` are filled in by
+/// [`SemanticPanel::link_issues`] once an issue export is available; `state`
+/// stays `None` when the export does not say.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IssueRef {
+    pub number: u64,
+    pub state: Option<IssueState>,
+    pub title: Option<String>,
+}
+
+/// An inline marker found in text (see [`inline_markers`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineMarker {
+    /// Lower-cased marker: `todo`, `fixme`, `xxx` or `hack`.
+    pub marker: String,
+    pub note: String,
+    /// `123` for `TODO(#123)`.
+    pub issue: Option<u64>,
+}
+
 /// One outline row (heading) for the outline panel.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OutlineRow {
@@ -53,9 +77,34 @@ pub struct SemanticPanel {
     pub open_questions: Vec<PanelEntry>,
 }
 
-/// Matches inline `TODO`/`FIXME`/`XXX`/`HACK` markers, capturing the trailing note.
+/// Matches inline `TODO`/`FIXME`/`XXX`/`HACK` markers with an optional
+/// `(#123)` / `(owner)` tag, capturing the tag and the trailing note.
 static MARKER_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)\b(TODO|FIXME|XXX|HACK)\b[:\s]*(.*)").expect("valid marker regex")
+    Regex::new(r"(?i)\b(TODO|FIXME|XXX|HACK)\b(?:\(([^)]*)\))?[:\s]*(.*)")
+        .expect("valid marker regex")
+});
+
+/// Per locale, its pack's marker words (`要対応`, `À FAIRE`) in the same
+/// shape. Case-sensitive: `offen` in running German text is not a marker.
+static LOCALE_MARKER_RES: LazyLock<Vec<(Locale, Option<Regex>)>> = LazyLock::new(|| {
+    Locale::ALL
+        .iter()
+        .map(|&locale| {
+            let markers = locale.pack().todo_markers;
+            let re = (!markers.is_empty()).then(|| {
+                let alternatives = markers
+                    .iter()
+                    .map(|(m, _)| regex::escape(m))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                Regex::new(&format!(
+                    r"(?:^|[^\p{{L}}])({alternatives})(?:\(([^)]*)\))?\s*[:：]\s*(.*)"
+                ))
+                .expect("valid locale marker regex")
+            });
+            (locale, re)
+        })
+        .collect()
 });
 
 /// Build the side panel from a model and its parsed tree.
@@ -68,6 +117,16 @@ pub fn build(model: &DocumentModel, tree: &BlockTree) -> SemanticPanel {
     }
 }
 
+impl SemanticPanel {
+    /// Join the TODOs' issue references against an issue export.
+    pub fn link_issues(&mut self, issues: &IssueList) {
+        for issue in self.todos.iter_mut().filter_map(|t| t.issue.as_mut()) {
+            issue.state = issues.state(issue.number);
+            issue.title = issues.get(issue.number).map(|i| i.title.clone());
+        }
+    }
+}
+
 fn outline_rows(model: &DocumentModel) -> Vec<OutlineRow> {
     model
         .outline
@@ -93,6 +152,7 @@ fn todos(model: &DocumentModel, tree: &BlockTree) -> Vec<TodoItem> {
             text: task.text.clone(),
             done: task.checked,
             marker: "task".to_string(),
+            issue: None,
             link: SourceRangeLink {
                 block_id: task.block_id,
                 range: task.range,
@@ -110,31 +170,18 @@ fn todos(model: &DocumentModel, tree: &BlockTree) -> Vec<TodoItem> {
         ) {
             continue;
         }
-        for caps in MARKER_RE.captures_iter(&block.text) {
-            let marker = caps
-                .get(1)
-                .map(|m| m.as_str().to_lowercase())
-                .unwrap_or_default();
-            // Trim trailing comment closers so `<!-- TODO: x -->` yields "x".
-            let note = caps
-                .get(2)
-                .map(|m| {
-                    m.as_str()
-                        .trim()
-                        .trim_end_matches("-->")
-                        .trim_end_matches("*/")
-                        .trim()
-                })
-                .unwrap_or("");
-            let text = if note.is_empty() {
-                marker.to_uppercase()
-            } else {
-                note.to_string()
-            };
+        let mut found = inline_markers(&block.text);
+        found.extend(locale_markers(&block.text, model.locale));
+        for found in found {
             out.push(TodoItem {
-                text,
+                text: found.note,
                 done: false,
-                marker,
+                marker: found.marker,
+                issue: found.issue.map(|number| IssueRef {
+                    number,
+                    state: None,
+                    title: None,
+                }),
                 link: SourceRangeLink {
                     block_id: block.id,
                     range: block.range,
@@ -146,6 +193,72 @@ fn todos(model: &DocumentModel, tree: &BlockTree) -> Vec<TodoItem> {
     out
 }
 
+/// Inline `TODO`/`FIXME`/`XXX`/`HACK` markers in `text`: the marker
+/// lower-cased, the note with comment closers trimmed (or the upper-cased
+/// marker when the note is empty), and the `#123` issue of a `TODO(#123)` tag.
+/// Shared with the repo-wide TODO dashboard, which also runs it over
+/// source-code comments.
+pub fn inline_markers(text: &str) -> Vec<InlineMarker> {
+    MARKER_RE
+        .captures_iter(text)
+        .map(|caps| {
+            let marker = caps
+                .get(1)
+                .map(|m| m.as_str().to_lowercase())
+                .unwrap_or_default();
+            marker_from(&caps, marker)
+        })
+        .collect()
+}
+
+/// Markers in the document's own language (`要対応: 料金表を更新`), reported
+/// under the English kind they stand for (`todo`, `fixme`).
+pub fn locale_markers(text: &str, locale: Locale) -> Vec<InlineMarker> {
+    let Some((_, Some(re))) = LOCALE_MARKER_RES.iter().find(|(l, _)| *l == locale) else {
+        return Vec::new();
+    };
+    let markers = locale.pack().todo_markers;
+    re.captures_iter(text)
+        .map(|caps| {
+            let kind = markers
+                .iter()
+                .find(|(m, _)| *m == &caps[1])
+                .map(|(_, kind)| kind.to_string())
+                .unwrap_or_default();
+            marker_from(&caps, kind)
+        })
+        .collect()
+}
+
+/// The note and issue of a marker match: group 2 is the tag, group 3 the note.
+fn marker_from(caps: &regex::Captures, marker: String) -> InlineMarker {
+    // Trim trailing comment closers so `<!-- TODO: x -->` yields "x".
+    let note = caps
+        .get(3)
+        .map(|m| {
+            m.as_str()
+                .trim()
+                .trim_end_matches("-->")
+                .trim_end_matches("*/")
+                .trim()
+        })
+        .unwrap_or("");
+    let note = if note.is_empty() {
+        marker.to_uppercase()
+    } else {
+        note.to_string()
+    };
+    let issue = caps
+        .get(2)
+        .and_then(|tag| tag.as_str().trim().strip_prefix('#'))
+        .and_then(|n| n.parse().ok());
+    InlineMarker {
+        marker,
+        note,
+        issue,
+    }
+}
+
 /// Collect panel entries for every block classified with `class`, using the
 /// block's own text as the entry label.
 fn entries_for_class(
@@ -186,7 +299,7 @@ mod tests {
 
     fn panel_for(md: &str) -> SemanticPanel {
         let tree = BlockTree::parse(md);
-        let model = build_model(md, &tree, None);
+        let model = build_model(md, &tree, None, &Default::default(), &Default::default());
         build(&model, &tree)
     }
 
@@ -217,12 +330,61 @@ mod tests {
         assert_eq!(todo.text, "wire up retries");
     }
 
+    #[test]
+    fn locale_markers_map_to_their_kind() {
+        let md = "# 料金改定\n\n要対応(#7)：料金表を更新する\n\n要修正: 税率の表記\n";
+        let panel = panel_for(md);
+        let rows: Vec<_> = panel
+            .todos
+            .iter()
+            .map(|t| {
+                (
+                    t.marker.as_str(),
+                    t.text.as_str(),
+                    t.issue.as_ref().map(|i| i.number),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("todo", "料金表を更新する", Some(7)),
+                ("fixme", "税率の表記", None)
+            ]
+        );
+        // An English document ignores the Japanese words.
+        assert!(locale_markers("要対応: x", Locale::En).is_empty());
+    }
+
+    #[test]
+    fn inline_todos_reference_issues() {
+        let md = "# T\n\nTODO(#12): retry on 503\n\nFIXME(alice): flaky\n";
+        let mut panel = panel_for(md);
+        let issue = panel.todos[0].issue.as_ref().unwrap();
+        assert_eq!((issue.number, issue.state), (12, None));
+        assert_eq!(panel.todos[0].text, "retry on 503");
+        assert_eq!(panel.todos[1].issue, None);
+        assert_eq!(panel.todos[1].text, "flaky");
+
+        let issues =
+            IssueList::parse(r#"[{"number": 12, "title": "Retries", "state": "closed"}]"#).unwrap();
+        panel.link_issues(&issues);
+        let issue = panel.todos[0].issue.as_ref().unwrap();
+        assert_eq!(issue.state, Some(IssueState::Closed));
+        assert_eq!(issue.title.as_deref(), Some("Retries"));
+    }
+
     #[test]
     fn risks_and_open_questions_collected_from_sections() {
         let md = "# Design\n\n## Risks\n\nData loss on crash.\n\n## Open Questions\n\nWhich DB?\n";
         let panel = panel_for(md);
         assert!(panel.risks.iter().any(|e| e.text.contains("Data loss")));
-        assert!(panel.open_questions.iter().any(|e| e.text.contains("Which DB")));
+        assert!(
+            panel
+                .open_questions
+                .iter()
+                .any(|e| e.text.contains("Which DB"))
+        );
         // Links point back into the document.
         assert!(panel.risks[0].link.range.start_line >= 1);
     }
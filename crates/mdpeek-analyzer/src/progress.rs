@@ -0,0 +1,122 @@
+//! Completion state of procedure steps, kept in a local JSON file.
+//!
+//! The file maps each document's absolute path to the [`Step::key`]s of its
+//! completed steps:
+//!
+//! ```json
+//! {"/srv/runbooks/rotate-certs.md": ["Drain the node", "rollback:Restore"]}
+//! ```
+//!
+//! Reads are forgiving — a missing or corrupt file is no progress — so a bad
+//! state file never stops a document from being viewed.
+//!
+//! [`Step::key`]: crate::analyzer::procedure::Step::key
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+type Entries = BTreeMap<String, BTreeSet<String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressStore {
+    path: PathBuf,
+}
+
+impl ProgressStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keys of the completed steps of `document`.
+    pub fn load(&self, document: &Path) -> BTreeSet<String> {
+        self.entries()
+            .remove(&document_key(document))
+            .unwrap_or_default()
+    }
+
+    /// Record step `key` of `document` as done (or not done).
+    pub fn set(&self, document: &Path, key: &str, done: bool) -> io::Result<()> {
+        let mut entries = self.entries();
+        let doc = document_key(document);
+        let steps = entries.entry(doc.clone()).or_default();
+        if done {
+            steps.insert(key.to_string());
+        } else {
+            steps.remove(key);
+        }
+        if steps.is_empty() {
+            entries.remove(&doc);
+        }
+        self.save(&entries)
+    }
+
+    /// Forget all progress on `document`.
+    pub fn reset(&self, document: &Path) -> io::Result<()> {
+        let mut entries = self.entries();
+        if entries.remove(&document_key(document)).is_none() {
+            return Ok(());
+        }
+        self.save(&entries)
+    }
+
+    fn entries(&self) -> Entries {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return Entries::new();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|err| {
+            tracing::debug!("ignoring unreadable {}: {err}", self.path.display());
+            Entries::new()
+        })
+    }
+
+    fn save(&self, entries: &Entries) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(entries).map_err(io::Error::other)?;
+        fs::write(&self.path, json + "\n")
+    }
+}
+
+/// Documents are identified by absolute path, so progress follows the file
+/// whichever directory mdpeek is started from.
+fn document_key(document: &Path) -> String {
+    fs::canonicalize(document)
+        .or_else(|_| std::path::absolute(document))
+        .unwrap_or_else(|_| document.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_steps_per_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ProgressStore::new(dir.path().join("state").join("progress.json"));
+        let a = dir.path().join("a.md");
+        let b = dir.path().join("b.md");
+        assert!(store.load(&a).is_empty());
+
+        store.set(&a, "Drain", true).unwrap();
+        store.set(&a, "Renew", true).unwrap();
+        store.set(&b, "Other", true).unwrap();
+        store.set(&a, "Renew", false).unwrap();
+        assert_eq!(store.load(&a), BTreeSet::from(["Drain".to_string()]));
+        assert_eq!(store.load(&b).len(), 1);
+
+        store.reset(&b).unwrap();
+        assert!(store.load(&b).is_empty());
+
+        fs::write(store.path(), "not json").unwrap();
+        assert!(store.load(&a).is_empty());
+    }
+}
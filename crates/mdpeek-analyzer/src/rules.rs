@@ -0,0 +1,367 @@
+//! User-extensible classification rules.
+//!
+//! The built-in section keywords ([`block_class`]) and document-type
+//! heuristics ([`doctype`]) cover common English and Japanese documents. A
+//! [`ClassificationRules`] (the `[analyzer]` table of `config.toml`) adds a
+//! team's own on top:
+//!
+//! ```toml
+//! [analyzer.sections]
+//! "risk register" = "decision"
+//! "作業" = { class = "step", confidence = 0.9 }
+//!
+//! [[analyzer.doctypes]]
+//! type = "minutes"
+//! filename = "meetings/**/*.md"
+//! confidence = 0.95
+//! ```
+//!
+//! Configured rules are tried before the built-in rules of the same tier, and
+//! every result names the rule that fired ([`Classified::rule`],
+//! [`ClassifiedBlock::rule`](crate::model::ClassifiedBlock::rule)).
+//!
+//! [`block_class`]: crate::analyzer::block_class
+//! [`doctype`]: crate::analyzer::doctype
+
+use crate::analyzer::block_class;
+use crate::locale::Locale;
+use crate::model::{BlockClass, Classified, DocumentType};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Confidence of a section keyword match, built-in or configured.
+pub const SECTION_CONFIDENCE: f32 = 0.8;
+
+/// Confidence of a configured document-type rule that does not give one.
+pub const DOCTYPE_CONFIDENCE: f32 = 0.9;
+
+/// The `[analyzer]` table: section keywords and document-type rules merged
+/// with the built-in ones.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClassificationRules {
+    /// Heading keyword (a case-insensitive substring of the title) → the
+    /// class of the section it introduces. When several match, the longest
+    /// keyword wins.
+    pub sections: BTreeMap<String, SectionRule>,
+    /// Document-type rules, tried in order.
+    pub doctypes: Vec<DocTypeRule>,
+    /// Language tag (`de`, `fr-CA`) of documents whose front matter names
+    /// none; detected from the text when unset. See [`crate::locale`].
+    pub lang: Option<String>,
+}
+
+/// One `[analyzer.sections]` entry: a class, or a class and a confidence.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SectionRule {
+    Class(BlockClass),
+    Weighted { class: BlockClass, confidence: f32 },
+}
+
+impl SectionRule {
+    pub fn class(self) -> BlockClass {
+        match self {
+            SectionRule::Class(class) | SectionRule::Weighted { class, .. } => class,
+        }
+    }
+
+    pub fn confidence(self) -> f32 {
+        match self {
+            SectionRule::Class(_) => SECTION_CONFIDENCE,
+            SectionRule::Weighted { confidence, .. } => confidence,
+        }
+    }
+}
+
+/// One `[[analyzer.doctypes]]` entry. Every condition given must hold; at
+/// least one is required.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DocTypeRule {
+    #[serde(rename = "type")]
+    pub doc_type: DocumentType,
+    /// Glob matched against the trailing components of the document's path:
+    /// `*` and `?` stay within one component, `**` spans directories.
+    pub filename: Option<String>,
+    /// Keywords that must each appear in some heading (case-insensitive).
+    #[serde(default)]
+    pub headings: Vec<String>,
+    /// A front matter `key: value` pair, or a bare value of `type:`.
+    pub frontmatter: Option<String>,
+    #[serde(default = "default_doctype_confidence")]
+    pub confidence: f32,
+}
+
+fn default_doctype_confidence() -> f32 {
+    DOCTYPE_CONFIDENCE
+}
+
+/// The built-in tier a [`DocTypeRule`] competes in: that of its most specific
+/// condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    FrontMatter,
+    Filename,
+    Headings,
+}
+
+/// A section heading matched by a keyword.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionMatch {
+    pub class: BlockClass,
+    pub confidence: f32,
+    /// `analyzer.sections "risk register"` or `section keyword "risk" (en)`.
+    pub rule: String,
+}
+
+/// What a document-type rule is matched against.
+#[derive(Debug, Clone, Copy)]
+pub struct DocFacts<'a> {
+    pub filename: Option<&'a str>,
+    pub frontmatter: Option<&'a str>,
+    /// Lowercased heading titles.
+    pub titles: &'a [String],
+}
+
+impl ClassificationRules {
+    /// Check what serde cannot: the language is supported, every
+    /// document-type rule has a condition and confidences lie in `0..=1`.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(lang) = &self.lang
+            && Locale::from_tag(lang).is_none()
+        {
+            return Err(format!(
+                "analyzer.lang {lang:?}: expected one of en, ja, zh, de, fr, es"
+            ));
+        }
+        for (keyword, rule) in &self.sections {
+            if keyword.trim().is_empty() {
+                return Err("analyzer.sections: empty keyword".to_string());
+            }
+            if !(0.0..=1.0).contains(&rule.confidence()) {
+                return Err(format!(
+                    "analyzer.sections {keyword:?}: confidence must be between 0 and 1"
+                ));
+            }
+        }
+        for (i, rule) in self.doctypes.iter().enumerate() {
+            if rule.tier().is_none() {
+                return Err(format!(
+                    "analyzer.doctypes[{i}]: needs a filename, headings or frontmatter condition"
+                ));
+            }
+            if !(0.0..=1.0).contains(&rule.confidence) {
+                return Err(format!(
+                    "analyzer.doctypes[{i}]: confidence must be between 0 and 1"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The class of the section `title` introduces: configured keywords
+    /// (longest first), then the built-in ones of `locale`.
+    pub fn section(&self, title: &str, locale: Locale) -> Option<SectionMatch> {
+        let t = title.to_lowercase();
+        let configured = self
+            .sections
+            .iter()
+            .filter(|(kw, _)| !kw.is_empty() && t.contains(&kw.to_lowercase()))
+            .max_by_key(|(kw, _)| kw.chars().count());
+        if let Some((kw, rule)) = configured {
+            return Some(SectionMatch {
+                class: rule.class(),
+                confidence: rule.confidence(),
+                rule: format!("analyzer.sections {kw:?}"),
+            });
+        }
+        block_class::builtin_section(title, locale).map(|(class, kw, pack)| SectionMatch {
+            class,
+            confidence: SECTION_CONFIDENCE,
+            rule: format!("section keyword {kw:?} ({pack})"),
+        })
+    }
+
+    /// The first configured document-type rule of `tier` that matches.
+    pub fn doc_type(&self, tier: Tier, facts: &DocFacts) -> Option<Classified<DocumentType>> {
+        self.doctypes
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.tier() == Some(tier) && rule.matches(facts))
+            .map(|(i, rule)| {
+                Classified::rules(rule.doc_type, rule.confidence)
+                    .because(format!("analyzer.doctypes[{i}] ({})", rule.describe()))
+            })
+    }
+}
+
+impl DocTypeRule {
+    /// The tier of the rule's most specific condition; `None` without any.
+    pub fn tier(&self) -> Option<Tier> {
+        if self.frontmatter.is_some() {
+            Some(Tier::FrontMatter)
+        } else if self.filename.is_some() {
+            Some(Tier::Filename)
+        } else if !self.headings.is_empty() {
+            Some(Tier::Headings)
+        } else {
+            None
+        }
+    }
+
+    /// Whether every condition of the rule holds for the document.
+    pub fn matches(&self, facts: &DocFacts) -> bool {
+        if let Some(pair) = &self.frontmatter
+            && !facts
+                .frontmatter
+                .is_some_and(|fm| frontmatter_has(fm, pair))
+        {
+            return false;
+        }
+        if let Some(glob) = &self.filename
+            && !facts.filename.is_some_and(|name| glob_matches(glob, name))
+        {
+            return false;
+        }
+        self.headings.iter().all(|kw| {
+            let kw = kw.to_lowercase();
+            facts.titles.iter().any(|t| t.contains(&kw))
+        })
+    }
+
+    /// The rule's conditions, e.g. `filename "meetings/*.md", headings
+    /// ["agenda"]`.
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(pair) = &self.frontmatter {
+            parts.push(format!("frontmatter {pair:?}"));
+        }
+        if let Some(glob) = &self.filename {
+            parts.push(format!("filename {glob:?}"));
+        }
+        if !self.headings.is_empty() {
+            parts.push(format!("headings {:?}", self.headings));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Whether raw front matter has `pair` (`key: value`, or a bare value of the
+/// `type` key). Keys and values compare case-insensitively, ignoring quotes.
+fn frontmatter_has(frontmatter: &str, pair: &str) -> bool {
+    let (want_key, want_value) = match pair.split_once(':') {
+        Some((key, value)) => (key.trim().to_lowercase(), value),
+        None => ("type".to_string(), pair),
+    };
+    let want_value = want_value.trim().trim_matches(['"', '\'']).to_lowercase();
+    frontmatter.lines().any(|line| {
+        let Some((key, value)) = line.trim().split_once([':', '=']) else {
+            return false;
+        };
+        key.trim().trim_matches('"').to_lowercase() == want_key
+            && value.trim().trim_matches(['"', '\'']).to_lowercase() == want_value
+    })
+}
+
+/// Match `glob` against the trailing path components of `path` (`\` is
+/// treated as `/`).
+pub fn glob_matches(glob: &str, path: &str) -> bool {
+    let mut pattern = String::from("(?:^|/)");
+    let mut chars = glob.trim_start_matches("./").chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches no directory at all.
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).is_ok_and(|re| re.is_match(&path.replace('\\', "/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_trailing_path_components() {
+        assert!(glob_matches("*.md", "/home/me/notes/a.md"));
+        assert!(glob_matches("meetings/*.md", "docs/meetings/2024-10-16.md"));
+        assert!(!glob_matches("meetings/*.md", "docs/meetings/2024/10.md"));
+        assert!(glob_matches("meetings/**/*.md", "docs/meetings/2024/10.md"));
+        assert!(glob_matches("meetings/**/*.md", "meetings/10.md"));
+        assert!(glob_matches("RFC-????.md", "rfcs\\RFC-0042.md"));
+        assert!(!glob_matches("notes.md", "my-notes.md"));
+    }
+
+    #[test]
+    fn config_rules_deserialize_and_validate() {
+        let rules: ClassificationRules = toml::from_str(
+            r#"
+            [sections]
+            "risk register" = "decision"
+            "作業" = { class = "step", confidence = 0.9 }
+
+            [[doctypes]]
+            type = "minutes"
+            filename = "meetings/*.md"
+
+            [[doctypes]]
+            type = "design_doc"
+            headings = ["motivation", "alternatives"]
+            confidence = 0.75
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rules.sections["作業"].class(), BlockClass::Step);
+        assert_eq!(
+            rules.sections["risk register"].confidence(),
+            SECTION_CONFIDENCE
+        );
+        assert_eq!(rules.doctypes[0].confidence, DOCTYPE_CONFIDENCE);
+        assert_eq!(rules.doctypes[1].tier(), Some(Tier::Headings));
+        assert!(rules.validate().is_ok());
+
+        let empty: ClassificationRules = toml::from_str("[[doctypes]]\ntype = \"faq\"\n").unwrap();
+        assert!(
+            empty
+                .validate()
+                .unwrap_err()
+                .contains("analyzer.doctypes[0]")
+        );
+    }
+
+    #[test]
+    fn doctype_rules_name_the_rule_that_fired() {
+        let rules: ClassificationRules = toml::from_str(
+            "[[doctypes]]\ntype = \"investigation\"\nfrontmatter = \"category: retro\"\n",
+        )
+        .unwrap();
+        let facts = DocFacts {
+            filename: None,
+            frontmatter: Some("title: Q3\ncategory: \"Retro\""),
+            titles: &[],
+        };
+        let c = rules
+            .doc_type(Tier::FrontMatter, &facts)
+            .expect("rule fires");
+        assert_eq!(c.value, DocumentType::Investigation);
+        assert_eq!(
+            c.rule.as_deref(),
+            Some("analyzer.doctypes[0] (frontmatter \"category: retro\")")
+        );
+        assert!(rules.doc_type(Tier::Filename, &facts).is_none());
+    }
+}
@@ -0,0 +1,331 @@
+//! Document statistics.
+//!
+//! Size metrics over the [`BlockTree`] — word and character counts, reading
+//! time, per-section sizes, how much of the document is code, link/image counts
+//! and heading depth — so writers can spot bloated sections at a glance.
+//!
+//! Counting is CJK-aware: Japanese and Chinese have no spaces between words, so
+//! every Han/kana character counts as one word (Hangul is spaced like English
+//! and counted by whitespace). Reading time uses [`WORDS_PER_MINUTE`] for
+//! spaced words and [`CJK_CHARS_PER_MINUTE`] for CJK characters; code blocks
+//! are skimmed rather than read and do not add to it.
+
+use crate::panel::SourceRangeLink;
+use mdpeek_gfm::MarkdownOptions;
+use mdpeek_gfm::cjk::is_east_asian_wide;
+use mdpeek_parser::{Block, BlockKind, BlockTree, SourceRange};
+use pulldown_cmark::{Event, Parser, Tag};
+use serde::Serialize;
+
+/// Reading speed for space-separated prose.
+pub const WORDS_PER_MINUTE: f64 = 200.0;
+/// Reading speed for Japanese/Chinese text, in characters.
+pub const CJK_CHARS_PER_MINUTE: f64 = 500.0;
+
+/// A section is flagged as bloated when it holds at least this many words…
+const BLOATED_MIN_WORDS: usize = 300;
+/// …and more than this multiple of the average section.
+const BLOATED_FACTOR: f64 = 2.0;
+
+/// Whole-document metrics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocumentStats {
+    /// Prose words (headings, paragraphs, lists, tables); each CJK character
+    /// counts as one word.
+    pub words: usize,
+    /// Non-whitespace prose characters.
+    pub characters: usize,
+    /// Estimated reading time in whole minutes (rounded up; 0 when empty).
+    pub reading_minutes: u32,
+    pub code_blocks: usize,
+    pub code_lines: usize,
+    /// Share of the content that is code: code characters / (code + prose
+    /// characters), from 0.0 to 1.0.
+    pub code_ratio: f64,
+    pub links: usize,
+    pub images: usize,
+    pub headings: usize,
+    /// Deepest heading level used (0 without headings).
+    pub max_heading_depth: u8,
+    /// Per-section sizes in document order.
+    pub sections: Vec<SectionStats>,
+}
+
+/// Size of one section: a heading and the content up to the next heading of
+/// any level (nested subsections are sections of their own).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionStats {
+    /// Heading text; empty for content before the first heading.
+    pub title: String,
+    /// Heading level; 0 for content before the first heading.
+    pub level: u8,
+    pub words: usize,
+    pub characters: usize,
+    pub code_lines: usize,
+    /// Much larger than the document's other sections.
+    pub bloated: bool,
+    /// The section's heading; `None` for content before the first heading.
+    pub link: Option<SourceRangeLink>,
+}
+
+/// Word, CJK-character and character counts of one piece of prose.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Counts {
+    pub(crate) words: usize,
+    pub(crate) cjk: usize,
+    pub(crate) characters: usize,
+}
+
+impl Counts {
+    fn add(&mut self, other: Counts) {
+        self.words += other.words;
+        self.cjk += other.cjk;
+        self.characters += other.characters;
+    }
+}
+
+/// Compute the statistics of `markdown`, already parsed into `tree` with the
+/// document's effective `options`.
+pub fn compute(markdown: &str, tree: &BlockTree, options: &MarkdownOptions) -> DocumentStats {
+    let mut stats = DocumentStats::default();
+    let mut prose = Counts::default();
+    let mut code_chars = 0;
+    let mut sections = vec![SectionStats::preamble()];
+
+    for block in tree.iter() {
+        match &block.kind {
+            BlockKind::Heading { level } => {
+                stats.headings += 1;
+                stats.max_heading_depth = stats.max_heading_depth.max(*level);
+                sections.push(SectionStats::starting_at(block, *level));
+            }
+            BlockKind::CodeBlock { .. } => {
+                let lines = block.text.lines().count();
+                stats.code_blocks += 1;
+                stats.code_lines += lines;
+                code_chars += non_whitespace(&block.text);
+                if let Some(section) = sections.last_mut() {
+                    section.code_lines += lines;
+                }
+                continue;
+            }
+            BlockKind::MetadataBlock | BlockKind::HtmlBlock => continue,
+            _ => {}
+        }
+        let counts = match block.kind {
+            BlockKind::Table => count(&table_text(markdown, block.range)),
+            _ => count(&block.text),
+        };
+        prose.add(counts);
+        if let Some(section) = sections.last_mut() {
+            section.words += counts.words;
+            section.characters += counts.characters;
+        }
+    }
+
+    for event in Parser::new_ext(markdown, options.parser_options()) {
+        match event {
+            Event::Start(Tag::Link { .. }) => stats.links += 1,
+            Event::Start(Tag::Image { .. }) => stats.images += 1,
+            _ => {}
+        }
+    }
+
+    stats.words = prose.words;
+    stats.characters = prose.characters;
+    stats.reading_minutes = reading_minutes(prose);
+    let content = prose.characters + code_chars;
+    if content > 0 {
+        stats.code_ratio = code_chars as f64 / content as f64;
+    }
+    // Drop an empty preamble (documents usually open with a heading).
+    if sections[0].words == 0 && sections[0].code_lines == 0 {
+        sections.remove(0);
+    }
+    flag_bloated(&mut sections);
+    stats.sections = sections;
+    stats
+}
+
+impl SectionStats {
+    fn preamble() -> Self {
+        SectionStats {
+            title: String::new(),
+            level: 0,
+            words: 0,
+            characters: 0,
+            code_lines: 0,
+            bloated: false,
+            link: None,
+        }
+    }
+
+    fn starting_at(heading: &Block, level: u8) -> Self {
+        SectionStats {
+            title: heading.text.clone(),
+            level,
+            link: Some(SourceRangeLink {
+                block_id: heading.id,
+                range: heading.range,
+            }),
+            ..Self::preamble()
+        }
+    }
+}
+
+/// Mark sections far above the average section size.
+fn flag_bloated(sections: &mut [SectionStats]) {
+    if sections.len() < 2 {
+        return;
+    }
+    let total: usize = sections.iter().map(|s| s.words).sum();
+    let average = total as f64 / sections.len() as f64;
+    for section in sections {
+        section.bloated =
+            section.words >= BLOATED_MIN_WORDS && section.words as f64 > average * BLOATED_FACTOR;
+    }
+}
+
+fn reading_minutes(prose: Counts) -> u32 {
+    let spaced = (prose.words - prose.cjk) as f64;
+    let minutes = spaced / WORDS_PER_MINUTE + prose.cjk as f64 / CJK_CHARS_PER_MINUTE;
+    minutes.ceil() as u32
+}
+
+/// Count words in `text`: whitespace-separated tokens containing a letter or
+/// digit, except that each CJK character is a word on its own.
+pub(crate) fn count(text: &str) -> Counts {
+    let mut counts = Counts::default();
+    for token in text.split_whitespace() {
+        // Whether the current run of non-CJK characters already has a letter.
+        let mut in_word = false;
+        for c in token.chars() {
+            counts.characters += 1;
+            if is_east_asian_wide(c) {
+                if c.is_alphanumeric() {
+                    counts.words += 1;
+                    counts.cjk += 1;
+                }
+                in_word = false;
+            } else if c.is_alphanumeric() && !in_word {
+                counts.words += 1;
+                in_word = true;
+            }
+        }
+    }
+    counts
+}
+
+fn non_whitespace(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// The cell text of a table, read back from the source: the block tree folds
+/// cells together without separators, which would merge words across cells.
+fn table_text(markdown: &str, range: SourceRange) -> String {
+    let first = range.start_line as usize - 1;
+    // The range ends before column 1 of the line after the table, if any.
+    let last = if range.end_column == 1 {
+        range.end_line as usize - 1
+    } else {
+        range.end_line as usize
+    };
+    markdown
+        .lines()
+        .take(last)
+        .skip(first)
+        .filter(|line| !is_delimiter_row(line))
+        .map(|line| line.replace('|', " "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `|---|:--:|` — the row separating the header from the body.
+fn is_delimiter_row(line: &str) -> bool {
+    let line = line.trim();
+    line.contains('-') && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats_of(md: &str) -> DocumentStats {
+        let options = MarkdownOptions::default();
+        compute(md, &BlockTree::parse(md), &options)
+    }
+
+    #[test]
+    fn counts_words_cjk_aware() {
+        assert_eq!(count("Hello, world!").words, 2);
+        assert_eq!(count("don't stop — 42").words, 3);
+        // Each kanji/kana is a word; the embedded Latin run is one more.
+        let ja = count("日本語のAPIです");
+        assert_eq!((ja.words, ja.cjk, ja.characters), (7, 6, 9));
+        // Hangul is spaced like English.
+        assert_eq!(count("한국어 문장").words, 2);
+    }
+
+    #[test]
+    fn document_metrics() {
+        let md = "---\ntitle: x\n---\n# Guide\n\n\
+                  See [docs](https://example.com) and ![logo](logo.png).\n\n\
+                  ## Usage\n\n```sh\nmake\nmake install\n```\n\n\
+                  ### Flags\n\n| Flag | Meaning |\n|---|---|\n| `-v` | verbose output |\n";
+        let s = stats_of(md);
+        assert_eq!(s.headings, 3);
+        assert_eq!(s.max_heading_depth, 3);
+        assert_eq!(s.links, 1);
+        assert_eq!(s.images, 1);
+        assert_eq!((s.code_blocks, s.code_lines), (1, 2));
+        // Guide, Usage, Flags + "See docs and logo." + table cells.
+        assert_eq!(s.words, 3 + 4 + 5);
+        assert_eq!(s.reading_minutes, 1);
+        assert!(s.code_ratio > 0.0 && s.code_ratio < 0.5, "{}", s.code_ratio);
+
+        let sections: Vec<(&str, u8, usize, usize)> = s
+            .sections
+            .iter()
+            .map(|x| (x.title.as_str(), x.level, x.words, x.code_lines))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![("Guide", 1, 5, 0), ("Usage", 2, 1, 2), ("Flags", 3, 6, 0)]
+        );
+        assert_eq!(s.sections[1].link.as_ref().unwrap().range.start_line, 8);
+    }
+
+    #[test]
+    fn content_before_the_first_heading_is_its_own_section() {
+        let s = stats_of("Intro text here.\n\n# Title\n\nBody.\n");
+        assert_eq!(s.sections[0].title, "");
+        assert_eq!(s.sections[0].level, 0);
+        assert_eq!(s.sections[0].words, 3);
+        assert!(s.sections[0].link.is_none());
+        assert_eq!(s.sections.len(), 2);
+    }
+
+    #[test]
+    fn flags_bloated_sections() {
+        let long = "word ".repeat(600);
+        let md = format!("# A\n\nShort.\n\n# B\n\n{long}\n\n# C\n\nAlso short.\n");
+        let s = stats_of(&md);
+        let bloated: Vec<&str> = s
+            .sections
+            .iter()
+            .filter(|x| x.bloated)
+            .map(|x| x.title.as_str())
+            .collect();
+        assert_eq!(bloated, vec!["B"]);
+        assert_eq!(s.reading_minutes, 4);
+    }
+
+    #[test]
+    fn japanese_reading_time_uses_characters() {
+        let md = format!("# 概要\n\n{}\n", "読みやすい文章です。".repeat(100));
+        let s = stats_of(&md);
+        // 2 + 900 CJK letters (punctuation is not a word) at 500/min.
+        assert_eq!(s.words, 902);
+        assert_eq!(s.reading_minutes, 2);
+    }
+}
@@ -0,0 +1,94 @@
+//! Changelog ↔ git tags: every release in a Keep a Changelog file should have
+//! been tagged.
+//!
+//! Tags are matched on the version alone, so `1.2.0`, `v1.2.0` and
+//! `mdpeek-v1.2.0` all tag release `1.2.0`. `Unreleased` and yanked releases
+//! are not expected to have a tag.
+
+use mdpeek_analyzer::analyzer::changelog::{Changelog, Problem, ProblemKind};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+/// The changelog's own validation problems plus its untagged releases, in
+/// document order. Tags are only compared inside a git checkout.
+pub fn check_changelog(changelog: &Changelog, root: &Path) -> Vec<Problem> {
+    let mut problems = changelog.problems.clone();
+    if let Some(tags) = git_tags(root) {
+        problems.extend(untagged_releases(changelog, &tags));
+        problems.sort_by_key(|p| p.link.range.start_line);
+    }
+    problems
+}
+
+/// Releases with no tag among `tags`.
+pub fn untagged_releases(changelog: &Changelog, tags: &BTreeSet<String>) -> Vec<Problem> {
+    let tagged: BTreeSet<&str> = tags.iter().map(|t| tag_version(t)).collect();
+    changelog
+        .releases
+        .iter()
+        .filter(|r| !r.unreleased && !r.yanked && !tagged.contains(r.version.as_str()))
+        .map(|r| Problem {
+            kind: ProblemKind::Untagged,
+            message: format!("{} has no git tag", r.version),
+            link: r.link.clone(),
+        })
+        .collect()
+}
+
+/// The version a tag names: what follows a `v` prefix, or a `name-v` /
+/// `name@` prefix for tags of one crate in a workspace.
+fn tag_version(tag: &str) -> &str {
+    for (i, _) in tag.char_indices() {
+        if i > 0 && !tag[..i].ends_with(['-', '/', '@']) {
+            continue;
+        }
+        let rest = &tag[i..];
+        let rest = rest.strip_prefix('v').unwrap_or(rest);
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return rest;
+        }
+    }
+    tag
+}
+
+/// Tags of the repository at `root`; `None` outside a git checkout.
+fn git_tags(root: &Path) -> Option<BTreeSet<String>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["tag", "--list"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdpeek_parser::BlockTree;
+
+    #[test]
+    fn releases_without_a_matching_tag() {
+        let md = "# Changelog\n\n## [Unreleased]\n\n\
+                  ## [1.1.0-rc.1] - 2024-03-01\n\n## [1.0.0] - 2024-02-01\n\n\
+                  ## [0.9.0] - 2024-01-01\n\n## [0.1.0] - 2023-01-01 [YANKED]\n";
+        let changelog = mdpeek_analyzer::analyzer::changelog::extract(&BlockTree::parse(md));
+        let tags = BTreeSet::from(["v1.1.0-rc.1".to_string(), "mdpeek-v1.0.0".to_string()]);
+        let untagged: Vec<String> = untagged_releases(&changelog, &tags)
+            .into_iter()
+            .map(|p| p.message)
+            .collect();
+        assert_eq!(untagged, vec!["0.9.0 has no git tag"]);
+    }
+}
@@ -0,0 +1,623 @@
+//! README ↔ code consistency.
+//!
+//! One pass over the document's events checks three things against a [`Repo`]:
+//!
+//! * **Paths** — inline code spans that look like repository paths
+//!   (`src/main.rs`, `docs/`) and relative link targets must exist, relative to
+//!   the document's directory or the repository root.
+//! * **Commands** — in shell blocks (per [`code::intent`]), `cargo run --bin X`
+//!   must name one of the crate's binaries, and `--flag`s passed to a binary
+//!   (directly or after `cargo run … --`) must be declared by its clap parser.
+//! * **Config** — `toml` / `json` snippets that claim to be configuration (the
+//!   enclosing heading, the preceding paragraph or the fence info mentions
+//!   "config") must be accepted by the repo's config validator.
+//! * **Syntax** — every JSON / YAML / TOML block must parse (per
+//!   [`snippet::check`]); a block with a syntax error is not also run through
+//!   the config validator.
+//!
+//! With an issue export on the [`Repo`], [`issues::check_todos`] runs too; with
+//! a linter, its warnings and errors are reported as [`FindingKind::Prose`].
+//!
+//! Each check is skipped when the repo side is undetectable (no binaries, no
+//! flags, no validator), so a document is never flagged for what the checker
+//! simply cannot see.
+
+use crate::issues;
+use crate::repo::{ConfigFormat, Repo};
+use crate::{Finding, FindingKind};
+use mdpeek_analyzer::analyzer::code::{self, CodeIntent};
+use mdpeek_analyzer::analyzer::snippet;
+use mdpeek_analyzer::lint::{Diagnostic, Severity};
+use mdpeek_gfm::MarkdownOptions;
+use mdpeek_parser::{BlockTree, LineIndex};
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+
+/// Placeholder prefixes that are never real paths.
+const PLACEHOLDERS: &[&str] = &["path/to/", "your/", "foo/", "<"];
+
+/// Check `markdown` (stored at `doc_path`) against `repo`, in document order.
+pub fn check_document(
+    markdown: &str,
+    doc_path: &Path,
+    repo: &Repo,
+    options: &MarkdownOptions,
+) -> Vec<Finding> {
+    let options = options.for_document(markdown);
+    let line_index = LineIndex::new(markdown);
+    let doc_dir = doc_path.parent().unwrap_or(Path::new(""));
+    let tree = BlockTree::parse_with_options(markdown, options.parser_options());
+    let snippet_errors = snippet::check(markdown, &tree);
+    let mut checker = Checker {
+        source: markdown,
+        line_index: &line_index,
+        doc_dir,
+        repo,
+        malformed: snippet_errors
+            .iter()
+            .map(|e| e.link.range.start_line)
+            .collect(),
+        findings: snippet_errors
+            .into_iter()
+            .map(|e| Finding {
+                kind: FindingKind::InvalidSnippet,
+                message: format!("{} snippet does not parse: {}", e.format, e.message),
+                range: e.range,
+            })
+            .collect(),
+    };
+
+    // Heading texts by level (index 0 = h1), the last paragraph's text, and
+    // the text of the heading/paragraph currently being read.
+    let mut headings: Vec<String> = Vec::new();
+    let mut last_paragraph = String::new();
+    let mut reading: Option<String> = None;
+    // Fence info and byte range of the code block being read.
+    let mut code: Option<(String, Range<usize>)> = None;
+    let mut code_text = String::new();
+
+    let parser = Parser::new_ext(markdown, options.parser_options()).into_offset_iter();
+    for (event, range) in parser {
+        match event {
+            Event::Start(Tag::Heading { .. }) | Event::Start(Tag::Paragraph) => {
+                reading = Some(String::new());
+            }
+            Event::End(TagEnd::Heading(level)) => {
+                let depth = level as usize;
+                headings.truncate(depth - 1);
+                headings.resize(depth - 1, String::new());
+                headings.push(reading.take().unwrap_or_default());
+                last_paragraph.clear();
+            }
+            Event::End(TagEnd::Paragraph) => {
+                last_paragraph = reading.take().unwrap_or_default();
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((info, range));
+                code_text.clear();
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((info, block)) = code.take() {
+                    let claims_config = mentions_config(&info)
+                        || mentions_config(&last_paragraph)
+                        || headings.iter().any(|h| mentions_config(h));
+                    checker.code_block(&info, &code_text, block, claims_config);
+                }
+                last_paragraph.clear();
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => checker.link(&dest_url, range),
+            Event::Code(text) => {
+                if let Some(buf) = reading.as_mut() {
+                    buf.push_str(&text);
+                }
+                checker.code_span(&text, range);
+            }
+            Event::Text(text) => {
+                if code.is_some() {
+                    code_text.push_str(&text);
+                } else if let Some(buf) = reading.as_mut() {
+                    buf.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(buf) = reading.as_mut() {
+                    buf.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut findings = checker.findings;
+    if let Some(issues) = repo.issues() {
+        findings.extend(issues::check_todos(
+            markdown,
+            &options,
+            repo.rules(),
+            issues,
+        ));
+    }
+    if let Some(linter) = repo.linter() {
+        findings.extend(
+            linter
+                .check(markdown, &tree, &options)
+                .into_iter()
+                .filter(|d| d.severity >= Severity::Warning)
+                .map(|d| Finding {
+                    kind: FindingKind::Prose,
+                    message: prose_message(&d),
+                    range: d.range,
+                }),
+        );
+    }
+    findings.sort_by_key(|f| (f.range.start_line, f.range.start_column));
+    findings
+}
+
+/// `Sentence has 41 words (limit 35) [sentence-length, warning]`, with the
+/// suggested fix when there is one.
+fn prose_message(d: &Diagnostic) -> String {
+    let fix = d
+        .fix
+        .as_ref()
+        .map_or(String::new(), |f| format!(" (fix: '{f}')"));
+    format!("{}{fix} [{}, {}]", d.message, d.rule, d.severity.as_str())
+}
+
+struct Checker<'a> {
+    source: &'a str,
+    line_index: &'a LineIndex,
+    doc_dir: &'a Path,
+    repo: &'a Repo,
+    /// Start lines of the code blocks with a syntax error.
+    malformed: HashSet<u32>,
+    findings: Vec<Finding>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, kind: FindingKind, message: String, range: Range<usize>) {
+        self.findings.push(Finding {
+            kind,
+            message,
+            range: self.line_index.source_range(range),
+        });
+    }
+
+    /// Whether `rel` exists next to the document or under the repository root.
+    fn exists(&self, rel: &str) -> bool {
+        let rel = rel.trim_start_matches("./");
+        self.doc_dir.join(rel).exists() || self.repo.root.join(rel).exists()
+    }
+
+    fn code_span(&mut self, text: &str, range: Range<usize>) {
+        if looks_like_path(text) && !self.exists(text) {
+            self.report(
+                FindingKind::MissingPath,
+                format!("`{text}` does not exist"),
+                range,
+            );
+        }
+    }
+
+    fn link(&mut self, url: &str, range: Range<usize>) {
+        let Some(target) = relative_link_target(url) else {
+            return;
+        };
+        if !self.exists(&target) {
+            self.report(
+                FindingKind::MissingPath,
+                format!("link target `{target}` does not exist"),
+                range,
+            );
+        }
+    }
+
+    fn code_block(&mut self, info: &str, text: &str, block: Range<usize>, claims_config: bool) {
+        let lang = info.split_whitespace().next();
+        match code::intent(lang, text) {
+            CodeIntent::Shell => self.shell_block(block),
+            CodeIntent::Toml if claims_config => self.config(ConfigFormat::Toml, text, block),
+            CodeIntent::Json if claims_config => self.config(ConfigFormat::Json, text, block),
+            _ => {}
+        }
+    }
+
+    fn config(&mut self, format: ConfigFormat, text: &str, block: Range<usize>) {
+        if self
+            .malformed
+            .contains(&self.line_index.line_col(block.start).0)
+        {
+            return;
+        }
+        if let Some(Err(err)) = self.repo.validate_config(format, text) {
+            let err = err.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            self.report(
+                FindingKind::InvalidConfig,
+                format!("config snippet does not parse: {}", err.trim()),
+                block,
+            );
+        }
+    }
+
+    fn shell_block(&mut self, block: Range<usize>) {
+        for command in commands(self.source, block) {
+            self.command(&command);
+        }
+    }
+
+    /// Check one simple command (tokens with their byte ranges).
+    fn command(&mut self, tokens: &[(String, Range<usize>)]) {
+        // Skip `VAR=value` prefixes.
+        let tokens: Vec<&(String, Range<usize>)> = tokens
+            .iter()
+            .skip_while(|(t, _)| t.contains('=') && !t.starts_with('-'))
+            .collect();
+        let Some((program, _)) = tokens.first() else {
+            return;
+        };
+        let words: Vec<&str> = tokens.iter().map(|(t, _)| t.as_str()).collect();
+        if *program == "cargo" && matches!(words.get(1), Some(&"run") | Some(&"install")) {
+            let mut iter = tokens.iter().enumerate().skip(2);
+            while let Some((i, (token, range))) = iter.next() {
+                if token == "--" {
+                    self.flags(&tokens[i + 1..]);
+                    break;
+                }
+                let bin = if token == "--bin" {
+                    iter.next()
+                        .map(|(_, (name, range))| (name.as_str(), range.clone()))
+                } else {
+                    token
+                        .strip_prefix("--bin=")
+                        .map(|name| (name, range.clone()))
+                };
+                if let Some((name, range)) = bin
+                    && !self.repo.binaries.is_empty()
+                    && !self.repo.binaries.contains(name)
+                {
+                    self.report(
+                        FindingKind::UnknownBinary,
+                        format!("`{name}` is not a binary of this repository"),
+                        range,
+                    );
+                }
+            }
+            return;
+        }
+        let name = program.rsplit('/').next().unwrap_or(program);
+        if self.repo.binaries.contains(name) {
+            self.flags(&tokens[1..]);
+        }
+    }
+
+    fn flags(&mut self, args: &[&(String, Range<usize>)]) {
+        if self.repo.flags.is_empty() {
+            return;
+        }
+        for (arg, range) in args {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let flag = flag.split('=').next().unwrap_or(flag);
+            let well_formed =
+                !flag.is_empty() && flag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if well_formed && !self.repo.flags.contains(flag) {
+                self.report(
+                    FindingKind::UnknownFlag,
+                    format!("`--{flag}` is not a known flag"),
+                    range.clone(),
+                );
+            }
+        }
+    }
+}
+
+fn mentions_config(text: &str) -> bool {
+    text.to_lowercase().contains("config")
+}
+
+/// Whether an inline code span reads as a repository path: a single token of
+/// path characters containing `/`, whose last segment has an extension or
+/// which ends in `/`. Home/absolute paths, domains and placeholders are not.
+fn looks_like_path(text: &str) -> bool {
+    let path_chars = text
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '/' | '.' | '_' | '-'));
+    if !path_chars || !text.contains('/') || text.starts_with('/') || text.contains("//") {
+        return false;
+    }
+    if PLACEHOLDERS.iter().any(|p| text.starts_with(p)) {
+        return false;
+    }
+    let first = text.split('/').next().unwrap_or("");
+    if first.contains('.') && !first.starts_with('.') {
+        // `example.com/x`
+        return false;
+    }
+    let last = text.rsplit('/').next().unwrap_or("");
+    text.ends_with('/')
+        || last
+            .rsplit_once('.')
+            .is_some_and(|(stem, ext)| !stem.is_empty() && !ext.is_empty())
+}
+
+/// The file a relative link points at, without fragment or query; `None` for
+/// URLs with a scheme, absolute paths and pure fragments.
+fn relative_link_target(url: &str) -> Option<String> {
+    if url.is_empty() || url.starts_with('#') || url.starts_with('/') {
+        return None;
+    }
+    let scheme = url
+        .split_once(':')
+        .is_some_and(|(s, _)| !s.is_empty() && !s.contains('/'));
+    if scheme {
+        return None;
+    }
+    let target = url.split(['#', '?']).next().unwrap_or("");
+    (!target.is_empty()).then(|| target.replace("%20", " "))
+}
+
+/// The simple commands of a shell block at `block` in `source`: fence lines
+/// dropped, `$ ` prompts and `#` comments stripped, `\` continuations joined,
+/// and split on `&&`, `||`, `|` and `;`. Tokens keep their byte ranges.
+fn commands(source: &str, block: Range<usize>) -> Vec<Vec<(String, Range<usize>)>> {
+    let text = &source[block.clone()];
+    let mut out = Vec::new();
+    let mut current: Vec<(String, Range<usize>)> = Vec::new();
+    let mut offset = block.start;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let body = line.trim_end_matches(['\n', '\r']);
+        let trimmed = body.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            continue;
+        }
+        let indent = body.len() - trimmed.len();
+        let (body, shift) = match trimmed.strip_prefix("$ ") {
+            Some(rest) => (rest, indent + 2),
+            None => (trimmed, indent),
+        };
+        let body = match body.find(" #") {
+            Some(at) => &body[..at],
+            None if body.starts_with('#') => "",
+            None => body,
+        };
+        let (body, continued) = match body.trim_end().strip_suffix('\\') {
+            Some(rest) => (rest, true),
+            None => (body, false),
+        };
+        let mut pos = 0;
+        for word in body.split_whitespace() {
+            let at = pos + body[pos..].find(word).unwrap_or(0);
+            pos = at + word.len();
+            if matches!(word, "&&" | "||" | "|" | ";") {
+                out.push(std::mem::take(&mut current));
+                continue;
+            }
+            let begin = start + shift + at;
+            current.push((
+                word.trim_matches(['"', '\'']).to_string(),
+                begin..begin + word.len(),
+            ));
+        }
+        if !continued {
+            out.push(std::mem::take(&mut current));
+        }
+    }
+    out.push(current);
+    out.retain(|c| !c.is_empty());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> (tempfile::TempDir, Repo) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"tool\"\n\n[[bin]]\nname = \"tool\"\npath = \"src/main.rs\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/main.rs"),
+            "#[arg(long)]\npub port: u16,\n#[arg(short, long = \"no-watch\")]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/guide.md"), "# Guide\n").unwrap();
+        let repo = Repo::discover(root).with_config_validator(|format, text| match format {
+            ConfigFormat::Toml => text
+                .parse::<toml::Table>()
+                .map_err(|e| e.to_string())
+                .and_then(|t| match t.keys().find(|k| *k != "port") {
+                    Some(k) => Err(format!("unknown field `{k}`")),
+                    None => Ok(()),
+                }),
+            ConfigFormat::Json => Ok(()),
+        });
+        (dir, repo)
+    }
+
+    fn check(repo: &Repo, md: &str) -> Vec<(FindingKind, u32, String)> {
+        let doc = repo.root.join("README.md");
+        check_document(md, &doc, repo, &MarkdownOptions::default())
+            .into_iter()
+            .map(|f| (f.kind, f.range.start_line, f.message))
+            .collect()
+    }
+
+    #[test]
+    fn reports_missing_paths_in_code_spans_and_links() {
+        let (_dir, repo) = fixture();
+        let md = "See `src/main.rs`, `src/gone.rs` and `docs/`.\n\n\
+                  [guide](docs/guide.md#intro), [old](docs/old.md), [web](https://x.test/a.md)\n\n\
+                  Not paths: `~/.config/tool/config.toml`, `example.com/a.js`, `path/to/file.md`, `a / b`.\n";
+        assert_eq!(
+            check(&repo, md),
+            vec![
+                (
+                    FindingKind::MissingPath,
+                    1,
+                    "`src/gone.rs` does not exist".to_string()
+                ),
+                (
+                    FindingKind::MissingPath,
+                    3,
+                    "link target `docs/old.md` does not exist".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_binaries_and_flags_in_shell_blocks() {
+        let (_dir, repo) = fixture();
+        let md = "```sh\n\
+                  $ cargo run --bin tool -- --port 8080 --verbose\n\
+                  cargo run --bin=other\n\
+                  RUST_LOG=debug tool --no-watch --help # --bogus in a comment\n\
+                  tool \\\n  --colour && ./target/release/tool --port=1\n\
+                  ```\n";
+        let found = check(&repo, md);
+        assert_eq!(
+            found,
+            vec![
+                (
+                    FindingKind::UnknownFlag,
+                    2,
+                    "`--verbose` is not a known flag".to_string()
+                ),
+                (
+                    FindingKind::UnknownBinary,
+                    3,
+                    "`other` is not a binary of this repository".to_string()
+                ),
+                (
+                    FindingKind::UnknownFlag,
+                    6,
+                    "`--colour` is not a known flag".to_string()
+                ),
+            ]
+        );
+        let range = check_document(
+            md,
+            &repo.root.join("README.md"),
+            &repo,
+            &MarkdownOptions::default(),
+        )[0]
+        .range;
+        assert_eq!((range.start_column, range.end_column), (39, 48));
+    }
+
+    #[test]
+    fn validates_only_snippets_that_claim_to_be_config() {
+        let (_dir, repo) = fixture();
+        let md = "# Configuration\n\n```toml\nport = 1\n```\n\n```toml\nhost = \"x\"\n```\n\n\
+                  # Cargo\n\n```toml\nhost = \"x\"\n```\n\nPut this in your config file:\n\n```toml\nport = \n```\n";
+        let found = check(&repo, md);
+        assert_eq!(found.len(), 2, "{found:?}");
+        assert_eq!(found[0].0, FindingKind::InvalidConfig);
+        assert_eq!(found[0].1, 7);
+        assert!(found[0].2.contains("unknown field `host`"));
+        // A snippet that does not parse at all is a syntax error instead.
+        assert_eq!(found[1].0, FindingKind::InvalidSnippet);
+        assert_eq!(found[1].1, 20);
+    }
+
+    #[test]
+    fn reports_prose_warnings_but_not_suggestions() {
+        use mdpeek_analyzer::lint::{LintConfig, Linter};
+        let dir = tempfile::tempdir().unwrap();
+        let repo =
+            Repo::discover(dir.path()).with_linter(Linter::new(&LintConfig::default()).unwrap());
+        // "was written" is a passive-voice suggestion; the repetition a warning.
+        let md = "# Notes\n\nIt was written for the the team.\n";
+        assert_eq!(
+            check(&repo, md),
+            vec![(
+                FindingKind::Prose,
+                3,
+                "Repeated word 'the' (fix: 'the') [repeated-word, warning]".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn checks_are_skipped_when_the_repo_side_is_unknown() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repo::discover(dir.path());
+        let md = "```sh\ncargo run --bin x -- --y\n```\n\n# Config\n\n```toml\nunknown = 1\n```\n";
+        assert!(check(&repo, md).is_empty());
+    }
+
+    #[test]
+    fn reports_syntax_errors_of_any_config_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repo::discover(dir.path());
+        let md = "# Deploy\n\n```yaml\nsteps:\n  - run: make\n   name: build\n```\n\n\
+                  ```json\n{\"a\": 1 \"b\": 2}\n```\n";
+        let found: Vec<_> = check_document(
+            md,
+            &dir.path().join("README.md"),
+            &repo,
+            &MarkdownOptions::default(),
+        )
+        .into_iter()
+        .map(|f| (f.kind, f.range.start_line, f.range.start_column, f.message))
+        .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    FindingKind::InvalidSnippet,
+                    6,
+                    4,
+                    "yaml snippet does not parse: bad indentation: the line matches no enclosing level"
+                        .to_string()
+                ),
+                (
+                    FindingKind::InvalidSnippet,
+                    10,
+                    9,
+                    "json snippet does not parse: expected `,` or `}`".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_heuristics() {
+        for path in [
+            "src/main.rs",
+            "./docs/",
+            "crates/a-b/Cargo.toml",
+            ".github/x.yml",
+        ] {
+            assert!(looks_like_path(path), "{path}");
+        }
+        for text in [
+            "src/main",
+            "/etc/hosts",
+            "a b/c.rs",
+            "https://x/y.rs",
+            "<dir>/a.md",
+        ] {
+            assert!(!looks_like_path(text), "{text}");
+        }
+        assert_eq!(
+            relative_link_target("docs/a%20b.md?x#y"),
+            Some("docs/a b.md".to_string())
+        );
+        assert_eq!(relative_link_target("mailto:a@b"), None);
+    }
+}
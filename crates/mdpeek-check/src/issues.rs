@@ -0,0 +1,307 @@
+//! TODO ↔ issue cross-referencing.
+//!
+//! Joins `TODO(#123)` markers (see [`mdpeek_analyzer::panel::inline_markers`])
+//! against a local issue export ([`IssueList`]):
+//!
+//! * per document — a TODO pointing at a closed issue, or at one the export
+//!   does not contain;
+//! * per repository — an open issue whose last `TODO(#n)` reference was
+//!   removed, found by reading the markdown history with `git log -p`.
+
+use crate::repo::{Repo, walk_repo};
+use crate::{Finding, FindingKind};
+use mdpeek_analyzer::RulesGenerator;
+use mdpeek_analyzer::issues::{IssueList, IssueState};
+use mdpeek_analyzer::panel::inline_markers;
+use mdpeek_analyzer::rules::ClassificationRules;
+use mdpeek_gfm::MarkdownOptions;
+use mdpeek_parser::SourceRange;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A repository-level finding, located in the file it concerns (relative to
+/// the repository root).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoFinding {
+    pub path: PathBuf,
+    pub finding: Finding,
+}
+
+/// TODOs in one document that reference closed or unknown issues. `rules`
+/// (the `[analyzer]` config) may select the language of the marker words.
+pub fn check_todos(
+    markdown: &str,
+    options: &MarkdownOptions,
+    rules: &ClassificationRules,
+    issues: &IssueList,
+) -> Vec<Finding> {
+    let mut analysis =
+        mdpeek_analyzer::analyze_with_rules(markdown, None, options, rules, &RulesGenerator);
+    analysis.panel.link_issues(issues);
+    analysis
+        .panel
+        .todos
+        .iter()
+        .filter_map(|todo| {
+            let issue = todo.issue.as_ref()?;
+            let n = issue.number;
+            let (kind, message) = match issue.state? {
+                IssueState::Open => return None,
+                IssueState::Closed => (
+                    FindingKind::ClosedIssue,
+                    match &issue.title {
+                        Some(title) if !title.is_empty() => {
+                            format!("TODO references closed issue #{n} ({title})")
+                        }
+                        _ => format!("TODO references closed issue #{n}"),
+                    },
+                ),
+                IssueState::Missing => (
+                    FindingKind::MissingIssue,
+                    format!("TODO references issue #{n}, which is not in the issue export"),
+                ),
+            };
+            Some(Finding {
+                kind,
+                message,
+                range: todo.link.range,
+            })
+        })
+        .collect()
+}
+
+/// Open issues that no TODO references any more, although one did: each is
+/// reported in the file its last reference was removed from.
+pub fn orphaned_issues(
+    repo: &Repo,
+    options: &MarkdownOptions,
+    issues: &IssueList,
+) -> Vec<RepoFinding> {
+    let present = referenced_issues(&repo.root, options, repo.rules());
+    let removed = removed_references(&repo.root);
+    issues
+        .iter()
+        .filter(|issue| issue.state == Some(IssueState::Open) && !present.contains(&issue.number))
+        .filter_map(|issue| {
+            let path = removed.get(&issue.number)?;
+            let n = issue.number;
+            let message = if issue.title.is_empty() {
+                format!("open issue #{n} lost its TODO(#{n}) reference")
+            } else {
+                format!(
+                    "open issue #{n} ({}) lost its TODO(#{n}) reference",
+                    issue.title
+                )
+            };
+            Some(RepoFinding {
+                path: path.clone(),
+                finding: Finding {
+                    kind: FindingKind::OrphanedIssue,
+                    message,
+                    range: SourceRange {
+                        start_line: 1,
+                        start_column: 1,
+                        end_line: 1,
+                        end_column: 1,
+                    },
+                },
+            })
+        })
+        .collect()
+}
+
+/// Issue numbers referenced by TODOs in the repository's markdown today.
+fn referenced_issues(
+    root: &Path,
+    options: &MarkdownOptions,
+    rules: &ClassificationRules,
+) -> BTreeSet<u64> {
+    walk_repo(root)
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|x| x == "md" || x == "markdown")
+        })
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .flat_map(|content| {
+            mdpeek_analyzer::analyze_with_rules(&content, None, options, rules, &RulesGenerator)
+                .panel
+                .todos
+                .into_iter()
+                .filter_map(|t| t.issue.map(|i| i.number))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Issue numbers whose `TODO(#n)` markers were removed from markdown under
+/// `root` at some point in history, with the file (relative to `root`) of the
+/// most recent removal. Empty outside a git checkout.
+fn removed_references(root: &Path) -> BTreeMap<u64, PathBuf> {
+    let Ok(out) = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "log",
+            "-p",
+            "--no-color",
+            "--no-ext-diff",
+            // Paths relative to `root`, which may be below the git top level.
+            "--relative",
+            "--format=",
+            r"-G\(#[0-9]+\)",
+            "--",
+            "*.md",
+            "*.markdown",
+        ])
+        .output()
+    else {
+        return BTreeMap::new();
+    };
+    if !out.status.success() {
+        return BTreeMap::new();
+    }
+    removed_in_log(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Parse `git log -p` output (newest first) for removed marker lines.
+fn removed_in_log(log: &str) -> BTreeMap<u64, PathBuf> {
+    let mut removed = BTreeMap::new();
+    let mut file: Option<PathBuf> = None;
+    for line in log.lines() {
+        if let Some(path) = line.strip_prefix("--- a/") {
+            file = Some(PathBuf::from(path));
+        } else if line.starts_with("--- ") {
+            file = None;
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            file.get_or_insert_with(|| PathBuf::from(path));
+        } else if let Some(text) = line.strip_prefix('-')
+            && let Some(path) = &file
+        {
+            for marker in inline_markers(text) {
+                if let Some(n) = marker.issue {
+                    removed.entry(n).or_insert_with(|| path.clone());
+                }
+            }
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> IssueList {
+        IssueList::parse(
+            r#"[
+                {"number": 1, "title": "Retries", "state": "open"},
+                {"number": 2, "title": "Old crash", "state": "closed"},
+                {"number": 3, "title": "Docs", "state": "open"},
+                {"number": 4, "title": "Frozen", "state": "locked"}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn flags_closed_and_unknown_issues() {
+        // #4's state is unknown to the export parser: neither open nor closed.
+        let md = "# Notes\n\nTODO(#1): retry\n\nFIXME(#2): crash\n\nTODO(#9): ghost\n\n\
+                  TODO: no issue\n\nTODO(#4): thaw\n";
+        let found: Vec<(FindingKind, u32, String)> = check_todos(
+            md,
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+            &export(),
+        )
+        .into_iter()
+        .map(|f| (f.kind, f.range.start_line, f.message))
+        .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    FindingKind::ClosedIssue,
+                    5,
+                    "TODO references closed issue #2 (Old crash)".to_string()
+                ),
+                (
+                    FindingKind::MissingIssue,
+                    7,
+                    "TODO references issue #9, which is not in the issue export".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn configured_language_picks_the_marker_words() {
+        let md = "# Notizen\n\nOFFEN(#2): Absturz\n";
+        let options = MarkdownOptions::default();
+        assert!(check_todos(md, &options, &ClassificationRules::default(), &export()).is_empty());
+        let german = ClassificationRules {
+            lang: Some("de".into()),
+            ..Default::default()
+        };
+        let found = check_todos(md, &options, &german, &export());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, FindingKind::ClosedIssue);
+        assert_eq!(found[0].range.start_line, 3);
+    }
+
+    #[test]
+    fn removed_references_come_from_deleted_lines() {
+        let log = "diff --git a/docs/a.md b/docs/a.md\n--- a/docs/a.md\n+++ b/docs/a.md\n\
+                   @@ -1,2 +1,1 @@\n-TODO(#3): write docs\n+Docs written.\n\
+                   diff --git a/new.md b/new.md\n--- /dev/null\n+++ b/new.md\n@@ -0,0 +1 @@\n+TODO(#1): x\n\
+                   diff --git a/docs/b.md b/docs/b.md\n--- a/docs/b.md\n+++ b/docs/b.md\n-TODO(#3): older\n";
+        let removed = removed_in_log(log);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(
+            removed[&3],
+            PathBuf::from("docs/a.md"),
+            "newest removal wins"
+        );
+    }
+
+    #[test]
+    fn orphaned_open_issues_are_found_in_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=Ada", "-c", "user.email=ada@example.com"])
+                .args(args)
+                .output()
+        };
+        if git(&["init", "-q"]).is_err() {
+            return; // git unavailable
+        }
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        let doc = dir.path().join("docs/guide.md");
+        std::fs::write(&doc, "# Guide\n\nTODO(#1): retry\n\nTODO(#3): write docs\n").unwrap();
+        git(&["add", "."]).unwrap();
+        git(&["commit", "-q", "-m", "Add guide"]).unwrap();
+        std::fs::write(&doc, "# Guide\n\nTODO(#1): retry\n\nDocs are pending.\n").unwrap();
+        git(&["commit", "-q", "-am", "Drop TODO"]).unwrap();
+
+        let repo = Repo::discover(dir.path());
+        let found = orphaned_issues(&repo, &MarkdownOptions::default(), &export());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, PathBuf::from("docs/guide.md"));
+        assert_eq!(found[0].finding.kind, FindingKind::OrphanedIssue);
+        assert_eq!(
+            found[0].finding.message,
+            "open issue #3 (Docs) lost its TODO(#3) reference"
+        );
+
+        // Checked from a subdirectory, paths are relative to it.
+        let repo = Repo::discover(&dir.path().join("docs"));
+        let found = orphaned_issues(&repo, &MarkdownOptions::default(), &export());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, PathBuf::from("guide.md"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Repository-aware checks.
+//!
+//! Cross-checks a markdown document against the repository it lives in and
+//! reports every mismatch as a [`Finding`] carrying the offending
+//! [`SourceRange`]:
+//!
+//! ```text
+//! markdown ─▶ BlockTree + inline code / links ─┐
+//!                                               ├─▶ consistency ─▶ Vec<Finding>
+//! repo root ─▶ Repo { binaries, flags, config } ┘
+//! ```
+//!
+//! * [`consistency`] — README ↔ code: referenced paths exist, `cargo run --bin`
+//!   and `--flag` names in shell blocks match the crate, config snippets parse,
+//!   and every JSON / YAML / TOML block is well-formed.
+//! * [`issues`] — `TODO(#123)` ↔ a local issue export: TODOs pointing at closed
+//!   or unknown issues, open issues whose TODO was removed.
+//! * [`changelog`] — CHANGELOG ↔ git tags: released versions with no tag.
+//! * prose — warnings and errors from the analyser's
+//!   [`Linter`](mdpeek_analyzer::lint::Linter); suggestions are left to the
+//!   viewers.
+//!
+//! [`Repo`] gathers what can be detected from the tree (Cargo binaries, clap
+//! flags); the caller may add a config validator for its own config struct, an
+//! issue export and a linter.
+
+pub mod changelog;
+pub mod consistency;
+pub mod issues;
+pub mod repo;
+
+pub use changelog::check_changelog;
+pub use consistency::check_document;
+pub use issues::{RepoFinding, orphaned_issues};
+pub use mdpeek_parser::SourceRange;
+pub use repo::{ConfigFormat, Repo};
+
+use serde::Serialize;
+
+/// What kind of mismatch a [`Finding`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// A path in backticks or a relative link that does not exist.
+    MissingPath,
+    /// `cargo run --bin X` (or similar) naming a binary the crate lacks.
+    UnknownBinary,
+    /// A `--flag` passed to one of the crate's binaries that it does not define.
+    UnknownFlag,
+    /// A config snippet that does not parse against the config struct.
+    InvalidConfig,
+    /// A JSON / YAML / TOML code block with a syntax error.
+    InvalidSnippet,
+    /// A `TODO(#n)` referencing a closed issue.
+    ClosedIssue,
+    /// A `TODO(#n)` referencing an issue missing from the export.
+    MissingIssue,
+    /// An open issue whose referencing TODO was removed.
+    OrphanedIssue,
+    /// A prose linter warning or error.
+    Prose,
+}
+
+impl FindingKind {
+    /// Stable kebab-case name, as printed by `mdpeek check`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FindingKind::MissingPath => "missing-path",
+            FindingKind::UnknownBinary => "unknown-binary",
+            FindingKind::UnknownFlag => "unknown-flag",
+            FindingKind::InvalidConfig => "invalid-config",
+            FindingKind::InvalidSnippet => "invalid-snippet",
+            FindingKind::ClosedIssue => "closed-issue",
+            FindingKind::MissingIssue => "missing-issue",
+            FindingKind::OrphanedIssue => "orphaned-issue",
+            FindingKind::Prose => "prose",
+        }
+    }
+}
+
+/// One mismatch between a document and the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    pub message: String,
+    pub range: SourceRange,
+}
@@ -0,0 +1,285 @@
+//! What the checks know about the repository: its Cargo binaries, the long
+//! flags its clap definitions declare, and (optionally) how to validate a
+//! config snippet.
+//!
+//! Detection is best-effort and purely textual — nothing is compiled. An empty
+//! set means "not detectable", and the corresponding check is skipped rather
+//! than reporting everything as unknown.
+
+use mdpeek_analyzer::issues::IssueList;
+use mdpeek_analyzer::lint::Linter;
+use mdpeek_analyzer::rules::ClassificationRules;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use walkdir::{DirEntry, WalkDir};
+
+/// Directories never scanned (build output, dependencies, VCS metadata).
+const SKIP_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+/// Every file under `root`, skipping [`SKIP_DIRS`]; unreadable entries are
+/// left out.
+pub(crate) fn walk_repo(root: &Path) -> impl Iterator<Item = DirEntry> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir()
+                && e.file_name()
+                    .to_str()
+                    .is_some_and(|n| SKIP_DIRS.contains(&n)))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+}
+
+/// Flags clap derives for every command.
+const BUILTIN_FLAGS: &[&str] = &["help", "version"];
+
+/// `long = "name"` inside a clap attribute, or a builder's `.long("name")`.
+static LONG_EXPLICIT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\blong\s*=\s*|\.long\(\s*)"([^"]+)""#).expect("valid long regex")
+});
+/// A bare `long` in a clap attribute: the flag is named after the field.
+static LONG_BARE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\blong\s*[,)]").expect("valid bare long regex"));
+/// A struct field declaration, capturing its name.
+static FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:r#)?([a-z_][a-z0-9_]*)\s*:")
+        .expect("valid field regex")
+});
+
+/// Format of a config snippet handed to the validator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+/// Validates a config snippet, returning the parse error on mismatch.
+pub type ConfigValidator = Box<dyn Fn(ConfigFormat, &str) -> Result<(), String> + Send + Sync>;
+
+/// The repository a document is checked against.
+pub struct Repo {
+    pub root: PathBuf,
+    /// Binary names (`[[bin]]`, `src/main.rs` packages, `src/bin/*`).
+    pub binaries: BTreeSet<String>,
+    /// Long flag names without the leading `--`.
+    pub flags: BTreeSet<String>,
+    config: Option<ConfigValidator>,
+    issues: Option<IssueList>,
+    linter: Option<Linter>,
+    rules: ClassificationRules,
+}
+
+impl Repo {
+    /// Scan `root` for Cargo manifests and clap flag declarations.
+    pub fn discover(root: &Path) -> Self {
+        let mut binaries = BTreeSet::new();
+        let mut flags = BTreeSet::new();
+        for entry in walk_repo(root) {
+            let path = entry.path();
+            if entry.file_name() == "Cargo.toml" {
+                binaries.extend(manifest_binaries(path));
+            } else if path.extension().is_some_and(|x| x == "rs")
+                && let Ok(source) = std::fs::read_to_string(path)
+            {
+                flags.extend(clap_flags(&source));
+            }
+        }
+        if !flags.is_empty() {
+            flags.extend(BUILTIN_FLAGS.iter().map(|f| f.to_string()));
+        }
+        Repo {
+            root: root.to_path_buf(),
+            binaries,
+            flags,
+            config: None,
+            issues: None,
+            linter: None,
+            rules: ClassificationRules::default(),
+        }
+    }
+
+    /// Validate fenced config snippets with `validator` (typically
+    /// deserialising into the project's config struct).
+    pub fn with_config_validator(
+        mut self,
+        validator: impl Fn(ConfigFormat, &str) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.config = Some(Box::new(validator));
+        self
+    }
+
+    /// Cross-reference `TODO(#n)` markers against `issues`.
+    pub fn with_issues(mut self, issues: IssueList) -> Self {
+        self.issues = Some(issues);
+        self
+    }
+
+    pub fn issues(&self) -> Option<&IssueList> {
+        self.issues.as_ref()
+    }
+
+    /// Report prose linter warnings and errors too.
+    pub fn with_linter(mut self, linter: Linter) -> Self {
+        self.linter = Some(linter);
+        self
+    }
+
+    pub fn linter(&self) -> Option<&Linter> {
+        self.linter.as_ref()
+    }
+
+    /// Analyse documents with the project's `[analyzer]` rules, so its
+    /// configured language picks the TODO marker words.
+    pub fn with_rules(mut self, rules: ClassificationRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn rules(&self) -> &ClassificationRules {
+        &self.rules
+    }
+
+    /// `None` when no validator is installed.
+    pub(crate) fn validate_config(
+        &self,
+        format: ConfigFormat,
+        snippet: &str,
+    ) -> Option<Result<(), String>> {
+        self.config.as_ref().map(|v| v(format, snippet))
+    }
+}
+
+/// Binaries a Cargo manifest defines: explicit `[[bin]]` targets, the package
+/// itself when it has `src/main.rs`, and `src/bin/*` targets.
+fn manifest_binaries(manifest: &Path) -> Vec<String> {
+    let Ok(text) = std::fs::read_to_string(manifest) else {
+        return Vec::new();
+    };
+    let Ok(value) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let dir = manifest.parent().unwrap_or(Path::new("."));
+    let mut out = Vec::new();
+    if let Some(bins) = value.get("bin").and_then(|b| b.as_array()) {
+        out.extend(
+            bins.iter()
+                .filter_map(|b| b.get("name").and_then(|n| n.as_str()))
+                .map(str::to_string),
+        );
+    }
+    if let Some(name) = value
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        && dir.join("src/main.rs").is_file()
+    {
+        out.push(name.to_string());
+    }
+    if let Ok(entries) = std::fs::read_dir(dir.join("src/bin")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let stem = if path.is_dir() {
+                path.file_name()
+            } else {
+                path.file_stem()
+            };
+            if let Some(stem) = stem.and_then(|s| s.to_str()) {
+                out.push(stem.to_string());
+            }
+        }
+    }
+    out
+}
+
+/// Long flags declared in Rust source via clap: `long = "x"` / `.long("x")`,
+/// or a bare `long` on the attribute above a field (`foo_bar` → `foo-bar`).
+fn clap_flags(source: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut pending_field = false;
+    for line in source.lines() {
+        let is_attr =
+            line.trim_start().starts_with("#[arg(") || line.trim_start().starts_with("#[clap(");
+        out.extend(LONG_EXPLICIT.captures_iter(line).map(|c| c[1].to_string()));
+        if is_attr && !LONG_EXPLICIT.is_match(line) && LONG_BARE.is_match(line) {
+            pending_field = true;
+            continue;
+        }
+        if pending_field
+            && !line.trim_start().starts_with("#[")
+            && !line.trim_start().starts_with("///")
+            && let Some(caps) = FIELD.captures(line)
+        {
+            out.push(caps[1].replace('_', "-"));
+            pending_field = false;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clap_flags_reads_explicit_and_field_names() {
+        let src = r#"
+            #[arg(short = 'n', long = "host", value_name = "HOST")]
+            pub host: Option<String>,
+            /// Disable watching
+            #[arg(long, global = true)]
+            pub no_watch: bool,
+            #[arg(long)]
+            #[arg(value_name = "X")]
+            pub dry_run: bool,
+            #[arg(short = 'w')]
+            pub watch: bool,
+            let a = Arg::new("x").long("extra");
+        "#;
+        assert_eq!(
+            clap_flags(src),
+            vec!["host", "no-watch", "dry-run", "extra"]
+        );
+    }
+
+    #[test]
+    fn discover_finds_binaries_and_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"tool\"\n\n[[bin]]\nname = \"toolctl\"\npath = \"src/ctl.rs\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("src/bin")).unwrap();
+        std::fs::write(
+            root.join("src/main.rs"),
+            "#[arg(long)]\npub verbose: bool,\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/bin/helper.rs"), "fn main() {}\n").unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(
+            root.join("target/debug/gen.rs"),
+            "#[arg(long = \"ghost\")]\n",
+        )
+        .unwrap();
+
+        let repo = Repo::discover(root);
+        let bins: Vec<&str> = repo.binaries.iter().map(String::as_str).collect();
+        assert_eq!(bins, vec!["helper", "tool", "toolctl"]);
+        assert!(repo.flags.contains("verbose"));
+        assert!(repo.flags.contains("help"));
+        assert!(!repo.flags.contains("ghost"), "target/ is skipped");
+    }
+
+    #[test]
+    fn flags_are_empty_when_nothing_is_detectable() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repo::discover(dir.path());
+        assert!(repo.flags.is_empty());
+        assert!(repo.validate_config(ConfigFormat::Toml, "").is_none());
+    }
+}
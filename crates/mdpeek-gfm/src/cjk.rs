@@ -0,0 +1,69 @@
+//! East Asian line joining.
+//!
+//! Japanese and Chinese prose has no spaces between words, so a soft line
+//! break inside a wrapped sentence must not turn into a space. Like GitHub and
+//! Pandoc's `east_asian_line_breaks`, a soft break between two East Asian wide
+//! characters is dropped; everywhere else it stays a space. Hangul is excluded
+//! because Korean separates words with spaces.
+
+/// Whether `c` is an East Asian wide character (kana, Han ideographs, CJK
+/// punctuation, fullwidth forms) for line-joining purposes.
+pub fn is_east_asian_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x2FDF     // CJK radicals, Kangxi radicals
+        | 0x2FF0..=0x2FFF   // ideographic description characters
+        | 0x3000..=0x303F   // CJK symbols and punctuation (、。「」)
+        | 0x3040..=0x309F   // Hiragana
+        | 0x30A0..=0x30FF   // Katakana
+        | 0x3100..=0x312F   // Bopomofo
+        | 0x3190..=0x31FF   // Kanbun, CJK strokes, Katakana extensions
+        | 0x3200..=0x33FF   // enclosed CJK letters, CJK compatibility
+        | 0x3400..=0x4DBF   // CJK extension A
+        | 0x4E00..=0x9FFF   // CJK unified ideographs
+        | 0xF900..=0xFAFF   // CJK compatibility ideographs
+        | 0xFE30..=0xFE4F   // CJK compatibility forms
+        | 0xFF01..=0xFF60   // fullwidth ASCII variants and brackets
+        | 0xFFE0..=0xFFE6   // fullwidth signs
+        | 0x20000..=0x3FFFF // CJK extensions B and later
+    )
+}
+
+/// Whether a soft line break between `before` (last character of the previous
+/// line) and `after` (first character of the next line) should vanish instead
+/// of becoming a space. `None` means the neighbour is unknown (start/end of the
+/// text or a non-text element), which keeps the space.
+pub fn joins_without_space(before: Option<char>, after: Option<char>) -> bool {
+    matches!((before, after), (Some(b), Some(a)) if is_east_asian_wide(b) && is_east_asian_wide(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn japanese_on_both_sides_joins() {
+        assert!(joins_without_space(Some('す'), Some('こ')));
+        assert!(joins_without_space(Some('。'), Some('次')));
+        assert!(joins_without_space(Some('漢'), Some('カ')));
+    }
+
+    #[test]
+    fn mixed_or_latin_keeps_the_space() {
+        assert!(!joins_without_space(Some('a'), Some('b')));
+        assert!(!joins_without_space(Some('語'), Some('E')));
+        assert!(!joins_without_space(Some('E'), Some('語')));
+        assert!(!joins_without_space(None, Some('語')));
+    }
+
+    #[test]
+    fn hangul_keeps_the_space() {
+        assert!(!joins_without_space(Some('한'), Some('국')));
+    }
+
+    #[test]
+    fn fullwidth_forms_count_as_wide() {
+        assert!(is_east_asian_wide('Ａ'));
+        assert!(is_east_asian_wide('（'));
+        assert!(!is_east_asian_wide('A'));
+    }
+}
@@ -0,0 +1,423 @@
+//! The one `:shortcode:` emoji resolver shared by the HTML and terminal paths.
+//!
+//! Resolution order for `:name:`:
+//!
+//! 1. user mappings (the `[emoji]` config section), either a Unicode string or
+//!    a local image path;
+//! 2. standard Unicode emoji from the `emojis` crate;
+//! 3. GitHub's custom image emoji (`:shipit:`, `:octocat:` …), served from
+//!    GitHub's asset CDN like on github.com.
+//!
+//! Unicode emoji are spliced into the text. Image emoji become an
+//! `<img class="emoji">` [`Event::InlineHtml`](pulldown_cmark::Event) which the
+//! HTML emitter passes through and the terminal emitter replaces with the alt
+//! text (see [`image_alt`]). Unknown shortcodes are left verbatim.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// GitHub's non-Unicode emoji: rendered as images on github.com.
+const GITHUB_CUSTOM: &[&str] = &[
+    "atom",
+    "basecamp",
+    "basecampy",
+    "bowtie",
+    "dependabot",
+    "electron",
+    "feelsgood",
+    "finnadie",
+    "fishsticks",
+    "goberserk",
+    "godmode",
+    "hurtrealbad",
+    "neckbeard",
+    "octocat",
+    "rage1",
+    "rage2",
+    "rage3",
+    "rage4",
+    "shipit",
+    "suspect",
+    "trollface",
+];
+
+/// Where GitHub serves its custom emoji images.
+const GITHUB_EMOJI_BASE: &str = "https://github.githubassets.com/images/icons/emoji";
+
+/// A user-defined emoji.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Emoji {
+    /// Replace the shortcode with this text (usually one Unicode emoji).
+    Unicode(String),
+    /// Show an image. `alt` is what the terminal prints; `:name:` when unset.
+    Image { src: String, alt: Option<String> },
+}
+
+/// What a shortcode resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolved<'r> {
+    Unicode(&'r str),
+    Image {
+        src: Cow<'r, str>,
+        alt: Cow<'r, str>,
+    },
+}
+
+/// Shortcode → emoji lookup. The default resolver knows the Unicode set and
+/// GitHub's custom emoji; [`EmojiResolver::insert`] adds user mappings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmojiResolver {
+    custom: HashMap<String, Emoji>,
+}
+
+/// One piece of text after shortcode substitution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment<'t> {
+    /// Text with Unicode emoji already substituted.
+    Text(Cow<'t, str>),
+    /// An `<img class="emoji">` tag.
+    Image(String),
+}
+
+impl EmojiResolver {
+    /// The built-in resolver (Unicode + GitHub custom emoji, no user mappings).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or override) a user mapping for `:name:`.
+    pub fn insert(&mut self, name: impl Into<String>, emoji: Emoji) {
+        self.custom.insert(name.into(), emoji);
+    }
+
+    /// The user mappings added with [`EmojiResolver::insert`].
+    pub fn custom(&self) -> impl Iterator<Item = (&str, &Emoji)> {
+        self.custom
+            .iter()
+            .map(|(name, emoji)| (name.as_str(), emoji))
+    }
+
+    /// Resolve a shortcode name (without colons).
+    pub fn resolve(&self, name: &str) -> Option<Resolved<'_>> {
+        match self.custom.get(name) {
+            Some(Emoji::Unicode(s)) => return Some(Resolved::Unicode(s)),
+            Some(Emoji::Image { src, alt }) => {
+                return Some(Resolved::Image {
+                    src: Cow::Borrowed(src),
+                    alt: match alt {
+                        Some(alt) => Cow::Borrowed(alt),
+                        None => Cow::Owned(format!(":{name}:")),
+                    },
+                });
+            }
+            None => {}
+        }
+        if let Some(emoji) = emojis::get_by_shortcode(name) {
+            return Some(Resolved::Unicode(emoji.as_str()));
+        }
+        GITHUB_CUSTOM
+            .binary_search(&name)
+            .ok()
+            .map(|_| Resolved::Image {
+                src: Cow::Owned(format!("{GITHUB_EMOJI_BASE}/{name}.png")),
+                alt: Cow::Owned(format!(":{name}:")),
+            })
+    }
+
+    /// Split `text` into plain-text and image segments, substituting Unicode
+    /// emoji in the text segments. Text without any substitution is returned as
+    /// a single borrowed segment.
+    pub fn segments<'t>(&self, text: &'t str) -> Vec<Segment<'t>> {
+        // Fast path: no colon at all → borrow with no allocation.
+        if !text.contains(':') {
+            return vec![Segment::Text(Cow::Borrowed(text))];
+        }
+
+        let bytes = text.as_bytes();
+        let len = bytes.len();
+        let mut segments = Vec::new();
+        let mut out = String::new();
+        let mut replaced = false;
+        // index up to which `text` has already been copied into `out`.
+        let mut copied = 0usize;
+        let mut i = 0usize;
+
+        while i < len {
+            if bytes[i] != b':' {
+                i += 1;
+                continue;
+            }
+
+            // bytes[i] == b':'  →  scan ahead for a closing ':'
+            let mut j = i + 1;
+            // Collect valid shortcode characters: [a-zA-Z0-9_+\-]
+            while j < len && is_shortcode_char(bytes[j]) {
+                j += 1;
+            }
+
+            // A valid token requires at least one shortcode char and a closing ':'.
+            if j > i + 1
+                && j < len
+                && bytes[j] == b':'
+                && let Some(resolved) = self.resolve(&text[i + 1..j])
+            {
+                // Copy the untouched gap (UTF-8 safe: slices on `:` boundaries).
+                out.push_str(&text[copied..i]);
+                replaced = true;
+                match resolved {
+                    Resolved::Unicode(s) => out.push_str(s),
+                    Resolved::Image { src, alt } => {
+                        if !out.is_empty() {
+                            segments.push(Segment::Text(Cow::Owned(std::mem::take(&mut out))));
+                        }
+                        segments.push(Segment::Image(image_html(&src, &alt)));
+                    }
+                }
+                i = j + 1; // skip past the closing ':'
+                copied = i;
+                continue;
+            }
+
+            // No match: leave the ':' in place and advance by one byte.
+            i += 1;
+        }
+
+        if !replaced {
+            return vec![Segment::Text(Cow::Borrowed(text))];
+        }
+        out.push_str(&text[copied..]);
+        if !out.is_empty() {
+            segments.push(Segment::Text(Cow::Owned(out)));
+        }
+        segments
+    }
+
+    /// Substitute Unicode emoji only; image emoji become their alt text. For
+    /// consumers that can only show plain text.
+    pub fn replace_shortcodes<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        let mut segments = self.segments(text);
+        if let [Segment::Text(_)] = segments.as_slice()
+            && let Some(Segment::Text(t)) = segments.pop()
+        {
+            return t;
+        }
+        let mut out = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Text(t) => out.push_str(&t),
+                Segment::Image(html) => out.push_str(&image_alt(&html).unwrap_or_default()),
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Returns `true` for characters that may appear inside a GitHub shortcode.
+#[inline]
+fn is_shortcode_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'+' || b == b'-'
+}
+
+/// GitHub's markup for an image emoji.
+fn image_html(src: &str, alt: &str) -> String {
+    let mut html = String::from("<img class=\"emoji\" title=\"");
+    escape_attr(&mut html, alt);
+    html.push_str("\" alt=\"");
+    escape_attr(&mut html, alt);
+    html.push_str("\" src=\"");
+    escape_attr(&mut html, src);
+    html.push_str("\" height=\"20\" width=\"20\" align=\"absmiddle\">");
+    html
+}
+
+fn escape_attr(buf: &mut String, s: &str) {
+    for ch in s.chars() {
+        match ch {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            other => buf.push(other),
+        }
+    }
+}
+
+/// Extract the (unescaped) alt text of an `<img class="emoji">` produced by
+/// this module. Returns `None` for any other HTML.
+pub fn image_alt(html: &str) -> Option<String> {
+    let rest = html.strip_prefix("<img class=\"emoji\" ")?;
+    let start = rest.find(" alt=\"")? + " alt=\"".len();
+    let end = start + rest[start..].find('"')?;
+    Some(
+        rest[start..end]
+            .replace("&quot;", "\"")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace_shortcodes(text: &str) -> Cow<'_, str> {
+        static RESOLVER: std::sync::LazyLock<EmojiResolver> =
+            std::sync::LazyLock::new(EmojiResolver::new);
+        RESOLVER.replace_shortcodes(text)
+    }
+
+    #[test]
+    fn test_smile() {
+        let result = replace_shortcodes(":smile:");
+        let expected = emojis::get_by_shortcode("smile").unwrap().as_str();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_unknown_shortcode_preserved() {
+        let input = ":unknown_xyz:";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_http_url_untouched() {
+        let input = "see http://example.com for details";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_https_url_untouched() {
+        let input = "https://example.com/path?a=1";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_no_replacement_returns_borrowed() {
+        let input = "hello world";
+        match replace_shortcodes(input) {
+            Cow::Borrowed(_) => {}
+            Cow::Owned(_) => panic!("Expected Cow::Borrowed for no-replacement case"),
+        }
+    }
+
+    #[test]
+    fn test_no_colon_returns_borrowed() {
+        let input = "plain text without colon";
+        match replace_shortcodes(input) {
+            Cow::Borrowed(_) => {}
+            Cow::Owned(_) => panic!("Expected Cow::Borrowed when no colon present"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_code_returns_borrowed() {
+        let input = ":unknown_xyz:";
+        match replace_shortcodes(input) {
+            Cow::Borrowed(_) => {}
+            Cow::Owned(_) => panic!("Expected Cow::Borrowed for all-unknown shortcodes"),
+        }
+    }
+
+    #[test]
+    fn test_adjacent_shortcodes() {
+        let smile = emojis::get_by_shortcode("smile").unwrap().as_str();
+        let plus1 = emojis::get_by_shortcode("+1").unwrap().as_str();
+        let result = replace_shortcodes(":smile::+1:");
+        assert_eq!(result, format!("{smile}{plus1}"));
+    }
+
+    #[test]
+    fn test_mixed_known_and_unknown() {
+        let smile = emojis::get_by_shortcode("smile").unwrap().as_str();
+        let input = ":smile: and :nope_xyz:";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, format!("{smile} and :nope_xyz:"));
+    }
+
+    #[test]
+    fn test_trailing_lone_colon() {
+        let input = "hello:";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_empty_colons() {
+        let input = "a::b";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_shortcode_with_spaces_invalid() {
+        let input = ":hello world:";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_multibyte_preserved_after_replacement() {
+        // 置換後に続くマルチバイト文字(日本語)が壊れないこと。
+        let smile = emojis::get_by_shortcode("smile").unwrap().as_str();
+        let input = ":smile: 日本語のテキスト";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, format!("{smile} 日本語のテキスト"));
+    }
+
+    #[test]
+    fn test_multibyte_between_shortcodes() {
+        let smile = emojis::get_by_shortcode("smile").unwrap().as_str();
+        let input = "あ:smile:い";
+        let result = replace_shortcodes(input);
+        assert_eq!(result, format!("あ{smile}い"));
+    }
+
+    #[test]
+    fn github_custom_list_is_sorted_for_binary_search() {
+        assert!(GITHUB_CUSTOM.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn shipit_is_a_github_image() {
+        let segments = EmojiResolver::new().segments("ship it :shipit: now");
+        assert_eq!(segments.len(), 3, "{segments:?}");
+        let Segment::Image(html) = &segments[1] else {
+            panic!("expected image: {segments:?}");
+        };
+        assert!(html.starts_with("<img class=\"emoji\""));
+        assert!(html.contains("/shipit.png"));
+        assert_eq!(image_alt(html).as_deref(), Some(":shipit:"));
+    }
+
+    #[test]
+    fn image_emoji_fall_back_to_alt_in_plain_text() {
+        assert_eq!(replace_shortcodes("go :shipit:"), "go :shipit:");
+        let mut resolver = EmojiResolver::new();
+        resolver.insert(
+            "logo",
+            Emoji::Image {
+                src: "img/logo.png".into(),
+                alt: Some("[logo]".into()),
+            },
+        );
+        assert_eq!(resolver.replace_shortcodes(":logo: Inc"), "[logo] Inc");
+    }
+
+    #[test]
+    fn user_mappings_override_builtins() {
+        let mut resolver = EmojiResolver::new();
+        resolver.insert("smile", Emoji::Unicode("🙂".into()));
+        resolver.insert("party", Emoji::Unicode("🎉".into()));
+        assert_eq!(resolver.replace_shortcodes(":smile: :party:"), "🙂 🎉");
+    }
+
+    #[test]
+    fn image_alt_ignores_other_html() {
+        assert_eq!(image_alt("<img src=\"x\">"), None);
+        assert_eq!(image_alt("<mark>"), None);
+    }
+}
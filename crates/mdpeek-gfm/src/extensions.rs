@@ -0,0 +1,560 @@
+//! Opt-in inline extensions borrowed from other markdown dialects.
+//!
+//! Docs migrated from MkDocs / Pandoc / markdown-it commonly use:
+//!
+//! * `==highlight==` → `<mark>`
+//! * `++insert++` → `<ins>`
+//! * `*[HTML]: Hyper Text` abbreviation definitions → every later `HTML`
+//!   becomes `<abbr title="Hyper Text">HTML</abbr>` and the definition
+//!   paragraph itself is hidden.
+//!
+//! `pulldown_cmark` has no events for these, so the adapter expresses them as
+//! [`Event::InlineHtml`] tags. The HTML emitter passes those through as-is and
+//! the terminal emitter recognises exactly these tags (see [`abbr_title`]).
+//! Heading attributes (`{#id .class}`) need no adapter: they are a parser
+//! option (see [`MarkdownOptions::parser_options`]).
+//!
+//! Delimiters are only paired inside one run of inline events (a paragraph,
+//! heading, table cell or tight list item), at the same inline nesting depth,
+//! so `==` never spans blocks or produces misnested tags. Code spans, code
+//! blocks, front matter and image alt text are never touched.
+
+use crate::MarkdownOptions;
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::iter::Peekable;
+use std::sync::LazyLock;
+
+/// Matches one abbreviation definition line: `*[HTML]: Hyper Text`.
+static ABBR_DEF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s{0,3}\*\[([^\]]+)\]:\s*(.*?)\s*$").expect("valid abbreviation regex")
+});
+
+/// An abbreviation and its expansion, collected from the whole document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviation {
+    pub abbr: String,
+    pub title: String,
+}
+
+/// Collect every `*[ABBR]: Title` definition in `source`, skipping fenced code.
+/// Later definitions of the same abbreviation override earlier ones. The result
+/// is ordered longest-first so overlapping abbreviations prefer the longer one.
+pub fn abbreviations(source: &str) -> Vec<Abbreviation> {
+    let mut out: Vec<Abbreviation> = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            fence = Some("```");
+            continue;
+        }
+        if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+            continue;
+        }
+        if let Some(caps) = ABBR_DEF_RE.captures(line) {
+            let abbr = caps[1].trim().to_string();
+            let title = caps[2].to_string();
+            if abbr.is_empty() {
+                continue;
+            }
+            out.retain(|a| a.abbr != abbr);
+            out.push(Abbreviation { abbr, title });
+        }
+    }
+    out.sort_by_key(|a| std::cmp::Reverse(a.abbr.len()));
+    out
+}
+
+/// Wrap `iter` with the inline extensions enabled in `options`. `source` is the
+/// full markdown text, pre-scanned for abbreviation definitions (which may
+/// appear after their first use).
+pub fn extensions<'a, I>(source: &str, iter: I, options: &MarkdownOptions) -> Extensions<'a, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    let abbrs = if options.abbreviations {
+        abbreviations(source)
+    } else {
+        Vec::new()
+    };
+    let abbr_re = (!abbrs.is_empty()).then(|| {
+        let alternation = abbrs
+            .iter()
+            .map(|a| regex::escape(&a.abbr))
+            .collect::<Vec<_>>()
+            .join("|");
+        Regex::new(&alternation).expect("escaped abbreviation alternation is valid")
+    });
+    Extensions {
+        iter: iter.peekable(),
+        mark: options.mark,
+        insert: options.insert,
+        abbrs,
+        abbr_re,
+        verbatim: false,
+        queue: VecDeque::new(),
+    }
+}
+
+/// Iterator adapter produced by [`extensions`].
+pub struct Extensions<'a, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    iter: Peekable<I>,
+    mark: bool,
+    insert: bool,
+    abbrs: Vec<Abbreviation>,
+    /// Alternation of every abbreviation (longest first); `None` when there are
+    /// no definitions.
+    abbr_re: Option<Regex>,
+    /// Inside a code block or metadata block: pass events through untouched.
+    verbatim: bool,
+    /// Processed events of the current inline run.
+    queue: VecDeque<Event<'a>>,
+}
+
+impl<'a, I> Extensions<'a, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    fn enabled(&self) -> bool {
+        self.mark || self.insert || self.abbr_re.is_some()
+    }
+
+    /// Pull the maximal run of inline events starting at the iterator head.
+    fn take_run(&mut self) -> Vec<Event<'a>> {
+        let mut run = Vec::new();
+        while self.iter.peek().is_some_and(is_inline) {
+            run.push(self.iter.next().expect("peeked"));
+        }
+        run
+    }
+
+    fn process(&self, run: Vec<Event<'a>>) -> Vec<Event<'a>> {
+        let mut run = run;
+        if self.mark {
+            run = pair_delimiters(run, "==", "mark");
+        }
+        if self.insert {
+            run = pair_delimiters(run, "++", "ins");
+        }
+        if let Some(re) = &self.abbr_re {
+            run = wrap_abbreviations(run, re, &self.abbrs);
+        }
+        run
+    }
+}
+
+impl<'a, I> Iterator for Extensions<'a, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ev) = self.queue.pop_front() {
+                return Some(ev);
+            }
+            let ev = self.iter.next()?;
+            if !self.enabled() {
+                return Some(ev);
+            }
+            match &ev {
+                Event::Start(Tag::CodeBlock(_) | Tag::MetadataBlock(_)) => {
+                    self.verbatim = true;
+                    return Some(ev);
+                }
+                Event::End(TagEnd::CodeBlock | TagEnd::MetadataBlock(_)) => {
+                    self.verbatim = false;
+                    return Some(ev);
+                }
+                _ if self.verbatim => return Some(ev),
+                Event::Start(Tag::Paragraph) => {
+                    let run = self.take_run();
+                    if self.abbr_re.is_some()
+                        && is_abbreviation_paragraph(&run)
+                        && matches!(self.iter.peek(), Some(Event::End(TagEnd::Paragraph)))
+                    {
+                        // Definitions are metadata: drop the whole paragraph.
+                        self.iter.next();
+                        continue;
+                    }
+                    self.queue.push_back(ev);
+                    self.queue.extend(self.process(run));
+                }
+                e if is_inline(e) => {
+                    let mut run = vec![ev];
+                    run.extend(self.take_run());
+                    self.queue.extend(self.process(run));
+                }
+                _ => return Some(ev),
+            }
+        }
+    }
+}
+
+/// Whether an event belongs to inline content (as opposed to block structure).
+fn is_inline(ev: &Event<'_>) -> bool {
+    match ev {
+        Event::Text(_)
+        | Event::Code(_)
+        | Event::InlineHtml(_)
+        | Event::InlineMath(_)
+        | Event::SoftBreak
+        | Event::HardBreak
+        | Event::FootnoteReference(_) => true,
+        Event::Start(tag) => matches!(
+            tag,
+            Tag::Emphasis
+                | Tag::Strong
+                | Tag::Strikethrough
+                | Tag::Superscript
+                | Tag::Subscript
+                | Tag::Link { .. }
+                | Tag::Image { .. }
+        ),
+        Event::End(tag) => matches!(
+            tag,
+            TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Superscript
+                | TagEnd::Subscript
+                | TagEnd::Link
+                | TagEnd::Image
+        ),
+        _ => false,
+    }
+}
+
+/// A paragraph made only of `*[X]: …` lines.
+fn is_abbreviation_paragraph(run: &[Event<'_>]) -> bool {
+    let mut text = String::new();
+    for ev in run {
+        match ev {
+            Event::Text(t) => text.push_str(t),
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            _ => return false,
+        }
+    }
+    !text.is_empty() && text.lines().all(|l| ABBR_DEF_RE.is_match(l))
+}
+
+/// One `==` / `++` occurrence inside a `Text` event of the run.
+struct Delim {
+    event: usize,
+    offset: usize,
+    depth: usize,
+    can_open: bool,
+    can_close: bool,
+}
+
+/// Pair `delim` occurrences inside `run` and replace each matched pair with
+/// `<tag>` / `</tag>` inline HTML. Unmatched delimiters stay literal.
+fn pair_delimiters<'a>(run: Vec<Event<'a>>, delim: &str, tag: &str) -> Vec<Event<'a>> {
+    let mut candidates = Vec::new();
+    let mut depth = 0usize;
+    let mut image_depth = 0usize;
+    for (i, ev) in run.iter().enumerate() {
+        match ev {
+            Event::Start(Tag::Image { .. }) => image_depth += 1,
+            Event::End(TagEnd::Image) => image_depth = image_depth.saturating_sub(1),
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Text(t) if image_depth == 0 => {
+                let mut from = 0;
+                while let Some(pos) = t[from..].find(delim) {
+                    let offset = from + pos;
+                    let end = offset + delim.len();
+                    let before = t[..offset].chars().next_back();
+                    let after = t[end..].chars().next();
+                    // A delimiter at a Text edge borders another inline event
+                    // (emphasis, code…) unless it is the run edge or a break.
+                    let before_solid = match before {
+                        Some(c) => !c.is_whitespace(),
+                        None => i > 0 && !is_break(&run[i - 1]),
+                    };
+                    let after_solid = match after {
+                        Some(c) => !c.is_whitespace(),
+                        None => run.get(i + 1).is_some_and(|e| !is_break(e)),
+                    };
+                    candidates.push(Delim {
+                        event: i,
+                        offset,
+                        depth,
+                        can_open: after_solid,
+                        can_close: before_solid,
+                    });
+                    from = end;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Left-to-right pairing: an open delimiter closes at the next eligible
+    // closer on the same nesting depth.
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    let mut open: Option<usize> = None;
+    for (idx, c) in candidates.iter().enumerate() {
+        match open {
+            Some(o) if c.can_close && candidates[o].depth == c.depth => {
+                pairs.push((o, idx));
+                open = None;
+            }
+            Some(o) if c.can_open && c.depth <= candidates[o].depth => open = Some(idx),
+            None if c.can_open => open = Some(idx),
+            _ => {}
+        }
+    }
+    if pairs.is_empty() {
+        return run;
+    }
+
+    // Per event: sorted (offset, replacement) cut points.
+    let mut cuts: Vec<Vec<(usize, String)>> = vec![Vec::new(); run.len()];
+    for (o, c) in pairs {
+        let (open, close) = (&candidates[o], &candidates[c]);
+        cuts[open.event].push((open.offset, format!("<{tag}>")));
+        cuts[close.event].push((close.offset, format!("</{tag}>")));
+    }
+
+    let mut out = Vec::with_capacity(run.len() + 4);
+    for (i, ev) in run.into_iter().enumerate() {
+        let mut event_cuts = std::mem::take(&mut cuts[i]);
+        if event_cuts.is_empty() {
+            out.push(ev);
+            continue;
+        }
+        let Event::Text(text) = ev else {
+            unreachable!("delimiters are only found in text events")
+        };
+        event_cuts.sort_by_key(|(offset, _)| *offset);
+        let mut last = 0;
+        for (offset, html) in event_cuts {
+            push_text(&mut out, &text[last..offset]);
+            out.push(Event::InlineHtml(CowStr::from(html)));
+            last = offset + delim.len();
+        }
+        push_text(&mut out, &text[last..]);
+    }
+    out
+}
+
+fn is_break(ev: &Event<'_>) -> bool {
+    matches!(ev, Event::SoftBreak | Event::HardBreak)
+}
+
+/// Wrap every whole-word abbreviation occurrence in `<abbr title="…">`.
+fn wrap_abbreviations<'a>(
+    run: Vec<Event<'a>>,
+    re: &Regex,
+    abbrs: &[Abbreviation],
+) -> Vec<Event<'a>> {
+    let mut out = Vec::with_capacity(run.len());
+    let mut image_depth = 0usize;
+    for ev in run {
+        match ev {
+            Event::Start(Tag::Image { .. }) => {
+                image_depth += 1;
+                out.push(ev);
+            }
+            Event::End(TagEnd::Image) => {
+                image_depth = image_depth.saturating_sub(1);
+                out.push(ev);
+            }
+            Event::Text(text) if image_depth == 0 => {
+                let mut last = 0;
+                for m in re.find_iter(&text) {
+                    let before = text[..m.start()].chars().next_back();
+                    let after = text[m.end()..].chars().next();
+                    if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+                        continue;
+                    }
+                    let Some(def) = abbrs.iter().find(|a| a.abbr == m.as_str()) else {
+                        continue;
+                    };
+                    push_text(&mut out, &text[last..m.start()]);
+                    let mut open = String::from("<abbr title=\"");
+                    escape_attr(&mut open, &def.title);
+                    open.push_str("\">");
+                    out.push(Event::InlineHtml(CowStr::from(open)));
+                    push_text(&mut out, m.as_str());
+                    out.push(Event::InlineHtml(CowStr::from("</abbr>")));
+                    last = m.end();
+                }
+                if last == 0 {
+                    out.push(Event::Text(text));
+                } else {
+                    push_text(&mut out, &text[last..]);
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn push_text<'a>(out: &mut Vec<Event<'a>>, s: &str) {
+    if !s.is_empty() {
+        out.push(Event::Text(CowStr::from(s.to_string())));
+    }
+}
+
+/// Escape an attribute value (`&`, `<`, `>`, `"`).
+fn escape_attr(buf: &mut String, s: &str) {
+    for ch in s.chars() {
+        match ch {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            other => buf.push(other),
+        }
+    }
+}
+
+/// Extract the (unescaped) `title` of an `<abbr title="…">` opener emitted by
+/// this adapter. Returns `None` for any other HTML.
+pub fn abbr_title(html: &str) -> Option<String> {
+    let raw = html.strip_prefix("<abbr title=\"")?.strip_suffix("\">")?;
+    Some(
+        raw.replace("&quot;", "\"")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::Parser;
+
+    fn all() -> MarkdownOptions {
+        MarkdownOptions {
+            mark: true,
+            insert: true,
+            abbreviations: true,
+            ..MarkdownOptions::default()
+        }
+    }
+
+    fn run(md: &str, options: &MarkdownOptions) -> Vec<Event<'static>> {
+        extensions(md, Parser::new_ext(md, options.parser_options()), options)
+            .map(Event::into_static)
+            .collect()
+    }
+
+    fn html(events: &[Event<'_>]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                Event::InlineHtml(h) => Some(h.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mark_pairs_become_mark_tags() {
+        let events = run("a ==hot== b\n", &all());
+        assert_eq!(html(&events), vec!["<mark>", "</mark>"]);
+        let text: String = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::Text(t) => Some(t.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "a hot b");
+    }
+
+    #[test]
+    fn insert_pairs_become_ins_tags() {
+        let events = run("++new++ text\n", &all());
+        assert_eq!(html(&events), vec!["<ins>", "</ins>"]);
+    }
+
+    #[test]
+    fn mark_spans_emphasis_inside_the_run() {
+        let events = run("==a *b* c==\n", &all());
+        assert_eq!(html(&events), vec!["<mark>", "</mark>"]);
+    }
+
+    #[test]
+    fn unmatched_or_spaced_delimiters_stay_literal() {
+        assert!(html(&run("x == y\n", &all())).is_empty());
+        assert!(html(&run("==open only\n", &all())).is_empty());
+    }
+
+    #[test]
+    fn disabled_extensions_leave_text_alone() {
+        let events = run("==hot== ++new++\n", &MarkdownOptions::default());
+        assert!(html(&events).is_empty());
+    }
+
+    #[test]
+    fn code_is_never_rewritten() {
+        let events = run("`==x==`\n\n```\n==y==\n```\n", &all());
+        assert!(html(&events).is_empty());
+    }
+
+    #[test]
+    fn abbreviation_definitions_are_collected_and_hidden() {
+        let md = "The HTML spec.\n\n*[HTML]: Hyper Text Markup Language\n";
+        let events = run(md, &all());
+        assert_eq!(
+            html(&events),
+            vec!["<abbr title=\"Hyper Text Markup Language\">", "</abbr>"]
+        );
+        // Only the first paragraph survives.
+        let paragraphs = events
+            .iter()
+            .filter(|e| matches!(e, Event::Start(Tag::Paragraph)))
+            .count();
+        assert_eq!(paragraphs, 1);
+    }
+
+    #[test]
+    fn abbreviations_match_whole_words_only() {
+        let md = "HTML and XHTML\n\n*[HTML]: Hyper Text\n";
+        let events = run(md, &all());
+        assert_eq!(html(&events).len(), 2, "XHTML must not match: {events:?}");
+    }
+
+    #[test]
+    fn abbreviations_prefer_the_longest_definition() {
+        let abbrs = abbreviations("*[W3]: short\n*[W3C]: World Wide Web Consortium\n");
+        assert_eq!(abbrs[0].abbr, "W3C");
+    }
+
+    #[test]
+    fn abbreviation_definitions_inside_fences_are_ignored() {
+        assert!(abbreviations("```\n*[X]: nope\n```\n").is_empty());
+    }
+
+    #[test]
+    fn abbr_title_round_trips_escaping() {
+        assert_eq!(
+            abbr_title("<abbr title=\"a &quot;b&quot; &amp; c\">").as_deref(),
+            Some("a \"b\" & c")
+        );
+        assert_eq!(abbr_title("<mark>"), None);
+    }
+}
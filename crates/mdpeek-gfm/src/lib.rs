@@ -4,41 +4,41 @@
 //! Two text-level GFM extensions that `pulldown_cmark` does not perform on its
 //! own are implemented here:
 //!
-//! * Emoji shortcodes (`:smile:` -> 😄) resolved via the `emojis` crate.
+//! * Emoji shortcodes (`:smile:` -> 😄, `:shipit:` -> GitHub's image) resolved
+//!   by [`emoji::EmojiResolver`].
 //! * GFM extended autolinks (bare `https://`, `http://` and `www.` URLs in
 //!   plain text) split into `Link` start / text / end events.
 //!
-//! Both transformations only act on [`Event::Text`]. Inline code
-//! ([`Event::Code`]) and raw HTML ([`Event::Html`] / [`Event::InlineHtml`])
-//! arrive as distinct events and are therefore left untouched automatically.
-
-use pulldown_cmark::{CowStr, Event, LinkType, Options, Tag, TagEnd};
+//! Both transformations only act on [`Event::Text`] outside code blocks and
+//! front matter. Inline code ([`Event::Code`]) and raw HTML ([`Event::Html`] /
+//! [`Event::InlineHtml`]) arrive as distinct events and are therefore left
+//! untouched automatically.
+//!
+//! Opt-in syntax from other dialects (`==mark==`, `++insert++`, abbreviations)
+//! lives in [`extensions`] and is applied before [`transform`]; smart
+//! typography ([`typography`]) is applied after it. [`source_lines`] sits
+//! in front of them all and records where each block starts in the source.
+
+pub mod cjk;
+pub mod emoji;
+pub mod extensions;
+mod options;
+pub mod source_lines;
+pub mod typography;
+
+pub use emoji::EmojiResolver;
+pub use extensions::extensions;
+pub use options::{MarkdownOptions, parser_options};
+pub use typography::{QuoteStyle, typography};
+
+use emoji::Segment;
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
 use regex::Regex;
 use std::collections::VecDeque;
 use std::sync::LazyLock;
 
-/// Parser options shared by the terminal and HTML renderers so that GFM
-/// features are parsed consistently in both modes.
-pub fn parser_options() -> Options {
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_GFM);
-    options.insert(Options::ENABLE_TASKLISTS);
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_MATH);
-    options.insert(Options::ENABLE_FOOTNOTES);
-    // Hide YAML front matter instead of rendering it as a heading/rule; the
-    // emitters skip the resulting `MetadataBlock` events.
-    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
-    options
-}
-
-/// Matches an emoji shortcode like `:smile:` or `:+1:`.
-///
-/// The shortcode body allows letters, digits, `_`, `+` and `-`, mirroring the
-/// character set used by GitHub / the `emojis` crate.
-static EMOJI_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r":([a-zA-Z0-9_+-]+):").expect("valid emoji regex"));
+/// Resolver used by [`transform`]: built-in emoji only.
+static DEFAULT_EMOJI: LazyLock<EmojiResolver> = LazyLock::new(EmojiResolver::new);
 
 /// Matches a bare URL candidate (`https://`, `http://` or `www.`).
 ///
@@ -50,23 +50,36 @@ static URL_RE: LazyLock<Regex> =
 
 /// Wraps an event iterator and applies the GFM text transformations
 /// (emoji shortcodes first, then extended autolinks).
-pub fn transform<'a, I>(iter: I) -> GfmTransform<'a, I>
+pub fn transform<'a, I>(iter: I) -> GfmTransform<'a, 'static, I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    transform_with_emoji(iter, &DEFAULT_EMOJI)
+}
+
+/// [`transform`] with a caller-supplied emoji resolver (user `[emoji]`
+/// mappings).
+pub fn transform_with_emoji<'a, 'r, I>(iter: I, emoji: &'r EmojiResolver) -> GfmTransform<'a, 'r, I>
 where
     I: Iterator<Item = Event<'a>>,
 {
     GfmTransform {
         iter,
+        emoji,
         queue: VecDeque::new(),
         link_depth: 0,
+        image_depth: 0,
+        verbatim: false,
     }
 }
 
 /// Iterator adapter produced by [`transform`].
-pub struct GfmTransform<'a, I>
+pub struct GfmTransform<'a, 'r, I>
 where
     I: Iterator<Item = Event<'a>>,
 {
     iter: I,
+    emoji: &'r EmojiResolver,
     /// Buffered events produced from a single source `Text` event. A bare URL
     /// expands into multiple events (link start / text / link end), so we need
     /// somewhere to stage the overflow.
@@ -75,9 +88,14 @@ where
     /// inside them: linkifying text that already belongs to a link would
     /// produce nested links (invalid `<a>` nesting / duplicated URLs).
     link_depth: usize,
+    /// Nesting depth of `Image` tags: alt text is plain, so image emoji fall
+    /// back to their alt text there.
+    image_depth: usize,
+    /// Inside a code block or front matter, where text is taken literally.
+    verbatim: bool,
 }
 
-impl<'a, I> Iterator for GfmTransform<'a, I>
+impl<'a, I> Iterator for GfmTransform<'a, '_, I>
 where
     I: Iterator<Item = Event<'a>>,
 {
@@ -88,23 +106,53 @@ where
             return Some(ev);
         }
         match self.iter.next()? {
+            ev @ Event::Start(Tag::CodeBlock(_) | Tag::MetadataBlock(_)) => {
+                self.verbatim = true;
+                Some(ev)
+            }
+            ev @ Event::End(TagEnd::CodeBlock | TagEnd::MetadataBlock(_)) => {
+                self.verbatim = false;
+                Some(ev)
+            }
+            ev @ Event::Text(_) if self.verbatim => Some(ev),
+            Event::Text(text) if self.image_depth > 0 => {
+                let replaced = self.emoji.replace_shortcodes(&text).into_owned();
+                Some(Event::Text(CowStr::from(replaced)))
+            }
             Event::Text(text) => {
                 // Resolve emoji shortcodes first, then detect autolinks within
-                // the (possibly emoji-substituted) text.
-                let replaced = replace_emoji(&text);
-                if self.link_depth > 0 {
-                    Some(Event::Text(CowStr::from(replaced)))
-                } else {
-                    expand_autolinks(&replaced, &mut self.queue);
-                    self.queue.pop_front()
+                // the (possibly emoji-substituted) text. Image emoji become
+                // inline HTML between the text pieces.
+                for segment in self.emoji.segments(&text) {
+                    match segment {
+                        Segment::Text(t) if self.link_depth > 0 => {
+                            push_text(&mut self.queue, &t);
+                        }
+                        Segment::Text(t) => expand_autolinks(&t, &mut self.queue),
+                        Segment::Image(html) => {
+                            self.queue.push_back(Event::InlineHtml(CowStr::from(html)));
+                        }
+                    }
                 }
+                // An empty source text still yields one event.
+                Some(
+                    self.queue
+                        .pop_front()
+                        .unwrap_or_else(|| Event::Text(CowStr::from(""))),
+                )
             }
             ev @ Event::Start(Tag::Link { .. } | Tag::Image { .. }) => {
                 self.link_depth += 1;
+                if matches!(ev, Event::Start(Tag::Image { .. })) {
+                    self.image_depth += 1;
+                }
                 Some(ev)
             }
             ev @ Event::End(TagEnd::Link | TagEnd::Image) => {
                 self.link_depth = self.link_depth.saturating_sub(1);
+                if matches!(ev, Event::End(TagEnd::Image)) {
+                    self.image_depth = self.image_depth.saturating_sub(1);
+                }
                 Some(ev)
             }
             other => Some(other),
@@ -112,31 +160,6 @@ where
     }
 }
 
-/// Replaces every resolvable `:shortcode:` in `text` with its emoji character.
-///
-/// Unknown shortcodes (e.g. GitHub-only `:shipit:`) are left verbatim,
-/// colons included. When no substitution happens the borrowed slice is reused.
-fn replace_emoji(text: &str) -> String {
-    if !text.contains(':') {
-        return text.to_string();
-    }
-    let mut out = String::with_capacity(text.len());
-    let mut last = 0;
-    for caps in EMOJI_RE.captures_iter(text) {
-        let whole = caps.get(0).unwrap();
-        let name = caps.get(1).unwrap().as_str();
-        if let Some(emoji) = emojis::get_by_shortcode(name) {
-            out.push_str(&text[last..whole.start()]);
-            out.push_str(emoji.as_str());
-            last = whole.end();
-        }
-        // Unknown shortcode: leave it untouched (the `last` cursor is not
-        // advanced, so the original text including colons is copied later).
-    }
-    out.push_str(&text[last..]);
-    out
-}
-
 /// Scans `text` for bare URLs and pushes the resulting event sequence onto
 /// `queue`. Plain text segments become `Text` events; each detected URL becomes
 /// a `Start(Link)` / `Text(url)` / `End(Link)` triple.
@@ -243,6 +266,10 @@ fn trim_autolink(raw: &str) -> (&str, usize) {
 mod tests {
     use super::*;
 
+    fn replace_emoji(text: &str) -> String {
+        EmojiResolver::new().replace_shortcodes(text).into_owned()
+    }
+
     fn texts(events: &[Event<'_>]) -> Vec<String> {
         events
             .iter()
@@ -367,4 +394,45 @@ mod tests {
         });
         assert!(autolinked, "events: {events:?}");
     }
+
+    #[test]
+    fn github_image_emoji_becomes_inline_html() {
+        use pulldown_cmark::Parser;
+        let events: Vec<_> = transform(Parser::new("ship :shipit: now")).collect();
+        let img = events
+            .iter()
+            .any(|e| matches!(e, Event::InlineHtml(h) if h.starts_with("<img class=\"emoji\"")));
+        assert!(img, "events: {events:?}");
+        assert_eq!(texts(&events), vec!["ship ", " now"]);
+    }
+
+    #[test]
+    fn image_emoji_in_alt_text_falls_back_to_alt() {
+        use pulldown_cmark::Parser;
+        let events: Vec<_> = transform(Parser::new("![go :shipit:](a.png)")).collect();
+        assert!(!events.iter().any(|e| matches!(e, Event::InlineHtml(_))));
+        assert_eq!(texts(&events), vec!["go :shipit:"]);
+    }
+
+    #[test]
+    fn code_blocks_are_left_verbatim() {
+        use pulldown_cmark::Parser;
+        let events: Vec<_> =
+            transform(Parser::new("```\n:smile: https://example.com\n```\n")).collect();
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, Event::Start(Tag::Link { .. })))
+        );
+        assert_eq!(texts(&events), vec![":smile: https://example.com\n"]);
+    }
+
+    #[test]
+    fn user_emoji_mapping_is_used() {
+        use pulldown_cmark::Parser;
+        let mut resolver = EmojiResolver::new();
+        resolver.insert("party", emoji::Emoji::Unicode("🎉".into()));
+        let events: Vec<_> = transform_with_emoji(Parser::new("a :party:"), &resolver).collect();
+        assert_eq!(texts(&events), vec!["a 🎉"]);
+    }
 }
@@ -0,0 +1,236 @@
+//! Which markdown syntax is recognised.
+//!
+//! Every consumer (block parser, both emitters, link extraction) builds its
+//! `pulldown_cmark` parser from one [`MarkdownOptions`] so a document is parsed
+//! identically everywhere. The options come from the `[markdown]` config
+//! section and can be overridden per document from front matter:
+//!
+//! ```yaml
+//! ---
+//! lang: ja
+//! markdown:
+//!   math: false
+//!   wiki_links: true
+//! ---
+//! ```
+
+use crate::typography::QuoteStyle;
+use pulldown_cmark::Options;
+
+/// Parser options for the default feature set (GFM plus math and footnotes).
+/// Equivalent to `MarkdownOptions::default().parser_options()`.
+pub fn parser_options() -> Options {
+    MarkdownOptions::default().parser_options()
+}
+
+/// Optional markdown syntax. GFM itself (tables, task lists, strikethrough,
+/// alerts) is always on; math and footnotes are on by default to match GitHub,
+/// everything else is opt-in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// `$inline$` / `$$display$$` math. Turn off for documents full of prices
+    /// like `$5 and $10`.
+    pub math: bool,
+    /// `[^1]` footnote references and definitions.
+    pub footnotes: bool,
+    /// Curly quotes, en/em dashes and ellipses (`"a"` → “a”, `--` → –),
+    /// applied by the [`crate::typography`] stage.
+    pub smart_punctuation: bool,
+    /// `Term` / `: definition` lists.
+    pub definition_lists: bool,
+    /// `# Heading {#id .class}` → custom anchor id and classes.
+    pub heading_attributes: bool,
+    /// `^superscript^`.
+    pub superscript: bool,
+    /// `~subscript~`. Single tildes then no longer mean strikethrough.
+    pub subscript: bool,
+    /// `[[Page]]` / `[[Page|label]]` wiki links.
+    pub wiki_links: bool,
+    /// `==text==` → `<mark>`.
+    pub mark: bool,
+    /// `++text++` → `<ins>`.
+    pub insert: bool,
+    /// `*[ABBR]: Expansion` definitions → `<abbr title>`.
+    pub abbreviations: bool,
+    /// Quotation marks used by smart punctuation. Set from the document's
+    /// front matter `lang:` or the configured language.
+    pub quotes: QuoteStyle,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            math: true,
+            footnotes: true,
+            smart_punctuation: false,
+            definition_lists: false,
+            heading_attributes: false,
+            superscript: false,
+            subscript: false,
+            wiki_links: false,
+            mark: false,
+            insert: false,
+            abbreviations: false,
+            quotes: QuoteStyle::English,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// The `pulldown_cmark` options for this feature set.
+    pub fn parser_options(&self) -> Options {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_GFM);
+        options.insert(Options::ENABLE_TASKLISTS);
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        // Hide YAML front matter instead of rendering it as a heading/rule; the
+        // emitters skip the resulting `MetadataBlock` events.
+        options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+        options.set(Options::ENABLE_MATH, self.math);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        options.set(Options::ENABLE_DEFINITION_LIST, self.definition_lists);
+        options.set(Options::ENABLE_HEADING_ATTRIBUTES, self.heading_attributes);
+        options.set(Options::ENABLE_SUPERSCRIPT, self.superscript);
+        options.set(Options::ENABLE_SUBSCRIPT, self.subscript);
+        options.set(Options::ENABLE_WIKILINKS, self.wiki_links);
+        options
+    }
+
+    /// These options with any `markdown:` overrides (and the top-level `lang:`)
+    /// from `source`'s YAML front matter applied. Unknown keys and non-boolean
+    /// values are ignored so a typo never breaks rendering.
+    pub fn for_document(&self, source: &str) -> Self {
+        let mut options = *self;
+        let Some(frontmatter) = frontmatter(source) else {
+            return options;
+        };
+        let mut in_section = false;
+        for line in frontmatter.lines() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let indented = line.starts_with([' ', '\t']);
+            if !indented {
+                in_section = line.trim_end() == "markdown:";
+                if let Some(lang) = line.strip_prefix("lang:") {
+                    let lang = lang.trim().trim_matches(['"', '\'']);
+                    if !lang.is_empty() {
+                        options.quotes = QuoteStyle::from_lang(lang);
+                    }
+                }
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let value = match value.trim() {
+                "true" | "yes" | "on" => true,
+                "false" | "no" | "off" => false,
+                _ => continue,
+            };
+            options.set(key.trim(), value);
+        }
+        options
+    }
+
+    /// Set the flag named `key` (the config / front matter spelling). Returns
+    /// `false` for an unknown key.
+    pub fn set(&mut self, key: &str, value: bool) -> bool {
+        let flag = match key {
+            "math" => &mut self.math,
+            "footnotes" => &mut self.footnotes,
+            "smart_punctuation" => &mut self.smart_punctuation,
+            "definition_lists" => &mut self.definition_lists,
+            "heading_attributes" => &mut self.heading_attributes,
+            "superscript" => &mut self.superscript,
+            "subscript" => &mut self.subscript,
+            "wiki_links" => &mut self.wiki_links,
+            "mark" => &mut self.mark,
+            "insert" => &mut self.insert,
+            "abbreviations" => &mut self.abbreviations,
+            _ => return false,
+        };
+        *flag = value;
+        true
+    }
+}
+
+/// The body of a leading `---` YAML front matter block, if any.
+fn frontmatter(source: &str) -> Option<&str> {
+    let rest = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return Some(&rest[..offset]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_the_historical_feature_set() {
+        let options = parser_options();
+        assert!(options.contains(Options::ENABLE_MATH));
+        assert!(options.contains(Options::ENABLE_FOOTNOTES));
+        assert!(!options.contains(Options::ENABLE_SMART_PUNCTUATION));
+        assert!(!options.contains(Options::ENABLE_WIKILINKS));
+    }
+
+    #[test]
+    fn disabling_math_leaves_dollars_as_text() {
+        let options = MarkdownOptions {
+            math: false,
+            ..MarkdownOptions::default()
+        };
+        let has_math = pulldown_cmark::Parser::new_ext("$5 and $10", options.parser_options())
+            .any(|e| matches!(e, pulldown_cmark::Event::InlineMath(_)));
+        assert!(!has_math);
+    }
+
+    #[test]
+    fn front_matter_overrides_config() {
+        let src = "---\ntitle: Prices\nmarkdown:\n  math: false\n  wiki_links: yes\n---\n\n$5\n";
+        let options = MarkdownOptions::default().for_document(src);
+        assert!(!options.math);
+        assert!(options.wiki_links);
+        // Untouched keys keep the configured value.
+        assert!(options.footnotes);
+    }
+
+    #[test]
+    fn front_matter_ignores_unknown_keys_and_other_sections() {
+        let src = "---\nother:\n  math: false\nmarkdown:\n  bogus: true\n  footnotes: maybe\n---\n";
+        assert_eq!(
+            MarkdownOptions::default().for_document(src),
+            MarkdownOptions::default()
+        );
+    }
+
+    #[test]
+    fn front_matter_lang_selects_quote_style() {
+        let src = "---\ntitle: 文書\nlang: \"ja-JP\"\n---\n";
+        let options = MarkdownOptions::default().for_document(src);
+        assert_eq!(options.quotes, QuoteStyle::Japanese);
+    }
+
+    #[test]
+    fn without_front_matter_options_are_unchanged() {
+        let options = MarkdownOptions {
+            smart_punctuation: true,
+            ..MarkdownOptions::default()
+        };
+        assert_eq!(options.for_document("# Title\n"), options);
+    }
+}
@@ -0,0 +1,137 @@
+//! Source lines of rendered blocks.
+//!
+//! The adapters in this crate work on plain [`Event`]s, so byte offsets have
+//! to be stripped before them. [`source_lines`] strips them while recording
+//! the 1-based start line of every block, and an emitter fed the adapted
+//! events takes each line back as it opens the block ([`SourceLines::take`]).
+//! The browser marks its elements with it (`data-source-line`); the terminal
+//! viewer maps source lines onto rendered lines.
+
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Start lines of the blocks read so far, in document order. Shared between
+/// the event iterator returned by [`source_lines`] and the emitter reading it.
+#[derive(Debug, Clone, Default)]
+pub struct SourceLines(Rc<RefCell<VecDeque<(TagEnd, u32)>>>);
+
+impl SourceLines {
+    /// The start line of the block `tag` opens, or `None` for inline tags.
+    /// Records of another kind are skipped: blocks the emitter does not ask
+    /// for, or ones an adapter dropped (abbreviation definitions), so the
+    /// lines of later blocks stay right.
+    pub fn take(&self, tag: &Tag) -> Option<u32> {
+        if !is_block(tag) {
+            return None;
+        }
+        let end = tag.to_end();
+        let mut queue = self.0.borrow_mut();
+        while let Some((kind, line)) = queue.pop_front() {
+            if kind == end {
+                return Some(line);
+            }
+        }
+        None
+    }
+}
+
+/// Block-level tags, whose start lines are recorded.
+fn is_block(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Paragraph
+            | Tag::Heading { .. }
+            | Tag::BlockQuote(_)
+            | Tag::CodeBlock(_)
+            | Tag::HtmlBlock
+            | Tag::List(_)
+            | Tag::Item
+            | Tag::FootnoteDefinition(_)
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+            | Tag::Table(_)
+            | Tag::MetadataBlock(_)
+    )
+}
+
+/// Strip the offsets of `Parser::into_offset_iter()` events over `source`,
+/// recording the start line of every block in the returned [`SourceLines`].
+/// Adapters may be layered on the returned events before they reach the
+/// emitter.
+pub fn source_lines<'a, I>(
+    source: &str,
+    iter: I,
+) -> (impl Iterator<Item = Event<'a>> + use<'a, I>, SourceLines)
+where
+    I: Iterator<Item = (Event<'a>, Range<usize>)>,
+{
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let lines = SourceLines::default();
+    let queue = lines.0.clone();
+    let events = iter.map(move |(event, range)| {
+        if let Event::Start(tag) = &event
+            && is_block(tag)
+        {
+            let line = line_starts.partition_point(|&start| start <= range.start) as u32;
+            queue.borrow_mut().push_back((tag.to_end(), line));
+        }
+        event
+    });
+    (events, lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Options, Parser};
+
+    #[test]
+    fn blocks_are_taken_back_in_order() {
+        let md = "# Title\n\nText with *emphasis*.\n\n- a\n- b\n";
+        let (events, lines) = source_lines(md, Parser::new(md).into_offset_iter());
+        let mut taken = Vec::new();
+        for event in events {
+            if let Event::Start(tag) = &event
+                && let Some(line) = lines.take(tag)
+            {
+                taken.push((tag.to_end(), line));
+            }
+        }
+        assert_eq!(
+            taken,
+            vec![
+                (TagEnd::Heading(pulldown_cmark::HeadingLevel::H1), 1),
+                (TagEnd::Paragraph, 3),
+                (TagEnd::List(false), 5),
+                (TagEnd::Item, 5),
+                (TagEnd::Item, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn unrequested_and_dropped_blocks_are_skipped() {
+        let md = "---\ntitle: x\n---\n\ndropped\n\n## Next\n\nkept\n";
+        let options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+        let (events, lines) = source_lines(md, Parser::new_ext(md, options).into_offset_iter());
+        // Read everything, as an emitter behind an adapter would.
+        let events: Vec<Event> = events.collect();
+        let heading = events
+            .iter()
+            .find_map(|e| match e {
+                Event::Start(tag @ Tag::Heading { .. }) => Some(tag.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(lines.take(&heading), Some(7));
+        assert_eq!(lines.take(&Tag::Paragraph), Some(9));
+        assert_eq!(lines.take(&Tag::Emphasis), None);
+        assert_eq!(lines.take(&Tag::Paragraph), None);
+    }
+}
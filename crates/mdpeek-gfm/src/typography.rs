@@ -0,0 +1,351 @@
+//! Smart typography: curly quotes, en/em dashes and ellipses.
+//!
+//! Applied as a stage after [`crate::transform`] so that every emitter gets the
+//! same text. Only prose is rewritten: code spans, math and raw HTML arrive as
+//! their own events and pass through, and the text of code blocks, front
+//! matter and autolinks (where `--` or `'` may be part of a URL) is left alone.
+//!
+//! The quote characters depend on the document language ([`QuoteStyle`]),
+//! taken from front matter `lang:` or the `[markdown] lang` config key.
+
+use crate::MarkdownOptions;
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+
+/// Which quotation marks `"` and `'` turn into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteStyle {
+    /// “double” and ‘single’.
+    #[default]
+    English,
+    /// 「かぎ括弧」 and 『二重かぎ括弧』.
+    Japanese,
+    /// « guillemets » with narrow no-break spaces inside, and ‹ single ›.
+    French,
+    /// „Anführungszeichen“ and ‚einfache‘.
+    German,
+}
+
+/// U+202F NARROW NO-BREAK SPACE, used inside French guillemets.
+const NNBSP: char = '\u{202F}';
+
+impl QuoteStyle {
+    /// Style for a BCP 47-ish language tag (`ja`, `fr-CA`, `de_DE`). Unknown
+    /// languages use English quotes.
+    pub fn from_lang(lang: &str) -> Self {
+        let primary = lang
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "ja" => Self::Japanese,
+            "fr" => Self::French,
+            "de" => Self::German,
+            _ => Self::English,
+        }
+    }
+
+    fn double(self, open: bool) -> &'static str {
+        match (self, open) {
+            (Self::English, true) => "“",
+            (Self::English, false) => "”",
+            (Self::Japanese, true) => "「",
+            (Self::Japanese, false) => "」",
+            (Self::French, true) => "«\u{202F}",
+            (Self::French, false) => "\u{202F}»",
+            (Self::German, true) => "„",
+            (Self::German, false) => "“",
+        }
+    }
+
+    fn single(self, open: bool) -> &'static str {
+        match (self, open) {
+            (Self::English, true) => "‘",
+            (Self::English, false) => "’",
+            (Self::Japanese, true) => "『",
+            (Self::Japanese, false) => "』",
+            (Self::French, true) => "‹\u{202F}",
+            (Self::French, false) => "\u{202F}›",
+            (Self::German, true) => "‚",
+            (Self::German, false) => "‘",
+        }
+    }
+}
+
+/// Wraps an event iterator with the typography stage. A no-op unless
+/// `options.smart_punctuation` is set.
+pub fn typography<'a, I>(iter: I, options: &MarkdownOptions) -> Typography<I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    Typography {
+        iter,
+        enabled: options.smart_punctuation,
+        quotes: options.quotes,
+        verbatim: false,
+        autolink_depth: 0,
+        prev: None,
+        double_open: false,
+        single_open: false,
+    }
+}
+
+/// Iterator adapter produced by [`typography`].
+pub struct Typography<I> {
+    iter: I,
+    enabled: bool,
+    quotes: QuoteStyle,
+    /// Inside a code block or front matter.
+    verbatim: bool,
+    /// Nesting depth of autolinks / email links, whose text is a URL.
+    autolink_depth: usize,
+    /// Last character of the current block's text so far. Quotes often sit in
+    /// a different text event than their neighbours (`"**bold**"`).
+    prev: Option<char>,
+    double_open: bool,
+    single_open: bool,
+}
+
+impl<'a, I> Iterator for Typography<I>
+where
+    I: Iterator<Item = Event<'a>>,
+{
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.iter.next()?;
+        if !self.enabled {
+            return Some(event);
+        }
+        match &event {
+            Event::Start(Tag::CodeBlock(_) | Tag::MetadataBlock(_)) => self.verbatim = true,
+            Event::End(TagEnd::CodeBlock | TagEnd::MetadataBlock(_)) => self.verbatim = false,
+            Event::Start(Tag::Link {
+                link_type: LinkType::Autolink | LinkType::Email,
+                ..
+            }) => self.autolink_depth += 1,
+            Event::End(TagEnd::Link) if self.autolink_depth > 0 => self.autolink_depth -= 1,
+            Event::Text(text) if !self.verbatim && self.autolink_depth == 0 => {
+                let out = self.smarten(text);
+                return Some(Event::Text(out.map(CowStr::from).unwrap_or(text.clone())));
+            }
+            Event::Text(text) | Event::Code(text) | Event::InlineMath(text) => {
+                if let Some(last) = text.chars().next_back() {
+                    self.prev = Some(last);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => self.prev = Some(' '),
+            Event::Start(tag) if !is_inline(tag) => self.reset(),
+            Event::End(tag) if !is_inline_end(tag) => self.reset(),
+            _ => {}
+        }
+        Some(event)
+    }
+}
+
+impl<I> Typography<I> {
+    /// Quote matching is per block.
+    fn reset(&mut self) {
+        self.prev = None;
+        self.double_open = false;
+        self.single_open = false;
+    }
+
+    /// Rewrite `text`, or `None` when nothing changed.
+    fn smarten(&mut self, text: &str) -> Option<String> {
+        if !text.contains(['"', '\'', '-', '.']) {
+            if let Some(last) = text.chars().next_back() {
+                self.prev = Some(last);
+            }
+            return None;
+        }
+        let mut out = String::with_capacity(text.len() + 8);
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            let next = chars.peek().copied();
+            match c {
+                '"' => {
+                    let open = self.opens(next, self.double_open);
+                    self.double_open = open;
+                    out.push_str(self.quotes.double(open));
+                }
+                '\'' => {
+                    let between_words = self.prev.is_some_and(is_word_char)
+                        && (next.is_some_and(is_word_char) || !self.single_open);
+                    let year = self.prev.is_none_or(char::is_whitespace)
+                        && next.is_some_and(|n| n.is_ascii_digit());
+                    if between_words || year {
+                        // Apostrophe (don't, dogs', '90s) in every language.
+                        out.push('’');
+                    } else {
+                        let open = self.opens(next, self.single_open);
+                        self.single_open = open;
+                        out.push_str(self.quotes.single(open));
+                    }
+                }
+                '-' => {
+                    let mut run = 1;
+                    while chars.next_if_eq(&'-').is_some() {
+                        run += 1;
+                    }
+                    match run {
+                        2 => out.push('–'),
+                        3 => out.push('—'),
+                        _ => out.extend(std::iter::repeat_n('-', run)),
+                    }
+                }
+                '.' => {
+                    let mut run = 1;
+                    while chars.next_if_eq(&'.').is_some() {
+                        run += 1;
+                    }
+                    if run == 3 {
+                        out.push('…');
+                    } else {
+                        out.extend(std::iter::repeat_n('.', run));
+                    }
+                }
+                _ => out.push(c),
+            }
+            self.prev = out.chars().next_back().filter(|&c| c != NNBSP).or(Some(c));
+        }
+        Some(out)
+    }
+
+    /// Whether a quote at the current position opens (rather than closes).
+    /// An open quote closes at the first chance; otherwise a quote opens when
+    /// text follows it. `next` is `None` at the end of a text event, where the
+    /// following text is unknown and assumed to exist.
+    fn opens(&self, next: Option<char>, already_open: bool) -> bool {
+        if already_open && self.prev.is_some_and(|p| !p.is_whitespace()) {
+            return false;
+        }
+        next.is_none_or(|n| !n.is_whitespace())
+    }
+}
+
+/// Letters and digits, excluding CJK where `'` is never an apostrophe.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !crate::cjk::is_east_asian_wide(c)
+}
+
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+fn is_inline_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::Parser;
+
+    fn smarten(md: &str, quotes: QuoteStyle) -> String {
+        let options = MarkdownOptions {
+            smart_punctuation: true,
+            quotes,
+            ..MarkdownOptions::default()
+        };
+        let mut out = String::new();
+        for event in typography(Parser::new_ext(md, options.parser_options()), &options) {
+            match event {
+                Event::Text(t) | Event::Code(t) | Event::InlineMath(t) => out.push_str(&t),
+                Event::SoftBreak => out.push(' '),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn english_quotes_dashes_and_ellipses() {
+        assert_eq!(
+            smarten(
+                r#"She said "it's fine" -- then 'left'... 1990---2000"#,
+                QuoteStyle::English
+            ),
+            "She said “it’s fine” – then ‘left’… 1990—2000"
+        );
+    }
+
+    #[test]
+    fn quotes_pair_across_inline_markup() {
+        assert_eq!(
+            smarten(r#""**bold**" text"#, QuoteStyle::English),
+            "“bold” text"
+        );
+    }
+
+    #[test]
+    fn japanese_uses_corner_brackets() {
+        assert_eq!(
+            smarten(
+                r#"彼は"こんにちは"と言った。'引用'も"#,
+                QuoteStyle::Japanese
+            ),
+            "彼は「こんにちは」と言った。『引用』も"
+        );
+    }
+
+    #[test]
+    fn french_uses_spaced_guillemets() {
+        assert_eq!(
+            smarten(r#"Il a dit "bonjour""#, QuoteStyle::French),
+            "Il a dit «\u{202F}bonjour\u{202F}»"
+        );
+    }
+
+    #[test]
+    fn code_math_and_autolinks_are_untouched() {
+        let out = smarten(
+            "`a -- \"b\"` $x--y$ <https://example.com/a--b> \"c\"\n\n```\n\"d\" --\n```\n",
+            QuoteStyle::English,
+        );
+        assert_eq!(
+            out,
+            "a -- \"b\" x--y https://example.com/a--b “c”\"d\" --\n"
+        );
+    }
+
+    #[test]
+    fn disabled_stage_is_a_passthrough() {
+        let options = MarkdownOptions::default();
+        let texts: Vec<_> = typography(Parser::new(r#""a" -- b"#), &options)
+            .filter_map(|e| match e {
+                Event::Text(t) => Some(t.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec![r#""a" -- b"#]);
+    }
+
+    #[test]
+    fn lang_tags_map_to_styles() {
+        assert_eq!(QuoteStyle::from_lang("ja"), QuoteStyle::Japanese);
+        assert_eq!(QuoteStyle::from_lang("fr-CA"), QuoteStyle::French);
+        assert_eq!(QuoteStyle::from_lang("de_DE"), QuoteStyle::German);
+        assert_eq!(QuoteStyle::from_lang("en-US"), QuoteStyle::English);
+        assert_eq!(QuoteStyle::from_lang("pt"), QuoteStyle::English);
+    }
+}
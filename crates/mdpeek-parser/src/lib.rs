@@ -158,7 +158,8 @@ pub struct Block {
     pub range: SourceRange,
     pub children: Vec<Block>,
     /// Text extracted for display / analysis (inline formatting flattened,
-    /// soft breaks collapsed to spaces, trimmed).
+    /// soft breaks collapsed to spaces — or dropped between CJK characters —
+    /// trimmed).
     pub text: String,
 }
 
@@ -450,7 +451,7 @@ fn consume<'a, I>(
             Event::Text(t) | Event::Code(t) => out_text.push_str(&t),
             Event::InlineMath(t) | Event::DisplayMath(t) => out_text.push_str(&t),
             Event::Html(t) | Event::InlineHtml(t) => out_text.push_str(&t),
-            Event::SoftBreak => out_text.push(' '),
+            Event::SoftBreak => out_text.push(SOFT_BREAK),
             Event::HardBreak => out_text.push('\n'),
             Event::Rule => {
                 if collect_blocks {
@@ -469,9 +470,27 @@ fn consume<'a, I>(
     }
 }
 
-/// Trims surrounding whitespace from extracted block text.
+/// Placeholder for a soft line break while a block's text is collected; the
+/// next character isn't known yet. `pulldown_cmark` replaces NUL in the source
+/// with U+FFFD, so it never occurs in real text.
+const SOFT_BREAK: char = '\0';
+
+/// Resolves soft breaks (a space, or nothing between two East Asian wide
+/// characters) and trims surrounding whitespace from extracted block text.
 fn normalize_text(text: &str) -> String {
-    text.trim().to_string()
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != SOFT_BREAK {
+            out.push(c);
+            continue;
+        }
+        let next = chars.peek().copied().filter(|&n| n != SOFT_BREAK);
+        if !mdpeek_gfm::cjk::joins_without_space(out.chars().next_back(), next) {
+            out.push(' ');
+        }
+    }
+    out.trim().to_string()
 }
 
 /// Assigns content-stable ids to `blocks` and their descendants in pre-order.
@@ -557,6 +576,33 @@ mod tests {
         assert_eq!(tree.blocks[0].text, "one two");
     }
 
+    #[test]
+    fn soft_break_between_japanese_is_dropped() {
+        let src = "日本語の文章が\n途中で折り返されている。\n";
+        let tree = BlockTree::parse(src);
+        assert_eq!(
+            tree.blocks[0].text,
+            "日本語の文章が途中で折り返されている。"
+        );
+    }
+
+    #[test]
+    fn soft_break_between_japanese_and_english_keeps_space() {
+        let src = "Rust で書かれた\nmarkdown viewer です。\nNext line\nは英語から。\n";
+        let tree = BlockTree::parse(src);
+        assert_eq!(
+            tree.blocks[0].text,
+            "Rust で書かれた markdown viewer です。 Next line は英語から。"
+        );
+    }
+
+    #[test]
+    fn soft_break_join_sees_through_inline_markup() {
+        let src = "これは\n**重要**です\n";
+        let tree = BlockTree::parse(src);
+        assert_eq!(tree.blocks[0].text, "これは重要です");
+    }
+
     #[test]
     fn fenced_code_block_language() {
         let src = "```rust\nfn main() {}\n```\n";
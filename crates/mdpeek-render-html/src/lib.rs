@@ -2,6 +2,12 @@ use pulldown_cmark::{Alignment, BlockQuoteKind, CodeBlockKind, Event, LinkType,
 use std::collections::HashMap;
 use tracing::{debug, error};
 
+/// ブロック要素のソース上の開始行（1始まり）の記録。[`source_lines`] が
+/// オフセット付きのイベント列から積み、[`HtmlEmitter::with_source_lines`] を
+/// 設定した emitter が `data-source-line` 属性に出力する。ブラウザはこの属性で
+/// 解析結果のブロックを描画済みの要素と対応付ける。
+pub use mdpeek_gfm::source_lines::{SourceLines, source_lines};
+
 enum TableState {
     Head,
     Body,
@@ -14,6 +20,12 @@ struct HeadingState {
     /// アンカーid生成用のプレーンテキスト（タグを含まない）
     text: String,
     end_newline: bool,
+    /// `{#id}` 属性で指定されたアンカーid（指定が無ければテキストから生成）
+    id: Option<String>,
+    /// `{.class}` 属性で指定されたクラス
+    classes: Vec<String>,
+    /// 見出しのソース上の開始行（`data-source-line` 属性に出力）
+    source_line: Option<u32>,
 }
 
 impl HeadingState {
@@ -22,6 +34,9 @@ impl HeadingState {
             end_newline: false,
             html: String::new(),
             text: String::new(),
+            id: None,
+            classes: Vec::new(),
+            source_line: None,
         }
     }
     fn push_html(&mut self, html: &str) {
@@ -36,6 +51,44 @@ impl HeadingState {
     }
 }
 
+/// 出力を保留しているソフト改行の挿入位置
+///
+/// CJK文字同士の改行は空白なしで連結したいので、次のテキストの先頭文字が
+/// 分かるまで改行を出力しない。
+#[derive(Debug, Clone, Copy)]
+struct PendingSoftBreak {
+    /// 本文バッファ内のバイト位置
+    body: usize,
+    /// 見出し内の場合の (html, text) のバイト位置
+    heading: Option<(usize, usize)>,
+}
+
+/// `data-source-line` を付けるブロック要素（見出し・段落・リスト・リスト項目・
+/// コードブロック・引用・表）
+fn is_source_block(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Heading { .. }
+            | Tag::Paragraph
+            | Tag::List(_)
+            | Tag::Item
+            | Tag::CodeBlock(_)
+            | Tag::BlockQuote(_)
+            | Tag::Table(_)
+    )
+}
+
+/// `from` 以降に出力された最初の開始タグのタグ名の直後に `data-source-line` を挿入する
+fn mark_source_line(buf: &mut String, from: usize, line: u32) {
+    let Some(open) = buf[from..].find('<') else {
+        return;
+    };
+    let name_end = buf[from + open..]
+        .find([' ', '>'])
+        .map_or(buf.len(), |i| from + open + i);
+    buf.insert_str(name_end, &format!(" data-source-line=\"{line}\""));
+}
+
 pub struct HtmlEmitter<I> {
     iter: I,
     end_newline: bool,
@@ -45,6 +98,11 @@ pub struct HtmlEmitter<I> {
     table_cell_index: usize,
     numbers: HashMap<String, usize>,
     heading_state: Option<HeadingState>,
+    soft_break: Option<PendingSoftBreak>,
+    /// 直前に出力したテキストの末尾文字（ソフト改行の連結判定用）
+    last_char: Option<char>,
+    /// ブロック要素の開始行（設定時のみ `data-source-line` を出力）
+    source_lines: Option<SourceLines>,
 }
 
 impl<'a, I> HtmlEmitter<I>
@@ -61,14 +119,65 @@ where
             table_cell_index: 0,
             numbers: HashMap::new(),
             heading_state: None,
+            soft_break: None,
+            last_char: None,
+            source_lines: None,
+        }
+    }
+    /// ブロック要素の開始タグに [`source_lines`] が記録した開始行を
+    /// `data-source-line` 属性として出力する。
+    pub fn with_source_lines(mut self, lines: SourceLines) -> Self {
+        self.source_lines = Some(lines);
+        self
+    }
+    /// `tag` の開始行。種類の合わない記録（アダプタが捨てたブロック）は読み飛ばす。
+    fn source_line(&self, tag: &Tag) -> Option<u32> {
+        if !is_source_block(tag) {
+            return None;
         }
+        self.source_lines.as_ref()?.take(tag)
     }
     pub fn run(&mut self) -> String {
         let mut html_body = String::new();
         while let Some(event) = self.iter.next() {
+            if !is_inline_markup(&event) {
+                let next = match &event {
+                    Event::Text(text) | Event::Code(text) => text.chars().next(),
+                    _ => None,
+                };
+                self.resolve_soft_break(&mut html_body, next);
+                if !matches!(event, Event::Text(_) | Event::Code(_) | Event::SoftBreak) {
+                    self.last_char = None;
+                }
+            }
             match event {
-                Event::Start(tag) => self.start_tag(&mut html_body, tag),
-                Event::End(tag) => self.end_tag(&mut html_body, tag),
+                Event::Start(tag) => {
+                    let line = self.source_line(&tag);
+                    let heading = matches!(tag, Tag::Heading { .. });
+                    let at = html_body.len();
+                    self.start_tag(&mut html_body, tag);
+                    match (line, self.heading_state.as_mut()) {
+                        // 見出しタグは終了時に出力するのでそこで付ける
+                        (Some(line), Some(heading_state)) if heading => {
+                            heading_state.source_line = Some(line)
+                        }
+                        (Some(line), _) => mark_source_line(&mut html_body, at, line),
+                        (None, _) => {}
+                    }
+                }
+                Event::End(tag) => {
+                    let line = match tag {
+                        TagEnd::Heading(_) => {
+                            self.heading_state.as_ref().and_then(|h| h.source_line)
+                        }
+                        _ => None,
+                    };
+                    let at = html_body.len();
+                    self.end_tag(&mut html_body, tag);
+                    if let Some(line) = line {
+                        mark_source_line(&mut html_body, at, line);
+                    }
+                }
                 Event::Text(text) => {
                     if !self.in_non_writing_block {
                         if let Some(heading_state) = self.heading_state.as_mut() {
@@ -78,9 +187,11 @@ where
                             escape_html_body(&mut html_body, &text);
                         }
                         self.end_newline = text.ends_with('\n');
+                        self.last_char = text.chars().next_back();
                     }
                 }
                 Event::Code(text) => {
+                    self.last_char = text.chars().next_back();
                     if let Some(heading_state) = self.heading_state.as_mut() {
                         // 見出し中: html側に<code>タグ付きで、text側にはプレーンテキストを蓄積
                         heading_state.push_html("<code>");
@@ -124,19 +235,26 @@ where
                         html_body.push_str(&span);
                     }
                 }
-                Event::Html(html) | Event::InlineHtml(html) => {
-                    html_body.push_str(&html);
-                }
-                Event::SoftBreak => {
-                    // Setext headings can span multiple source lines.
+                Event::InlineHtml(html) => {
+                    // 見出し内のインラインHTML（<mark> 等）は見出しタグの内側に出力する
                     if let Some(heading_state) = self.heading_state.as_mut() {
-                        heading_state.push_html("\n");
-                        heading_state.text.push(' ');
+                        heading_state.push_html(&html);
                     } else {
-                        self.end_newline = true;
-                        html_body.push('\n');
+                        html_body.push_str(&html);
                     }
                 }
+                Event::Html(html) => {
+                    html_body.push_str(&html);
+                }
+                Event::SoftBreak => {
+                    // 改行を出すかどうかは次のテキストを見てから決める
+                    self.soft_break = Some(PendingSoftBreak {
+                        body: html_body.len(),
+                        heading: self.heading_state.as_ref().map(|heading_state| {
+                            (heading_state.html.len(), heading_state.text.len())
+                        }),
+                    });
+                }
                 Event::HardBreak => {
                     if let Some(heading_state) = self.heading_state.as_mut() {
                         heading_state.push_html("<br />\n");
@@ -174,8 +292,31 @@ where
                 }
             }
         }
+        self.resolve_soft_break(&mut html_body, None);
         html_body
     }
+    /// 保留中のソフト改行を確定する。
+    /// 前後が共にCJK文字なら捨て、それ以外は保留位置に改行を挿入する。
+    fn resolve_soft_break(&mut self, buf: &mut String, next: Option<char>) {
+        let Some(pending) = self.soft_break.take() else {
+            return;
+        };
+        if mdpeek_gfm::cjk::joins_without_space(self.last_char, next) {
+            return;
+        }
+        // Setext headings can span multiple source lines.
+        if let (Some((html, text)), Some(heading_state)) =
+            (pending.heading, self.heading_state.as_mut())
+        {
+            heading_state.html.insert(html, '\n');
+            heading_state.text.insert(text, ' ');
+        } else {
+            if pending.body == buf.len() {
+                self.end_newline = true;
+            }
+            buf.insert(pending.body, '\n');
+        }
+    }
     fn start_tag(&mut self, buf: &mut String, tag: Tag) {
         match tag {
             Tag::HtmlBlock => (),
@@ -186,7 +327,7 @@ where
                     buf.push_str("\n<p>")
                 }
             }
-            Tag::Heading { .. } => {
+            Tag::Heading { id, classes, .. } => {
                 match self.heading_state.as_ref() {
                     Some(_) => {
                         error!("Heading is nested. This case is not considered.");
@@ -197,6 +338,8 @@ where
                 }
                 if let Some(heading_state) = self.heading_state.as_mut() {
                     heading_state.end_newline = self.end_newline;
+                    heading_state.id = id.map(|id| id.to_string());
+                    heading_state.classes = classes.iter().map(|c| c.to_string()).collect();
                 }
                 // buf.push('<');
                 // } else {
@@ -379,13 +522,17 @@ where
                 }
             }
             Tag::Link {
-                link_type: _,
+                link_type,
                 dest_url,
                 title,
                 id: _,
             } => {
                 let mut tmp = String::new();
-                tmp.push_str("<a href=\"");
+                if matches!(link_type, LinkType::WikiLink { .. }) {
+                    tmp.push_str("<a class=\"wikilink\" href=\"");
+                } else {
+                    tmp.push_str("<a href=\"");
+                }
                 escape_href(&mut tmp, &dest_url);
                 if !title.is_empty() {
                     tmp.push_str("\" title=\"");
@@ -450,6 +597,8 @@ where
                         html,
                         text,
                         end_newline,
+                        id,
+                        classes,
                         ..
                     }) => {
                         if *end_newline {
@@ -458,16 +607,29 @@ where
                             buf.push_str("\n<");
                         }
                         buf.push_str(&format!("{level}"));
-                        if !text.is_empty() {
+                        if !classes.is_empty() {
+                            buf.push_str(" class=\"");
+                            escape_html(buf, &classes.join(" "));
+                            buf.push('"');
+                        }
+                        if let Some(id) = id {
+                            // {#id} 指定時はそのままアンカーに使う
+                            let mut anchor = String::new();
+                            escape_html(&mut anchor, id);
+                            buf.push_str(&format!(" id=\"{anchor}\">{html}"));
+                            buf.push_str(&format!(
+                                "<a class=\"anchor\" aria-label=\"Permalink\" href=\"#{anchor}\">"
+                            ));
+                            buf.push_str(OCTICON_LINK);
+                            buf.push_str("</a>");
+                        } else if !text.is_empty() {
                             // id はプレーンテキストから生成、表示は html バッファを使用
                             let anchor = convert_to_anochor_text(text.clone());
                             buf.push_str(&format!(" id=\"{anchor}\">{html}"));
                             buf.push_str(&format!(
                                 "<a class=\"anchor\" aria-label=\"Permalink\" href=\"#{anchor}\">"
                             ));
-                            buf.push_str("<svg class=\"octicon octicon-link\" viewBox=\"0 0 16 16\" version=\"16\" height=\"16\" aria-hidden=\"true\">");
-                            buf.push_str("<path d=\"m7.775 3.275 1.25-1.25a3.5 3.5 0 1 1 4.95 4.95l-2.5 2.5a3.5 3.5 0 0 1-4.95 0 .751.751 0 0 1 .018-1.042.751.751 0 0 1 1.042-.018 1.998 1.998 0 0 0 2.83 0l2.5-2.5a2.002 2.002 0 0 0-2.83-2.83l-1.25 1.25a.751.751 0 0 1-1.042-.018.751.751 0 0 1-.018-1.042Zm-4.69 9.64a1.998 1.998 0 0 0 2.83 0l1.25-1.25a.751.751 0 0 1 1.042.018.751.751 0 0 1 .018 1.042l-1.25 1.25a3.5 3.5 0 1 1-4.95-4.95l2.5-2.5a3.5 3.5 0 0 1 4.95 0 .751.751 0 0 1-.018 1.042.751.751 0 0 1-1.042.018 1.998 1.998 0 0 0-2.83 0l-2.5 2.5a1.998 1.998 0 0 0 0 2.83Z\"></path>");
-                            buf.push_str("</svg>");
+                            buf.push_str(OCTICON_LINK);
                             buf.push_str("</a>");
                         } else {
                             buf.push_str(&format!(">{html}"));
@@ -652,6 +814,15 @@ static HEX_CHARS: &[u8] = b"0123456789ABCDEF";
 static AMP_ESCAPE: &str = "&amp;";
 static SINGLE_QUOTE_ESCAPE: &str = "&#x27;";
 
+/// ソフト改行の保留を解除しないインライン装飾のイベント
+fn is_inline_markup(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Start(Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. })
+            | Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link)
+    )
+}
+
 fn escape_html_body(buf: &mut String, text: &str) {
     let bytes = text.as_bytes();
     let mut mark = 0;
@@ -793,6 +964,13 @@ fn is_number_or_percent(token: &str) -> bool {
     seen_digit
 }
 
+/// GitHub の見出しパーマリンクアイコン
+const OCTICON_LINK: &str = concat!(
+    "<svg class=\"octicon octicon-link\" viewBox=\"0 0 16 16\" version=\"16\" height=\"16\" aria-hidden=\"true\">",
+    "<path d=\"m7.775 3.275 1.25-1.25a3.5 3.5 0 1 1 4.95 4.95l-2.5 2.5a3.5 3.5 0 0 1-4.95 0 .751.751 0 0 1 .018-1.042.751.751 0 0 1 1.042-.018 1.998 1.998 0 0 0 2.83 0l2.5-2.5a2.002 2.002 0 0 0-2.83-2.83l-1.25 1.25a.751.751 0 0 1-1.042-.018.751.751 0 0 1-.018-1.042Zm-4.69 9.64a1.998 1.998 0 0 0 2.83 0l1.25-1.25a.751.751 0 0 1 1.042.018.751.751 0 0 1 .018 1.042l-1.25 1.25a3.5 3.5 0 1 1-4.95-4.95l2.5-2.5a3.5 3.5 0 0 1 4.95 0 .751.751 0 0 1-.018 1.042.751.751 0 0 1-1.042.018 1.998 1.998 0 0 0-2.83 0l-2.5 2.5a1.998 1.998 0 0 0 0 2.83Z\"></path>",
+    "</svg>",
+);
+
 fn convert_to_anochor_text(heading_text: String) -> String {
     let mut anchor = String::with_capacity(heading_text.len());
     let mut prev_hyphen = false;
@@ -847,6 +1025,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn softbreak_between_japanese_is_joined() {
+        let out = render("日本語の文章が\n途中で折り返されている。\n");
+        assert!(
+            out.contains("日本語の文章が途中で折り返されている。"),
+            "CJK lines should be joined without a break: {out}"
+        );
+    }
+
+    #[test]
+    fn softbreak_between_japanese_and_english_is_kept() {
+        let out = render("Rust で書かれた\nmarkdown viewer です。\nIt works.\n");
+        assert!(
+            out.contains("書かれた\nmarkdown viewer です。\nIt works."),
+            "breaks next to latin text should remain: {out}"
+        );
+    }
+
+    #[test]
+    fn softbreak_join_sees_through_emphasis() {
+        let out = render("これは**強調**\n*された*文章です\n");
+        assert!(
+            out.contains("<strong>強調</strong><em>された</em>文章です"),
+            "break before emphasis should be joined: {out}"
+        );
+    }
+
+    #[test]
+    fn setext_heading_japanese_softbreak_is_joined() {
+        let out = render("日本語の\n見出し\n=====\n");
+        assert!(
+            out.contains(">日本語の見出し"),
+            "heading lines should join: {out}"
+        );
+    }
+
     #[test]
     fn math_in_heading_stays_inside_heading_tag() {
         let mut options = pulldown_cmark::Options::empty();
@@ -856,4 +1070,78 @@ mod tests {
         let math = out.find("math-inline").expect("math span");
         assert!(math > h_open, "math leaked before heading: {out}");
     }
+
+    #[test]
+    fn heading_attributes_set_custom_id_and_classes() {
+        let mut options = pulldown_cmark::Options::empty();
+        options.insert(pulldown_cmark::Options::ENABLE_HEADING_ATTRIBUTES);
+        let out = HtmlEmitter::new(Parser::new_ext("# Title {#intro .lead}", options)).run();
+        assert!(
+            out.contains("<h1 class=\"lead\" id=\"intro\">Title"),
+            "custom id/class missing: {out}"
+        );
+        assert!(
+            out.contains("href=\"#intro\""),
+            "permalink should use custom id: {out}"
+        );
+    }
+
+    #[test]
+    fn inline_html_in_heading_stays_inside_heading_tag() {
+        let out = render("# a <mark>b</mark>");
+        let h_open = out.find("<h1").expect("h1 tag");
+        let mark = out.find("<mark>").expect("mark tag");
+        assert!(mark > h_open, "inline html leaked before heading: {out}");
+        assert!(
+            out.contains("id=\"a-b\""),
+            "anchor should ignore tags: {out}"
+        );
+    }
+
+    #[test]
+    fn wiki_link_gets_wikilink_class() {
+        let mut options = pulldown_cmark::Options::empty();
+        options.insert(pulldown_cmark::Options::ENABLE_WIKILINKS);
+        let out = HtmlEmitter::new(Parser::new_ext("[[Home]]", options)).run();
+        assert!(
+            out.contains("<a class=\"wikilink\" href=\"Home\">Home</a>"),
+            "{out}"
+        );
+    }
+
+    fn render_with_lines(md: &str) -> String {
+        let (events, lines) = source_lines(md, Parser::new(md).into_offset_iter());
+        HtmlEmitter::new(events).with_source_lines(lines).run()
+    }
+
+    #[test]
+    fn blocks_carry_their_source_line() {
+        let out = render_with_lines(
+            "# Title\n\nFirst line\nsecond line\n\n- a\n- b\n\n> quote\n\n```rust\nfn x() {}\n```\n",
+        );
+        for tag in [
+            "<h1 data-source-line=\"1\" id=\"title\">",
+            "<p data-source-line=\"3\">First line",
+            "<ul data-source-line=\"6\">",
+            "<li data-source-line=\"6\">a",
+            "<li data-source-line=\"7\">b",
+            "<blockquote data-source-line=\"9\">",
+            "<p data-source-line=\"9\">quote",
+            "<pre data-source-line=\"11\"><code class=\"language-rust\">",
+        ] {
+            assert!(out.contains(tag), "missing {tag}: {out}");
+        }
+        assert!(!render("# Title").contains("data-source-line"));
+    }
+
+    #[test]
+    fn source_lines_survive_dropped_blocks() {
+        // アダプタが最初の段落を捨てても後続の行はずれない
+        let md = "dropped\n\n## Next\n\nkept\n";
+        let (events, lines) = source_lines(md, Parser::new(md).into_offset_iter());
+        let events = events.skip(3);
+        let out = HtmlEmitter::new(events).with_source_lines(lines).run();
+        assert!(out.contains("<h2 data-source-line=\"3\""), "{out}");
+        assert!(out.contains("<p data-source-line=\"5\">kept"), "{out}");
+    }
 }
@@ -1,15 +1,21 @@
+use mdpeek_gfm::source_lines::SourceLines;
 use owo_colors::{OwoColorize, Style};
 use pulldown_cmark::{BlockQuoteKind, CodeBlockKind, Event, HeadingLevel, LinkType, Tag, TagEnd};
 use std::collections::HashMap;
 
 mod color;
-mod emoji;
 mod highlight;
 
 /// ANSI escape that turns on the terminal "crossed out" attribute.
 const STRIKE_ON: &str = "\x1b[9m";
 /// ANSI escape that turns off the "crossed out" attribute.
 const STRIKE_OFF: &str = "\x1b[29m";
+/// ANSI escapes for reverse video, used for `==mark==` highlights.
+const REVERSE_ON: &str = "\x1b[7m";
+const REVERSE_OFF: &str = "\x1b[27m";
+/// ANSI escapes for underline, used for `++insert++`.
+const UNDERLINE_ON: &str = "\x1b[4m";
+const UNDERLINE_OFF: &str = "\x1b[24m";
 
 enum ListState {
     Ordered { index: usize },
@@ -135,6 +141,10 @@ impl Default for Theme {
     }
 }
 
+/// Checks the code of a block in the given language, returning the 0-based
+/// line of the first error and its message.
+pub type CodeCheck = Box<dyn Fn(&str, &str) -> Option<(usize, String)>>;
+
 pub struct TerminalEmitter<I> {
     iter: I,
     theme: Theme,
@@ -160,6 +170,26 @@ pub struct TerminalEmitter<I> {
     link_stack: Vec<String>,
     code_block_lang: String,
     code_block_buf: String,
+    /// Expansions of the currently open `<abbr>` tags.
+    abbr_stack: Vec<String>,
+    /// Position of a soft break whose output waits for the next text, so
+    /// that lines wrapped between CJK characters can be joined.
+    soft_break: Option<PendingSoftBreak>,
+    /// Last character of the most recent text (for soft break joining).
+    last_char: Option<char>,
+    /// Marks the erroneous line of code blocks in a gutter.
+    code_check: Option<CodeCheck>,
+    /// Start lines of the blocks, when a line map is wanted.
+    source_lines: Option<SourceLines>,
+    /// `(source line, rendered line)` of every block written so far.
+    line_map: Vec<(u32, usize)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingSoftBreak {
+    /// Byte offset into the output, or into the current table cell.
+    offset: usize,
+    in_table_cell: bool,
 }
 
 impl<'a, I> TerminalEmitter<I>
@@ -192,14 +222,74 @@ where
             link_stack: Vec::new(),
             code_block_lang: String::new(),
             code_block_buf: String::new(),
+            abbr_stack: Vec::new(),
+            soft_break: None,
+            last_char: None,
+            code_check: None,
+            source_lines: None,
+            line_map: Vec::new(),
         }
     }
 
+    /// Check each code block with `check`, flagging the line of an error
+    /// with a `⚠` gutter and the message below the block.
+    pub fn with_code_check(mut self, check: CodeCheck) -> Self {
+        self.code_check = Some(check);
+        self
+    }
+
+    /// Record where each block lands in the output, from the start lines
+    /// [`mdpeek_gfm::source_lines::source_lines`] collected; read the result
+    /// with [`TerminalEmitter::line_map`] after [`TerminalEmitter::run`].
+    pub fn with_source_lines(mut self, lines: SourceLines) -> Self {
+        self.source_lines = Some(lines);
+        self
+    }
+
+    /// `(1-based source line, 0-based rendered line)` of every block written,
+    /// in document order. Empty without [`TerminalEmitter::with_source_lines`].
+    pub fn line_map(&self) -> &[(u32, usize)] {
+        &self.line_map
+    }
+
+    /// Note that the block starting on source `line` is written from `from`
+    /// on: its rendered line is that of the first output after the newlines
+    /// separating it from the previous block.
+    fn map_line(&mut self, out: &str, from: usize, line: u32) {
+        // `from` may have moved: a block quote drops one separating newline.
+        let (before, after) = out.as_bytes().split_at(from.min(out.len()));
+        let separators = after.iter().take_while(|b| **b == b'\n').count();
+        let rendered = before.iter().filter(|b| **b == b'\n').count() + separators;
+        self.line_map.push((line, rendered));
+    }
+
     pub fn run(&mut self) -> String {
         let mut out = String::new();
         while let Some(event) = self.iter.next() {
+            if !is_inline_markup(&event) {
+                let next = match &event {
+                    Event::Text(text) | Event::Code(text) => text.chars().next(),
+                    _ => None,
+                };
+                self.resolve_soft_break(&mut out, next);
+                if !matches!(event, Event::Text(_) | Event::Code(_) | Event::SoftBreak) {
+                    self.last_char = None;
+                }
+            }
             match event {
-                Event::Start(tag) => self.start_tag(&mut out, tag),
+                Event::Start(tag) => {
+                    // Front matter and raw HTML are not written.
+                    let line = self
+                        .source_lines
+                        .as_ref()
+                        .and_then(|lines| lines.take(&tag))
+                        .filter(|_| !matches!(tag, Tag::MetadataBlock(_) | Tag::HtmlBlock));
+                    let from = out.len();
+                    self.start_tag(&mut out, tag);
+                    if let Some(line) = line {
+                        self.map_line(&out, from, line);
+                    }
+                }
                 Event::End(tag) => self.end_tag(&mut out, tag),
                 Event::Text(text) => {
                     if !self.in_non_writing_block {
@@ -207,8 +297,6 @@ where
                             // Buffer raw code; highlighting happens at block end.
                             self.code_block_buf.push_str(&text);
                         } else {
-                            // Substitute `:shortcode:` emoji outside code spans.
-                            let text = emoji::replace_shortcodes(&text);
                             if self.in_table_cell {
                                 self.push_table_text(&text);
                             } else {
@@ -217,9 +305,11 @@ where
                             }
                         }
                         self.end_newline = text.ends_with('\n');
+                        self.last_char = text.chars().next_back();
                     }
                 }
                 Event::Code(text) => {
+                    self.last_char = text.chars().next_back();
                     if self.in_table_cell {
                         self.push_table_text(&text);
                     } else {
@@ -259,16 +349,22 @@ where
                         out.push_str("$$");
                     }
                 }
-                Event::Html(_) | Event::InlineHtml(_) => {
+                Event::InlineHtml(html) => self.inline_html(&mut out, &html),
+                Event::Html(_) => {
                     // Skip raw HTML for terminal output.
                 }
                 Event::SoftBreak => {
-                    if self.in_table_cell {
-                        self.push_table_text(" ");
+                    // Whether this becomes a space is decided by the next text.
+                    let offset = if self.in_table_cell {
+                        self.current_cell.len()
                     } else {
                         self.flush_pending_marker(&mut out);
-                        out.push(' ');
-                    }
+                        out.len()
+                    };
+                    self.soft_break = Some(PendingSoftBreak {
+                        offset,
+                        in_table_cell: self.in_table_cell,
+                    });
                 }
                 Event::HardBreak => {
                     if self.in_table_cell {
@@ -317,9 +413,26 @@ where
                 }
             }
         }
+        self.resolve_soft_break(&mut out, None);
         out
     }
 
+    /// Settles a pending soft break: dropped between two CJK characters,
+    /// otherwise a space at the position where the break occurred.
+    fn resolve_soft_break(&mut self, out: &mut String, next: Option<char>) {
+        let Some(pending) = self.soft_break.take() else {
+            return;
+        };
+        if mdpeek_gfm::cjk::joins_without_space(self.last_char, next) {
+            return;
+        }
+        if pending.in_table_cell {
+            self.current_cell.insert(pending.offset, ' ');
+        } else {
+            out.insert(pending.offset, ' ');
+        }
+    }
+
     fn start_tag(&mut self, out: &mut String, tag: Tag) {
         match tag {
             Tag::HtmlBlock => (),
@@ -511,6 +624,14 @@ where
                 self.in_link = true;
                 self.link_stack.push(format!("mailto:{dest_url}"));
             }
+            Tag::Link {
+                link_type: LinkType::WikiLink { has_pothole: false },
+                ..
+            } => {
+                // `[[Page]]`: the text already is the target, don't repeat it.
+                self.in_link = true;
+                self.link_stack.push(String::new());
+            }
             Tag::Link { dest_url, .. } => {
                 self.in_link = true;
                 self.link_stack.push(dest_url.to_string());
@@ -615,11 +736,32 @@ where
                 let buf = std::mem::take(&mut self.code_block_buf);
                 let lang = std::mem::take(&mut self.code_block_lang);
                 let highlighted = highlight::highlight(&buf, &lang);
-                out.push_str(&highlighted);
-                if !highlighted.ends_with('\n') {
-                    out.push('\n');
+                let error = self
+                    .code_check
+                    .as_ref()
+                    .and_then(|check| check(&lang, &buf));
+                if let Some((error_line, _)) = &error {
+                    for (i, line) in highlighted.lines().enumerate() {
+                        if i == *error_line {
+                            out.push_str(&format!("{} ", "⚠".bright_yellow()));
+                        } else {
+                            out.push_str("  ");
+                        }
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                } else {
+                    out.push_str(&highlighted);
+                    if !highlighted.ends_with('\n') {
+                        out.push('\n');
+                    }
                 }
-                out.push_str("```\n\n");
+                out.push_str("```\n");
+                if let Some((error_line, message)) = error {
+                    let warning = format!("⚠ line {}: {message}", error_line + 1);
+                    out.push_str(&format!("{}\n", warning.bright_yellow()));
+                }
+                out.push('\n');
                 self.in_code_block = false;
                 self.end_newline = true;
             }
@@ -740,6 +882,51 @@ where
         }
     }
 
+    /// Render the inline tags produced by `mdpeek_gfm` (extensions and image
+    /// emoji); any other raw HTML is skipped like block HTML.
+    fn inline_html(&mut self, out: &mut String, html: &str) {
+        // Table cells are measured by character count, so use plain markers.
+        let (ansi, plain) = match html {
+            "<mark>" => (REVERSE_ON, "=="),
+            "</mark>" => (REVERSE_OFF, "=="),
+            "<ins>" => (UNDERLINE_ON, "++"),
+            "</ins>" => (UNDERLINE_OFF, "++"),
+            "</abbr>" => {
+                if let Some(title) = self.abbr_stack.pop()
+                    && !title.is_empty()
+                {
+                    let expanded = format!(" ({title})");
+                    if self.in_table_cell {
+                        self.push_table_text(&expanded);
+                    } else {
+                        out.push_str(&expanded);
+                    }
+                }
+                return;
+            }
+            _ => {
+                if let Some(title) = mdpeek_gfm::extensions::abbr_title(html) {
+                    self.abbr_stack.push(title);
+                } else if let Some(alt) = mdpeek_gfm::emoji::image_alt(html) {
+                    // Terminals can't show image emoji: print the alt text.
+                    if self.in_table_cell {
+                        self.push_table_text(&alt);
+                    } else {
+                        self.flush_pending_marker(out);
+                        self.push_text(out, &alt);
+                    }
+                }
+                return;
+            }
+        };
+        if self.in_table_cell {
+            self.push_table_text(plain);
+        } else {
+            self.flush_pending_marker(out);
+            out.push_str(ansi);
+        }
+    }
+
     fn push_table_text(&mut self, text: &str) {
         self.current_cell.push_str(text);
     }
@@ -851,6 +1038,15 @@ where
     }
 }
 
+/// Inline markup events that keep a soft break pending.
+fn is_inline_markup(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Start(Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. })
+            | Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link)
+    )
+}
+
 fn pad(text: &str, width: usize) -> String {
     let len = text.chars().count();
     if len >= width {
@@ -868,7 +1064,54 @@ mod integration_tests {
     /// 端末向け機能をすべて有効にしたパーサで markdown をレンダリングする。
     fn render(md: &str) -> String {
         let parser = Parser::new_ext(md, mdpeek_gfm::parser_options());
-        TerminalEmitter::new(parser, Theme::glow()).run()
+        TerminalEmitter::new(mdpeek_gfm::transform(parser), Theme::glow()).run()
+    }
+
+    /// ANSI のエスケープシーケンスを取り除く。
+    fn strip_ansi(out: &str) -> String {
+        let mut plain = String::new();
+        let mut chars = out.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                plain.push(c);
+            }
+        }
+        plain
+    }
+
+    #[test]
+    fn softbreak_between_japanese_is_joined() {
+        let out = render("日本語の文章が\n途中で折り返されている。");
+        assert!(
+            out.contains("日本語の文章が途中で折り返されている。"),
+            "CJK lines should join without a space: {out:?}"
+        );
+    }
+
+    #[test]
+    fn softbreak_between_japanese_and_english_keeps_space() {
+        let out = render("Rust で書かれた\nmarkdown viewer です。\nIt works.");
+        assert!(
+            out.contains("Rust で書かれた markdown viewer です。 It works."),
+            "breaks next to latin text should become spaces: {out:?}"
+        );
+    }
+
+    #[test]
+    fn softbreak_join_sees_through_emphasis() {
+        let out = render("**強調**\n*された*文章");
+        assert!(!out.contains(' '), "no space between CJK runs: {out:?}");
+    }
+
+    #[test]
+    fn softbreak_in_list_item_is_joined() {
+        let out = render("- 箇条書きの\n  項目です");
+        assert!(
+            out.contains("箇条書きの項目です"),
+            "list item lines should join: {out:?}"
+        );
     }
 
     #[test]
@@ -908,6 +1151,27 @@ mod integration_tests {
         assert!(out.contains("\x1b[38;2;"), "code not highlighted: {out:?}");
     }
 
+    #[test]
+    fn code_check_marks_the_error_line() {
+        let parser = Parser::new_ext("```json\n{\n  1\n}\n```", mdpeek_gfm::parser_options());
+        let check: CodeCheck = Box::new(|lang, code| {
+            assert_eq!((lang, code), ("json", "{\n  1\n}\n"));
+            Some((1, "key must be a string".to_string()))
+        });
+        let out = TerminalEmitter::new(parser, Theme::glow())
+            .with_code_check(check)
+            .run();
+        let plain = strip_ansi(&out);
+        assert!(
+            plain.contains("  {\n⚠   1\n  }\n```"),
+            "gutter missing: {plain:?}"
+        );
+        assert!(
+            plain.contains("⚠ line 2: key must be a string"),
+            "message missing: {plain:?}"
+        );
+    }
+
     #[test]
     fn inline_math_is_rendered() {
         let out = render("$E = mc^2$");
@@ -982,4 +1246,123 @@ mod integration_tests {
         assert!(!out.contains("title: meta"), "front matter leaked: {out:?}");
         assert!(out.contains("body text"));
     }
+
+    /// 拡張構文を有効にしたパーサでレンダリングする。
+    fn render_extended(md: &str) -> String {
+        let options = mdpeek_gfm::MarkdownOptions {
+            mark: true,
+            insert: true,
+            abbreviations: true,
+            ..Default::default()
+        };
+        let parser = Parser::new_ext(md, options.parser_options());
+        TerminalEmitter::new(mdpeek_gfm::extensions(md, parser, &options), Theme::glow()).run()
+    }
+
+    #[test]
+    fn mark_and_insert_use_reverse_and_underline() {
+        let out = render_extended("==hot== ++new++");
+        assert!(
+            out.contains(&format!("{REVERSE_ON}hot{REVERSE_OFF}")),
+            "{out:?}"
+        );
+        assert!(
+            out.contains(&format!("{UNDERLINE_ON}new{UNDERLINE_OFF}")),
+            "{out:?}"
+        );
+        assert!(!out.contains("=="), "literal delimiters should not leak");
+    }
+
+    #[test]
+    fn abbreviation_is_expanded_inline() {
+        let out = render_extended("Uses HTML.\n\n*[HTML]: Hyper Text Markup Language\n");
+        assert!(
+            out.contains("HTML (Hyper Text Markup Language)."),
+            "{out:?}"
+        );
+        assert!(
+            !out.contains("*[HTML]"),
+            "definition should be hidden: {out:?}"
+        );
+    }
+
+    #[test]
+    fn mark_in_table_cell_uses_plain_markers() {
+        let out = render_extended("| a |\n|---|\n| ==x== |\n");
+        assert!(out.contains("==x=="), "{out:?}");
+        assert!(
+            !out.contains(REVERSE_ON),
+            "no ANSI inside table cells: {out:?}"
+        );
+    }
+
+    #[test]
+    fn wiki_link_does_not_repeat_its_target() {
+        let options = mdpeek_gfm::MarkdownOptions {
+            wiki_links: true,
+            ..Default::default()
+        };
+        let md = "[[Home]] and [[Home|start]]";
+        let parser = Parser::new_ext(md, options.parser_options());
+        let out = TerminalEmitter::new(parser, Theme::glow()).run();
+        // Once as the first link's text, once as the second link's target.
+        assert_eq!(out.matches("Home").count(), 2, "{out:?}");
+        assert!(out.contains("start"), "{out:?}");
+    }
+
+    #[test]
+    fn math_can_be_disabled() {
+        let options = mdpeek_gfm::MarkdownOptions {
+            math: false,
+            ..Default::default()
+        };
+        let md = "$5 and $10";
+        let parser = Parser::new_ext(md, options.parser_options());
+        let out = TerminalEmitter::new(parser, Theme::glow()).run();
+        assert!(out.contains("$5 and $10"), "{out:?}");
+    }
+
+    #[test]
+    fn github_image_emoji_falls_back_to_shortcode() {
+        let out = render("ship :shipit: now");
+        assert!(out.contains("ship :shipit: now"), "{out:?}");
+        assert!(!out.contains("<img"), "raw html must not leak: {out:?}");
+    }
+
+    #[test]
+    fn line_map_points_at_each_rendered_block() {
+        let md = "---\ntitle: x\n---\n# Plan\n\n:rocket: \"Ship\" it\n\n\
+                  - [ ] add tests\n- [x] write docs\n\n\
+                  | a | b |\n|---|---|\n| 1 | 2 |\n\n\
+                  Term\n: its definition\n\n> quoted\n";
+        let options = mdpeek_gfm::MarkdownOptions {
+            smart_punctuation: true,
+            definition_lists: true,
+            ..Default::default()
+        };
+        let parser = Parser::new_ext(md, options.parser_options()).into_offset_iter();
+        let (events, lines) = mdpeek_gfm::source_lines::source_lines(md, parser);
+        let events = mdpeek_gfm::typography(mdpeek_gfm::transform(events), &options);
+        let mut emitter = TerminalEmitter::new(events, Theme::mono()).with_source_lines(lines);
+        let plain = strip_ansi(&emitter.run());
+        let rendered: Vec<&str> = plain.lines().collect();
+        let at = |source: u32| {
+            let (_, line) = emitter
+                .line_map()
+                .iter()
+                .find(|(l, _)| *l == source)
+                .unwrap_or_else(|| panic!("no block on line {source}"));
+            rendered[*line]
+        };
+        assert_eq!(at(4), " Plan ");
+        assert_eq!(at(6), "🚀 “Ship” it");
+        assert_eq!(at(8), "  [ ] add tests");
+        assert_eq!(at(9), "  [✓] write docs");
+        assert!(at(11).contains("a │ b"), "{plain}");
+        assert_eq!(at(15), "Term");
+        assert_eq!(at(16), ": its definition");
+        assert_eq!(at(18), "│ quoted");
+        // Front matter is not written, so it has no rendered line.
+        assert!(emitter.line_map().iter().all(|(l, _)| *l >= 4));
+    }
 }
@@ -0,0 +1,617 @@
+//! Repository ADR index.
+//!
+//! Reads every Architecture Decision Record the explorer discovers (documents
+//! the analyser classifies as ADRs, or numbered files under an `adr/`-style
+//! directory) with [`mdpeek_analyzer::analyzer::adr`], resolves their
+//! `Supersedes` / `Superseded by` references to ADR numbers, validates the
+//! supersession chain per worktree, and correlates each record with the git
+//! commit that added it. Backs `mdpeek adrs` and the browser's `/adrs` page.
+
+use crate::explorer;
+use mdpeek_analyzer::analyzer::adr::{self, AdrRef, AdrStatus};
+use mdpeek_analyzer::model::DocumentType;
+use mdpeek_analyzer::rules::ClassificationRules;
+use mdpeek_analyzer::{MarkdownOptions, RulesGenerator};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+
+/// Directory names that hold ADRs by convention.
+const ADR_DIRS: &[&str] = &["adr", "adrs", "decisions", "architecture-decisions"];
+
+/// One ADR in the repository.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepoAdr {
+    /// Worktree (explorer group) name.
+    pub worktree: String,
+    pub branch: Option<String>,
+    /// Absolute path, as served by the explorer.
+    pub path: String,
+    /// Path relative to the worktree root.
+    pub rel: String,
+    pub number: Option<u32>,
+    pub title: String,
+    pub status: Option<AdrStatus>,
+    pub date: Option<String>,
+    /// Numbers of the ADRs this one supersedes (as resolved).
+    pub supersedes: Vec<u32>,
+    /// Numbers of the ADRs that supersede this one (as resolved).
+    pub superseded_by: Vec<u32>,
+    /// The commit that added the file, when the worktree is a git checkout.
+    pub commit: Option<AdrCommit>,
+}
+
+/// The commit that introduced an ADR.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AdrCommit {
+    pub hash: String,
+    pub author: String,
+    /// Author date, `YYYY-MM-DD`.
+    pub date: String,
+    pub subject: String,
+}
+
+/// What is wrong with the supersession chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdrIssue {
+    /// A reference to an ADR that does not exist.
+    Dangling,
+    /// ADRs that (transitively) supersede themselves.
+    Cycle,
+    /// One-sided supersession, or a status that contradicts it.
+    Inconsistent,
+    /// Two ADRs with the same number.
+    Duplicate,
+}
+
+impl AdrIssue {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdrIssue::Dangling => "dangling",
+            AdrIssue::Cycle => "cycle",
+            AdrIssue::Inconsistent => "inconsistent",
+            AdrIssue::Duplicate => "duplicate",
+        }
+    }
+}
+
+/// A supersession problem, located at the line that states it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AdrDiagnostic {
+    pub worktree: String,
+    pub rel: String,
+    pub line: u32,
+    pub issue: AdrIssue,
+    pub message: String,
+}
+
+/// Every ADR plus the diagnostics of their supersession chains.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AdrIndex {
+    pub adrs: Vec<RepoAdr>,
+    pub diagnostics: Vec<AdrDiagnostic>,
+}
+
+/// An ADR as read, before its references are resolved.
+struct Parsed {
+    entry: RepoAdr,
+    supersedes: Vec<AdrRef>,
+    superseded_by: Vec<AdrRef>,
+    status_line: u32,
+}
+
+/// The ADR index of the repository (or directory) discovered from `start`, in
+/// worktree / number order. Documents are classified with `rules`, matched
+/// against their worktree-relative path.
+pub fn collect(start: &Path, markdown: &MarkdownOptions, rules: &ClassificationRules) -> AdrIndex {
+    let tree = explorer::build_tree(start);
+    let mut index = AdrIndex::default();
+    for group in &tree.groups {
+        let mut parsed = Vec::new();
+        for file in &group.files {
+            let Ok(content) = std::fs::read_to_string(&file.path) else {
+                continue;
+            };
+            let options = markdown.for_document(&content);
+            let analysis = mdpeek_analyzer::analyze_with_rules(
+                &content,
+                Some(&file.rel),
+                markdown,
+                rules,
+                &RulesGenerator,
+            );
+            let record = adr::extract(&content, &analysis.tree, Some(&file.rel), &options);
+            let in_adr_dir = Path::new(&file.rel)
+                .parent()
+                .into_iter()
+                .flat_map(|p| p.components())
+                .any(|c| {
+                    c.as_os_str()
+                        .to_str()
+                        .is_some_and(|n| ADR_DIRS.contains(&n.to_lowercase().as_str()))
+                });
+            let is_adr = analysis.model.doc_type.value == DocumentType::Adr
+                || (in_adr_dir && record.number.is_some());
+            if !is_adr {
+                continue;
+            }
+            let commit = if tree.git {
+                introducing_commit(Path::new(&group.root), &file.rel)
+            } else {
+                None
+            };
+            parsed.push(Parsed {
+                entry: RepoAdr {
+                    worktree: group.name.clone(),
+                    branch: group.branch.clone(),
+                    path: file.path.clone(),
+                    rel: file.rel.clone(),
+                    number: record.number,
+                    title: record.title,
+                    status: record.status,
+                    date: record.date,
+                    supersedes: Vec::new(),
+                    superseded_by: Vec::new(),
+                    commit,
+                },
+                supersedes: record.supersedes,
+                superseded_by: record.superseded_by,
+                status_line: record.status_range.map_or(1, |r| r.start_line),
+            });
+        }
+        let (adrs, diagnostics) = resolve(parsed);
+        index.adrs.extend(adrs);
+        index.diagnostics.extend(diagnostics);
+    }
+    index
+}
+
+/// Resolve references to numbers and validate one worktree's chain.
+fn resolve(mut parsed: Vec<Parsed>) -> (Vec<RepoAdr>, Vec<AdrDiagnostic>) {
+    parsed.sort_by(|a, b| (a.entry.number, &a.entry.rel).cmp(&(b.entry.number, &b.entry.rel)));
+    let mut diagnostics = Vec::new();
+    let diagnostic = |p: &Parsed, line: u32, issue: AdrIssue, message: String| AdrDiagnostic {
+        worktree: p.entry.worktree.clone(),
+        rel: p.entry.rel.clone(),
+        line,
+        issue,
+        message,
+    };
+
+    let mut by_number: BTreeMap<u32, usize> = BTreeMap::new();
+    for (i, p) in parsed.iter().enumerate() {
+        let Some(n) = p.entry.number else {
+            continue;
+        };
+        if let Some(&first) = by_number.get(&n) {
+            let message = format!("ADR {n} is also {}", parsed[first].entry.rel);
+            diagnostics.push(diagnostic(p, 1, AdrIssue::Duplicate, message));
+        } else {
+            by_number.insert(n, i);
+        }
+    }
+
+    // Resolve each reference: its stated number, else the ADR its link points at.
+    let resolve_ref = |p: &Parsed, r: &AdrRef| -> Option<u32> {
+        if let Some(n) = r.number {
+            return by_number.contains_key(&n).then_some(n);
+        }
+        let target = r.target.as_deref()?.split(['#', '?']).next()?;
+        let dir = Path::new(&p.entry.rel).parent().unwrap_or(Path::new(""));
+        let wanted = normalize(&dir.join(target));
+        parsed
+            .iter()
+            .find(|q| normalize(Path::new(&q.entry.rel)) == wanted)
+            .and_then(|q| q.entry.number)
+    };
+    let mut resolved: Vec<(Vec<u32>, Vec<u32>)> = Vec::new();
+    for p in &parsed {
+        let mut sides = (Vec::new(), Vec::new());
+        for (refs, out, verb) in [
+            (&p.supersedes, &mut sides.0, "supersedes"),
+            (&p.superseded_by, &mut sides.1, "is superseded by"),
+        ] {
+            for r in refs {
+                match resolve_ref(p, r) {
+                    Some(n) if !out.contains(&n) => out.push(n),
+                    Some(_) => {}
+                    None => {
+                        let what = match (r.number, &r.target) {
+                            (Some(n), _) => format!("ADR {n}"),
+                            (None, Some(t)) => format!("`{t}`"),
+                            (None, None) => "an unknown ADR".to_string(),
+                        };
+                        diagnostics.push(diagnostic(
+                            p,
+                            r.range.start_line,
+                            AdrIssue::Dangling,
+                            format!("{verb} {what}, which does not exist"),
+                        ));
+                    }
+                }
+            }
+        }
+        resolved.push(sides);
+    }
+
+    // Both sides of every supersession should agree, and the status follow.
+    let number_of = |i: usize| parsed[i].entry.number.unwrap_or_default();
+    for (i, p) in parsed.iter().enumerate() {
+        let Some(me) = p.entry.number else {
+            continue;
+        };
+        for &old in &resolved[i].0 {
+            if let Some(&j) = by_number.get(&old)
+                && !resolved[j].1.contains(&me)
+            {
+                let line = ref_line(&p.supersedes, old);
+                diagnostics.push(diagnostic(
+                    p,
+                    line,
+                    AdrIssue::Inconsistent,
+                    format!(
+                        "supersedes ADR {old}, which does not say it is superseded by ADR {me}"
+                    ),
+                ));
+            }
+        }
+        for &new in &resolved[i].1 {
+            if let Some(&j) = by_number.get(&new)
+                && !resolved[j].0.contains(&me)
+            {
+                let line = ref_line(&p.superseded_by, new);
+                diagnostics.push(diagnostic(
+                    p,
+                    line,
+                    AdrIssue::Inconsistent,
+                    format!(
+                        "is superseded by ADR {new}, which does not say it supersedes ADR {me}"
+                    ),
+                ));
+            }
+        }
+        let replaced = !resolved[i].1.is_empty()
+            || resolved
+                .iter()
+                .enumerate()
+                .any(|(j, (sup, _))| j != i && sup.contains(&me));
+        match p.entry.status {
+            Some(AdrStatus::Superseded) if !replaced => diagnostics.push(diagnostic(
+                p,
+                p.status_line,
+                AdrIssue::Inconsistent,
+                "status is superseded, but no ADR supersedes it".to_string(),
+            )),
+            Some(status) if replaced && status != AdrStatus::Superseded => {
+                diagnostics.push(diagnostic(
+                    p,
+                    p.status_line,
+                    AdrIssue::Inconsistent,
+                    format!("is superseded, but its status is {}", status.as_str()),
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    // Cycles over "old → new" edges, from either side's statement.
+    let mut successors: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+    for (i, (sup, by)) in resolved.iter().enumerate() {
+        if parsed[i].entry.number.is_none() {
+            continue;
+        }
+        let me = number_of(i);
+        for &old in sup {
+            successors.entry(old).or_default().insert(me);
+        }
+        for &new in by {
+            successors.entry(me).or_default().insert(new);
+        }
+    }
+    for cycle in cycles(&successors) {
+        let first = cycle[0];
+        let Some(&i) = by_number.get(&first) else {
+            continue;
+        };
+        let chain: Vec<String> = cycle
+            .iter()
+            .chain(std::iter::once(&first))
+            .map(u32::to_string)
+            .collect();
+        diagnostics.push(diagnostic(
+            &parsed[i],
+            parsed[i].status_line,
+            AdrIssue::Cycle,
+            format!("supersession cycle: ADR {}", chain.join(" → ")),
+        ));
+    }
+
+    let adrs = parsed
+        .into_iter()
+        .zip(resolved)
+        .map(|(p, (supersedes, superseded_by))| RepoAdr {
+            supersedes,
+            superseded_by,
+            ..p.entry
+        })
+        .collect();
+    diagnostics.sort_by(|a, b| (&a.rel, a.line).cmp(&(&b.rel, b.line)));
+    (adrs, diagnostics)
+}
+
+/// Line of the reference to ADR `number` (falls back to the first line).
+fn ref_line(refs: &[AdrRef], number: u32) -> u32 {
+    refs.iter()
+        .find(|r| r.number == Some(number) || r.number.is_none())
+        .map_or(1, |r| r.range.start_line)
+}
+
+/// Each elementary cycle once, starting at its smallest number.
+fn cycles(successors: &BTreeMap<u32, BTreeSet<u32>>) -> Vec<Vec<u32>> {
+    fn walk(
+        node: u32,
+        start: u32,
+        path: &mut Vec<u32>,
+        successors: &BTreeMap<u32, BTreeSet<u32>>,
+        out: &mut Vec<Vec<u32>>,
+    ) {
+        for &next in successors.get(&node).into_iter().flatten() {
+            if next == start {
+                out.push(path.clone());
+            } else if next > start && !path.contains(&next) {
+                path.push(next);
+                walk(next, start, path, successors, out);
+                path.pop();
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for &start in successors.keys() {
+        walk(start, start, &mut vec![start], successors, &mut out);
+    }
+    out
+}
+
+/// `a/./b/../c` → `a/c`, for comparing link targets with explorer paths.
+fn normalize(path: &Path) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.to_str().unwrap_or("").split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.join("/")
+}
+
+/// The commit that added `rel` in the checkout at `root` (following renames).
+fn introducing_commit(root: &Path, rel: &str) -> Option<AdrCommit> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "log",
+            "--follow",
+            "--diff-filter=A",
+            "--date=short",
+            "--format=%H%x1f%an%x1f%ad%x1f%s",
+            "--",
+            rel,
+        ])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let line = text.lines().rfind(|l| !l.trim().is_empty())?;
+    let mut fields = line.split('\x1f');
+    Some(AdrCommit {
+        hash: fields.next()?.to_string(),
+        author: fields.next()?.to_string(),
+        date: fields.next()?.to_string(),
+        subject: fields.next().unwrap_or("").to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdpeek_analyzer::rules::DocTypeRule;
+
+    fn write(dir: &Path, rel: &str, content: &str) {
+        let path = dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn issues(index: &AdrIndex) -> Vec<(&str, u32, AdrIssue)> {
+        index
+            .diagnostics
+            .iter()
+            .map(|d| (d.rel.as_str(), d.line, d.issue))
+            .collect()
+    }
+
+    #[test]
+    fn indexes_a_consistent_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "docs/adr/0001-use-mysql.md",
+            "# 1. Use MySQL\n\nDate: 2023-01-01\n\n## Status\n\nSuperseded by [2. Use PostgreSQL](0002-use-postgresql.md)\n",
+        );
+        write(
+            dir.path(),
+            "docs/adr/0002-use-postgresql.md",
+            "# 2. Use PostgreSQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nSupersedes [1. Use MySQL](0001-use-mysql.md)\n",
+        );
+        write(dir.path(), "README.md", "# Project\n");
+        let index = collect(
+            dir.path(),
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+        );
+        type Row<'a> = (Option<u32>, &'a str, Option<AdrStatus>, Vec<u32>, Vec<u32>);
+        let summary: Vec<Row> = index
+            .adrs
+            .iter()
+            .map(|a| {
+                (
+                    a.number,
+                    a.title.as_str(),
+                    a.status,
+                    a.supersedes.clone(),
+                    a.superseded_by.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Some(1),
+                    "Use MySQL",
+                    Some(AdrStatus::Superseded),
+                    vec![],
+                    vec![2]
+                ),
+                (
+                    Some(2),
+                    "Use PostgreSQL",
+                    Some(AdrStatus::Accepted),
+                    vec![1],
+                    vec![]
+                ),
+            ]
+        );
+        assert!(index.diagnostics.is_empty(), "{:?}", index.diagnostics);
+        assert_eq!(index.adrs[0].commit, None, "not a git checkout");
+    }
+
+    #[test]
+    fn reports_dangling_inconsistent_and_cyclic_supersession() {
+        let dir = tempfile::tempdir().unwrap();
+        let adr = |n: u32, status: &str, rest: &str| {
+            format!("# {n}. Decision {n}\n\n## Status\n\n{status}\n\n{rest}\n")
+        };
+        write(
+            dir.path(),
+            "adr/0001-a.md",
+            &adr(1, "Superseded by ADR-2", ""),
+        );
+        write(
+            dir.path(),
+            "adr/0002-b.md",
+            &adr(2, "Superseded by ADR-3", "Supersedes ADR-1"),
+        );
+        write(
+            dir.path(),
+            "adr/0003-c.md",
+            &adr(
+                3,
+                "Superseded by ADR-1",
+                "Supersedes ADR-2\n\nSupersedes ADR-9",
+            ),
+        );
+        write(
+            dir.path(),
+            "adr/0004-d.md",
+            &adr(4, "Accepted", "Supersedes ADR-5"),
+        );
+        write(dir.path(), "adr/0005-e.md", &adr(5, "Accepted", ""));
+        let index = collect(
+            dir.path(),
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+        );
+        assert_eq!(
+            issues(&index),
+            vec![
+                ("adr/0001-a.md", 5, AdrIssue::Cycle),
+                ("adr/0003-c.md", 5, AdrIssue::Inconsistent),
+                ("adr/0003-c.md", 9, AdrIssue::Dangling),
+                ("adr/0004-d.md", 7, AdrIssue::Inconsistent),
+                ("adr/0005-e.md", 5, AdrIssue::Inconsistent),
+            ]
+        );
+        let cycle = index
+            .diagnostics
+            .iter()
+            .find(|d| d.issue == AdrIssue::Cycle)
+            .unwrap();
+        assert_eq!(cycle.message, "supersession cycle: ADR 1 → 2 → 3 → 1");
+    }
+
+    #[test]
+    fn correlates_the_introducing_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=Ada", "-c", "user.email=ada@example.com"])
+                .args(args)
+                .output()
+        };
+        if git(&["init", "-q"]).is_err() {
+            return; // git unavailable
+        }
+        write(
+            dir.path(),
+            "docs/adr/0001-record.md",
+            "# 1. Record decisions\n\n## Status\n\nAccepted\n",
+        );
+        git(&["add", "."]).unwrap();
+        git(&["commit", "-q", "-m", "Add ADR 1"]).unwrap();
+        let index = collect(
+            dir.path(),
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+        );
+        let commit = index.adrs[0].commit.as_ref().expect("commit found");
+        assert_eq!(commit.author, "Ada");
+        assert_eq!(commit.subject, "Add ADR 1");
+        assert_eq!(commit.hash.len(), 40);
+    }
+
+    #[test]
+    fn configured_doctype_rules_feed_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "docs/records/choose-a-database.md",
+            "# Choose a database\n\nWe use PostgreSQL.\n",
+        );
+        write(dir.path(), "docs/guide.md", "# Guide\n\nHow to start.\n");
+        let markdown = MarkdownOptions::default();
+        let index = collect(dir.path(), &markdown, &ClassificationRules::default());
+        assert!(index.adrs.is_empty());
+
+        let rules = ClassificationRules {
+            doctypes: vec![DocTypeRule {
+                doc_type: DocumentType::Adr,
+                filename: Some("docs/records/*.md".into()),
+                headings: Vec::new(),
+                frontmatter: None,
+                confidence: 0.9,
+            }],
+            ..Default::default()
+        };
+        let index = collect(dir.path(), &markdown, &rules);
+        let found: Vec<(&str, &str)> = index
+            .adrs
+            .iter()
+            .map(|a| (a.rel.as_str(), a.title.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("docs/records/choose-a-database.md", "Choose a database")]
+        );
+    }
+}
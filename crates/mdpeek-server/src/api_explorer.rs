@@ -0,0 +1,163 @@
+//! API explorer proxy.
+//!
+//! The browser renders each HTTP code block of the active document as a
+//! request card; its "send" button posts the block's index to
+//! `POST /api/http`, and the request written in the document is replayed here
+//! against the configured base URL (`[api_explorer] base_url`): the path and
+//! query of the block's target are appended to it and the block's headers and
+//! body are sent as written. The page never names the host, so the server
+//! cannot be used to reach anything but the base URL, and redirects are
+//! returned rather than followed. Without a base URL the proxy is off.
+
+use mdpeek_analyzer::analyzer::http::{HttpHeader, HttpRequest};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Headers that describe the connection the document's request was written
+/// for, not the request; the proxy's own connection sets them.
+const HOP_HEADERS: &[&str] = &["host", "content-length", "connection", "transfer-encoding"];
+
+/// Sends documented requests to one base URL.
+#[derive(Debug, Clone)]
+pub struct ApiExplorer {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+/// What the proxied request got back, for the request card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProxiedResponse {
+    /// The URL the request was sent to.
+    pub url: String,
+    pub status: u16,
+    pub reason: String,
+    pub headers: Vec<HttpHeader>,
+    /// The body, decoded as UTF-8 (invalid bytes replaced).
+    pub body: String,
+    pub elapsed_ms: u64,
+}
+
+impl ApiExplorer {
+    /// Proxy to `base_url` (e.g. `http://localhost:8080/v1`), giving up on a
+    /// request after `timeout`.
+    pub fn new(base_url: impl Into<String>, timeout: Duration) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(timeout))
+            .http_status_as_error(false)
+            .max_redirects(0)
+            .allow_non_standard_methods(true)
+            .build()
+            .into();
+        Self {
+            base_url: base_url.into(),
+            agent,
+        }
+    }
+
+    /// The URL `request` is sent to: the base URL with the request's path and
+    /// query appended.
+    pub fn url_for(&self, request: &HttpRequest) -> String {
+        let path = request.path();
+        let base = self.base_url.trim_end_matches('/');
+        if path.starts_with('/') {
+            format!("{base}{path}")
+        } else {
+            format!("{base}/{path}")
+        }
+    }
+
+    /// The method, URL and headers `request` is sent with: the documented
+    /// ones, against [`ApiExplorer::url_for`] and without [`HOP_HEADERS`].
+    fn builder(&self, request: &HttpRequest) -> ureq::http::request::Builder {
+        let mut builder = ureq::http::Request::builder()
+            .method(request.method.as_str())
+            .uri(self.url_for(request));
+        for h in &request.headers {
+            if !HOP_HEADERS.contains(&h.name.to_ascii_lowercase().as_str()) {
+                builder = builder.header(&h.name, &h.value);
+            }
+        }
+        builder
+    }
+
+    /// Send `request` and read the whole response.
+    pub fn send(&self, request: &HttpRequest) -> Result<ProxiedResponse, ureq::Error> {
+        let url = self.url_for(request);
+        let builder = self.builder(request);
+        let started = Instant::now();
+        let mut response = match &request.body {
+            Some(body) => self.agent.run(builder.body(body.clone())?)?,
+            None => self.agent.run(builder.body(())?)?,
+        };
+        let status = response.status();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| HttpHeader {
+                name: name.to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            })
+            .collect();
+        let body = response.body_mut().read_to_vec()?;
+        Ok(ProxiedResponse {
+            url,
+            status: status.as_u16(),
+            reason: status.canonical_reason().unwrap_or("").to_string(),
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+            elapsed_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdpeek_analyzer::BlockTree;
+    use mdpeek_analyzer::analyzer::http;
+
+    fn request(md: &str) -> HttpRequest {
+        let exchanges = http::extract(&BlockTree::parse(md));
+        exchanges[0].request.clone().expect("request")
+    }
+
+    #[test]
+    fn documented_request_is_replayed_against_the_base_url() {
+        let explorer = ApiExplorer::new("http://localhost:8080/v1/", Duration::from_secs(5));
+        let request = request(
+            "```http\nPOST https://api.example.com/users?notify=1 HTTP/1.1\n\
+             Host: api.example.com\nContent-Type: application/json\nX-Trace: abc\n\n\
+             {\"name\": \"alice\"}\n```\n",
+        );
+        let builder = explorer.builder(&request);
+        assert_eq!(builder.method_ref().map(|m| m.as_str()), Some("POST"));
+        assert_eq!(
+            builder.uri_ref().map(|u| u.to_string()).as_deref(),
+            Some("http://localhost:8080/v1/users?notify=1")
+        );
+        let headers = builder.headers_ref().expect("headers");
+        assert!(headers.get("host").is_none(), "host must not be forwarded");
+        assert_eq!(headers["x-trace"], "abc");
+        assert_eq!(headers["content-type"], "application/json");
+        assert_eq!(request.body.as_deref(), Some("{\"name\": \"alice\"}"));
+    }
+
+    #[test]
+    fn relative_targets_are_appended_to_the_base_url() {
+        let explorer = ApiExplorer::new("http://localhost:8080", Duration::from_secs(5));
+        let request = request("```http\nDELETE /items/3\n```\n");
+        assert_eq!(explorer.url_for(&request), "http://localhost:8080/items/3");
+        assert_eq!(
+            explorer.builder(&request).method_ref().map(|m| m.as_str()),
+            Some("DELETE")
+        );
+    }
+
+    #[test]
+    fn error_statuses_and_redirects_are_returned() {
+        let explorer = ApiExplorer::new("http://localhost:8080", Duration::from_secs(5));
+        let config = explorer.agent.config();
+        assert!(!config.http_status_as_error());
+        assert_eq!(config.max_redirects(), 0);
+    }
+}
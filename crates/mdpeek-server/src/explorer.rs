@@ -207,8 +207,14 @@ pub fn allowed_roots(start: &Path) -> Vec<PathBuf> {
 /// Resolve a client-supplied path: it must canonicalize to an existing markdown
 /// file located under one of `roots`. Returns `None` (reject) otherwise.
 pub fn resolve_within(roots: &[PathBuf], requested: &str) -> Option<PathBuf> {
+    resolve_file_within(roots, requested).filter(|canon| markdown_ext(canon))
+}
+
+/// [`resolve_within`] for any regular file, markdown or not (the TODO
+/// dashboard's source view).
+pub fn resolve_file_within(roots: &[PathBuf], requested: &str) -> Option<PathBuf> {
     let canon = Path::new(requested).canonicalize().ok()?;
-    if !canon.is_file() || !markdown_ext(&canon) {
+    if !canon.is_file() {
         return None;
     }
     roots.iter().any(|r| canon.starts_with(r)).then_some(canon)
@@ -1,11 +1,14 @@
+pub mod adrs;
+pub mod api_explorer;
 mod explorer;
+pub mod todos;
 
 use anyhow::Result;
 use axum::{
     Json, Router,
     body::Body,
     extract::{
-        Path as AxumPath, State,
+        Path as AxumPath, Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
     http::{StatusCode, header},
@@ -15,8 +18,10 @@ use axum::{
 use core::fmt;
 use futures::{SinkExt, StreamExt};
 use pulldown_cmark::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::Sender as StdSender;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -24,20 +29,34 @@ use tokio::net::TcpListener;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
+use adrs::AdrIndex;
+use api_explorer::ApiExplorer;
+use mdpeek_analyzer::analyzer::recipe::{self, Recipe, UnitSystem};
+use mdpeek_analyzer::analyzer::{http, minutes};
+use mdpeek_analyzer::{Analysis, AnalysisExtras, BlockTree, Generator, RulesGenerator};
+use mdpeek_gfm::emoji::Emoji;
+use mdpeek_gfm::{EmojiResolver, MarkdownOptions};
 use mdpeek_render_html::HtmlEmitter;
 use mdpeek_watcher::watch_channel;
+use todos::{RepoTodo, TodoFilter};
 
 #[derive(Clone)]
 struct AppState {
     tx: broadcast::Sender<Message>,
     file_path: Arc<RwLock<PathBuf>>,
     theme: Arc<RwLock<Theme>>,
+    /// Markdown feature set and emoji used for every render.
+    render: RenderOptions,
+    /// Local image files behind user image emoji, served at `/emoji/{name}`.
+    emoji_files: Arc<HashMap<String, PathBuf>>,
     /// Canonical roots a selected file must live under (#14 path safety).
     roots: Arc<Vec<PathBuf>>,
     /// Directory discovery starts from when (re)building the explorer tree.
     scan_root: Arc<PathBuf>,
     /// Tells the watch loop to re-point at a newly selected file or diff pair.
     rewatch: StdSender<WatchTarget>,
+    /// Proxy behind the request cards' "send" button; `None` when disabled.
+    api_explorer: Option<Arc<ApiExplorer>>,
 }
 
 /// What the server watches and re-renders on change: a single file (normal
@@ -71,6 +90,29 @@ struct DiffOptions {
     layout: DiffLayout,
 }
 
+/// Everything that affects how markdown becomes HTML, shared by the page, live
+/// updates and rendered diffs so they all agree.
+#[derive(Clone)]
+struct RenderOptions {
+    markdown: MarkdownOptions,
+    emoji: Arc<EmojiResolver>,
+    /// Builds the semantic side panel's [`Analysis`] (rules, or rules + LLM).
+    generator: Arc<dyn Generator>,
+    /// Issue export and prose linter applied to every analysis.
+    extras: Arc<AnalysisExtras>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            markdown: MarkdownOptions::default(),
+            emoji: Arc::default(),
+            generator: Arc::new(RulesGenerator),
+            extras: Arc::default(),
+        }
+    }
+}
+
 /// Browser colour theme selected for the served page. The caller (the `mdpeek`
 /// binary) maps its own config theme onto this so the server crate stays
 /// independent of the binary's config types.
@@ -89,7 +131,18 @@ impl fmt::Display for Theme {
     }
 }
 
-pub fn serve(watch_path: PathBuf, host: String, port: String, theme: Theme) {
+#[allow(clippy::too_many_arguments)]
+pub fn serve(
+    watch_path: PathBuf,
+    host: String,
+    port: String,
+    theme: Theme,
+    markdown: MarkdownOptions,
+    emoji: EmojiResolver,
+    generator: Arc<dyn Generator>,
+    extras: AnalysisExtras,
+    api_explorer: Option<ApiExplorer>,
+) {
     // Discover the enclosing repo/worktrees from where the user ran mdpeek so
     // the explorer sidebar (#14) works regardless of the file argument.
     let scan_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
@@ -98,6 +151,14 @@ pub fn serve(watch_path: PathBuf, host: String, port: String, theme: Theme) {
     // discovered under the repo, else fall back to the requested path.
     let active = explorer::initial_active(&watch_path, &scan_root).unwrap_or(watch_path);
 
+    let (emoji, emoji_files) = browser_emoji(emoji);
+    let render = RenderOptions {
+        markdown,
+        emoji: Arc::new(emoji),
+        generator,
+        extras: Arc::new(extras),
+    };
+
     let (tx, _) = broadcast::channel::<Message>(16);
     let file_path = Arc::new(RwLock::new(active.clone()));
     let theme = Arc::new(RwLock::new(theme));
@@ -106,9 +167,12 @@ pub fn serve(watch_path: PathBuf, host: String, port: String, theme: Theme) {
         tx: tx.clone(),
         file_path: Arc::clone(&file_path),
         theme: Arc::clone(&theme),
+        render: render.clone(),
+        emoji_files: Arc::new(emoji_files),
         roots: Arc::new(roots),
         scan_root: Arc::new(scan_root),
         rewatch: rewatch_tx,
+        api_explorer: api_explorer.map(Arc::new),
     };
     let server = std::thread::spawn(move || run_server(state, host, port));
 
@@ -120,15 +184,15 @@ pub fn serve(watch_path: PathBuf, host: String, port: String, theme: Theme) {
     let mut watched: Vec<PathBuf> = Vec::new();
     let mut target = WatchTarget::Single(active);
     watch_target(&mut handle, &mut watched, &target);
-    broadcast_for(&target, &tx);
+    broadcast_for(&target, &tx, &render);
     loop {
         while let Ok(next) = rewatch_rx.try_recv() {
             watch_target(&mut handle, &mut watched, &next);
             target = next;
-            broadcast_for(&target, &tx);
+            broadcast_for(&target, &tx, &render);
         }
         match rx.recv_timeout(Duration::from_millis(300)) {
-            Ok(()) => broadcast_for(&target, &tx),
+            Ok(()) => broadcast_for(&target, &tx, &render),
             Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
             Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
         }
@@ -159,23 +223,29 @@ fn watch_target(
 }
 
 /// Broadcast the appropriate update for the current watch target.
-fn broadcast_for(target: &WatchTarget, tx: &broadcast::Sender<Message>) {
+fn broadcast_for(target: &WatchTarget, tx: &broadcast::Sender<Message>, render: &RenderOptions) {
     match target {
-        WatchTarget::Single(p) => broadcast_update(p, tx),
+        WatchTarget::Single(p) => broadcast_update(p, tx, render),
         WatchTarget::Pair(a, b, opts) => {
             let msg =
-                serde_json::json!({ "type": "diff-update", "html": render_diff(a, b, *opts) })
+                serde_json::json!({ "type": "diff-update", "html": render_diff(a, b, *opts, render) })
                     .to_string();
             let _ = tx.send(Message::text(msg));
         }
     }
 }
 
+/// Bumped by every live update; an analysis still running when a newer
+/// update starts is dropped rather than sent out of order.
+static ANALYSIS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 /// Re-render `path` and broadcast an in-place update to connected clients (#16).
-fn broadcast_update(path: &Path, tx: &broadcast::Sender<Message>) {
+/// The HTML goes out first; the analysis, which may wait on the LLM, follows
+/// as a separate `analysis` message from a background thread.
+fn broadcast_update(path: &Path, tx: &broadcast::Sender<Message>, render: &RenderOptions) {
     match std::fs::read_to_string(path) {
         Ok(content) => {
-            let (body, frontmatter) = render_markdown(&content);
+            let (body, frontmatter) = render_markdown(&content, render);
             let msg = serde_json::json!({
                 "type": "update",
                 "html": body,
@@ -184,6 +254,19 @@ fn broadcast_update(path: &Path, tx: &broadcast::Sender<Message>) {
             .to_string();
             let result = tx.send(Message::text(msg));
             debug!("Pushed live update for {}: {:#?}", path.display(), result);
+
+            let generation = ANALYSIS_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+            let (path, tx, render) = (path.to_path_buf(), tx.clone(), render.clone());
+            std::thread::spawn(move || {
+                let analysis = analyze_markdown(&content, &path, &render);
+                if ANALYSIS_GENERATION.load(Ordering::SeqCst) != generation {
+                    debug!("Dropped stale analysis for {}", path.display());
+                    return;
+                }
+                let analysis = DocumentAnalysis::new(&path, analysis);
+                let msg = serde_json::json!({ "type": "analysis", "analysis": analysis });
+                let _ = tx.send(Message::text(msg.to_string()));
+            });
         }
         Err(e) => error!("Failed to read '{}' on change: {e}", path.display()),
     }
@@ -195,9 +278,20 @@ async fn run_server(state: AppState, host: String, port: String) -> Result<()> {
         .route("/", get(file_handler))
         .route("/ws", get(websocket_handler))
         .route("/api/tree", get(tree_handler))
+        .route("/api/analysis", get(analysis_handler))
+        .route("/api/todos", get(todos_api_handler))
+        .route("/todos", get(todos_page_handler))
+        .route("/todos/source", get(todos_source_handler))
+        .route("/api/adrs", get(adrs_api_handler))
+        .route("/adrs", get(adrs_page_handler))
         .route("/api/select", post(select_handler))
+        .route("/api/progress", post(progress_handler))
+        .route("/api/recipe", get(recipe_handler))
+        .route("/api/minutes.ics", get(minutes_ical_handler))
+        .route("/api/http", post(http_handler))
         .route("/api/diff", post(diff_handler))
         .route("/static/{*path}", get(static_handler))
+        .route("/emoji/{name}", get(emoji_handler))
         .with_state(state);
     let listener = match TcpListener::bind(format!("{host}:{port}")).await {
         Ok(listner) => listner,
@@ -242,7 +336,7 @@ async fn file_handler(State(state): State<AppState>) -> impl IntoResponse {
             return Html(error_html);
         }
     };
-    let (html_body, frontmatter) = render_markdown(&markdown_content);
+    let (html_body, frontmatter) = render_markdown(&markdown_content, &state.render);
 
     // Front matter panel (#19): surface the leading YAML/+++ block, which the
     // renderer otherwise hides. Escaped and stashed in a hidden element for the
@@ -274,18 +368,43 @@ async fn file_handler(State(state): State<AppState>) -> impl IntoResponse {
 /// the HTTP handler (initial page) and the live-update watch callback (#16) so
 /// both produce identical markup. The front matter is returned raw (unescaped);
 /// each caller escapes it as its transport requires.
-fn render_markdown(content: &str) -> (String, Option<String>) {
-    let parser = Parser::new_ext(content, mdpeek_gfm::parser_options());
-    let parser = mdpeek_gfm::transform(parser);
-    let mut emitter = HtmlEmitter::new(parser);
+fn render_markdown(content: &str, render: &RenderOptions) -> (String, Option<String>) {
+    // Front matter may override the configured feature set for this document.
+    let markdown = &render.markdown.for_document(content);
+    let parser = Parser::new_ext(content, markdown.parser_options()).into_offset_iter();
+    // Block source lines let the client find the analysis' blocks in the page.
+    let (parser, lines) = mdpeek_render_html::source_lines(content, parser);
+    let parser = mdpeek_gfm::extensions(content, parser, markdown);
+    let parser = mdpeek_gfm::transform_with_emoji(parser, &render.emoji);
+    let parser = mdpeek_gfm::typography(parser, markdown);
+    let mut emitter = HtmlEmitter::new(parser).with_source_lines(lines);
     let body = emitter.run();
-    let frontmatter = mdpeek_parser::BlockTree::parse(content)
-        .frontmatter()
-        .filter(|fm| !fm.trim().is_empty())
-        .map(|fm| fm.to_string());
+    let frontmatter =
+        mdpeek_parser::BlockTree::parse_with_options(content, markdown.parser_options())
+            .frontmatter()
+            .filter(|fm| !fm.trim().is_empty())
+            .map(|fm| fm.to_string());
     (body, frontmatter)
 }
 
+/// Layer 2 analysis of `content` for the semantic side panel. The path
+/// sharpens document-type inference (and is what `[analyzer]` filename globs
+/// match); the generator may call out to an LLM, so async callers run this on
+/// a blocking thread.
+fn analyze_markdown(content: &str, path: &Path, render: &RenderOptions) -> Analysis {
+    let mut analysis = mdpeek_analyzer::analyze_with_rules(
+        content,
+        path.to_str(),
+        &render.markdown,
+        &render.extras.rules,
+        render.generator.as_ref(),
+    );
+    render
+        .extras
+        .apply(content, Some(path), &render.markdown, &mut analysis);
+    analysis
+}
+
 /// Minimal HTML-body escaping (`&`, `<`, `>`) so arbitrary front matter text
 /// can be embedded in a hidden element without breaking out of it. Newlines are
 /// preserved for the client's front matter panel.
@@ -315,6 +434,532 @@ async fn tree_handler(State(state): State<AppState>) -> impl IntoResponse {
     Json(serde_json::json!({ "tree": tree, "active": active }))
 }
 
+/// An [`Analysis`] with the document it is of, so that what the page does
+/// with it (saving step progress) names that document rather than whichever
+/// file is active by then.
+#[derive(Serialize)]
+struct DocumentAnalysis {
+    path: String,
+    #[serde(flatten)]
+    analysis: Analysis,
+}
+
+impl DocumentAnalysis {
+    fn new(path: &Path, analysis: Analysis) -> Self {
+        DocumentAnalysis {
+            path: path.to_string_lossy().into_owned(),
+            analysis,
+        }
+    }
+}
+
+/// `GET /api/analysis` — the active file's [`Analysis`] (block tree, semantic
+/// model and outline / TODO / risk / open-question panel) as JSON.
+async fn analysis_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let path = state.file_path.read().unwrap().to_path_buf();
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) => {
+            error!("Failed to read '{}' for analysis: {e}", path.display());
+            return StatusCode::NOT_FOUND.into_response();
+        }
+    };
+    let render = state.render.clone();
+    let analysis = tokio::task::spawn_blocking(move || {
+        DocumentAnalysis::new(&path, analyze_markdown(&content, &path, &render))
+    });
+    match analysis.await {
+        Ok(analysis) => Json(analysis).into_response(),
+        Err(e) => {
+            error!("Analysis task failed: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Query of the recipe scaler: target servings (the multiplier, when the
+/// recipe does not say how many it serves) and units. Both default to the
+/// recipe as written.
+#[derive(Debug, Default, Deserialize)]
+struct RecipeQuery {
+    servings: Option<f64>,
+    units: Option<UnitSystem>,
+}
+
+/// The active file's path and block tree, parsed as the analysis parses it
+/// (front matter overrides included), for endpoints that need one
+/// extractor's output rather than the whole (possibly LLM-refined) analysis.
+/// `None` when the file cannot be read.
+async fn active_tree(state: &AppState) -> Option<(PathBuf, BlockTree)> {
+    let path = state.file_path.read().unwrap().to_path_buf();
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) => {
+            error!("Failed to read '{}': {e}", path.display());
+            return None;
+        }
+    };
+    let markdown = state.render.markdown.for_document(&content);
+    let tree = BlockTree::parse_with_options(&content, markdown.parser_options());
+    Some((path, tree))
+}
+
+/// `GET /api/recipe?servings=6&units=metric` — the active recipe's
+/// ingredients rescaled: each with its measure as written (to find in the
+/// page) and as scaled. 404 when the active file lists no ingredients.
+async fn recipe_handler(
+    State(state): State<AppState>,
+    Query(query): Query<RecipeQuery>,
+) -> impl IntoResponse {
+    let Some((_, tree)) = active_tree(&state).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let recipe = recipe::extract(&tree);
+    if recipe.ingredients.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    Json(scaled_recipe(&recipe, &query)).into_response()
+}
+
+/// `GET /api/minutes.ics` — the active minutes' action items as an
+/// iCalendar download of `VTODO`s. 404 when the active file has no action
+/// items.
+async fn minutes_ical_handler(State(state): State<AppState>) -> Response {
+    let Some((path, tree)) = active_tree(&state).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let minutes = minutes::extract(&tree);
+    if minutes.actions.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let name = path
+        .file_stem()
+        .map_or("minutes".into(), |s| s.to_string_lossy().into_owned());
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.ics\"", name.replace('"', "")),
+        )
+        .body(Body::from(minutes.to_ical(std::time::SystemTime::now())))
+        .expect("calendar response should be valid")
+}
+
+#[derive(Deserialize)]
+struct HttpSendRequest {
+    index: usize,
+}
+
+/// `POST /api/http {index}` — send the request of the active file's `index`th
+/// HTTP code block (`analysis.http`) through the API explorer proxy and
+/// return the response. 404 when the proxy is disabled or there is no such
+/// request; 502 when the request fails.
+async fn http_handler(State(state): State<AppState>, Json(req): Json<HttpSendRequest>) -> Response {
+    let Some(explorer) = state.api_explorer.clone() else {
+        return (
+            StatusCode::NOT_FOUND,
+            "the API explorer is disabled; set `api_explorer.base_url` in config.toml",
+        )
+            .into_response();
+    };
+    let Some((_, tree)) = active_tree(&state).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(request) = http::extract(&tree)
+        .into_iter()
+        .nth(req.index)
+        .and_then(|e| e.request)
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let result = tokio::task::spawn_blocking(move || explorer.send(&request)).await;
+    match result {
+        Ok(Ok(response)) => Json(response).into_response(),
+        Ok(Err(e)) => {
+            warn!("API explorer request failed: {e}");
+            (StatusCode::BAD_GATEWAY, format!("request failed: {e}")).into_response()
+        }
+        Err(e) => {
+            error!("API explorer task failed: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn scaled_recipe(recipe: &Recipe, query: &RecipeQuery) -> serde_json::Value {
+    let factor = query
+        .servings
+        .map_or(1.0, |servings| recipe.factor(servings));
+    let ingredients: Vec<serde_json::Value> = recipe
+        .ingredients
+        .iter()
+        .map(|i| {
+            serde_json::json!({
+                "text": i.scaled(factor, query.units),
+                "measure": i.measure.as_ref().map(|m| &m.text),
+                "scaled": i.measure.as_ref().map(|m| m.scaled(factor, query.units)),
+                "link": i.link,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "servings": recipe.servings,
+        "factor": factor,
+        "ingredients": ingredients,
+    })
+}
+
+/// Query of the TODO dashboard (`/todos`, `/api/todos`), as sent by its filter
+/// form: empty fields mean "any".
+#[derive(Debug, Default, Deserialize)]
+struct TodosQuery {
+    marker: Option<String>,
+    /// `open`, `done`, or empty for both.
+    state: Option<String>,
+    file: Option<String>,
+    worktree: Option<String>,
+    /// Present (any value) to include source-code comments.
+    code: Option<String>,
+}
+
+impl TodosQuery {
+    fn filter(&self) -> TodoFilter {
+        let set = |v: &Option<String>| v.clone().filter(|s| !s.trim().is_empty());
+        TodoFilter {
+            marker: set(&self.marker),
+            done: match self.state.as_deref() {
+                Some("done") => Some(true),
+                Some("open") => Some(false),
+                _ => None,
+            },
+            file: set(&self.file),
+            worktree: set(&self.worktree),
+        }
+    }
+
+    fn code(&self) -> bool {
+        self.code.is_some()
+    }
+}
+
+/// Collect the repository's TODOs off the async runtime (it walks the tree and
+/// shells out to git).
+async fn collect_todos(state: &AppState, code: bool) -> Vec<RepoTodo> {
+    let scan_root = Arc::clone(&state.scan_root);
+    let markdown = state.render.markdown;
+    let extras = Arc::clone(&state.render.extras);
+    tokio::task::spawn_blocking(move || todos::collect(&scan_root, &markdown, &extras.rules, code))
+        .await
+        .unwrap_or_else(|e| {
+            error!("TODO collection failed: {e}");
+            Vec::new()
+        })
+}
+
+/// `GET /api/todos?marker=&state=&file=&worktree=&code=` — every TODO in the
+/// repository's markdown (and optionally code comments), filtered.
+async fn todos_api_handler(
+    State(state): State<AppState>,
+    Query(query): Query<TodosQuery>,
+) -> impl IntoResponse {
+    let filter = query.filter();
+    let todos: Vec<RepoTodo> = collect_todos(&state, query.code())
+        .await
+        .into_iter()
+        .filter(|t| filter.matches(t))
+        .collect();
+    Json(todos)
+}
+
+/// `GET /todos` — the repository TODO dashboard page.
+async fn todos_page_handler(
+    State(state): State<AppState>,
+    Query(query): Query<TodosQuery>,
+) -> impl IntoResponse {
+    let all = collect_todos(&state, query.code()).await;
+    let theme = state.theme.read().unwrap().to_string();
+    let page = include_str!("../../../static/todos.html")
+        .replace("{{theme}}", &theme)
+        .replace("{{ title }}", "TODOs")
+        .replace("{{ content }}", &todos_dashboard(&all, &query));
+    Html(page)
+}
+
+#[derive(Deserialize)]
+struct SourceQuery {
+    path: String,
+    line: Option<u32>,
+}
+
+/// `GET /todos/source?path=&line=` — a source file (for code-comment TODOs)
+/// with numbered, linkable lines. The path must be a source file the TODO
+/// scan reads under the discovered roots; anything else is forbidden.
+async fn todos_source_handler(
+    State(state): State<AppState>,
+    Query(query): Query<SourceQuery>,
+) -> Response {
+    let Some(abs) = explorer::resolve_file_within(&state.roots, &query.path)
+        .filter(|abs| todos::is_scanned_source(&state.roots, abs))
+    else {
+        warn!(
+            "Rejected source view for '{}' (not a scanned source file)",
+            query.path
+        );
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let Ok(content) = tokio::fs::read_to_string(&abs).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut body = format!(
+        "<p><a href=\"/todos\">← TODOs</a></p><h2><code>{}</code></h2><table class=\"mdpeek-source\"><tbody>",
+        escape_html_min(&abs.to_string_lossy())
+    );
+    for (i, line) in content.lines().enumerate() {
+        let n = i as u32 + 1;
+        let class = if Some(n) == query.line {
+            " class=\"mdpeek-source-target\""
+        } else {
+            ""
+        };
+        body.push_str(&format!(
+            "<tr id=\"L{n}\"{class}><td class=\"mdpeek-source-num\"><a href=\"#L{n}\">{n}</a></td><td><pre>{}</pre></td></tr>",
+            escape_html_min(line)
+        ));
+    }
+    body.push_str("</tbody></table>");
+    let theme = state.theme.read().unwrap().to_string();
+    let title = abs
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Html(
+        include_str!("../../../static/todos.html")
+            .replace("{{theme}}", &theme)
+            .replace("{{ title }}", &title)
+            .replace("{{ content }}", &body),
+    )
+    .into_response()
+}
+
+/// The dashboard body: a filter form and one table row per matching TODO,
+/// linking markdown entries into the preview (`/?open=&line=`) and code entries
+/// to the source view.
+fn todos_dashboard(all: &[RepoTodo], query: &TodosQuery) -> String {
+    let filter = query.filter();
+    let shown: Vec<&RepoTodo> = all.iter().filter(|t| filter.matches(t)).collect();
+
+    let select = |name: &str, current: Option<&str>, options: &[(String, String)]| {
+        let mut out = format!("<select name=\"{name}\">");
+        for (value, label) in options {
+            let selected = if current == Some(value.as_str()) {
+                " selected"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<option value=\"{}\"{selected}>{}</option>",
+                escape_attr(value),
+                escape_html_min(label)
+            ));
+        }
+        out.push_str("</select>");
+        out
+    };
+    let any = || (String::new(), "any".to_string());
+    let mut markers = vec![any()];
+    markers.extend(
+        ["task", "todo", "fixme", "xxx", "hack"]
+            .iter()
+            .map(|m| (m.to_string(), m.to_string())),
+    );
+    let states = [
+        any(),
+        ("open".to_string(), "open".to_string()),
+        ("done".to_string(), "done".to_string()),
+    ];
+    let mut worktrees = vec![any()];
+    for t in all {
+        if !worktrees.iter().any(|(v, _)| *v == t.worktree) {
+            let label = match &t.branch {
+                Some(b) => format!("{} ({b})", t.worktree),
+                None => t.worktree.clone(),
+            };
+            worktrees.push((t.worktree.clone(), label));
+        }
+    }
+
+    let mut out = String::from(
+        "<h1>TODOs</h1><form method=\"get\" action=\"/todos\" class=\"mdpeek-todos-filter\">",
+    );
+    out.push_str(&format!(
+        "<label>Marker {}</label> <label>State {}</label> <label>Worktree {}</label> \
+         <label>File <input name=\"file\" value=\"{}\" placeholder=\"path contains…\"></label> \
+         <label><input type=\"checkbox\" name=\"code\" value=\"1\"{}> code comments</label> \
+         <button type=\"submit\">Filter</button></form>",
+        select("marker", query.marker.as_deref(), &markers),
+        select("state", query.state.as_deref(), &states),
+        select("worktree", query.worktree.as_deref(), &worktrees),
+        escape_attr(query.file.as_deref().unwrap_or("")),
+        if query.code() { " checked" } else { "" },
+    ));
+    out.push_str(&format!("<p>{} of {} entries</p>", shown.len(), all.len()));
+    out.push_str(
+        "<table class=\"mdpeek-todos\"><thead><tr><th></th><th>Marker</th><th>Location</th><th>Text</th><th>Worktree</th></tr></thead><tbody>",
+    );
+    for t in shown {
+        let href = if t.rel.ends_with(".md") || t.rel.ends_with(".markdown") {
+            format!("/?open={}&line={}", encode_component(&t.path), t.line)
+        } else {
+            format!(
+                "/todos/source?path={}&line={}#L{}",
+                encode_component(&t.path),
+                t.line,
+                t.line
+            )
+        };
+        let check = match (t.marker.as_str(), t.done) {
+            ("task", true) => "☑",
+            ("task", false) => "☐",
+            _ => "",
+        };
+        out.push_str(&format!(
+            "<tr><td>{check}</td><td><code>{}</code></td><td><a href=\"{href}\">{}:{}</a></td><td>{}</td><td>{}</td></tr>",
+            escape_html_min(&t.marker),
+            escape_html_min(&t.rel),
+            t.line,
+            escape_html_min(&t.text),
+            escape_html_min(t.branch.as_deref().unwrap_or(&t.worktree)),
+        ));
+    }
+    out.push_str("</tbody></table>");
+    out
+}
+
+/// Build the repository's ADR index off the async runtime (it walks the tree
+/// and shells out to git).
+async fn collect_adrs(state: &AppState) -> AdrIndex {
+    let scan_root = Arc::clone(&state.scan_root);
+    let markdown = state.render.markdown;
+    let extras = Arc::clone(&state.render.extras);
+    tokio::task::spawn_blocking(move || adrs::collect(&scan_root, &markdown, &extras.rules))
+        .await
+        .unwrap_or_else(|e| {
+            error!("ADR collection failed: {e}");
+            AdrIndex::default()
+        })
+}
+
+/// `GET /api/adrs` — every ADR with its resolved supersession links and the
+/// chain diagnostics.
+async fn adrs_api_handler(State(state): State<AppState>) -> impl IntoResponse {
+    Json(collect_adrs(&state).await)
+}
+
+/// `GET /adrs` — the ADR index page.
+async fn adrs_page_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let index = collect_adrs(&state).await;
+    let theme = state.theme.read().unwrap().to_string();
+    let page = include_str!("../../../static/todos.html")
+        .replace("{{theme}}", &theme)
+        .replace("{{ title }}", "ADRs")
+        .replace("{{ content }}", &adrs_index(&index));
+    Html(page)
+}
+
+/// The ADR index body: diagnostics first, then one row per ADR linking into
+/// the preview, with its supersession links and introducing commit.
+fn adrs_index(index: &AdrIndex) -> String {
+    let many = index
+        .adrs
+        .iter()
+        .any(|a| a.worktree != index.adrs[0].worktree);
+    let mut out = format!("<h1>ADRs</h1><p>{} records</p>", index.adrs.len());
+    if !index.diagnostics.is_empty() {
+        out.push_str("<ul class=\"mdpeek-adrs-diagnostics\">");
+        for d in &index.diagnostics {
+            let path = index
+                .adrs
+                .iter()
+                .find(|a| a.rel == d.rel && a.worktree == d.worktree)
+                .map_or(String::new(), |a| a.path.clone());
+            out.push_str(&format!(
+                "<li><code>{}</code> <a href=\"/?open={}&line={}\">{}:{}</a> {}</li>",
+                d.issue.as_str(),
+                encode_component(&path),
+                d.line,
+                escape_html_min(&d.rel),
+                d.line,
+                escape_html_min(&d.message),
+            ));
+        }
+        out.push_str("</ul>");
+    }
+    out.push_str(
+        "<table class=\"mdpeek-adrs\"><thead><tr><th>#</th><th>Title</th><th>Status</th><th>Date</th><th>Supersedes</th><th>Superseded by</th><th>Introduced</th></tr></thead><tbody>",
+    );
+    let numbers = |ns: &[u32]| {
+        ns.iter()
+            .map(|n| format!("<a href=\"#adr-{n}\">{n}</a>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    for a in &index.adrs {
+        let (id, number) = match a.number {
+            Some(n) => (format!(" id=\"adr-{n}\""), n.to_string()),
+            None => (String::new(), String::new()),
+        };
+        let status = a.status.map_or("", |s| s.as_str());
+        let title = if many {
+            format!(
+                "{} <small>({})</small>",
+                escape_html_min(&a.title),
+                escape_html_min(a.branch.as_deref().unwrap_or(&a.worktree))
+            )
+        } else {
+            escape_html_min(&a.title)
+        };
+        let commit = a.commit.as_ref().map_or(String::new(), |c| {
+            format!(
+                "<code title=\"{}\">{}</code> {} {}",
+                escape_attr(&c.subject),
+                escape_html_min(&c.hash[..c.hash.len().min(7)]),
+                escape_html_min(&c.date),
+                escape_html_min(&c.author),
+            )
+        });
+        out.push_str(&format!(
+            "<tr{id}><td>{number}</td><td><a href=\"/?open={}\">{title}</a></td><td><span class=\"mdpeek-adr-status mdpeek-adr-{status}\">{status}</span></td><td>{}</td><td>{}</td><td>{}</td><td>{commit}</td></tr>",
+            encode_component(&a.path),
+            escape_html_min(a.date.as_deref().unwrap_or("")),
+            numbers(&a.supersedes),
+            numbers(&a.superseded_by),
+        ));
+    }
+    out.push_str("</tbody></table>");
+    out
+}
+
+/// [`escape_html_min`] plus double quotes, for attribute values.
+fn escape_attr(s: &str) -> String {
+    escape_html_min(s).replace('"', "&quot;")
+}
+
+/// Percent-encode a query-string component (everything but RFC 3986
+/// unreserved characters and `/`).
+fn encode_component(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
 #[derive(Deserialize)]
 struct SelectRequest {
     path: String,
@@ -345,6 +990,43 @@ async fn select_handler(
     }
 }
 
+#[derive(Deserialize)]
+struct ProgressRequest {
+    /// The document the step belongs to (the analysis' `path`).
+    path: String,
+    key: String,
+    done: bool,
+}
+
+/// `POST /api/progress {path, key, done}` — mark a procedure step of a
+/// document done or not done in the progress state file. The path must be a
+/// markdown file under the discovered roots; 404 when progress tracking is
+/// disabled.
+async fn progress_handler(
+    State(state): State<AppState>,
+    Json(req): Json<ProgressRequest>,
+) -> impl IntoResponse {
+    let Some(store) = state.render.extras.progress.clone() else {
+        return StatusCode::NOT_FOUND;
+    };
+    let Some(path) = explorer::resolve_within(&state.roots, &req.path) else {
+        warn!("Rejected step progress for '{}' (outside roots)", req.path);
+        return StatusCode::FORBIDDEN;
+    };
+    let result = tokio::task::spawn_blocking(move || store.set(&path, &req.key, req.done)).await;
+    match result {
+        Ok(Ok(())) => StatusCode::NO_CONTENT,
+        Ok(Err(e)) => {
+            error!("Failed to save step progress: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        Err(e) => {
+            error!("Progress task failed: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct DiffRequest {
     a: String,
@@ -371,7 +1053,7 @@ async fn diff_handler(
     };
     match (a, b) {
         (Some(a), Some(b)) => {
-            let html = render_diff(&a, &b, opts);
+            let html = render_diff(&a, &b, opts, &state.render);
             if state.rewatch.send(WatchTarget::Pair(a, b, opts)).is_err() {
                 error!("watch loop is gone; cannot start diff");
                 return (
@@ -396,14 +1078,14 @@ async fn diff_handler(
 /// source (raw line diff) or rendered (block-level HTML diff), laid out unified
 /// (one column) or split (two columns). The file labels/header are drawn by the
 /// client (which knows the worktree/branch of each side).
-fn render_diff(a: &Path, b: &Path, opts: DiffOptions) -> String {
+fn render_diff(a: &Path, b: &Path, opts: DiffOptions, render: &RenderOptions) -> String {
     let ta = std::fs::read_to_string(a).unwrap_or_default();
     let tb = std::fs::read_to_string(b).unwrap_or_default();
     match (opts.mode, opts.layout) {
         (DiffMode::Source, DiffLayout::Unified) => source_unified(&ta, &tb),
         (DiffMode::Source, DiffLayout::Split) => source_split(&ta, &tb),
-        (DiffMode::Rendered, DiffLayout::Unified) => rendered_unified(&ta, &tb),
-        (DiffMode::Rendered, DiffLayout::Split) => rendered_split(&ta, &tb),
+        (DiffMode::Rendered, DiffLayout::Unified) => rendered_unified(&ta, &tb, render),
+        (DiffMode::Rendered, DiffLayout::Split) => rendered_split(&ta, &tb, render),
     }
 }
 
@@ -465,7 +1147,7 @@ fn source_split(ta: &str, tb: &str) -> String {
 
 /// Rendered block diff, one column: each block rendered to HTML, add/del/context
 /// highlighted.
-fn rendered_unified(ta: &str, tb: &str) -> String {
+fn rendered_unified(ta: &str, tb: &str, render: &RenderOptions) -> String {
     use similar::TextDiff;
     let a_blocks = split_blocks(ta);
     let b_blocks = split_blocks(tb);
@@ -475,7 +1157,7 @@ fn rendered_unified(ta: &str, tb: &str) -> String {
     let mut out = String::from("<div class=\"mdpeek-rdiff markdown-body\">");
     for change in diff.iter_all_changes() {
         let cls = tag_class(change.tag());
-        let html = render_markdown(change.value()).0;
+        let html = render_markdown(change.value(), render).0;
         out.push_str(&format!(
             "<div class=\"mdpeek-rdiff-block {cls}\">{html}</div>"
         ));
@@ -486,7 +1168,7 @@ fn rendered_unified(ta: &str, tb: &str) -> String {
 
 /// Rendered block diff, two columns: file A rendered on the left, file B on the
 /// right, changed blocks aligned and highlighted.
-fn rendered_split(ta: &str, tb: &str) -> String {
+fn rendered_split(ta: &str, tb: &str, render: &RenderOptions) -> String {
     use similar::TextDiff;
     let a_blocks = split_blocks(ta);
     let b_blocks = split_blocks(tb);
@@ -495,7 +1177,7 @@ fn rendered_split(ta: &str, tb: &str) -> String {
     let diff = TextDiff::from_slices(&a_refs, &b_refs);
     let changes: Vec<(similar::ChangeTag, String)> = diff
         .iter_all_changes()
-        .map(|c| (c.tag(), render_markdown(c.value()).0))
+        .map(|c| (c.tag(), render_markdown(c.value(), render).0))
         .collect();
     let rows = pair_changes(changes);
     let mut out =
@@ -658,6 +1340,73 @@ fn embedded_static_asset(path: &str) -> Option<StaticAsset> {
     }
 }
 
+/// Point user image emoji with a local path at `/emoji/{name}` (the browser
+/// can't load `file://` images from an `http://` page) and remember which file
+/// each name serves. Remote (`http(s)://`, `data:`) sources are kept as-is.
+fn browser_emoji(emoji: EmojiResolver) -> (EmojiResolver, HashMap<String, PathBuf>) {
+    let mut browser = emoji.clone();
+    let mut files = HashMap::new();
+    for (name, entry) in emoji.custom() {
+        if let Emoji::Image { src, alt } = entry
+            && !is_remote(src)
+        {
+            files.insert(name.to_string(), PathBuf::from(src));
+            browser.insert(
+                name,
+                Emoji::Image {
+                    src: format!("/emoji/{name}"),
+                    alt: alt.clone(),
+                },
+            );
+        }
+    }
+    (browser, files)
+}
+
+fn is_remote(src: &str) -> bool {
+    ["http://", "https://", "data:"]
+        .iter()
+        .any(|scheme| src.starts_with(scheme))
+}
+
+/// `GET /emoji/{name}` — the local image behind a user-defined emoji. Only
+/// files named in the `[emoji]` config are served.
+async fn emoji_handler(
+    State(state): State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> Response {
+    let Some(path) = state.emoji_files.get(&name) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, image_content_type(path))
+            .body(Body::from(bytes))
+            .expect("emoji response should be valid"),
+        Err(e) => {
+            warn!("Failed to read emoji image '{}': {e}", path.display());
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+fn image_content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
 async fn static_handler(AxumPath(path): AxumPath<String>) -> Response {
     match embedded_static_asset(&path) {
         Some(asset) => Response::builder()
@@ -769,7 +1518,10 @@ mod tests {
     #[test]
     fn render_markdown_emits_body_and_frontmatter() {
         use super::render_markdown;
-        let (body, fm) = render_markdown("---\ntitle: Hi\n---\n\n# Heading\n\ntext\n");
+        let (body, fm) = render_markdown(
+            "---\ntitle: Hi\n---\n\n# Heading\n\ntext\n",
+            &Default::default(),
+        );
         assert!(body.contains("<h1"), "body should contain rendered heading");
         assert_eq!(fm.as_deref(), Some("title: Hi"));
     }
@@ -777,11 +1529,377 @@ mod tests {
     #[test]
     fn render_markdown_without_frontmatter_is_none() {
         use super::render_markdown;
-        let (body, fm) = render_markdown("# Only heading\n");
+        let (body, fm) = render_markdown("# Only heading\n", &Default::default());
         assert!(body.contains("Only heading"));
         assert!(fm.is_none());
     }
 
+    #[test]
+    fn render_markdown_applies_enabled_extensions() {
+        use super::render_markdown;
+        let render = super::RenderOptions {
+            markdown: super::MarkdownOptions {
+                mark: true,
+                heading_attributes: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (body, _) = render_markdown("# Top {#top}\n\n==hi==\n", &render);
+        assert!(body.contains("id=\"top\""), "custom heading id: {body}");
+        assert!(body.contains("<mark>hi</mark>"), "mark tag: {body}");
+        let (plain, _) = render_markdown("==hi==\n", &Default::default());
+        assert!(plain.contains("==hi=="), "extensions are opt-in: {plain}");
+    }
+
+    #[test]
+    fn render_markdown_applies_front_matter_typography() {
+        use super::render_markdown;
+        let src = "---\nlang: ja\nmarkdown:\n  smart_punctuation: true\n---\n\n\"引用\"です... `\"code\"`\n";
+        let (body, _) = render_markdown(src, &Default::default());
+        assert!(body.contains("「引用」です…"), "localized quotes: {body}");
+        assert!(
+            body.contains("<code>\"code\"</code>"),
+            "code untouched: {body}"
+        );
+    }
+
+    #[test]
+    fn analysis_serialises_the_semantic_panel() {
+        let src = "# Plan\n\n## Risks\n\nIt may overheat.\n\n- [ ] add tests\n";
+        let analysis = super::analyze_markdown(
+            src,
+            std::path::Path::new("notes/plan.md"),
+            &Default::default(),
+        );
+        let json = serde_json::to_value(&analysis).unwrap();
+        let panel = &json["panel"];
+        assert_eq!(panel["outline"].as_array().unwrap().len(), 2);
+        assert_eq!(panel["todos"][0]["text"], "add tests");
+        assert_eq!(panel["outline"][0]["link"]["range"]["start_line"], 1);
+        assert_eq!(json["stats"]["headings"], 2);
+        assert_eq!(json["stats"]["sections"][1]["title"], "Risks");
+        // Entries resolve to a block in the serialised tree by id.
+        let id = &panel["todos"][0]["link"]["block_id"];
+        let blocks = json["tree"]["blocks"].to_string();
+        assert!(blocks.contains(&format!("\"id\":{id}")), "{blocks}");
+    }
+
+    #[test]
+    fn analysis_is_refined_by_the_served_generator() {
+        use mdpeek_analyzer::model::{Classified, DocumentModel, DocumentType};
+        use mdpeek_analyzer::{BlockTree, Generator};
+
+        struct Stub;
+        impl Generator for Stub {
+            fn name(&self) -> &'static str {
+                "stub"
+            }
+            fn refine(&self, _source: &str, _tree: &BlockTree, model: &mut DocumentModel) {
+                model.doc_type = Classified::llm(DocumentType::Faq, 0.9);
+            }
+        }
+
+        let render = super::RenderOptions {
+            generator: std::sync::Arc::new(Stub),
+            ..Default::default()
+        };
+        let analysis = super::analyze_markdown(
+            "# Notes\n\nSome text.\n",
+            std::path::Path::new("n.md"),
+            &render,
+        );
+        let json = serde_json::to_value(&analysis).unwrap();
+        assert_eq!(json["model"]["doc_type"]["value"], "faq");
+        assert_eq!(json["model"]["doc_type"]["by"], "llm");
+    }
+
+    #[test]
+    fn live_update_sends_html_before_the_analysis() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.md");
+        std::fs::write(&path, "# Doc\n\n- [ ] ship\n").unwrap();
+        let (tx, mut rx) = tokio::sync::broadcast::channel(4);
+        super::broadcast_update(&path, &tx, &Default::default());
+        let message = |rx: &mut tokio::sync::broadcast::Receiver<axum::extract::ws::Message>| {
+            let text = rx.blocking_recv().unwrap().into_text().unwrap();
+            serde_json::from_str::<serde_json::Value>(&text).unwrap()
+        };
+        let update = message(&mut rx);
+        assert_eq!(update["type"], "update");
+        assert!(update["html"].as_str().unwrap().contains("<h1"));
+        assert!(update.get("analysis").is_none());
+        let analysis = message(&mut rx);
+        assert_eq!(analysis["type"], "analysis");
+        assert_eq!(analysis["analysis"]["panel"]["todos"][0]["text"], "ship");
+        // Step progress is saved against the document the analysis names.
+        assert_eq!(analysis["analysis"]["path"], path.to_str().unwrap());
+    }
+
+    #[test]
+    fn analysis_applies_issue_export_and_linter() {
+        use mdpeek_analyzer::issues::IssueList;
+        use mdpeek_analyzer::lint::{LintConfig, Linter};
+        let issues =
+            IssueList::parse(r#"[{"number": 4, "title": "Flaky", "state": "closed"}]"#).unwrap();
+        let render = super::RenderOptions {
+            extras: std::sync::Arc::new(super::AnalysisExtras {
+                issues: Some(issues),
+                linter: Some(Linter::new(&LintConfig::default()).unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let analysis = super::analyze_markdown(
+            "# Plan\n\nTODO(#4): retry the the job\n",
+            std::path::Path::new("plan.md"),
+            &render,
+        );
+        let json = serde_json::to_value(&analysis).unwrap();
+        let issue = &json["panel"]["todos"][0]["issue"];
+        assert_eq!(issue["number"], 4);
+        assert_eq!(issue["state"], "closed");
+        assert_eq!(issue["title"], "Flaky");
+        let diagnostic = &json["diagnostics"][0];
+        assert_eq!(diagnostic["rule"], "repeated-word");
+        assert_eq!(diagnostic["severity"], "warning");
+        assert_eq!(diagnostic["fix"], "the");
+        assert_eq!(diagnostic["range"]["start_line"], 3);
+    }
+
+    #[test]
+    fn analysis_carries_novel_chapters_and_roster() {
+        let analysis = super::analyze_markdown(
+            "---\ntype: novel\ncharacters: [Mole]\n---\n# Chapter 1\n\nMole woke.\n",
+            std::path::Path::new("book.md"),
+            &super::RenderOptions::default(),
+        );
+        let json = serde_json::to_value(&analysis).unwrap();
+        let novel = &json["novel"];
+        assert_eq!(novel["chapters"][0]["number"], 1);
+        assert_eq!(novel["chapters"][0]["link"]["range"]["start_line"], 5);
+        assert_eq!(novel["characters"][0]["name"], "Mole");
+        assert_eq!(novel["characters"][0]["first_chapter"], 1);
+    }
+
+    #[test]
+    fn analysis_carries_contract_obligations() {
+        let analysis = super::analyze_markdown(
+            "---\ntype: contract\n---\n# Terms\n\n## 2. Fees\n\nThe Customer shall pay monthly.\n",
+            std::path::Path::new("terms.md"),
+            &super::RenderOptions::default(),
+        );
+        let json = serde_json::to_value(&analysis).unwrap();
+        let contract = &json["contract"];
+        assert_eq!(contract["parties"][0]["name"], "Customer");
+        assert_eq!(contract["obligations"][0]["action"], "pay monthly");
+        assert_eq!(contract["obligations"][0]["clause"], "2");
+        assert_eq!(contract["obligations"][0]["link"]["range"]["start_line"], 8);
+    }
+
+    #[test]
+    fn analysis_carries_procedure_steps_with_progress() {
+        use mdpeek_analyzer::progress::ProgressStore;
+        let dir = tempfile::tempdir().unwrap();
+        let doc = dir.path().join("deploy.md");
+        let store = ProgressStore::new(dir.path().join("progress.json"));
+        store.set(&doc, "Build", true).unwrap();
+        let render = super::RenderOptions {
+            extras: std::sync::Arc::new(super::AnalysisExtras {
+                progress: Some(store),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let analysis = super::analyze_markdown(
+            "---\ntype: runbook\n---\n# Deploy\n\n## Steps\n\n1. Build\n\n   ```sh\n   make\n   ```\n\n2. Ship\n",
+            &doc,
+            &render,
+        );
+        let json = serde_json::to_value(&analysis).unwrap();
+        let steps = &json["procedure"]["steps"];
+        assert_eq!(steps[0]["key"], "Build");
+        assert_eq!(steps[0]["commands"][0], "make");
+        assert_eq!(steps[0]["done"], true);
+        assert_eq!(steps[1]["done"], false);
+    }
+
+    #[test]
+    fn recipe_scaling_rewrites_measures() {
+        let analysis = super::analyze_markdown(
+            "# Pancakes\n\nServes 2.\n\n## Ingredients\n\n- 1 cup flour\n- Salt to taste\n\n\
+             ## Instructions\n\n1. Mix\n",
+            std::path::Path::new("pancakes.md"),
+            &super::RenderOptions::default(),
+        );
+        let recipe = analysis.recipe.expect("recipe");
+        let query: super::RecipeQuery =
+            serde_json::from_str(r#"{"servings": 4, "units": "us"}"#).unwrap();
+        let json = super::scaled_recipe(&recipe, &query);
+        assert_eq!(json["factor"], 2.0);
+        let flour = &json["ingredients"][0];
+        assert_eq!(flour["measure"], "1 cup");
+        assert_eq!(flour["scaled"], "2 cups");
+        assert_eq!(flour["text"], "2 cups flour");
+        assert_eq!(flour["link"]["range"]["start_line"], 7);
+        assert!(json["ingredients"][1]["scaled"].is_null());
+    }
+
+    #[tokio::test]
+    async fn active_tree_applies_front_matter_overrides() {
+        use std::sync::{Arc, RwLock};
+        // Footnotes are on by default, putting a first request inside the
+        // footnote; turned off, its indented lines are a plain code block.
+        let content = "---\nmarkdown:\n  footnotes: false\n---\n\n# API\n\n\
+                       [^1]: Health check\n\n    ```http\n    GET /health\n    ```\n\n\
+                       ```http\nGET /users\n```\n";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.md");
+        std::fs::write(&path, content).unwrap();
+        let (tx, _) = tokio::sync::broadcast::channel(1);
+        let (rewatch, _) = std::sync::mpsc::channel();
+        let state = super::AppState {
+            tx,
+            file_path: Arc::new(RwLock::new(path.clone())),
+            theme: Arc::new(RwLock::new(super::Theme::Light)),
+            render: super::RenderOptions::default(),
+            emoji_files: Arc::default(),
+            roots: Arc::new(vec![dir.path().to_path_buf()]),
+            scan_root: Arc::new(dir.path().to_path_buf()),
+            rewatch,
+            api_explorer: None,
+        };
+        let urls = |exchanges: Vec<super::http::HttpExchange>| -> Vec<String> {
+            exchanges
+                .into_iter()
+                .filter_map(|e| e.request.map(|r| r.url))
+                .collect()
+        };
+        let (_, tree) = super::active_tree(&state).await.expect("readable file");
+        let analysis = super::analyze_markdown(content, &path, &state.render);
+        assert_eq!(urls(super::http::extract(&tree)), ["/users"]);
+        assert_eq!(urls(analysis.http), ["/users"]);
+    }
+
+    #[test]
+    fn encode_component_escapes_query_syntax() {
+        assert_eq!(
+            super::encode_component("/repo/a b&c.md"),
+            "/repo/a%20b%26c.md"
+        );
+        assert_eq!(super::encode_component("日"), "%E6%97%A5");
+    }
+
+    #[test]
+    fn todos_dashboard_links_entries_and_applies_filters() {
+        use super::todos::RepoTodo;
+        let todo = |rel: &str, marker: &str, done: bool| RepoTodo {
+            worktree: "repo".into(),
+            branch: Some("main".into()),
+            path: format!("/repo/{rel}"),
+            rel: rel.into(),
+            line: 7,
+            marker: marker.into(),
+            done,
+            text: "<b>fix</b>".into(),
+            issue: None,
+        };
+        let all = vec![
+            todo("docs/a.md", "task", false),
+            todo("docs/a.md", "task", true),
+            todo("src/lib.rs", "fixme", false),
+        ];
+        let query = super::TodosQuery {
+            state: Some("open".into()),
+            ..Default::default()
+        };
+        let html = super::todos_dashboard(&all, &query);
+        assert!(html.contains("2 of 3 entries"), "{html}");
+        assert!(
+            html.contains("href=\"/?open=/repo/docs/a.md&line=7\""),
+            "{html}"
+        );
+        assert!(
+            html.contains("href=\"/todos/source?path=/repo/src/lib.rs&line=7#L7\""),
+            "{html}"
+        );
+        assert!(html.contains("&lt;b&gt;fix&lt;/b&gt;"), "text is escaped");
+        assert!(html.contains("<option value=\"open\" selected>"), "{html}");
+    }
+
+    #[test]
+    fn adrs_index_lists_records_and_diagnostics() {
+        use super::adrs::{AdrDiagnostic, AdrIndex, AdrIssue, RepoAdr};
+        use mdpeek_analyzer::analyzer::adr::AdrStatus;
+        let adr = |n: u32, status, supersedes: Vec<u32>, superseded_by: Vec<u32>| RepoAdr {
+            worktree: "repo".into(),
+            branch: Some("main".into()),
+            path: format!("/repo/adr/{n:04}.md"),
+            rel: format!("adr/{n:04}.md"),
+            number: Some(n),
+            title: format!("Decision <{n}>"),
+            status: Some(status),
+            date: Some("2024-01-01".into()),
+            supersedes,
+            superseded_by,
+            commit: None,
+        };
+        let index = AdrIndex {
+            adrs: vec![
+                adr(1, AdrStatus::Superseded, vec![], vec![2]),
+                adr(2, AdrStatus::Accepted, vec![1], vec![]),
+            ],
+            diagnostics: vec![AdrDiagnostic {
+                worktree: "repo".into(),
+                rel: "adr/0002.md".into(),
+                line: 9,
+                issue: AdrIssue::Dangling,
+                message: "supersedes ADR 7, which does not exist".into(),
+            }],
+        };
+        let html = super::adrs_index(&index);
+        assert!(html.contains("2 records"), "{html}");
+        assert!(html.contains("<tr id=\"adr-1\">"), "{html}");
+        assert!(html.contains("Decision &lt;2&gt;"), "title is escaped");
+        assert!(html.contains("<a href=\"#adr-2\">2</a>"), "{html}");
+        assert!(
+            html.contains("<a href=\"/?open=/repo/adr/0002.md&line=9\">adr/0002.md:9</a>"),
+            "{html}"
+        );
+        assert!(html.contains("mdpeek-adr-superseded"), "{html}");
+    }
+
+    #[test]
+    fn local_image_emoji_are_served_by_name() {
+        use mdpeek_gfm::emoji::Emoji;
+        let mut emoji = super::EmojiResolver::new();
+        emoji.insert(
+            "logo",
+            Emoji::Image {
+                src: "assets/logo.png".into(),
+                alt: None,
+            },
+        );
+        emoji.insert(
+            "remote",
+            Emoji::Image {
+                src: "https://example.com/r.png".into(),
+                alt: None,
+            },
+        );
+        let (browser, files) = super::browser_emoji(emoji);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files["logo"], std::path::PathBuf::from("assets/logo.png"));
+        let render = super::RenderOptions {
+            emoji: std::sync::Arc::new(browser),
+            ..Default::default()
+        };
+        let (body, _) = super::render_markdown(":logo: :remote: :shipit:\n", &render);
+        assert!(body.contains("src=\"/emoji/logo\""), "{body}");
+        assert!(body.contains("src=\"https://example.com/r.png\""), "{body}");
+        assert!(body.contains("/shipit.png"), "{body}");
+    }
+
     #[test]
     fn render_diff_marks_added_and_removed_lines() {
         use super::render_diff;
@@ -792,7 +1910,7 @@ mod tests {
         std::fs::write(&a, "line one\nshared\n").unwrap();
         std::fs::write(&b, "line ONE\nshared\n").unwrap();
 
-        let html = render_diff(&a, &b, super::DiffOptions::default());
+        let html = render_diff(&a, &b, super::DiffOptions::default(), &Default::default());
         assert!(
             html.contains("mdpeek-diff-del"),
             "should mark the removed line"
@@ -813,6 +1931,7 @@ mod tests {
                 mode: super::DiffMode::Rendered,
                 layout: super::DiffLayout::Split,
             },
+            &Default::default(),
         );
         assert!(rendered.contains("mdpeek-diff-split"));
         assert!(rendered.contains("mdpeek-diff-del") && rendered.contains("mdpeek-diff-add"));
@@ -826,6 +1945,7 @@ mod tests {
                 mode: super::DiffMode::Source,
                 layout: super::DiffLayout::Split,
             },
+            &Default::default(),
         );
         assert!(
             split.contains("mdpeek-diff-ctx"),
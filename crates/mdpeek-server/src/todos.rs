@@ -0,0 +1,448 @@
+//! Repository-wide TODO/FIXME dashboard.
+//!
+//! Aggregates the per-file [`mdpeek_analyzer::panel`] TODOs (task items and
+//! inline `TODO`/`FIXME`/`XXX`/`HACK` markers) over every markdown file the
+//! explorer discovers, across all worktrees, and — optionally — the same
+//! markers in source-code comments. Backs `mdpeek todos` and the browser's
+//! `/todos` page; every entry carries its file and 1-based line.
+
+use crate::explorer;
+use mdpeek_analyzer::RulesGenerator;
+use mdpeek_analyzer::panel::{InlineMarker, inline_markers};
+use mdpeek_analyzer::rules::ClassificationRules;
+use mdpeek_gfm::MarkdownOptions;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Source files whose comments are scanned when code markers are requested.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "mjs", "ts", "tsx", "jsx", "go", "c", "h", "cc", "cpp", "hpp", "java", "kt",
+    "swift", "rb", "sh", "bash", "zsh", "lua", "sql", "hs", "toml", "yaml", "yml",
+];
+
+/// Build / dependency directories never worth scanning for code markers.
+const SKIP_DIRS: &[&str] = &[".git", "target", "node_modules", "vendor", "dist", "build"];
+
+/// One TODO somewhere in the repository.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepoTodo {
+    /// Worktree (explorer group) name.
+    pub worktree: String,
+    /// Checked-out branch of that worktree, when known.
+    pub branch: Option<String>,
+    /// Absolute path, as served by the explorer.
+    pub path: String,
+    /// Path relative to the worktree root.
+    pub rel: String,
+    /// 1-based line the entry starts on.
+    pub line: u32,
+    /// `"task"`, `"todo"`, `"fixme"`, `"xxx"` or `"hack"`.
+    pub marker: String,
+    pub done: bool,
+    pub text: String,
+    /// The issue a `TODO(#123)` marker references.
+    pub issue: Option<u64>,
+}
+
+/// Narrows a TODO list. Every set field must match; unset fields match all.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TodoFilter {
+    /// Marker kind, case-insensitive (`task`, `todo`, `fixme`, …).
+    pub marker: Option<String>,
+    /// Done (`true`) or open (`false`) entries only.
+    pub done: Option<bool>,
+    /// Substring of the worktree-relative path.
+    pub file: Option<String>,
+    /// Worktree name or branch.
+    pub worktree: Option<String>,
+}
+
+impl TodoFilter {
+    pub fn matches(&self, todo: &RepoTodo) -> bool {
+        self.marker
+            .as_ref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(&todo.marker))
+            && self.done.is_none_or(|d| d == todo.done)
+            && self
+                .file
+                .as_ref()
+                .is_none_or(|f| todo.rel.contains(f.as_str()))
+            && self
+                .worktree
+                .as_ref()
+                .is_none_or(|w| *w == todo.worktree || todo.branch.as_deref() == Some(w.as_str()))
+    }
+}
+
+/// Every TODO under the repository (or directory) discovered from `start`, in
+/// worktree / path / line order. Markdown is analysed with `rules`, so the
+/// configured `[analyzer]` language picks the TODO marker words. With `code`,
+/// source-code comments are scanned too.
+pub fn collect(
+    start: &Path,
+    markdown: &MarkdownOptions,
+    rules: &ClassificationRules,
+    code: bool,
+) -> Vec<RepoTodo> {
+    let tree = explorer::build_tree(start);
+    let roots: Vec<PathBuf> = tree.groups.iter().map(|g| PathBuf::from(&g.root)).collect();
+    let mut out = Vec::new();
+    for group in &tree.groups {
+        let entry = |path: String, rel: String, line: u32, marker, done, text, issue| RepoTodo {
+            worktree: group.name.clone(),
+            branch: group.branch.clone(),
+            path,
+            rel,
+            line,
+            marker,
+            done,
+            text,
+            issue,
+        };
+        for file in &group.files {
+            let Ok(content) = std::fs::read_to_string(&file.path) else {
+                continue;
+            };
+            let analysis = mdpeek_analyzer::analyze_with_rules(
+                &content,
+                Some(&file.rel),
+                markdown,
+                rules,
+                &RulesGenerator,
+            );
+            for todo in analysis.panel.todos {
+                out.push(entry(
+                    file.path.clone(),
+                    file.rel.clone(),
+                    todo.link.range.start_line,
+                    todo.marker,
+                    todo.done,
+                    todo.text,
+                    todo.issue.map(|i| i.number),
+                ));
+            }
+        }
+        if code {
+            let root = PathBuf::from(&group.root);
+            let exclude: Vec<&PathBuf> = roots.iter().filter(|r| **r != root).collect();
+            for path in scan_sources(&root, &exclude) {
+                let Ok(content) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let rel = path
+                    .strip_prefix(&root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let ext = path.extension().and_then(|x| x.to_str()).unwrap_or("");
+                for (line, found) in comment_markers(&content, ext) {
+                    out.push(entry(
+                        path.to_string_lossy().to_string(),
+                        rel.clone(),
+                        line,
+                        found.marker,
+                        false,
+                        found.note,
+                        found.issue,
+                    ));
+                }
+            }
+        }
+    }
+    out.sort_by(|a, b| (&a.worktree, &a.rel, a.line).cmp(&(&b.worktree, &b.rel, b.line)));
+    out
+}
+
+/// Source files under `root`, skipping build/dependency directories and other
+/// worktree roots nested inside it.
+fn scan_sources(root: &Path, exclude: &[&PathBuf]) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            let skipped = e.file_name().to_str().is_some_and(skipped_dir);
+            !(e.file_type().is_dir() && (skipped || exclude.iter().any(|x| *x == e.path())))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && source_ext(e.path()))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Whether `path` (canonical) is a file [`scan_sources`] would pick up under
+/// one of `roots`: a source extension, outside build/dependency directories.
+/// Only such files may be shown by the `/todos/source` view.
+pub(crate) fn is_scanned_source(roots: &[PathBuf], path: &Path) -> bool {
+    source_ext(path)
+        && roots.iter().any(|root| {
+            path.strip_prefix(root).is_ok_and(|rel| {
+                !rel.components()
+                    .any(|c| c.as_os_str().to_str().is_some_and(skipped_dir))
+            })
+        })
+}
+
+fn source_ext(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|x| SOURCE_EXTENSIONS.contains(&x))
+}
+
+fn skipped_dir(name: &str) -> bool {
+    SKIP_DIRS.contains(&name)
+}
+
+/// How comments and string literals are written in a source file.
+struct Syntax {
+    /// Comment openers; the first one outside a string literal starts the
+    /// comment.
+    openers: &'static [&'static str],
+    /// Characters that open and close a string literal.
+    quotes: &'static [char],
+}
+
+/// The comment syntax of a source file with extension `ext`.
+fn syntax(ext: &str) -> Syntax {
+    match ext {
+        "py" | "rb" | "sh" | "bash" | "zsh" | "toml" | "yaml" | "yml" => Syntax {
+            openers: &["#"],
+            quotes: &['"', '\''],
+        },
+        "sql" | "lua" => Syntax {
+            openers: &["--"],
+            quotes: &['"', '\''],
+        },
+        // `'` is part of identifiers (`x'`) in Haskell and of lifetimes
+        // (`'a`) in Rust, not a string delimiter.
+        "hs" => Syntax {
+            openers: &["--"],
+            quotes: &['"'],
+        },
+        "rs" => Syntax {
+            openers: &["//", "/*"],
+            quotes: &['"'],
+        },
+        "js" | "mjs" | "ts" | "tsx" | "jsx" => Syntax {
+            openers: &["//", "/*"],
+            quotes: &['"', '\'', '`'],
+        },
+        _ => Syntax {
+            openers: &["//", "/*"],
+            quotes: &['"', '\''],
+        },
+    }
+}
+
+/// The text after the first comment opener of `line` that is not inside a
+/// string literal.
+fn comment_text<'a>(line: &'a str, syntax: &Syntax) -> Option<&'a str> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (at, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if syntax.quotes.contains(&c) => quote = Some(c),
+            None => {
+                if let Some(opener) = syntax.openers.iter().find(|o| line[at..].starts_with(**o)) {
+                    return Some(&line[at + opener.len()..]);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Markers inside the comments of a source file with extension `ext`, with
+/// their 1-based line: line comments (`#` for shell, Python, Ruby, TOML and
+/// YAML; `--` for SQL, Lua and Haskell; `//` otherwise) and, in C-like
+/// languages, block comments (`/*`, a leading `*`). Openers inside string
+/// literals are skipped, so a `TODO` in a string is not picked up.
+fn comment_markers(content: &str, ext: &str) -> Vec<(u32, InlineMarker)> {
+    let syntax = syntax(ext);
+    let block_comments = syntax.openers.contains(&"/*");
+    let mut out = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let comment = match line.trim_start().strip_prefix('*') {
+            Some(rest) if block_comments => Some(rest),
+            _ => comment_text(line, &syntax),
+        };
+        let Some(comment) = comment else {
+            continue;
+        };
+        for found in inline_markers(comment) {
+            out.push((i as u32 + 1, found));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(marker: &str, done: bool, rel: &str) -> RepoTodo {
+        RepoTodo {
+            worktree: "repo".into(),
+            branch: Some("main".into()),
+            path: format!("/repo/{rel}"),
+            rel: rel.into(),
+            line: 1,
+            marker: marker.into(),
+            done,
+            text: "x".into(),
+            issue: None,
+        }
+    }
+
+    #[test]
+    fn filter_matches_every_set_field() {
+        let t = todo("fixme", false, "docs/guide.md");
+        assert!(TodoFilter::default().matches(&t));
+        let by_marker = TodoFilter {
+            marker: Some("FIXME".into()),
+            ..Default::default()
+        };
+        assert!(by_marker.matches(&t));
+        let done_only = TodoFilter {
+            done: Some(true),
+            ..Default::default()
+        };
+        assert!(!done_only.matches(&t));
+        let by_file_and_branch = TodoFilter {
+            file: Some("docs/".into()),
+            worktree: Some("main".into()),
+            ..Default::default()
+        };
+        assert!(by_file_and_branch.matches(&t));
+        let other_worktree = TodoFilter {
+            worktree: Some("feature".into()),
+            ..Default::default()
+        };
+        assert!(!other_worktree.matches(&t));
+    }
+
+    fn markers(src: &str, ext: &str) -> Vec<(u32, String, String)> {
+        comment_markers(src, ext)
+            .into_iter()
+            .map(|(line, m)| (line, m.marker, m.note))
+            .collect()
+    }
+
+    #[test]
+    fn comment_markers_only_read_comments() {
+        let src = "fn main() {\n    let s = \"TODO: not me\";\n    // TODO: wire it up\n    x(); # FIXME\n    let url = \"http://x\"; // XXX: pin\n}\n/*\n * HACK: temporary\n */\n";
+        assert_eq!(
+            markers(src, "rs"),
+            vec![
+                (3, "todo".to_string(), "wire it up".to_string()),
+                (5, "xxx".to_string(), "pin".to_string()),
+                (8, "hack".to_string(), "temporary".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn comment_openers_follow_the_file_type() {
+        let py = "x = 1  # TODO: tidy\ns = \"# FIXME: not me\"\n// HACK: not a comment\n";
+        assert_eq!(
+            markers(py, "py"),
+            vec![(1, "todo".to_string(), "tidy".to_string())]
+        );
+        let sql = "SELECT '--TODO: not me' -- FIXME: index\n/* HACK: not here */\n";
+        assert_eq!(
+            markers(sql, "sql"),
+            vec![(1, "fixme".to_string(), "index".to_string())]
+        );
+    }
+
+    #[test]
+    fn only_scanned_sources_may_be_viewed() {
+        let roots = [PathBuf::from("/repo")];
+        assert!(is_scanned_source(&roots, Path::new("/repo/src/main.rs")));
+        assert!(is_scanned_source(&roots, Path::new("/repo/config.toml")));
+        assert!(!is_scanned_source(&roots, Path::new("/repo/.env")));
+        assert!(!is_scanned_source(&roots, Path::new("/repo/.git/config")));
+        assert!(!is_scanned_source(
+            &roots,
+            Path::new("/repo/.git/hooks/pre-commit.sh")
+        ));
+        assert!(!is_scanned_source(&roots, Path::new("/repo/target/gen.rs")));
+        assert!(!is_scanned_source(&roots, Path::new("/repo/id_ed25519")));
+        assert!(!is_scanned_source(&roots, Path::new("/elsewhere/main.rs")));
+    }
+
+    #[test]
+    fn collect_walks_markdown_and_optionally_code() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("README.md"),
+            "# Readme\n\n- [ ] write docs\n- [x] ship\n\nTODO: benchmark\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "// FIXME: handle errors\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/gen.rs"), "// TODO: generated\n").unwrap();
+
+        let md_only = collect(
+            dir.path(),
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+            false,
+        );
+        let summary: Vec<(&str, u32, &str, bool)> = md_only
+            .iter()
+            .map(|t| (t.rel.as_str(), t.line, t.marker.as_str(), t.done))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("README.md", 3, "task", false),
+                ("README.md", 4, "task", true),
+                ("README.md", 6, "todo", false),
+            ]
+        );
+
+        let with_code = collect(
+            dir.path(),
+            &MarkdownOptions::default(),
+            &ClassificationRules::default(),
+            true,
+        );
+        assert_eq!(with_code.len(), 4);
+        let fixme = with_code.iter().find(|t| t.marker == "fixme").unwrap();
+        assert_eq!((fixme.rel.as_str(), fixme.line), ("src/lib.rs", 1));
+        assert_eq!(fixme.text, "handle errors");
+    }
+
+    #[test]
+    fn configured_language_picks_the_marker_words() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("plan.md"),
+            "# Plan\n\nOFFEN: check the release plan\n",
+        )
+        .unwrap();
+        let markdown = MarkdownOptions::default();
+
+        let found = collect(
+            dir.path(),
+            &markdown,
+            &ClassificationRules::default(),
+            false,
+        );
+        assert!(found.is_empty());
+
+        let german = ClassificationRules {
+            lang: Some("de".into()),
+            ..Default::default()
+        };
+        let found = collect(dir.path(), &markdown, &german, false);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].line, found[0].marker.as_str()), (3, "todo"));
+        assert_eq!(found[0].text, "check the release plan");
+    }
+}
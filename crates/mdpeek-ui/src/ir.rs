@@ -0,0 +1,145 @@
+//! UI IR — the generated-UI component tree (AGENTS.md §4.3).
+//!
+//! A [`Page`] is what the browser / TUI render next to (not instead of) the
+//! markdown. Every component, and every row inside one, carries a
+//! [`SourceRangeLink`] back to the markdown it was generated from, so the UI can
+//! always jump to the source (design思想「全 UI は sourceRange に紐づく」).
+//!
+//! The IR is plain data serialised with an internal `"type"` tag, e.g.
+//! `{"type": "checklist_panel", "title": "TODO", "items": [...], "link": {...}}`.
+
+use mdpeek_analyzer::model::DocumentType;
+use mdpeek_analyzer::panel::SourceRangeLink;
+use serde::Serialize;
+
+/// The generated UI for one document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Page {
+    pub doc_type: DocumentType,
+    /// The document's first top-level heading, if any.
+    pub title: Option<String>,
+    /// Components in display order (most relevant for the document type first).
+    pub components: Vec<Component>,
+}
+
+/// One generated UI component.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Component {
+    StepNavigator(StepNavigator),
+    ApiExplorer(ApiExplorer),
+    ConfigViewer(ConfigViewer),
+    DecisionCard(DecisionCard),
+    StatusBoard(StatusBoard),
+    ChecklistPanel(ChecklistPanel),
+}
+
+impl Component {
+    /// The markdown span the whole component was generated from.
+    pub fn link(&self) -> &SourceRangeLink {
+        match self {
+            Component::StepNavigator(c) => &c.link,
+            Component::ApiExplorer(c) => &c.link,
+            Component::ConfigViewer(c) => &c.link,
+            Component::DecisionCard(c) => &c.link,
+            Component::StatusBoard(c) => &c.link,
+            Component::ChecklistPanel(c) => &c.link,
+        }
+    }
+}
+
+/// An ordered procedure shown one step at a time.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepNavigator {
+    /// Heading of the section holding the steps.
+    pub title: Option<String>,
+    pub steps: Vec<Step>,
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Step {
+    pub text: String,
+    pub link: SourceRangeLink,
+}
+
+/// An HTTP request snippet that can be inspected (and, later, sent).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiExplorer {
+    /// Request method (`GET`, `POST`, …) when the snippet has a request line.
+    pub method: Option<String>,
+    /// Request target (path or URL) of that request line.
+    pub target: Option<String>,
+    /// The snippet as written.
+    pub request: String,
+    pub link: SourceRangeLink,
+}
+
+/// A JSON / YAML / TOML block shown as structured configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigViewer {
+    pub format: ConfigFormat,
+    /// Heading of the enclosing section.
+    pub title: Option<String>,
+    pub content: String,
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+/// A recorded decision (ADR, design doc, minutes).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionCard {
+    pub title: String,
+    /// ADR status (`Accepted`, `Proposed`, …) when the document states one.
+    pub status: Option<String>,
+    pub decision: String,
+    pub consequences: Vec<String>,
+    pub link: SourceRangeLink,
+}
+
+/// A table with a status column, laid out as one lane per status value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusBoard {
+    pub title: Option<String>,
+    /// Header of the status column.
+    pub status_column: String,
+    pub lanes: Vec<StatusLane>,
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusLane {
+    pub status: String,
+    pub cards: Vec<StatusCard>,
+}
+
+/// One table row on a [`StatusBoard`], labelled by its first non-status cell.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusCard {
+    pub label: String,
+    pub link: SourceRangeLink,
+}
+
+/// A task list with progress.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChecklistPanel {
+    pub title: Option<String>,
+    pub items: Vec<ChecklistItem>,
+    pub done: usize,
+    pub total: usize,
+    pub link: SourceRangeLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub done: bool,
+    pub link: SourceRangeLink,
+}
@@ -0,0 +1,18 @@
+//! Layer 3 — Generated UI.
+//!
+//! Turns a Layer 2 [`mdpeek_analyzer::Analysis`] into a [`Page`]: a serialisable
+//! tree of UI components chosen for the document type.
+//!
+//! ```text
+//! Analysis ─▶ planner(rules) ─▶ Page { components } ─▶ browser / TUI
+//! ```
+//!
+//! The [`ir`] module defines the component types; the [`planner`] builds them
+//! from rules signals (block classes, code-block intents, table shapes). Each
+//! component carries a `SourceRangeLink` back to the markdown.
+
+pub mod ir;
+pub mod planner;
+
+pub use ir::{Component, Page};
+pub use planner::plan;
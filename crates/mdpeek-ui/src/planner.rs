@@ -0,0 +1,525 @@
+//! Rules planner: [`Analysis`] → [`Page`] (AGENTS.md §3.3).
+//!
+//! Each component comes from one rules signal:
+//!
+//! | Component        | Source                                                        |
+//! |------------------|---------------------------------------------------------------|
+//! | `StepNavigator`  | a list in a `Step` section, or any ordered list in a runbook / procedure / recipe |
+//! | `ApiExplorer`    | a code block with [`CodeIntent::Http`] and its parsed request |
+//! | `ConfigViewer`   | a code block with [`CodeIntent::Json`] / `Yaml` / `Toml`      |
+//! | `DecisionCard`   | a `Decision` section (plus `Status` / `Consequence` sections)  |
+//! | `StatusBoard`    | a table whose [`TableInfo`] has a status column               |
+//! | `ChecklistPanel` | a task list                                                   |
+//!
+//! The document type then decides the order: a runbook leads with its steps,
+//! an ADR with its decision, a README with its examples.
+
+use crate::ir::*;
+use mdpeek_analyzer::Analysis;
+use mdpeek_analyzer::analyzer::code::{self, CodeIntent};
+use mdpeek_analyzer::analyzer::http::HttpRequest;
+use mdpeek_analyzer::analyzer::table::{self, TableInfo};
+use mdpeek_analyzer::locale::Locale;
+use mdpeek_analyzer::model::{BlockClass, DocumentType};
+use mdpeek_analyzer::panel::SourceRangeLink;
+use mdpeek_parser::{Block, BlockId, BlockKind, BlockTree, SourceRange};
+use std::collections::HashMap;
+
+/// Plan the generated UI for an analysed document. `source` is the markdown
+/// the analysis was built from.
+pub fn plan(source: &str, analysis: &Analysis) -> Page {
+    let tree = &analysis.tree;
+    let doc_type = analysis.model.doc_type.value;
+    let ctx = Context {
+        tree,
+        doc_type,
+        classes: analysis
+            .model
+            .blocks
+            .iter()
+            .map(|b| (b.block_id, b.class))
+            .collect(),
+        sections: sections(tree),
+        locale: analysis.model.locale,
+        requests: analysis
+            .http
+            .iter()
+            .filter_map(|e| Some((e.link.block_id, e.request.as_ref()?)))
+            .collect(),
+    };
+
+    let mut components = Vec::new();
+    step_navigators(&ctx, &mut components);
+    code_components(&ctx, &mut components);
+    decision_cards(&ctx, &mut components);
+    status_boards(source, &ctx, &mut components);
+    checklists(&ctx, &mut components);
+    components.sort_by_key(|c| (rank(doc_type, c), c.link().range.start_line));
+
+    Page {
+        doc_type,
+        title: analysis
+            .model
+            .outline
+            .iter()
+            .find(|e| e.level == 1)
+            .map(|e| e.title.clone()),
+        components,
+    }
+}
+
+struct Context<'a> {
+    tree: &'a BlockTree,
+    doc_type: DocumentType,
+    classes: HashMap<BlockId, BlockClass>,
+    /// Title of the heading each block sits under.
+    sections: HashMap<BlockId, String>,
+    locale: Locale,
+    /// Parsed request of each HTTP code block.
+    requests: HashMap<BlockId, &'a HttpRequest>,
+}
+
+impl Context<'_> {
+    fn class(&self, id: BlockId) -> Option<BlockClass> {
+        self.classes.get(&id).copied()
+    }
+
+    fn section(&self, id: BlockId) -> Option<String> {
+        self.sections.get(&id).cloned()
+    }
+}
+
+fn sections(tree: &BlockTree) -> HashMap<BlockId, String> {
+    let mut out = HashMap::new();
+    let mut current: Option<&str> = None;
+    for block in tree.iter() {
+        if matches!(block.kind, BlockKind::Heading { .. }) {
+            current = Some(&block.text);
+        } else if let Some(title) = current {
+            out.insert(block.id, title.to_string());
+        }
+    }
+    out
+}
+
+fn link(block: &Block) -> SourceRangeLink {
+    SourceRangeLink {
+        block_id: block.id,
+        range: block.range,
+    }
+}
+
+fn is_task_list(list: &Block) -> bool {
+    list.children
+        .iter()
+        .any(|item| matches!(item.kind, BlockKind::Item { task: Some(_) }))
+}
+
+fn step_navigators(ctx: &Context, out: &mut Vec<Component>) {
+    let procedural = matches!(
+        ctx.doc_type,
+        DocumentType::Runbook | DocumentType::Procedure | DocumentType::Recipe
+    );
+    for list in &ctx.tree.blocks {
+        let BlockKind::List { ordered, .. } = list.kind else {
+            continue;
+        };
+        let in_steps = ctx.class(list.id) == Some(BlockClass::Step);
+        if !(in_steps || ordered && procedural) || is_task_list(list) {
+            continue;
+        }
+        let steps = list
+            .children
+            .iter()
+            .map(|item| Step {
+                text: item.text.clone(),
+                link: link(item),
+            })
+            .collect();
+        out.push(Component::StepNavigator(StepNavigator {
+            title: ctx.section(list.id),
+            steps,
+            link: link(list),
+        }));
+    }
+}
+
+fn code_components(ctx: &Context, out: &mut Vec<Component>) {
+    for (id, intent) in code::classify(ctx.tree) {
+        let Some(block) = ctx.tree.find(id) else {
+            continue;
+        };
+        let format = match intent {
+            CodeIntent::Http => {
+                let request = ctx.requests.get(&id);
+                out.push(Component::ApiExplorer(ApiExplorer {
+                    method: request.map(|r| r.method.clone()),
+                    target: request.map(|r| r.url.clone()),
+                    request: block.text.clone(),
+                    link: link(block),
+                }));
+                continue;
+            }
+            CodeIntent::Json => ConfigFormat::Json,
+            CodeIntent::Yaml => ConfigFormat::Yaml,
+            CodeIntent::Toml => ConfigFormat::Toml,
+            _ => continue,
+        };
+        out.push(Component::ConfigViewer(ConfigViewer {
+            format,
+            title: ctx.section(id),
+            content: block.text.clone(),
+            link: link(block),
+        }));
+    }
+}
+
+/// A top-level heading and the blocks up to the next heading of the same or a
+/// higher level.
+fn section_bodies(tree: &BlockTree) -> Vec<(&Block, &[Block])> {
+    let blocks = &tree.blocks;
+    let mut out = Vec::new();
+    for (i, heading) in blocks.iter().enumerate() {
+        let BlockKind::Heading { level } = heading.kind else {
+            continue;
+        };
+        let end = blocks[i + 1..]
+            .iter()
+            .position(|b| matches!(b.kind, BlockKind::Heading { level: l } if l <= level))
+            .map_or(blocks.len(), |p| i + 1 + p);
+        out.push((heading, &blocks[i + 1..end]));
+    }
+    out
+}
+
+/// Whether `heading` opens a status section: its title has a status keyword
+/// of the document's locale packs, and no section rule (built-in or
+/// configured) claimed it for another class.
+fn is_status_heading(ctx: &Context, heading: &Block) -> bool {
+    let title = heading.text.to_lowercase();
+    ctx.class(heading.id) == Some(BlockClass::Heading)
+        && ctx
+            .locale
+            .packs()
+            .flat_map(|pack| pack.doc_types.adr_status)
+            .any(|kw| title.contains(kw))
+}
+
+fn decision_cards(ctx: &Context, out: &mut Vec<Component>) {
+    let sections = section_bodies(ctx.tree);
+    let status = sections
+        .iter()
+        .find(|(h, _)| is_status_heading(ctx, h))
+        .and_then(|(_, body)| body.first())
+        .map(|b| b.text.clone());
+    let consequences: Vec<String> = sections
+        .iter()
+        .filter(|(h, _)| ctx.class(h.id) == Some(BlockClass::Consequence))
+        .flat_map(|(_, body)| body.iter())
+        .flat_map(|b| match b.kind {
+            BlockKind::List { .. } => b.children.iter().map(|i| i.text.clone()).collect(),
+            _ => vec![b.text.clone()],
+        })
+        .collect();
+    let doc_title = ctx
+        .tree
+        .blocks
+        .iter()
+        .find(|b| matches!(b.kind, BlockKind::Heading { level: 1 }))
+        .map(|b| b.text.clone());
+
+    for (heading, body) in sections {
+        if ctx.class(heading.id) != Some(BlockClass::Decision) {
+            continue;
+        }
+        let decision = body
+            .iter()
+            .filter(|b| !matches!(b.kind, BlockKind::Heading { .. }))
+            .map(|b| b.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let title = match (&doc_title, ctx.doc_type) {
+            (Some(title), DocumentType::Adr) => title.clone(),
+            _ => heading.text.clone(),
+        };
+        let end = body.last().unwrap_or(heading).range;
+        out.push(Component::DecisionCard(DecisionCard {
+            title,
+            status: status.clone(),
+            decision,
+            consequences: consequences.clone(),
+            link: SourceRangeLink {
+                block_id: heading.id,
+                range: SourceRange {
+                    end_line: end.end_line,
+                    end_column: end.end_column,
+                    ..heading.range
+                },
+            },
+        }));
+    }
+}
+
+fn status_boards(source: &str, ctx: &Context, out: &mut Vec<Component>) {
+    let lines: Vec<&str> = source.lines().collect();
+    for (id, info) in table::analyze_all(source, ctx.tree) {
+        let TableInfo {
+            columns,
+            status_column: Some(status_column),
+            rows,
+            ..
+        } = info
+        else {
+            continue;
+        };
+        let Some(block) = ctx.tree.find(id) else {
+            continue;
+        };
+        let mut lanes: Vec<StatusLane> = Vec::new();
+        for row in rows {
+            let status = row.cells.get(status_column).cloned().unwrap_or_default();
+            let label = row
+                .cells
+                .iter()
+                .enumerate()
+                .find(|(i, c)| *i != status_column && !c.is_empty())
+                .map(|(_, c)| c.clone())
+                .unwrap_or_default();
+            let width = lines.get(row.line as usize - 1).map_or(0, |l| l.len());
+            let card = StatusCard {
+                label,
+                link: SourceRangeLink {
+                    block_id: id,
+                    range: SourceRange {
+                        start_line: row.line,
+                        start_column: 1,
+                        end_line: row.line,
+                        end_column: width as u32 + 1,
+                    },
+                },
+            };
+            match lanes.iter_mut().find(|l| l.status == status) {
+                Some(lane) => lane.cards.push(card),
+                None => lanes.push(StatusLane {
+                    status,
+                    cards: vec![card],
+                }),
+            }
+        }
+        out.push(Component::StatusBoard(StatusBoard {
+            title: ctx.section(id),
+            status_column: columns[status_column].clone(),
+            lanes,
+            link: link(block),
+        }));
+    }
+}
+
+fn checklists(ctx: &Context, out: &mut Vec<Component>) {
+    for list in &ctx.tree.blocks {
+        if !matches!(list.kind, BlockKind::List { .. }) || !is_task_list(list) {
+            continue;
+        }
+        let items: Vec<ChecklistItem> = list
+            .descendants()
+            .filter_map(|b| match b.kind {
+                BlockKind::Item { task: Some(done) } => Some(ChecklistItem {
+                    text: b.text.clone(),
+                    done,
+                    link: link(b),
+                }),
+                _ => None,
+            })
+            .collect();
+        out.push(Component::ChecklistPanel(ChecklistPanel {
+            title: ctx.section(list.id),
+            done: items.iter().filter(|i| i.done).count(),
+            total: items.len(),
+            items,
+            link: link(list),
+        }));
+    }
+}
+
+/// Display rank of a component for a document type (lower comes first).
+fn rank(doc_type: DocumentType, component: &Component) -> usize {
+    use DocumentType::*;
+    let order: &[&str] = match doc_type {
+        Runbook | Procedure | Recipe => &["steps", "checklist", "api", "config"],
+        Adr | DesignDoc | Minutes => &["decision", "status", "checklist"],
+        Readme => &["api", "config", "steps"],
+        ProductionOrder => &["status", "checklist"],
+        _ => &[],
+    };
+    let kind = match component {
+        Component::StepNavigator(_) => "steps",
+        Component::ApiExplorer(_) => "api",
+        Component::ConfigViewer(_) => "config",
+        Component::DecisionCard(_) => "decision",
+        Component::StatusBoard(_) => "status",
+        Component::ChecklistPanel(_) => "checklist",
+    };
+    order.iter().position(|k| *k == kind).unwrap_or(order.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdpeek_analyzer::rules::{ClassificationRules, SectionRule};
+    use mdpeek_analyzer::{MarkdownOptions, RulesGenerator, analyze, analyze_with_rules};
+
+    fn page(md: &str, filename: Option<&str>) -> Page {
+        plan(md, &analyze(md, filename))
+    }
+
+    #[test]
+    fn runbook_leads_with_steps() {
+        let md = "# Deploy\n\n## Prerequisites\n\n```json\n{\"region\": \"eu\"}\n```\n\n\
+                  ## Procedure\n\n1. Drain traffic\n2. Deploy\n3. Verify\n\n\
+                  ## Rollback\n\n- [ ] restore snapshot\n";
+        let page = page(md, None);
+        assert_eq!(page.doc_type, DocumentType::Runbook);
+        assert_eq!(page.title.as_deref(), Some("Deploy"));
+        let Component::StepNavigator(nav) = &page.components[0] else {
+            panic!("steps first: {:?}", page.components);
+        };
+        assert_eq!(nav.title.as_deref(), Some("Procedure"));
+        let texts: Vec<_> = nav.steps.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Drain traffic", "Deploy", "Verify"]);
+        assert_eq!(nav.steps[1].link.range.start_line, 12);
+        assert!(matches!(page.components[1], Component::ChecklistPanel(_)));
+        assert!(matches!(page.components[2], Component::ConfigViewer(_)));
+    }
+
+    #[test]
+    fn adr_becomes_a_decision_card() {
+        let md = "# ADR 7: Use SQLite\n\n## Status\n\nAccepted\n\n## Context\n\nWe need storage.\n\n\
+                  ## Decision\n\nUse SQLite.\n\n## Consequences\n\n- No server\n- Single writer\n";
+        let page = page(md, None);
+        assert_eq!(page.doc_type, DocumentType::Adr);
+        let Component::DecisionCard(card) = &page.components[0] else {
+            panic!("decision first: {:?}", page.components);
+        };
+        assert_eq!(card.title, "ADR 7: Use SQLite");
+        assert_eq!(card.status.as_deref(), Some("Accepted"));
+        assert_eq!(card.decision, "Use SQLite.");
+        assert_eq!(card.consequences, vec!["No server", "Single writer"]);
+        // The card spans the Decision heading through its body.
+        assert_eq!(card.link.range.start_line, 11);
+        assert!(card.link.range.end_line >= 13);
+    }
+
+    #[test]
+    fn status_keywords_come_from_the_locale_pack() {
+        let md = "---\nlang: fr\n---\n\n# ADR 3 : Cache\n\n## Statut\n\nAccepté\n\n\
+                  ## Contexte\n\nLent.\n\n## Décision\n\nAjouter un cache.\n";
+        let page = page(md, None);
+        let Component::DecisionCard(card) = &page.components[0] else {
+            panic!("{:?}", page.components);
+        };
+        assert_eq!(card.status.as_deref(), Some("Accepté"));
+    }
+
+    #[test]
+    fn configured_section_rules_override_the_status_keyword() {
+        let md = "# Rollout\n\n## Status report\n\nAll green.\n\n## Decision\n\nShip it.\n";
+        assert_eq!(
+            decision_status(md, &ClassificationRules::default()),
+            Some("All green.".to_string())
+        );
+
+        let mut rules = ClassificationRules::default();
+        rules
+            .sections
+            .insert("status report".into(), SectionRule::Class(BlockClass::Step));
+        assert_eq!(decision_status(md, &rules), None);
+    }
+
+    fn decision_status(md: &str, rules: &ClassificationRules) -> Option<String> {
+        let analysis = analyze_with_rules(
+            md,
+            None,
+            &MarkdownOptions::default(),
+            rules,
+            &RulesGenerator,
+        );
+        plan(md, &analysis)
+            .components
+            .into_iter()
+            .find_map(|c| match c {
+                Component::DecisionCard(card) => Some(card.status),
+                _ => None,
+            })?
+    }
+
+    #[test]
+    fn status_table_becomes_a_board() {
+        let md = "# Plan\n\n| Task | Status |\n|------|--------|\n| A | done |\n| B | todo |\n| C | done |\n";
+        let page = page(md, None);
+        let Component::StatusBoard(board) = &page.components[0] else {
+            panic!("{:?}", page.components);
+        };
+        assert_eq!(board.status_column, "Status");
+        let lanes: Vec<_> = board
+            .lanes
+            .iter()
+            .map(|l| (l.status.as_str(), l.cards.len()))
+            .collect();
+        assert_eq!(lanes, vec![("done", 2), ("todo", 1)]);
+        assert_eq!(board.lanes[0].cards[1].label, "C");
+        assert_eq!(board.lanes[0].cards[1].link.range.start_line, 7);
+    }
+
+    #[test]
+    fn checklist_counts_progress() {
+        let page = page("## TODO\n\n- [x] a\n- [ ] b\n  - [x] b.1\n", None);
+        let Component::ChecklistPanel(panel) = &page.components[0] else {
+            panic!("{:?}", page.components);
+        };
+        assert_eq!((panel.done, panel.total), (2, 3));
+        assert_eq!(panel.title.as_deref(), Some("TODO"));
+    }
+
+    #[test]
+    fn http_block_becomes_api_explorer() {
+        let md = "# API\n\n```http\nPOST /v1/items HTTP/1.1\nContent-Type: application/json\n\n{}\n```\n";
+        let page = page(md, Some("README.md"));
+        let Component::ApiExplorer(api) = &page.components[0] else {
+            panic!("{:?}", page.components);
+        };
+        assert_eq!(api.method.as_deref(), Some("POST"));
+        assert_eq!(api.target.as_deref(), Some("/v1/items"));
+    }
+
+    #[test]
+    fn plain_prose_plans_no_components() {
+        let page = page("# Notes\n\nJust text.\n\n- a bullet\n", None);
+        assert!(page.components.is_empty());
+    }
+
+    #[test]
+    fn every_component_links_into_the_document() {
+        let md = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../docs/sample-design-doc.md"
+        ))
+        .unwrap();
+        let lines = md.lines().count() as u32 + 1;
+        let page = page(&md, None);
+        for component in &page.components {
+            let range = component.link().range;
+            assert!(
+                range.start_line >= 1 && range.end_line <= lines,
+                "{component:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn serializes_with_a_type_tag() {
+        let page = page("- [ ] a\n", None);
+        let json = serde_json::to_value(&page).unwrap();
+        assert_eq!(json["components"][0]["type"], "checklist_panel");
+        assert_eq!(json["components"][0]["link"]["range"]["start_line"], 1);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::config::{BrowserTheme, Config, DefaultMode};
 use anyhow::Result;
 use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use mdpeek_server::todos::TodoFilter;
 use serde::Deserialize;
 use std::{io::IsTerminal, path::PathBuf};
 
 const DEFAULT_ROOT: &str = "README.md";
+const DEFAULT_CHANGELOG: &str = "CHANGELOG.md";
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: &str = "3030";
 
@@ -38,6 +40,18 @@ pub enum Commands {
     Serve(ServeArg),
     /// Display pretty rendered markdown on your terminal
     Term(TermArg),
+    /// List TODO/FIXME markers and task items across the repository
+    Todos(TodosArg),
+    /// Check that a README's paths, commands and config snippets match the code
+    Check(CheckArg),
+    /// Index the repository's architecture decision records
+    Adrs(AdrsArg),
+    /// Print a document's type and statistics (words, reading time, section sizes)
+    Analyze(AnalyzeArg),
+    /// Validate a Keep a Changelog file against the git tags, or print release notes
+    Changelog(ChangelogArg),
+    /// List a meeting's action items and decisions, or export them as iCalendar
+    Actions(ActionsArg),
 }
 
 // Subcommand arguments are optional so that an unset flag can fall back to
@@ -61,6 +75,103 @@ pub struct TermArg {
     pub theme: Option<ThemeChoice>,
 }
 
+#[derive(Debug, Args)]
+pub struct TodosArg {
+    /// Directory to discover the repository from (defaults to the current one)
+    #[arg(value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+    /// Only this marker: task, todo, fixme, xxx or hack
+    #[arg(long, value_name = "MARKER")]
+    pub marker: Option<String>,
+    /// Only checked task items
+    #[arg(long, conflicts_with = "open")]
+    pub done: bool,
+    /// Only open entries
+    #[arg(long)]
+    pub open: bool,
+    /// Only files whose worktree-relative path contains TEXT
+    #[arg(long, value_name = "TEXT")]
+    pub file: Option<String>,
+    /// Only this worktree (directory name or branch)
+    #[arg(long, value_name = "NAME")]
+    pub worktree: Option<String>,
+    /// Also scan comments in source files
+    #[arg(long)]
+    pub code: bool,
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArg {
+    /// Documents to check (defaults to README.md)
+    #[arg(value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+    /// Repository root the documents are checked against (defaults to the current directory)
+    #[arg(long, value_name = "DIR")]
+    pub root: Option<PathBuf>,
+    /// JSON issue export to check TODO(#123) references against (overrides `issues.export`)
+    #[arg(long, value_name = "FILE")]
+    pub issues: Option<PathBuf>,
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct AdrsArg {
+    /// Directory to discover the repository from (defaults to the current one)
+    #[arg(value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct AnalyzeArg {
+    /// Document to analyse (defaults to README.md)
+    #[arg(value_name = "FILE")]
+    pub file: Option<PathBuf>,
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct ChangelogArg {
+    /// Changelog to read (defaults to CHANGELOG.md)
+    #[arg(value_name = "FILE")]
+    pub file: Option<PathBuf>,
+    /// Print the notes of the newest release instead of validating
+    #[arg(long)]
+    pub latest: bool,
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct ActionsArg {
+    /// Meeting minutes to read
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+    #[arg(long, value_enum)]
+    pub format: Option<ActionsFormat>,
+}
+
+/// Output format of `actions`: the reporting formats plus iCalendar `VTODO`s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ActionsFormat {
+    Text,
+    Json,
+    Ical,
+}
+
+/// Output format of the reporting subcommands (`todos`, `check`, `adrs`,
+/// `analyze`, `changelog`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ThemeChoice {
@@ -89,6 +200,36 @@ pub enum Mode {
         /// disables paging, `Some(cmd)` runs `cmd`.
         pager: Option<String>,
     },
+    /// Repository-wide TODO dashboard, printed once.
+    Todos {
+        dir: PathBuf,
+        filter: TodoFilter,
+        code: bool,
+        format: OutputFormat,
+    },
+    /// ADR index with supersession diagnostics, printed once.
+    Adrs { dir: PathBuf, format: OutputFormat },
+    /// README ↔ code consistency report; exits non-zero on findings.
+    Check {
+        files: Vec<PathBuf>,
+        root: PathBuf,
+        issues: Option<PathBuf>,
+        format: OutputFormat,
+    },
+    /// Document type and statistics, printed once.
+    Analyze { file: PathBuf, format: OutputFormat },
+    /// Changelog releases and problems (exits non-zero on problems), or the
+    /// newest release's notes with `latest`.
+    Changelog {
+        file: PathBuf,
+        latest: bool,
+        format: OutputFormat,
+    },
+    /// Action items grouped by owner and decisions, printed once.
+    Actions {
+        file: PathBuf,
+        format: ActionsFormat,
+    },
 }
 
 impl Cli {
@@ -145,6 +286,48 @@ impl Cli {
                 theme: arg.theme.or(config.term.theme).unwrap_or(ThemeChoice::Glow),
                 pager,
             }),
+            Some(Commands::Todos(arg)) => Ok(Mode::Todos {
+                dir: arg.dir.unwrap_or_else(|| PathBuf::from(".")),
+                filter: TodoFilter {
+                    marker: arg.marker,
+                    done: match (arg.done, arg.open) {
+                        (true, _) => Some(true),
+                        (_, true) => Some(false),
+                        _ => None,
+                    },
+                    file: arg.file,
+                    worktree: arg.worktree,
+                },
+                code: arg.code,
+                format: arg.format.unwrap_or(OutputFormat::Text),
+            }),
+            Some(Commands::Adrs(arg)) => Ok(Mode::Adrs {
+                dir: arg.dir.unwrap_or_else(|| PathBuf::from(".")),
+                format: arg.format.unwrap_or(OutputFormat::Text),
+            }),
+            Some(Commands::Check(arg)) => Ok(Mode::Check {
+                files: if arg.files.is_empty() {
+                    vec![PathBuf::from(DEFAULT_ROOT)]
+                } else {
+                    arg.files
+                },
+                root: arg.root.unwrap_or_else(|| PathBuf::from(".")),
+                issues: arg.issues,
+                format: arg.format.unwrap_or(OutputFormat::Text),
+            }),
+            Some(Commands::Analyze(arg)) => Ok(Mode::Analyze {
+                file: arg.file.unwrap_or_else(|| PathBuf::from(DEFAULT_ROOT)),
+                format: arg.format.unwrap_or(OutputFormat::Text),
+            }),
+            Some(Commands::Changelog(arg)) => Ok(Mode::Changelog {
+                file: arg.file.unwrap_or_else(|| PathBuf::from(DEFAULT_CHANGELOG)),
+                latest: arg.latest,
+                format: arg.format.unwrap_or(OutputFormat::Text),
+            }),
+            Some(Commands::Actions(arg)) => Ok(Mode::Actions {
+                file: arg.file,
+                format: arg.format.unwrap_or(ActionsFormat::Text),
+            }),
             None => {
                 let root = self.root.unwrap_or_else(|| PathBuf::from(DEFAULT_ROOT));
                 let host = self
@@ -11,9 +11,22 @@
 
 use crate::cli::ThemeChoice;
 use mdpeek_analyzer::generation::DEFAULT_CONFIDENCE_THRESHOLD;
-use mdpeek_analyzer::{GenerationConfig, GenerationStrategy};
+use mdpeek_analyzer::generator::llm::DEFAULT_API_KEY_ENV;
+use mdpeek_analyzer::issues::IssueList;
+use mdpeek_analyzer::lint::{LintConfig, Linter};
+use mdpeek_analyzer::progress::ProgressStore;
+use mdpeek_analyzer::rules::ClassificationRules;
+use mdpeek_analyzer::{
+    AnalysisExtras, GenerationConfig, GenerationStrategy, Generator, LlmGenerator, LlmSettings,
+    RulesGenerator,
+};
+use mdpeek_gfm::emoji::Emoji;
+use mdpeek_gfm::{EmojiResolver, MarkdownOptions, QuoteStyle};
+use mdpeek_server::api_explorer::ApiExplorer;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Top-level configuration mirroring the structure of `config.toml`.
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -28,6 +41,20 @@ pub struct Config {
     /// Generative-UI / LLM settings (rules-first vs LLM-first). Consumed by the
     /// generator (Layer 3); read here at startup.
     pub llm: LlmConfig,
+    /// Markdown feature set shared by `serve`, `term` and the analyser.
+    pub markdown: MarkdownConfig,
+    /// Custom `:shortcode:` emoji: name → Unicode text or image.
+    pub emoji: BTreeMap<String, EmojiConfig>,
+    /// Local issue export joined against `TODO(#123)` markers.
+    pub issues: IssuesConfig,
+    /// Prose linter rules (off unless `enabled = true`).
+    pub lint: LintConfig,
+    /// Where completed procedure steps are remembered.
+    pub progress: ProgressConfig,
+    /// Section keywords and document-type rules added to the built-in ones.
+    pub analyzer: ClassificationRules,
+    /// Where the browser's HTTP request cards are sent (off by default).
+    pub api_explorer: ApiExplorerConfig,
 }
 
 /// `[server]` section: browser previewer defaults.
@@ -53,6 +80,37 @@ pub struct TermConfig {
     pub pager: Option<String>,
 }
 
+/// `[issues]` section: where the issue tracker export lives.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IssuesConfig {
+    /// JSON export (GitHub or GitLab issue list) that `TODO(#123)` markers are
+    /// checked against. Relative paths are resolved from the working directory.
+    pub export: Option<PathBuf>,
+}
+
+/// `[progress]` section: the state file of the step-by-step procedure mode.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProgressConfig {
+    /// JSON file recording completed steps per document. Defaults to
+    /// `$XDG_STATE_HOME/mdpeek/progress.json` (or
+    /// `~/.local/state/mdpeek/progress.json`).
+    pub file: Option<PathBuf>,
+}
+
+/// `[api_explorer]` section: the proxy behind the "send" button of the
+/// browser's HTTP request cards.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiExplorerConfig {
+    /// Base URL the documented requests are sent to, their path and query
+    /// appended (e.g. `"http://localhost:8080"`). Unset disables the proxy.
+    pub base_url: Option<String>,
+    /// Seconds to wait for a response. Defaults to 30.
+    pub timeout_secs: Option<u64>,
+}
+
 /// `[llm]` section: how generated UI chooses between deterministic rules and
 /// the LLM. This is read from `config.toml` at application startup; the actual
 /// generator (Layer 3) consults the resolved [`GenerationConfig`].
@@ -69,6 +127,124 @@ pub struct LlmConfig {
     /// Confidence below which a rules result is escalated to the LLM under
     /// `rules_first`. Defaults to 0.6 when unset.
     pub confidence_threshold: Option<f32>,
+    /// Messages API endpoint. Defaults to `https://api.anthropic.com`.
+    pub base_url: Option<String>,
+    /// Model name sent with each request.
+    pub model: Option<String>,
+    /// Environment variable holding the API key. Defaults to
+    /// `ANTHROPIC_API_KEY`; the key itself never lives in the config file.
+    pub api_key_env: Option<String>,
+    /// Seconds to wait for a reply before keeping the rules result.
+    pub timeout_secs: Option<u64>,
+    /// Directory for cached replies. Defaults to `$XDG_CACHE_HOME/mdpeek/llm`
+    /// (or `~/.cache/mdpeek/llm`).
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// `[markdown]` section: which syntax the parser recognises. GFM is always on;
+/// `math` and `footnotes` default to `true` (as on GitHub), the rest to `false`.
+/// A document can override any of these in a `markdown:` front matter map.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarkdownConfig {
+    /// `$inline$` / `$$display$$` math.
+    pub math: bool,
+    /// `[^1]` footnotes.
+    pub footnotes: bool,
+    /// Curly quotes, en/em dashes and ellipses.
+    pub smart_punctuation: bool,
+    /// Language whose quotation marks smart punctuation uses (`ja` → 「」,
+    /// `fr` → « »). Front matter `lang:` takes precedence.
+    pub lang: Option<String>,
+    /// `Term` / `: definition` lists.
+    pub definition_lists: bool,
+    /// `^superscript^`.
+    pub superscript: bool,
+    /// `~subscript~` (single tildes stop meaning strikethrough).
+    pub subscript: bool,
+    /// `[[Page]]` wiki links.
+    pub wiki_links: bool,
+    /// `==text==` → highlighted (`<mark>`).
+    pub mark: bool,
+    /// `++text++` → inserted (`<ins>`).
+    pub insert: bool,
+    /// `*[ABBR]: Expansion` definitions → `<abbr title>`.
+    pub abbreviations: bool,
+    /// `# Heading {#id .class}` → custom anchor id and classes.
+    pub heading_attributes: bool,
+}
+
+impl Default for MarkdownConfig {
+    fn default() -> Self {
+        let defaults = MarkdownOptions::default();
+        Self {
+            math: defaults.math,
+            footnotes: defaults.footnotes,
+            smart_punctuation: defaults.smart_punctuation,
+            lang: None,
+            definition_lists: defaults.definition_lists,
+            superscript: defaults.superscript,
+            subscript: defaults.subscript,
+            wiki_links: defaults.wiki_links,
+            mark: defaults.mark,
+            insert: defaults.insert,
+            abbreviations: defaults.abbreviations,
+            heading_attributes: defaults.heading_attributes,
+        }
+    }
+}
+
+/// One `[emoji]` entry. A string is used as-is, unless it looks like an image
+/// path (`.png`, `.gif`, `.jpg`, `.jpeg`, `.webp`, `.svg`); a table gives an
+/// image plus the alt text the terminal prints instead of `:name:`. Relative
+/// image paths are resolved against the directory holding the config file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum EmojiConfig {
+    Text(String),
+    Image { image: String, alt: Option<String> },
+}
+
+impl EmojiConfig {
+    fn to_emoji(&self) -> Emoji {
+        match self {
+            EmojiConfig::Text(s) if is_image_path(s) => Emoji::Image {
+                src: s.clone(),
+                alt: None,
+            },
+            EmojiConfig::Text(s) => Emoji::Unicode(s.clone()),
+            EmojiConfig::Image { image, alt } => Emoji::Image {
+                src: image.clone(),
+                alt: alt.clone(),
+            },
+        }
+    }
+
+    /// Make a relative local image path relative to `dir` (the directory of
+    /// the config file) instead of whatever the working directory happens to be.
+    fn resolve_against(&mut self, dir: &Path) {
+        let image = match self {
+            EmojiConfig::Text(s) if is_image_path(s) => s,
+            EmojiConfig::Text(_) => return,
+            EmojiConfig::Image { image, .. } => image,
+        };
+        if !is_remote(image) && Path::new(image.as_str()).is_relative() {
+            *image = dir.join(image.as_str()).to_string_lossy().into_owned();
+        }
+    }
+}
+
+fn is_image_path(s: &str) -> bool {
+    let lower = s.to_ascii_lowercase();
+    [".png", ".gif", ".jpg", ".jpeg", ".webp", ".svg"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+}
+
+fn is_remote(s: &str) -> bool {
+    ["http://", "https://", "data:"]
+        .iter()
+        .any(|scheme| s.starts_with(scheme))
 }
 
 /// Mode selected when no subcommand is given.
@@ -124,14 +300,161 @@ impl Config {
         }
     }
 
+    /// Build the Layer 3 generator: the LLM generator when `[llm]` is enabled
+    /// and an API key is present in the environment, rules only otherwise.
+    pub fn generator(&self) -> Box<dyn Generator> {
+        let env = self
+            .llm
+            .api_key_env
+            .as_deref()
+            .unwrap_or(DEFAULT_API_KEY_ENV);
+        let api_key = std::env::var(env).ok().filter(|key| !key.trim().is_empty());
+        if self.llm.enabled && api_key.is_none() {
+            tracing::warn!("[llm] is enabled but ${env} is not set; using rules only");
+        }
+        self.generator_with_key(api_key)
+    }
+
+    fn generator_with_key(&self, api_key: Option<String>) -> Box<dyn Generator> {
+        let Some(api_key) = api_key.filter(|_| self.llm.enabled) else {
+            return Box::new(RulesGenerator);
+        };
+        let defaults = LlmSettings::new(api_key);
+        let settings = LlmSettings {
+            base_url: self
+                .llm
+                .base_url
+                .clone()
+                .unwrap_or(defaults.base_url.clone()),
+            model: self.llm.model.clone().unwrap_or(defaults.model.clone()),
+            timeout: self
+                .llm
+                .timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+            cache_dir: self.llm.cache_dir.clone().or_else(llm_cache_dir),
+            ..defaults
+        };
+        Box::new(LlmGenerator::new(self.generation_config(), settings))
+    }
+
+    /// Resolve the markdown feature set from `[markdown]`. Per-document front
+    /// matter overrides are applied later, when each document is parsed.
+    pub fn markdown_options(&self) -> MarkdownOptions {
+        MarkdownOptions {
+            math: self.markdown.math,
+            footnotes: self.markdown.footnotes,
+            smart_punctuation: self.markdown.smart_punctuation,
+            definition_lists: self.markdown.definition_lists,
+            superscript: self.markdown.superscript,
+            subscript: self.markdown.subscript,
+            wiki_links: self.markdown.wiki_links,
+            mark: self.markdown.mark,
+            insert: self.markdown.insert,
+            abbreviations: self.markdown.abbreviations,
+            heading_attributes: self.markdown.heading_attributes,
+            quotes: self
+                .markdown
+                .lang
+                .as_deref()
+                .map(QuoteStyle::from_lang)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Build the emoji resolver: built-in emoji plus the `[emoji]` mappings.
+    pub fn emoji_resolver(&self) -> EmojiResolver {
+        let mut resolver = EmojiResolver::new();
+        for (name, entry) in &self.emoji {
+            resolver.insert(name, entry.to_emoji());
+        }
+        resolver
+    }
+
+    /// Load the `[issues]` export, warning (and returning `None`) when the file
+    /// cannot be read or parsed.
+    pub fn issue_list(&self) -> Option<IssueList> {
+        let path = self.issues.export.as_deref()?;
+        match IssueList::load(path) {
+            Ok(list) => Some(list),
+            Err(e) => {
+                eprintln!("mdpeek: warning: failed to load issue export {e}");
+                None
+            }
+        }
+    }
+
+    /// Compile the `[lint]` rules, or `None` when linting is disabled or the
+    /// rules are invalid (reported as a warning).
+    pub fn linter(&self) -> Option<Linter> {
+        if !self.lint.enabled {
+            return None;
+        }
+        match Linter::new(&self.lint) {
+            Ok(linter) => Some(linter),
+            Err(e) => {
+                eprintln!("mdpeek: warning: invalid lint rules: {e}");
+                None
+            }
+        }
+    }
+
+    /// The `[analyzer]` rules, or only the built-in ones when they are invalid
+    /// (reported as a warning). Without an `[analyzer] lang`, documents are
+    /// read in the `[markdown] lang`.
+    pub fn classification_rules(&self) -> ClassificationRules {
+        let mut rules = match self.analyzer.validate() {
+            Ok(()) => self.analyzer.clone(),
+            Err(e) => {
+                eprintln!("mdpeek: warning: invalid analyzer rules: {e}");
+                ClassificationRules::default()
+            }
+        };
+        if rules.lang.is_none() {
+            rules.lang = self.markdown.lang.clone();
+        }
+        rules
+    }
+
+    /// The step progress store: `[progress] file`, or the XDG state default.
+    pub fn progress_store(&self) -> Option<ProgressStore> {
+        let path = self.progress.file.clone().or_else(progress_file)?;
+        Some(ProgressStore::new(path))
+    }
+
+    /// The API explorer proxy, or `None` unless `[api_explorer] base_url` is
+    /// set.
+    pub fn api_explorer(&self) -> Option<ApiExplorer> {
+        let base_url = self.api_explorer.base_url.clone()?;
+        let timeout = Duration::from_secs(self.api_explorer.timeout_secs.unwrap_or(30));
+        Some(ApiExplorer::new(base_url, timeout))
+    }
+
+    /// The classification rules, issue export, linter and step progress of
+    /// every analysis.
+    pub fn analysis_extras(&self) -> AnalysisExtras {
+        AnalysisExtras {
+            rules: self.classification_rules(),
+            issues: self.issue_list(),
+            linter: self.linter(),
+            progress: self.progress_store(),
+        }
+    }
+
     fn load_from(path: &Path) -> Self {
         let content = match std::fs::read_to_string(path) {
             Ok(content) => content,
             // A missing config file is the normal case, not an error.
             Err(_) => return Self::default(),
         };
-        match toml::from_str(&content) {
-            Ok(config) => config,
+        match toml::from_str::<Self>(&content) {
+            Ok(mut config) => {
+                let dir = path.parent().unwrap_or(Path::new(""));
+                for entry in config.emoji.values_mut() {
+                    entry.resolve_against(dir);
+                }
+                config
+            }
             Err(e) => {
                 eprintln!("mdpeek: warning: failed to parse {}: {e}", path.display());
                 Self::default()
@@ -151,9 +474,32 @@ pub fn config_path() -> Option<PathBuf> {
     Some(base.join("mdpeek").join("config.toml"))
 }
 
+/// Default LLM reply cache: `$XDG_CACHE_HOME/mdpeek/llm`, or
+/// `~/.cache/mdpeek/llm` when `XDG_CACHE_HOME` is unset or empty.
+fn llm_cache_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(value) if !value.is_empty() => PathBuf::from(value),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("mdpeek").join("llm"))
+}
+
+/// Default step progress file: `$XDG_STATE_HOME/mdpeek/progress.json`, or
+/// `~/.local/state/mdpeek/progress.json`.
+fn progress_file() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_STATE_HOME") {
+        Some(value) if !value.is_empty() => PathBuf::from(value),
+        _ => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("state"),
+    };
+    Some(base.join("mdpeek").join("progress.json"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mdpeek_analyzer::analyzer::http::HttpRequest;
 
     #[test]
     fn empty_config_is_all_defaults() {
@@ -246,11 +592,246 @@ mod tests {
         assert!(!policy.should_use_llm(0.95));
     }
 
+    #[test]
+    fn generator_needs_both_enabled_and_an_api_key() {
+        let enabled: Config = toml::from_str("[llm]\nenabled = true\n").unwrap();
+        assert_eq!(enabled.generator_with_key(None).name(), "rules");
+        assert_eq!(enabled.generator_with_key(Some("k".into())).name(), "llm");
+        let disabled = Config::default();
+        assert_eq!(
+            disabled.generator_with_key(Some("k".into())).name(),
+            "rules"
+        );
+    }
+
+    #[test]
+    fn llm_connection_settings_parse() {
+        let toml = r#"
+            [llm]
+            enabled = true
+            base_url = "http://127.0.0.1:8080"
+            model = "local-model"
+            api_key_env = "MY_KEY"
+            timeout_secs = 5
+            cache_dir = "/tmp/mdpeek-llm"
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.llm.base_url.as_deref(),
+            Some("http://127.0.0.1:8080")
+        );
+        assert_eq!(config.llm.api_key_env.as_deref(), Some("MY_KEY"));
+        assert_eq!(config.llm.timeout_secs, Some(5));
+    }
+
     #[test]
     fn llm_unknown_key_is_rejected() {
         assert!(toml::from_str::<Config>("[llm]\nbogus = 1").is_err());
     }
 
+    #[test]
+    fn markdown_extensions_default_off() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.markdown_options(), MarkdownOptions::default());
+    }
+
+    #[test]
+    fn markdown_extensions_parse() {
+        let toml = r#"
+            [markdown]
+            mark = true
+            abbreviations = true
+            heading_attributes = true
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let options = config.markdown_options();
+        assert!(options.mark);
+        assert!(!options.insert);
+        assert!(options.abbreviations);
+        assert!(options.heading_attributes);
+    }
+
+    #[test]
+    fn markdown_features_can_be_toggled() {
+        let toml = r#"
+            [markdown]
+            math = false
+            smart_punctuation = true
+            wiki_links = true
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let options = config.markdown_options();
+        assert!(!options.math);
+        assert!(options.footnotes, "unset keys keep their defaults");
+        assert!(options.smart_punctuation);
+        assert!(options.wiki_links);
+        assert!(!options.definition_lists);
+    }
+
+    #[test]
+    fn markdown_lang_selects_quote_style() {
+        let config: Config = toml::from_str("[markdown]\nlang = \"fr\"\n").unwrap();
+        assert_eq!(config.markdown_options().quotes, QuoteStyle::French);
+        assert_eq!(
+            Config::default().markdown_options().quotes,
+            QuoteStyle::English
+        );
+    }
+
+    #[test]
+    fn emoji_mappings_parse() {
+        let toml = r#"
+            [emoji]
+            party = "🎉"
+            logo = "assets/logo.png"
+            team = { image = "https://example.com/team.gif", alt = "[team]" }
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let resolver = config.emoji_resolver();
+        assert_eq!(resolver.replace_shortcodes(":party:"), "🎉");
+        // Image emoji fall back to `:name:` or their alt text as plain text.
+        assert_eq!(resolver.replace_shortcodes(":logo:"), ":logo:");
+        assert_eq!(resolver.replace_shortcodes(":team:"), "[team]");
+        let logo = resolver.custom().find(|(name, _)| *name == "logo");
+        assert!(matches!(logo, Some((_, Emoji::Image { .. }))));
+    }
+
+    #[test]
+    fn emoji_images_resolve_against_the_config_directory() {
+        let dir = std::env::temp_dir().join(format!("mdpeek-emoji-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [emoji]
+            logo = "assets/logo.png"
+            team = { image = "team.gif", alt = "[team]" }
+            remote = "https://example.com/remote.png"
+            party = "🎉"
+            "#,
+        )
+        .unwrap();
+        let config = Config::load_explicit(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let image = |name: &str| match config.emoji_resolver().custom().find(|(n, _)| *n == name) {
+            Some((_, Emoji::Image { src, .. })) => src.clone(),
+            other => panic!("{name}: {other:?}"),
+        };
+        assert_eq!(image("logo"), dir.join("assets/logo.png").to_string_lossy());
+        assert_eq!(image("team"), dir.join("team.gif").to_string_lossy());
+        assert_eq!(image("remote"), "https://example.com/remote.png");
+        assert_eq!(config.emoji["party"], EmojiConfig::Text("🎉".into()));
+    }
+
+    #[test]
+    fn issue_export_path_parses() {
+        let config: Config = toml::from_str("[issues]\nexport = \"issues.json\"\n").unwrap();
+        assert_eq!(config.issues.export, Some(PathBuf::from("issues.json")));
+        assert!(Config::default().issue_list().is_none());
+    }
+
+    #[test]
+    fn api_explorer_is_off_unless_a_base_url_is_set() {
+        assert!(Config::default().api_explorer().is_none());
+        let config: Config =
+            toml::from_str("[api_explorer]\nbase_url = \"http://localhost:8080/v1/\"\n").unwrap();
+        let explorer = config.api_explorer().expect("API explorer");
+        let request = HttpRequest {
+            method: "GET".into(),
+            url: "https://api.example.com/users?page=2".into(),
+            version: None,
+            headers: Vec::new(),
+            body: None,
+        };
+        assert_eq!(
+            explorer.url_for(&request),
+            "http://localhost:8080/v1/users?page=2"
+        );
+    }
+
+    #[test]
+    fn progress_file_can_be_overridden() {
+        let config: Config = toml::from_str("[progress]\nfile = \"/tmp/steps.json\"\n").unwrap();
+        let store = config.progress_store().expect("progress store");
+        assert_eq!(store.path(), Path::new("/tmp/steps.json"));
+    }
+
+    #[test]
+    fn analyzer_rules_parse_and_invalid_ones_are_dropped() {
+        let toml = r#"
+            [analyzer.sections]
+            "risk register" = "decision"
+
+            [[analyzer.doctypes]]
+            type = "minutes"
+            filename = "meetings/*.md"
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let extras = config.analysis_extras();
+        assert_eq!(extras.rules.doctypes.len(), 1);
+        assert_eq!(extras.rules.sections.len(), 1);
+
+        let invalid: Config =
+            toml::from_str("[[analyzer.doctypes]]\ntype = \"minutes\"\nconfidence = 0.9\n")
+                .unwrap();
+        assert_eq!(
+            invalid.classification_rules(),
+            ClassificationRules::default()
+        );
+    }
+
+    #[test]
+    fn analyzer_lang_falls_back_to_markdown_lang() {
+        let config: Config = toml::from_str("[markdown]\nlang = \"de\"\n").unwrap();
+        assert_eq!(config.classification_rules().lang.as_deref(), Some("de"));
+        let config: Config =
+            toml::from_str("[markdown]\nlang = \"de\"\n\n[analyzer]\nlang = \"fr\"\n").unwrap();
+        assert_eq!(config.classification_rules().lang.as_deref(), Some("fr"));
+        let invalid: Config = toml::from_str("[analyzer]\nlang = \"xx\"\n").unwrap();
+        assert_eq!(invalid.classification_rules().lang, None);
+    }
+
+    #[test]
+    fn lint_rules_parse_and_are_opt_in() {
+        use mdpeek_analyzer::lint::Severity;
+        assert!(Config::default().linter().is_none());
+        let toml = r#"
+            [lint]
+            enabled = true
+            sentence_length = 25
+            passive_voice = false
+
+            [lint.severity]
+            repeated-word = "error"
+
+            [[lint.substitute]]
+            pattern = "\\butilize\\b"
+            replacement = "use"
+
+            [[lint.ban]]
+            words = ["simply"]
+
+            [[lint.terms]]
+            preferred = "GitHub"
+            variants = ["Github"]
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.lint.sentence_length, 25);
+        assert!(!config.lint.passive_voice);
+        assert_eq!(config.lint.severity["repeated-word"], Severity::Error);
+        assert_eq!(config.lint.substitutions[0].replacement, "use");
+        assert_eq!(config.lint.bans[0].words, vec!["simply"]);
+        assert_eq!(config.lint.terms[0].preferred, "GitHub");
+        assert!(config.linter().is_some());
+    }
+
+    #[test]
+    fn lint_unknown_key_is_rejected() {
+        assert!(toml::from_str::<Config>("[lint]\nenable = true\n").is_err());
+    }
+
     #[test]
     fn explicit_missing_path_falls_back_to_defaults() {
         let config = Config::load_explicit(Path::new("/no/such/mdpeek-config.toml"));
@@ -2,15 +2,32 @@ mod cli;
 mod config;
 mod tui;
 
-use crate::cli::{Cli, Mode, ThemeChoice};
+use crate::cli::{ActionsFormat, Cli, Mode, OutputFormat, ThemeChoice};
 use crate::config::{BrowserTheme, Config};
 use anyhow::Result;
-use mdpeek_analyzer::GenerationConfig;
-use mdpeek_render_term::{TerminalEmitter, Theme};
+use mdpeek_analyzer::analyzer::changelog::{self, Release};
+use mdpeek_analyzer::analyzer::contract::Contract;
+use mdpeek_analyzer::analyzer::http::HttpExchange;
+use mdpeek_analyzer::analyzer::minutes::{self, Minutes};
+use mdpeek_analyzer::analyzer::procedure::Procedure;
+use mdpeek_analyzer::analyzer::recipe::{Ingredient, Recipe};
+use mdpeek_analyzer::analyzer::snippet;
+use mdpeek_analyzer::issues::IssueList;
+use mdpeek_analyzer::rules::ClassificationRules;
+use mdpeek_analyzer::{AnalysisExtras, Generator, RulesGenerator};
+use mdpeek_check::{
+    ConfigFormat, Repo, RepoFinding, check_changelog, check_document, orphaned_issues,
+};
+use mdpeek_gfm::source_lines::source_lines;
+use mdpeek_gfm::{EmojiResolver, MarkdownOptions};
+use mdpeek_render_term::{CodeCheck, TerminalEmitter, Theme};
+use mdpeek_server::adrs;
+use mdpeek_server::api_explorer::ApiExplorer;
 use mdpeek_server::serve;
+use mdpeek_server::todos::{self, TodoFilter};
 use mdpeek_watcher::notify_on_change;
 use pulldown_cmark::Parser;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Once;
 use tracing::error;
 use tracing_subscriber::EnvFilter;
@@ -23,45 +40,122 @@ fn main() -> Result<()> {
         None => Config::load(),
     };
     let mode = cmd.resolve_mode(&config)?;
-    // Generation policy (rules-first vs LLM-first) is read from config at startup;
-    // Layer 3's generator will consult it. Server mode is the generative-UI path,
+    // Generation policy (rules-first vs LLM-first) is read from config at startup
+    // and consulted by Layer 3's generator. Server mode is the generative-UI path,
     // so it is where the policy takes effect.
     let generation = config.generation_config();
+    let markdown = config.markdown_options();
+    let emoji = config.emoji_resolver();
     match mode {
         Mode::Serve {
             file,
             host,
             port,
             theme,
-        } => handle_serve(file, host, port, theme, generation),
+        } => {
+            init_tracing();
+            tracing::info!(
+                "generation policy: {:?} (llm {}, confidence_threshold {})",
+                generation.strategy,
+                if generation.is_rules_only() {
+                    "disabled — rules only"
+                } else {
+                    "enabled"
+                },
+                generation.confidence_threshold,
+            );
+            // Built after tracing is up so a missing API key is reported.
+            let generator = config.generator();
+            let extras = config.analysis_extras();
+            let api_explorer = config.api_explorer();
+            handle_serve(
+                file,
+                host,
+                port,
+                theme,
+                generator,
+                markdown,
+                emoji,
+                extras,
+                api_explorer,
+            )
+        }
         Mode::Term {
             file,
             watch,
             theme,
             pager,
-        } => handle_term(file, watch, theme, pager),
+        } => {
+            let extras = config.analysis_extras();
+            handle_term(file, watch, theme, pager, markdown, emoji, extras)
+        }
+        Mode::Todos {
+            dir,
+            filter,
+            code,
+            format,
+        } => handle_todos(
+            &dir,
+            &filter,
+            code,
+            format,
+            &markdown,
+            &config.classification_rules(),
+        )?,
+        Mode::Adrs { dir, format } => {
+            handle_adrs(&dir, format, &markdown, &config.classification_rules())?
+        }
+        Mode::Analyze { file, format } => {
+            handle_analyze(&file, format, &markdown, &config.classification_rules())?
+        }
+        Mode::Changelog {
+            file,
+            latest,
+            format,
+        } => {
+            if handle_changelog(&file, latest, format, &markdown)? > 0 {
+                std::process::exit(1);
+            }
+        }
+        Mode::Actions { file, format } => handle_actions(&file, format, &markdown)?,
+        Mode::Check {
+            files,
+            root,
+            issues,
+            format,
+        } => {
+            // An explicit `--issues` file must load; the config's is best-effort.
+            let issues = match issues {
+                Some(path) => Some(IssueList::load(&path).map_err(anyhow::Error::msg)?),
+                None => config.issue_list(),
+            };
+            let extras = AnalysisExtras {
+                rules: config.classification_rules(),
+                issues,
+                linter: config.linter(),
+                ..AnalysisExtras::default()
+            };
+            if handle_check(&files, &root, extras, format, &markdown)? > 0 {
+                std::process::exit(1);
+            }
+        }
     }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_serve(
     root: PathBuf,
     host: String,
     port: String,
     theme: BrowserTheme,
-    generation: GenerationConfig,
+    generator: Box<dyn Generator>,
+    markdown: MarkdownOptions,
+    emoji: EmojiResolver,
+    extras: AnalysisExtras,
+    api_explorer: Option<ApiExplorer>,
 ) {
-    init_tracing();
-    tracing::info!(
-        "generation policy: {:?} (llm {}, confidence_threshold {})",
-        generation.strategy,
-        if generation.is_rules_only() {
-            "disabled — rules only"
-        } else {
-            "enabled"
-        },
-        generation.confidence_threshold,
-    );
+    tracing::info!("semantic generator: {}", generator.name());
     // Map the binary's config theme onto the server crate's own theme so the
     // server stays independent of the binary's config types.
     let theme = match theme {
@@ -71,10 +165,547 @@ fn handle_serve(
     // `serve` discovers the repo/worktree markdown tree (explorer mode, #14) and
     // picks a valid active file, so we hand off even when `root` doesn't exist
     // (e.g. the default README.md is absent) rather than bailing out here.
-    serve(root, host, port, theme);
+    serve(
+        root,
+        host,
+        port,
+        theme,
+        markdown,
+        emoji,
+        generator.into(),
+        extras,
+        api_explorer,
+    );
+}
+
+fn handle_todos(
+    dir: &Path,
+    filter: &TodoFilter,
+    code: bool,
+    format: OutputFormat,
+    markdown: &MarkdownOptions,
+    rules: &ClassificationRules,
+) -> Result<()> {
+    let todos: Vec<_> = todos::collect(dir, markdown, rules, code)
+        .into_iter()
+        .filter(|t| filter.matches(t))
+        .collect();
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&todos)?),
+        OutputFormat::Text => {
+            // Name the worktree only when entries come from more than one.
+            let many = todos.iter().any(|t| t.worktree != todos[0].worktree);
+            for t in &todos {
+                let prefix = if many {
+                    format!("{}:", t.branch.as_deref().unwrap_or(&t.worktree))
+                } else {
+                    String::new()
+                };
+                let label = match (t.marker.as_str(), t.done) {
+                    ("task", true) => format!("[x] {}", t.text),
+                    ("task", false) => format!("[ ] {}", t.text),
+                    (marker, _) => format!("{}: {}", marker.to_uppercase(), t.text),
+                };
+                println!("{prefix}{}:{}: {label}", t.rel, t.line);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_adrs(
+    dir: &Path,
+    format: OutputFormat,
+    markdown: &MarkdownOptions,
+    rules: &ClassificationRules,
+) -> Result<()> {
+    let index = adrs::collect(dir, markdown, rules);
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&index)?),
+        OutputFormat::Text => {
+            let many = index
+                .adrs
+                .iter()
+                .any(|a| a.worktree != index.adrs[0].worktree);
+            let numbers = |ns: &[u32]| ns.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+            for a in &index.adrs {
+                let prefix = if many {
+                    format!("{}:", a.branch.as_deref().unwrap_or(&a.worktree))
+                } else {
+                    String::new()
+                };
+                let mut links = String::new();
+                if !a.supersedes.is_empty() {
+                    links.push_str(&format!(" (supersedes {})", numbers(&a.supersedes)));
+                }
+                if !a.superseded_by.is_empty() {
+                    links.push_str(&format!(" (superseded by {})", numbers(&a.superseded_by)));
+                }
+                let commit = a.commit.as_ref().map_or(String::new(), |c| {
+                    format!("  {}", &c.hash[..c.hash.len().min(7)])
+                });
+                println!(
+                    "{:>4}  {:<10}  {:<10}  {}{links}  {prefix}{}{commit}",
+                    a.number.map_or("-".to_string(), |n| n.to_string()),
+                    a.status.map_or("-", |s| s.as_str()),
+                    a.date.as_deref().unwrap_or("-"),
+                    a.title,
+                    a.rel,
+                );
+            }
+            for d in &index.diagnostics {
+                eprintln!("{}:{}: {}: {}", d.rel, d.line, d.issue.as_str(), d.message);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_analyze(
+    file: &Path,
+    format: OutputFormat,
+    markdown: &MarkdownOptions,
+    rules: &ClassificationRules,
+) -> Result<()> {
+    let content =
+        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("{}: {e}", file.display()))?;
+    let analysis = mdpeek_analyzer::analyze_with_rules(
+        &content,
+        file.to_str(),
+        markdown,
+        rules,
+        &RulesGenerator,
+    );
+    let doc_type = &analysis.model.doc_type;
+    let stats = &analysis.stats;
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "file": file.display().to_string(),
+                "doc_type": doc_type,
+                "locale": analysis.model.locale,
+                "stats": stats,
+                "novel": analysis.novel,
+                "contract": analysis.contract,
+                "procedure": analysis.procedure,
+                "recipe": analysis.recipe,
+                "changelog": analysis.changelog,
+                "minutes": analysis.minutes,
+                "http": analysis.http,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Text => {
+            let rule = doc_type
+                .rule
+                .as_deref()
+                .map_or(String::new(), |r| format!(" via {r}"));
+            println!(
+                "{}: {:?} ({:.0}%){rule}",
+                file.display(),
+                doc_type.value,
+                doc_type.confidence * 100.0
+            );
+            println!("language {}", analysis.model.locale);
+            println!(
+                "words {}  characters {}  reading ~{} min",
+                stats.words, stats.characters, stats.reading_minutes
+            );
+            println!(
+                "code {} blocks, {} lines ({:.0}% of content)",
+                stats.code_blocks,
+                stats.code_lines,
+                stats.code_ratio * 100.0
+            );
+            println!(
+                "links {}  images {}  headings {} (max depth {})",
+                stats.links, stats.images, stats.headings, stats.max_heading_depth
+            );
+            if !stats.sections.is_empty() {
+                println!();
+                println!("{:>6}  {:>5}  section", "words", "code");
+            }
+            for s in &stats.sections {
+                let title = if s.level == 0 {
+                    "(before first heading)".to_string()
+                } else {
+                    format!("{} {}", "#".repeat(usize::from(s.level)), s.title)
+                };
+                let bloated = if s.bloated { "  [bloated]" } else { "" };
+                println!("{:>6}  {:>5}  {title}{bloated}", s.words, s.code_lines);
+            }
+            if let Some(novel) = &analysis.novel {
+                println!();
+                println!("{:>6}  chapter", "words");
+                for c in &novel.chapters {
+                    println!("{:>6}  {}. {}", c.words, c.number, c.title);
+                }
+                println!();
+                println!("{:>8}  {:>5}  character", "mentions", "first");
+                for c in &novel.characters {
+                    let first = c
+                        .first_chapter
+                        .map_or("-".to_string(), |n| format!("ch. {n}"));
+                    println!("{:>8}  {first:>5}  {}", c.mentions, c.name);
+                }
+            }
+            if let Some(contract) = &analysis.contract {
+                print_obligations(contract);
+            }
+            if let Some(procedure) = &analysis.procedure {
+                print_procedure(procedure);
+            }
+            if let Some(recipe) = &analysis.recipe {
+                print_ingredients(recipe);
+            }
+            if let Some(changelog) = &analysis.changelog {
+                println!();
+                print_releases(&changelog.releases);
+                for p in &changelog.problems {
+                    println!(
+                        "line {}: {}: {}",
+                        p.link.range.start_line,
+                        p.kind.as_str(),
+                        p.message
+                    );
+                }
+            }
+            if let Some(minutes) = &analysis.minutes {
+                print_minutes(minutes);
+            }
+            if !analysis.http.is_empty() {
+                print_http(&analysis.http);
+            }
+        }
+    }
+    Ok(())
 }
 
-fn handle_term(root: PathBuf, watch: bool, theme: ThemeChoice, pager: Option<String>) {
+/// Print a meeting's action items and decisions, or export them as
+/// iCalendar `VTODO`s.
+fn handle_actions(file: &Path, format: ActionsFormat, markdown: &MarkdownOptions) -> Result<()> {
+    let content =
+        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("{}: {e}", file.display()))?;
+    // Read as minutes whatever the file is classified as.
+    let options = markdown.for_document(&content);
+    let tree = mdpeek_analyzer::BlockTree::parse_with_options(&content, options.parser_options());
+    let minutes = minutes::extract(&tree);
+    match format {
+        ActionsFormat::Json => println!("{}", serde_json::to_string_pretty(&minutes)?),
+        ActionsFormat::Ical => print!("{}", minutes.to_ical(std::time::SystemTime::now())),
+        ActionsFormat::Text => print_minutes(&minutes),
+    }
+    Ok(())
+}
+
+/// One line per HTTP code block: the request line and the response status.
+fn print_http(exchanges: &[HttpExchange]) {
+    println!();
+    for e in exchanges {
+        let request = e
+            .request
+            .as_ref()
+            .map_or("-".to_string(), |r| format!("{} {}", r.method, r.url));
+        let response = e.response.as_ref().map_or(String::new(), |r| {
+            format!(" -> {} {}", r.status, r.reason)
+                .trim_end()
+                .to_string()
+        });
+        println!("line {}: {request}{response}", e.link.range.start_line);
+    }
+}
+
+/// Action items grouped by owner, then the decisions.
+fn print_minutes(minutes: &Minutes) {
+    for (owner, items) in minutes.by_owner() {
+        println!();
+        println!("{}", owner.unwrap_or("(no owner)"));
+        for item in items {
+            let mark = if item.done { "[x]" } else { "[ ]" };
+            let due = match &item.due {
+                Some(due) => format!("  (due {})", due.date.as_deref().unwrap_or(&due.text)),
+                None => String::new(),
+            };
+            println!("  {mark} {}{due}", item.text);
+        }
+    }
+    if !minutes.decisions.is_empty() {
+        println!();
+        println!("decisions");
+        for d in &minutes.decisions {
+            println!("  - {}", d.text);
+        }
+    }
+}
+
+/// Validate a changelog (returning the number of problems), or print the
+/// notes of its newest release when `latest` is set.
+fn handle_changelog(
+    file: &Path,
+    latest: bool,
+    format: OutputFormat,
+    markdown: &MarkdownOptions,
+) -> Result<usize> {
+    let content =
+        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("{}: {e}", file.display()))?;
+    // Parsed as a changelog whatever the file is called or classified as.
+    let options = markdown.for_document(&content);
+    let tree = mdpeek_analyzer::BlockTree::parse_with_options(&content, options.parser_options());
+    let changelog = changelog::extract(&tree);
+    if latest {
+        let release = changelog
+            .latest()
+            .ok_or_else(|| anyhow::anyhow!("{}: no released version", file.display()))?;
+        let notes = release.notes(&content);
+        match format {
+            OutputFormat::Json => {
+                let json = serde_json::json!({
+                    "version": release.version,
+                    "date": release.date,
+                    "notes": notes,
+                });
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            OutputFormat::Text => println!("{notes}"),
+        }
+        return Ok(0);
+    }
+    let root = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let problems = check_changelog(&changelog, root);
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "file": file.display().to_string(),
+                "releases": changelog.releases,
+                "problems": problems,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Text => {
+            print_releases(&changelog.releases);
+            for p in &problems {
+                eprintln!(
+                    "{}:{}:{}: {}: {}",
+                    file.display(),
+                    p.link.range.start_line,
+                    p.link.range.start_column,
+                    p.kind.as_str(),
+                    p.message
+                );
+            }
+        }
+    }
+    Ok(problems.len())
+}
+
+/// One line per release: version, date and change counts by type.
+fn print_releases(releases: &[Release]) {
+    println!("{:<14}  {:<10}  changes", "version", "date");
+    for r in releases {
+        let changes: Vec<String> = r
+            .sections
+            .iter()
+            .filter(|s| !s.entries.is_empty())
+            .map(|s| format!("{} {}", s.title, s.entries.len()))
+            .collect();
+        let yanked = if r.yanked { "  [yanked]" } else { "" };
+        println!(
+            "{:<14}  {:<10}  {}{yanked}",
+            r.version,
+            r.date.as_deref().unwrap_or("-"),
+            changes.join(", ")
+        );
+    }
+}
+
+/// The ingredients with their measures in a column of their own.
+fn print_ingredients(recipe: &Recipe) {
+    let measure = |i: &Ingredient| {
+        i.measure
+            .as_ref()
+            .map_or("-", |m| m.text.as_str())
+            .to_string()
+    };
+    let width = recipe
+        .ingredients
+        .iter()
+        .map(|i| measure(i).chars().count())
+        .max()
+        .unwrap_or(0);
+    println!();
+    match recipe.servings {
+        Some(servings) => println!("ingredients (serves {servings})"),
+        None => println!("ingredients"),
+    }
+    for i in &recipe.ingredients {
+        println!("  {:<width$}  {}", measure(i), i.item);
+    }
+}
+
+/// Prerequisites, then each step with its commands (`$`) and expected
+/// output (`→`), then the rollback steps.
+fn print_procedure(procedure: &Procedure) {
+    if !procedure.prerequisites.is_empty() {
+        println!();
+        println!("prerequisites");
+        for p in &procedure.prerequisites {
+            println!("  - {}", p.text);
+        }
+    }
+    for (title, steps) in [
+        ("steps", &procedure.steps),
+        ("rollback", &procedure.rollback),
+    ] {
+        if steps.is_empty() {
+            continue;
+        }
+        println!();
+        println!("{title}");
+        for step in steps {
+            println!("{:>3}. {}", step.number, step.title);
+            for line in step.commands.iter().flat_map(|c| c.lines()) {
+                println!("       $ {}", line.strip_prefix("$ ").unwrap_or(line));
+            }
+            for line in step.expected.iter().flat_map(|e| e.lines()) {
+                println!("       → {line}");
+            }
+        }
+    }
+}
+
+/// The obligation matrix as a table: one row per statement, grouped by party.
+fn print_obligations(contract: &Contract) {
+    let rows: Vec<[String; 5]> = contract
+        .parties
+        .iter()
+        .flat_map(|party| {
+            contract
+                .obligations_of(party)
+                .map(|o| (party.name.as_str(), o))
+        })
+        .chain(
+            contract
+                .obligations
+                .iter()
+                .filter(|o| o.party.is_none())
+                .map(|o| ("-", o)),
+        )
+        .map(|(party, o)| {
+            [
+                party.to_string(),
+                o.clause.clone().unwrap_or_else(|| "-".to_string()),
+                o.modality.as_str().to_string(),
+                o.deadline.clone().unwrap_or_else(|| "-".to_string()),
+                o.action.clone(),
+            ]
+        })
+        .collect();
+    let header = ["party", "clause", "modality", "deadline", "action"].map(str::to_string);
+    let width = |col: usize| {
+        rows.iter()
+            .chain([&header])
+            .map(|row| row[col].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let widths = [width(0), width(1), width(2), width(3)];
+    println!();
+    for row in [&header].into_iter().chain(&rows) {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(widths) {
+            line.push_str(&format!("{cell:<width$}  "));
+        }
+        line.push_str(&row[4]);
+        println!("{line}");
+    }
+}
+
+/// Check each document against the repository at `root`, print the findings
+/// and return how many there were.
+fn handle_check(
+    files: &[PathBuf],
+    root: &Path,
+    extras: AnalysisExtras,
+    format: OutputFormat,
+    markdown: &MarkdownOptions,
+) -> Result<usize> {
+    let mut repo = Repo::discover(root);
+    // Config snippets can only be validated against our own `Config`, so only
+    // when checking mdpeek's repository (or a fork of it).
+    if repo.binaries.contains("mdpeek") {
+        repo = repo.with_config_validator(|format, text| match format {
+            ConfigFormat::Toml => toml::from_str::<Config>(text)
+                .map(|_| ())
+                .map_err(|e| e.message().to_string()),
+            ConfigFormat::Json => serde_json::from_str::<Config>(text)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+        });
+    }
+    repo = repo.with_rules(extras.rules);
+    if let Some(issues) = extras.issues {
+        repo = repo.with_issues(issues);
+    }
+    if let Some(linter) = extras.linter {
+        repo = repo.with_linter(linter);
+    }
+    let mut report = Vec::new();
+    for file in files {
+        let content = std::fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", file.display()))?;
+        for finding in check_document(&content, file, &repo, markdown) {
+            report.push((file.display().to_string(), finding));
+        }
+    }
+    // Open issues whose TODO was removed are a repository-wide finding, not
+    // tied to the documents named on the command line.
+    if let Some(issues) = repo.issues() {
+        for RepoFinding { path, finding } in orphaned_issues(&repo, markdown, issues) {
+            report.push((root.join(path).display().to_string(), finding));
+        }
+    }
+    match format {
+        OutputFormat::Json => {
+            let json: Vec<_> = report
+                .iter()
+                .map(|(file, f)| {
+                    serde_json::json!({
+                        "file": file,
+                        "kind": f.kind,
+                        "message": f.message,
+                        "range": f.range,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Text => {
+            for (file, f) in &report {
+                println!(
+                    "{file}:{}:{}: {}: {}",
+                    f.range.start_line,
+                    f.range.start_column,
+                    f.kind.as_str(),
+                    f.message
+                );
+            }
+        }
+    }
+    Ok(report.len())
+}
+
+fn handle_term(
+    root: PathBuf,
+    watch: bool,
+    theme: ThemeChoice,
+    pager: Option<String>,
+    markdown: MarkdownOptions,
+    emoji: EmojiResolver,
+    extras: AnalysisExtras,
+) {
     use std::io::IsTerminal;
 
     init_tracing();
@@ -90,13 +721,13 @@ fn handle_term(root: PathBuf, watch: bool, theme: ThemeChoice, pager: Option<Str
     // Interactive live viewer: only when watching AND stdout is a real TTY.
     // Piped/redirected stdout falls through to the clear+reprint loop below.
     if watch && std::io::stdout().is_terminal() {
-        if let Err(e) = tui::run_tui(root.clone(), theme) {
+        if let Err(e) = tui::run_tui(root.clone(), theme, markdown, emoji, extras) {
             error!("TUI viewer error: {e}");
         }
         return;
     }
 
-    match render_term(&root, theme) {
+    match render_term(&root, theme, &markdown, &emoji) {
         Ok(rendered) => {
             if watch {
                 // Watch mode redraws continuously, so a pager would get in the way.
@@ -111,7 +742,7 @@ fn handle_term(root: PathBuf, watch: bool, theme: ThemeChoice, pager: Option<Str
     if watch {
         let watch_path = root.clone();
         notify_on_change(watch_path, move || {
-            if let Ok(rendered) = render_term(&root, theme) {
+            if let Ok(rendered) = render_term(&root, theme, &markdown, &emoji) {
                 clear_terminal();
                 println!("{rendered}");
             }
@@ -186,10 +817,31 @@ fn page(content: &str, pager_cfg: &Option<String>) -> std::io::Result<()> {
     Ok(())
 }
 
-fn render_term(root: &PathBuf, theme: ThemeChoice) -> Result<String> {
+fn render_term(
+    root: &PathBuf,
+    theme: ThemeChoice,
+    markdown: &MarkdownOptions,
+    emoji: &EmojiResolver,
+) -> Result<String> {
+    render_term_mapped(root, theme, markdown, emoji).map(|(rendered, _)| rendered)
+}
+
+/// [`render_term`] plus where each block landed: `(source line, rendered
+/// line)` pairs in document order.
+fn render_term_mapped(
+    root: &PathBuf,
+    theme: ThemeChoice,
+    markdown: &MarkdownOptions,
+    emoji: &EmojiResolver,
+) -> Result<(String, Vec<(u32, usize)>)> {
     let markdown_content = std::fs::read_to_string(root)?;
-    let parser = Parser::new_ext(&markdown_content, mdpeek_gfm::parser_options());
-    let parser = mdpeek_gfm::transform(parser);
+    // Front matter may override the configured feature set for this document.
+    let markdown = &markdown.for_document(&markdown_content);
+    let parser = Parser::new_ext(&markdown_content, markdown.parser_options()).into_offset_iter();
+    let (parser, lines) = source_lines(&markdown_content, parser);
+    let parser = mdpeek_gfm::extensions(&markdown_content, parser, markdown);
+    let parser = mdpeek_gfm::transform_with_emoji(parser, emoji);
+    let parser = mdpeek_gfm::typography(parser, markdown);
     let theme = match theme {
         ThemeChoice::Glow => Theme::glow(),
         ThemeChoice::Mono => Theme::mono(),
@@ -199,8 +851,23 @@ fn render_term(root: &PathBuf, theme: ThemeChoice) -> Result<String> {
         ThemeChoice::Nord => Theme::nord(),
         ThemeChoice::Ayu => Theme::ayu(),
     };
-    let mut emitter = TerminalEmitter::new(parser, theme);
-    Ok(emitter.run())
+    // Config snippets that do not parse get their error line flagged.
+    let check: CodeCheck = Box::new(|lang, code| {
+        let format = snippet::format(Some(lang))?;
+        let error = snippet::validate(format, code).err()?;
+        let line = code[..error.span.start.min(code.len())]
+            .matches('\n')
+            .count();
+        Some((
+            line,
+            format!("{format} snippet does not parse: {}", error.message),
+        ))
+    });
+    let mut emitter = TerminalEmitter::new(parser, theme)
+        .with_code_check(check)
+        .with_source_lines(lines);
+    let rendered = emitter.run();
+    Ok((rendered, emitter.line_map().to_vec()))
 }
 
 fn clear_terminal() {
@@ -6,9 +6,34 @@
 //! flicker/scroll-loss of the clear+reprint fallback. Supports wrapping,
 //! half-page scrolling, an in-app help overlay, and a vim-style `/` search with
 //! match highlighting and `n`/`N` navigation.
+//!
+//! A toggleable right-hand pane (`p`) shows the Layer 2 semantic panel —
+//! document type, outline, TODOs, risks and open questions — re-analysed on
+//! every reload. Selecting an entry scrolls the document to its block via the
+//! source-line → rendered-line map the terminal renderer records as it writes
+//! each block (see [`crate::render_term_mapped`]).
+//!
+//! For novels the pane adds chapters and the character roster, and `[` / `]`
+//! set a "read up to chapter N" spoiler mode: later chapters are blurred in
+//! the document and pane, and characters first seen after N are left out.
+//! Contracts get their obligation matrix: each party's obligations, with
+//! clause numbers, jumping to the clause.
+//!
+//! Runbooks and procedures list their steps; `s` switches the pane to a
+//! step-by-step mode (prerequisites, each step's commands and expected output,
+//! rollback) where `Space` ticks the selected step off and moves on to the
+//! next. Completed steps are saved to the progress state file.
+//!
+//! Recipes list their ingredients; `+` / `-` scale the servings and `u`
+//! switches between the written, metric and US units, re-rendering the
+//! quantities in the document in place.
+//!
+//! Changelogs list their releases (yanked ones struck through) and the
+//! problems found validating them. Meeting minutes list their action items
+//! grouped by owner, and their decisions.
 
 use crate::cli::ThemeChoice;
-use crate::render_term;
+use crate::render_term_mapped;
 use ansi_to_tui::IntoText;
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
@@ -16,20 +41,31 @@ use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
+use mdpeek_analyzer::analyzer::contract::{Modality, Obligation};
+use mdpeek_analyzer::analyzer::procedure::{Procedure, Step};
+use mdpeek_analyzer::analyzer::recipe::{Recipe, UnitSystem};
+use mdpeek_analyzer::lint::Severity;
+use mdpeek_analyzer::panel::IssueRef;
+use mdpeek_analyzer::progress::ProgressStore;
+use mdpeek_analyzer::{Analysis, AnalysisExtras, RulesGenerator};
+use mdpeek_gfm::cjk::is_east_asian_wide;
+use mdpeek_gfm::{EmojiResolver, MarkdownOptions};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Constraint, Layout, Position, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap};
 use std::io::{self, Stdout};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Once;
 use std::time::Duration;
 
 /// Horizontal / top padding between the terminal edge and the content.
 const PAD_X: u16 = 2;
 const PAD_TOP: u16 = 1;
+/// Width of the semantic side pane (capped at half the terminal).
+const PANE_WIDTH: u16 = 36;
 
 /// Clamp a scroll offset so it never scrolls past the last visible line.
 ///
@@ -149,6 +185,456 @@ fn highlight_line(line: &Line<'static>, query: &str, current: bool) -> Line<'sta
     Line::from(out)
 }
 
+/// The rendered line for a source line: the last anchor at or before it.
+fn rendered_line(anchors: &[(u32, usize)], source_line: u32) -> Option<usize> {
+    anchors
+        .iter()
+        .rev()
+        .find(|(line, _)| *line <= source_line)
+        .map(|(_, rendered)| *rendered)
+}
+
+/// One row of the semantic side pane. Rows with a `line` are selectable and
+/// jump to that source line; rows with a `step` key can be ticked off.
+#[derive(Debug, Clone, PartialEq)]
+struct PaneRow {
+    text: String,
+    style: Style,
+    line: Option<u32>,
+    step: Option<String>,
+}
+
+impl PaneRow {
+    fn label(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            line: None,
+            step: None,
+        }
+    }
+
+    fn entry(text: impl Into<String>, line: u32) -> Self {
+        Self {
+            text: text.into(),
+            style: Style::default(),
+            line: Some(line),
+            step: None,
+        }
+    }
+
+    /// A procedure step with its completion box, e.g. `[x] 2. Drain the node`.
+    fn step(step: &Step) -> Self {
+        let mark = if step.done { "[x]" } else { "[ ]" };
+        let style = if step.done {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+        Self {
+            text: format!("{mark} {}. {}", step.number, step.title),
+            style,
+            line: Some(step.link.range.start_line),
+            step: Some(step.key.clone()),
+        }
+    }
+}
+
+/// Replace every visible character with a shade block of the same width, so
+/// blurred text keeps its layout (and wrapping) but cannot be read.
+fn obscure(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            c if c.is_whitespace() => c.to_string(),
+            c if is_east_asian_wide(c) => "░░".to_string(),
+            _ => "░".to_string(),
+        })
+        .collect()
+}
+
+/// A rendered line blurred by the spoiler mode.
+fn obscure_line(line: &Line<'static>) -> Line<'static> {
+    let blurred = Style::default().fg(Color::DarkGray);
+    Line::from(
+        line.spans
+            .iter()
+            .map(|span| Span::styled(obscure(&span.content), blurred))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// How a recipe's quantities are shown: for how many servings (`None` as
+/// written) and in which units (`None` as written).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Scale {
+    servings: Option<f64>,
+    units: Option<UnitSystem>,
+}
+
+impl Scale {
+    /// The multiplier for `recipe`'s quantities.
+    fn factor(&self, recipe: &Recipe) -> f64 {
+        self.servings
+            .map_or(1.0, |servings| recipe.factor(servings))
+    }
+
+    /// The status line's description, e.g. `serves 6 (×1.5) · metric`.
+    fn describe(&self, recipe: &Recipe) -> String {
+        let factor = self.factor(recipe);
+        let mut text = match recipe.servings {
+            Some(base) => {
+                let servings = self.servings.unwrap_or(base);
+                format!("serves {servings} (×{})", round2(factor))
+            }
+            None => format!("×{}", round2(factor)),
+        };
+        match self.units {
+            Some(UnitSystem::Metric) => text.push_str(" · metric"),
+            Some(UnitSystem::Imperial) => text.push_str(" · US units"),
+            None => {}
+        }
+        text
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// `line` with the first occurrence of `from` replaced by `to`, when it lies
+/// within one span (so its styling is kept).
+fn replace_in_line(line: &Line<'static>, from: &str, to: &str) -> Option<Line<'static>> {
+    let index = line
+        .spans
+        .iter()
+        .position(|span| span.content.contains(from))?;
+    let mut line = line.clone();
+    let span = &mut line.spans[index];
+    span.content = span.content.replacen(from, to, 1).into();
+    Some(line)
+}
+
+/// Lay the analysis out as pane rows: document type, then one titled section
+/// per non-empty panel list. `upto` is the spoiler mode's last read chapter
+/// and `scale` the recipe scaler's setting.
+fn pane_rows(analysis: &Analysis, upto: Option<usize>, scale: Scale) -> Vec<PaneRow> {
+    let heading = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let doc_type = &analysis.model.doc_type;
+    let mut rows = vec![PaneRow::label(
+        format!("{:?} ({:.0}%)", doc_type.value, doc_type.confidence * 100.0),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if let Some(rule) = &doc_type.rule {
+        rows.push(PaneRow::label(
+            format!("via {rule}"),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    let panel = &analysis.panel;
+    let novel = analysis.novel.as_ref();
+    let spoiler_line = novel
+        .zip(upto)
+        .and_then(|(novel, upto)| novel.spoiler_line(upto));
+    let spoiled = |line: u32| spoiler_line.is_some_and(|from| line >= from);
+    let blurred = |row: PaneRow| match row.line {
+        Some(line) if spoiled(line) => PaneRow {
+            text: obscure(&row.text),
+            style: Style::default().fg(Color::DarkGray),
+            ..row
+        },
+        _ => row,
+    };
+    let mut section = |title: &str, entries: Vec<PaneRow>| {
+        if entries.is_empty() {
+            return;
+        }
+        rows.push(PaneRow::label("", Style::default()));
+        rows.push(PaneRow::label(title, heading));
+        rows.extend(entries);
+    };
+    section(
+        "Outline",
+        panel
+            .outline
+            .iter()
+            .map(|o| {
+                let indent = "  ".repeat(usize::from(o.level.saturating_sub(1)));
+                blurred(PaneRow::entry(
+                    format!("{indent}{}", o.title),
+                    o.link.range.start_line,
+                ))
+            })
+            .collect(),
+    );
+    if let Some(novel) = novel {
+        section(
+            "Chapters",
+            novel
+                .chapters
+                .iter()
+                .map(|c| {
+                    let line = c.link.range.start_line;
+                    let text = format!("{} ({} words)", c.title, c.words);
+                    let row = blurred(PaneRow::entry(text, line));
+                    // Keep the number readable to pick a chapter by.
+                    PaneRow {
+                        text: format!("{:>2}. {}", c.number, row.text),
+                        ..row
+                    }
+                })
+                .collect(),
+        );
+        section(
+            "Characters",
+            novel
+                .revealed(upto.unwrap_or(usize::MAX))
+                .map(|c| match (&c.first_seen, c.first_chapter) {
+                    (Some(seen), Some(chapter)) => PaneRow::entry(
+                        format!("{} ×{} (ch. {chapter})", c.name, c.mentions),
+                        seen.range.start_line,
+                    ),
+                    _ => PaneRow::label(format!("{} (not mentioned)", c.name), Style::default()),
+                })
+                .collect(),
+        );
+    }
+    if let Some(contract) = &analysis.contract {
+        let obligation = |o: &Obligation| {
+            let clause = o
+                .clause
+                .as_ref()
+                .map_or(String::new(), |c| format!("§{c} "));
+            let color = match o.modality {
+                Modality::Obligation => Color::Reset,
+                Modality::Prohibition => Color::Red,
+                Modality::Permission => Color::Green,
+            };
+            PaneRow {
+                style: Style::default().fg(color),
+                ..PaneRow::entry(
+                    format!("  {clause}{} {}", o.modality.as_str(), o.action),
+                    o.link.range.start_line,
+                )
+            }
+        };
+        let mut rows = Vec::new();
+        for party in &contract.parties {
+            rows.push(PaneRow::label(
+                party.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            rows.extend(contract.obligations_of(party).map(obligation));
+        }
+        let unbound: Vec<PaneRow> = contract
+            .obligations
+            .iter()
+            .filter(|o| o.party.is_none())
+            .map(obligation)
+            .collect();
+        if !unbound.is_empty() {
+            rows.push(PaneRow::label(
+                "(no party)",
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            rows.extend(unbound);
+        }
+        section("Obligations", rows);
+    }
+    if let Some(procedure) = &analysis.procedure {
+        section("Steps", procedure.steps.iter().map(PaneRow::step).collect());
+    }
+    if let Some(recipe) = &analysis.recipe {
+        let factor = scale.factor(recipe);
+        section(
+            "Ingredients",
+            recipe
+                .ingredients
+                .iter()
+                .map(|i| PaneRow::entry(i.scaled(factor, scale.units), i.link.range.start_line))
+                .collect(),
+        );
+    }
+    if let Some(changelog) = &analysis.changelog {
+        section(
+            "Releases",
+            changelog
+                .releases
+                .iter()
+                .map(|r| {
+                    let counts: Vec<String> = r
+                        .sections
+                        .iter()
+                        .filter(|s| !s.entries.is_empty())
+                        .map(|s| format!("{} {}", s.title, s.entries.len()))
+                        .collect();
+                    let date = r
+                        .date
+                        .as_deref()
+                        .map_or(String::new(), |d| format!(" ({d})"));
+                    let row = PaneRow::entry(
+                        format!("{}{date}  {}", r.version, counts.join(", ")),
+                        r.link.range.start_line,
+                    );
+                    if r.yanked {
+                        PaneRow {
+                            style: Style::default()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::CROSSED_OUT),
+                            ..row
+                        }
+                    } else {
+                        row
+                    }
+                })
+                .collect(),
+        );
+        section(
+            "Release problems",
+            changelog
+                .problems
+                .iter()
+                .map(|p| PaneRow {
+                    style: Style::default().fg(Color::Yellow),
+                    ..PaneRow::entry(p.message.clone(), p.link.range.start_line)
+                })
+                .collect(),
+        );
+    }
+    if let Some(minutes) = &analysis.minutes {
+        let mut rows = Vec::new();
+        for (owner, items) in minutes.by_owner() {
+            rows.push(PaneRow::label(
+                owner.unwrap_or("(no owner)").to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            rows.extend(items.into_iter().map(|item| {
+                let mark = if item.done { "[x]" } else { "[ ]" };
+                let due = item.due.as_ref().map_or(String::new(), |d| {
+                    format!(" (due {})", d.date.as_deref().unwrap_or(&d.text))
+                });
+                PaneRow::entry(
+                    format!("  {mark} {}{due}", item.text),
+                    item.link.range.start_line,
+                )
+            }));
+        }
+        section("Action items", rows);
+        section(
+            "Decisions",
+            minutes
+                .decisions
+                .iter()
+                .map(|d| PaneRow::entry(d.text.clone(), d.link.range.start_line))
+                .collect(),
+        );
+    }
+    section(
+        "TODO",
+        panel
+            .todos
+            .iter()
+            .map(|t| {
+                let mark = if t.done { "[x]" } else { "[ ]" };
+                let issue = match &t.issue {
+                    Some(IssueRef {
+                        number,
+                        state: Some(state),
+                        ..
+                    }) => format!(" (#{number} {})", state.as_str()),
+                    _ => String::new(),
+                };
+                PaneRow::entry(format!("{mark} {}{issue}", t.text), t.link.range.start_line)
+            })
+            .collect(),
+    );
+    section(
+        "Risks",
+        panel
+            .risks
+            .iter()
+            .map(|e| PaneRow::entry(e.text.clone(), e.link.range.start_line))
+            .collect(),
+    );
+    section(
+        "Open questions",
+        panel
+            .open_questions
+            .iter()
+            .map(|e| PaneRow::entry(e.text.clone(), e.link.range.start_line))
+            .collect(),
+    );
+    section(
+        "Diagnostics",
+        analysis
+            .diagnostics
+            .iter()
+            .map(|d| {
+                let fix = d.fix.as_ref().map_or(String::new(), |f| format!(" → {f}"));
+                let color = match d.severity {
+                    Severity::Error => Color::Red,
+                    Severity::Warning => Color::Yellow,
+                    Severity::Suggestion => Color::Reset,
+                };
+                PaneRow {
+                    style: Style::default().fg(color),
+                    ..PaneRow::entry(format!("{}{fix}", d.message), d.range.start_line)
+                }
+            })
+            .collect(),
+    );
+    rows
+}
+
+/// The pane of the step-by-step mode: prerequisites, then every step with the
+/// commands to run and the output to expect, then the rollback steps.
+fn step_rows(procedure: &Procedure) -> Vec<PaneRow> {
+    let heading = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(Color::DarkGray);
+    let done = procedure.steps.iter().filter(|s| s.done).count();
+    let mut rows = vec![PaneRow::label(
+        format!("Step by step ({done}/{} done)", procedure.steps.len()),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if !procedure.prerequisites.is_empty() {
+        rows.push(PaneRow::label("", Style::default()));
+        rows.push(PaneRow::label("Prerequisites", heading));
+        rows.extend(
+            procedure
+                .prerequisites
+                .iter()
+                .map(|p| PaneRow::entry(format!("• {}", p.text), p.link.range.start_line)),
+        );
+    }
+    for (title, steps) in [
+        ("Steps", &procedure.steps),
+        ("Rollback", &procedure.rollback),
+    ] {
+        if steps.is_empty() {
+            continue;
+        }
+        rows.push(PaneRow::label("", Style::default()));
+        rows.push(PaneRow::label(title, heading));
+        for step in steps {
+            rows.push(PaneRow::step(step));
+            for line in step.commands.iter().flat_map(|c| c.lines()) {
+                let line = line.strip_prefix("$ ").unwrap_or(line);
+                rows.push(PaneRow::label(
+                    format!("    $ {line}"),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            for line in step.expected.iter().flat_map(|e| e.lines()) {
+                rows.push(PaneRow::label(format!("    → {line}"), dim));
+            }
+        }
+    }
+    rows
+}
+
 /// Whether the viewer is accepting scroll/nav keys or typing a search query.
 enum Mode {
     Normal,
@@ -165,12 +651,34 @@ struct App {
     query: Option<String>,
     matches: Vec<usize>,
     current: usize,
+    /// Semantic side pane: visibility, keyboard focus and its rows.
+    show_pane: bool,
+    pane_focus: bool,
+    pane: Vec<PaneRow>,
+    /// Index into the pane's selectable rows.
+    selected: usize,
+    /// Source line → rendered line anchors for the current document.
+    anchors: Vec<(u32, usize)>,
+    /// Document size shown in the status line, e.g. `1204 words · ~6 min`.
+    stats: Option<String>,
+    analysis: Option<Analysis>,
+    /// Spoiler mode: the last chapter read (0 = only what precedes chapter 1),
+    /// or `None` to show the whole novel.
+    upto: Option<usize>,
+    /// Step-by-step mode: the pane walks through the procedure's steps.
+    step_mode: bool,
+    /// Where ticked-off steps are saved: the document and the state file.
+    progress: Option<(PathBuf, ProgressStore)>,
+    /// The last step change could not be saved.
+    unsaved: bool,
+    /// Recipe scaler: servings and units the quantities are shown in.
+    scale: Scale,
 }
 
 impl App {
-    fn new(text: Text<'static>) -> Self {
+    fn new(text: Text<'static>, anchors: Vec<(u32, usize)>, analysis: Option<Analysis>) -> Self {
         let plain = plain_lines(&text);
-        Self {
+        let mut app = Self {
             text,
             plain,
             scroll: 0,
@@ -180,19 +688,221 @@ impl App {
             query: None,
             matches: Vec::new(),
             current: 0,
-        }
+            show_pane: false,
+            pane_focus: false,
+            pane: Vec::new(),
+            selected: 0,
+            anchors,
+            stats: None,
+            analysis: None,
+            upto: None,
+            step_mode: false,
+            progress: None,
+            unsaved: false,
+            scale: Scale::default(),
+        };
+        app.set_analysis(analysis);
+        app
     }
 
-    /// Replace the document (on file change) and refresh any active search.
-    fn reload(&mut self, text: Text<'static>) {
+    /// Replace the document (on file change) and refresh any active search and
+    /// the side pane.
+    fn reload(
+        &mut self,
+        (text, anchors): (Text<'static>, Vec<(u32, usize)>),
+        analysis: Option<Analysis>,
+    ) {
         self.plain = plain_lines(&text);
         self.text = text;
+        self.anchors = anchors;
         if let Some(q) = self.query.clone() {
             self.matches = find_matches(&self.plain, &q);
             if self.current >= self.matches.len() {
                 self.current = 0;
             }
         }
+        self.set_analysis(analysis);
+    }
+
+    fn set_analysis(&mut self, analysis: Option<Analysis>) {
+        match &analysis {
+            Some(a) => {
+                self.stats = Some(format!(
+                    "{} words · ~{} min",
+                    a.stats.words, a.stats.reading_minutes
+                ));
+            }
+            None => self.stats = None,
+        }
+        self.analysis = analysis;
+        self.refresh_pane();
+    }
+
+    fn refresh_pane(&mut self) {
+        let procedure = self.analysis.as_ref().and_then(|a| a.procedure.as_ref());
+        self.pane = match (&self.analysis, procedure) {
+            (Some(_), Some(procedure)) if self.step_mode => step_rows(procedure),
+            (Some(a), _) => pane_rows(a, self.upto, self.scale),
+            (None, _) => vec![PaneRow::label("(no analysis)", Style::default())],
+        };
+        let n = self.selectable().count();
+        if self.selected >= n {
+            self.selected = n.saturating_sub(1);
+        }
+    }
+
+    /// Move the spoiler mode's last read chapter by `delta`; moving past the
+    /// last chapter turns the mode off. A no-op for documents that are not
+    /// novels with chapters.
+    fn move_spoiler(&mut self, delta: i32) {
+        let chapters = self
+            .analysis
+            .as_ref()
+            .and_then(|a| a.novel.as_ref())
+            .map_or(0, |novel| novel.chapters.len());
+        if chapters == 0 {
+            return;
+        }
+        let current = self.upto.unwrap_or(chapters).min(chapters) as i32;
+        let next = (current + delta).clamp(0, chapters as i32) as usize;
+        self.upto = (next < chapters).then_some(next);
+        self.refresh_pane();
+    }
+
+    fn recipe(&self) -> Option<&Recipe> {
+        self.analysis.as_ref()?.recipe.as_ref()
+    }
+
+    /// Add `delta` servings (or, when the recipe does not say how many it
+    /// serves, half that to the multiplier). A no-op for other documents.
+    fn scale_servings(&mut self, delta: f64) {
+        let Some(recipe) = self.recipe() else {
+            return;
+        };
+        let (current, step) = match recipe.servings {
+            Some(base) => (self.scale.servings.unwrap_or(base), delta),
+            None => (self.scale.servings.unwrap_or(1.0), delta / 2.0),
+        };
+        let next = (current + step).max(step.abs());
+        self.scale.servings = Some(next);
+        self.refresh_pane();
+    }
+
+    /// Cycle the recipe's units: as written → metric → US → as written.
+    fn cycle_units(&mut self) {
+        if self.recipe().is_none() {
+            return;
+        }
+        self.scale.units = match self.scale.units {
+            None => Some(UnitSystem::Metric),
+            Some(UnitSystem::Metric) => Some(UnitSystem::Imperial),
+            Some(UnitSystem::Imperial) => None,
+        };
+        self.refresh_pane();
+    }
+
+    /// Enter or leave the step-by-step mode. Entering opens the pane on the
+    /// first step not yet done; a no-op for documents without steps.
+    fn toggle_step_mode(&mut self, width: u16) {
+        let has_steps = self
+            .analysis
+            .as_ref()
+            .and_then(|a| a.procedure.as_ref())
+            .is_some_and(|p| !p.steps.is_empty());
+        if !has_steps {
+            return;
+        }
+        self.step_mode = !self.step_mode;
+        self.selected = 0;
+        self.refresh_pane();
+        if self.step_mode {
+            self.show_pane = true;
+            self.pane_focus = true;
+            self.select_next_step(width);
+        }
+    }
+
+    /// Tick the selected step off (or back on), save it, and after ticking
+    /// move on to the next step not yet done.
+    fn toggle_step(&mut self, width: u16) {
+        let Some(key) = self.selected_row().and_then(|i| self.pane[i].step.clone()) else {
+            return;
+        };
+        let Some(step) = self
+            .analysis
+            .as_mut()
+            .and_then(|a| a.procedure.as_mut())
+            .and_then(|p| p.step_mut(&key))
+        else {
+            return;
+        };
+        step.done = !step.done;
+        let done = step.done;
+        if let Some((path, store)) = &self.progress {
+            self.unsaved = store.set(path, &key, done).is_err();
+        }
+        self.refresh_pane();
+        if done && !key.starts_with("rollback:") {
+            self.select_next_step(width);
+        }
+    }
+
+    /// Select the first step not yet done and scroll the document to it.
+    fn select_next_step(&mut self, width: u16) {
+        let next = self
+            .analysis
+            .as_ref()
+            .and_then(|a| a.procedure.as_ref())
+            .and_then(Procedure::next_step)
+            .map(|step| step.key.clone());
+        let position = next.and_then(|key| {
+            self.selectable()
+                .position(|i| self.pane[i].step.as_deref() == Some(key.as_str()))
+        });
+        if let Some(position) = position {
+            self.selected = position;
+            self.jump_to_selected(width);
+        }
+    }
+
+    /// The first rendered line the spoiler mode blurs.
+    fn spoiler_from(&self) -> Option<usize> {
+        let novel = self.analysis.as_ref()?.novel.as_ref()?;
+        let line = novel.spoiler_line(self.upto?)?;
+        rendered_line(&self.anchors, line)
+    }
+
+    /// Pane row indices that can be selected (those linked to a source line).
+    fn selectable(&self) -> impl Iterator<Item = usize> + '_ {
+        self.pane
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.line.is_some())
+            .map(|(i, _)| i)
+    }
+
+    /// The pane row index of the current selection, if there is any entry.
+    fn selected_row(&self) -> Option<usize> {
+        self.selectable().nth(self.selected)
+    }
+
+    fn move_selection(&mut self, delta: i32) {
+        let n = self.selectable().count() as i32;
+        if n == 0 {
+            return;
+        }
+        self.selected = (self.selected as i32 + delta).clamp(0, n - 1) as usize;
+    }
+
+    /// Scroll the document to the block behind the selected pane entry.
+    fn jump_to_selected(&mut self, width: u16) {
+        let line = self
+            .selected_row()
+            .and_then(|i| self.pane[i].line)
+            .and_then(|l| rendered_line(&self.anchors, l));
+        if let Some(line) = line {
+            self.scroll_to(line, width);
+        }
     }
 
     /// Scroll so the given logical line sits near the top with a little context.
@@ -210,8 +920,44 @@ impl App {
         self.scroll_to(line, width);
     }
 
-    /// The document with search matches highlighted (or the plain styled text).
+    /// The document with search matches highlighted (or the plain styled text),
+    /// recipe quantities scaled and unread chapters blurred.
     fn display(&self) -> Text<'static> {
+        let mut text = self.highlighted();
+        if let Some(recipe) = self.recipe().filter(|_| self.scale != Scale::default()) {
+            let factor = self.scale.factor(recipe);
+            for ingredient in &recipe.ingredients {
+                let Some(measure) = &ingredient.measure else {
+                    continue;
+                };
+                let Some(i) = rendered_line(&self.anchors, ingredient.link.range.start_line) else {
+                    continue;
+                };
+                let scaled = measure.scaled(factor, self.scale.units);
+                if let Some(line) = replace_in_line(&text.lines[i], &measure.text, &scaled) {
+                    text.lines[i] = line;
+                }
+            }
+        }
+        match self.spoiler_from() {
+            Some(from) => Text::from(
+                text.lines
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| {
+                        if i >= from {
+                            obscure_line(line)
+                        } else {
+                            line.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            None => text,
+        }
+    }
+
+    fn highlighted(&self) -> Text<'static> {
         match &self.query {
             Some(q) if !q.is_empty() => {
                 let cur_line = self.matches.get(self.current).copied();
@@ -280,14 +1026,45 @@ fn install_panic_hook() {
     });
 }
 
-/// Render the file and convert its ANSI output to ratatui `Text`, degrading to
-/// a plain-text error message so the viewer stays open on transient failures.
-fn load(path: &PathBuf, theme: ThemeChoice) -> Text<'static> {
-    match render_term(path, theme) {
-        Ok(rendered) => rendered
-            .into_text()
-            .unwrap_or_else(|e| Text::raw(format!("Failed to parse rendered output: {e}"))),
-        Err(e) => Text::raw(format!("Failed to render '{}': {e}", path.display())),
+/// Run the rules analyser over the file for the side pane.
+fn load_analysis(
+    path: &Path,
+    markdown: &MarkdownOptions,
+    extras: &AnalysisExtras,
+) -> Option<Analysis> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut analysis = mdpeek_analyzer::analyze_with_rules(
+        &content,
+        path.to_str(),
+        markdown,
+        &extras.rules,
+        &RulesGenerator,
+    );
+    extras.apply(&content, Some(path), markdown, &mut analysis);
+    Some(analysis)
+}
+
+/// Render the file and convert its ANSI output to ratatui `Text`, with the
+/// rendered line of each block, degrading to a plain-text error message so the
+/// viewer stays open on transient failures.
+fn load(
+    path: &PathBuf,
+    theme: ThemeChoice,
+    markdown: &MarkdownOptions,
+    emoji: &EmojiResolver,
+) -> (Text<'static>, Vec<(u32, usize)>) {
+    match render_term_mapped(path, theme, markdown, emoji) {
+        Ok((rendered, anchors)) => match rendered.into_text() {
+            Ok(text) => (text, anchors),
+            Err(e) => (
+                Text::raw(format!("Failed to parse rendered output: {e}")),
+                Vec::new(),
+            ),
+        },
+        Err(e) => (
+            Text::raw(format!("Failed to render '{}': {e}", path.display())),
+            Vec::new(),
+        ),
     }
 }
 
@@ -300,6 +1077,14 @@ const HELP_LINES: &[(&str, &str)] = &[
     ("/", "search"),
     ("n / N", "next / previous match"),
     ("Esc", "clear search"),
+    ("p", "toggle the semantic side pane"),
+    ("Tab", "switch focus document / pane"),
+    ("[ / ]", "novel: read up to previous / next chapter"),
+    ("s", "procedure: step-by-step mode"),
+    ("+ / -", "recipe: more / fewer servings"),
+    ("u", "recipe: written / metric / US units"),
+    ("j / k, Enter", "pane: select / jump to entry"),
+    ("Space", "pane: tick the selected step off"),
     ("?", "toggle this help"),
 ];
 
@@ -320,6 +1105,38 @@ fn help_widget() -> Paragraph<'static> {
     )
 }
 
+/// The side pane: rows with the selection highlighted (reversed while the pane
+/// has focus), scrolled so the selection stays in view.
+fn pane_widget(app: &App, height: u16) -> Paragraph<'static> {
+    let selected = app.selected_row();
+    let lines: Vec<Line> = app
+        .pane
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let style = if Some(i) == selected {
+                if app.pane_focus {
+                    row.style.add_modifier(Modifier::REVERSED)
+                } else {
+                    row.style.fg(Color::Cyan)
+                }
+            } else {
+                row.style
+            };
+            Line::from(Span::styled(row.text.clone(), style))
+        })
+        .collect();
+    let offset = selected
+        .map(|i| (i as u16).saturating_sub(height.saturating_sub(3)))
+        .unwrap_or(0);
+    Paragraph::new(Text::from(lines)).scroll((offset, 0)).block(
+        Block::default()
+            .borders(Borders::LEFT)
+            .title(" Analysis ")
+            .padding(Padding::horizontal(1)),
+    )
+}
+
 /// A rectangle of the given size centred within `area`.
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + area.width.saturating_sub(width) / 2;
@@ -344,10 +1161,30 @@ fn status_line(app: &App) -> Line<'static> {
                 dim,
             )]),
             Some(q) => Line::from(vec![Span::styled(format!("/{q}  [no matches]"), dim)]),
-            None => Line::from(vec![Span::styled(
-                "q quit · j/k scroll · Ctrl-d/u half-page · / search · ? help".to_string(),
-                dim,
-            )]),
+            None => {
+                let keys = "q quit · j/k scroll · Ctrl-d/u half-page · / search · p pane · ? help";
+                let mut text = match &app.stats {
+                    Some(stats) => format!("{stats} │ {keys}"),
+                    None => keys.to_string(),
+                };
+                let chapters = app.analysis.as_ref().and_then(|a| a.novel.as_ref());
+                if let (Some(upto), Some(novel)) = (app.upto, chapters) {
+                    text = format!("read to ch. {upto}/{} │ {text}", novel.chapters.len());
+                }
+                if let Some(recipe) = app.recipe().filter(|_| app.scale != Scale::default()) {
+                    text = format!("{} │ {text}", app.scale.describe(recipe));
+                }
+                let procedure = app.analysis.as_ref().and_then(|a| a.procedure.as_ref());
+                if let Some(procedure) = procedure.filter(|_| app.step_mode) {
+                    let done = procedure.steps.iter().filter(|s| s.done).count();
+                    let unsaved = if app.unsaved { " (not saved)" } else { "" };
+                    text = format!(
+                        "steps {done}/{}{unsaved} │ Space done · s exit │ {text}",
+                        procedure.steps.len()
+                    );
+                }
+                Line::from(vec![Span::styled(text, dim)])
+            }
         },
     }
 }
@@ -392,6 +1229,31 @@ fn handle_key(app: &mut App, key: KeyEvent, viewport: u16, width: u16) -> bool {
             false
         }
         Mode::Normal => {
+            if app.show_pane && app.pane_focus {
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        app.move_selection(1);
+                        return false;
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        app.move_selection(-1);
+                        return false;
+                    }
+                    KeyCode::Enter => {
+                        app.jump_to_selected(width);
+                        return false;
+                    }
+                    KeyCode::Char(' ') => {
+                        app.toggle_step(width);
+                        return false;
+                    }
+                    KeyCode::Esc => {
+                        app.pane_focus = false;
+                        return false;
+                    }
+                    _ => {}
+                }
+            }
             match (key.code, key.modifiers) {
                 (KeyCode::Char('q'), _) => return true,
                 (KeyCode::Char('c'), KeyModifiers::CONTROL) => return true,
@@ -418,6 +1280,17 @@ fn handle_key(app: &mut App, key: KeyEvent, viewport: u16, width: u16) -> bool {
                 (KeyCode::Char('n'), _) => app.move_match(1, width),
                 (KeyCode::Char('N'), _) => app.move_match(-1, width),
                 (KeyCode::Char('?'), _) => app.show_help = true,
+                (KeyCode::Char('p'), _) => {
+                    app.show_pane = !app.show_pane;
+                    app.pane_focus = app.show_pane;
+                }
+                (KeyCode::Char('['), _) => app.move_spoiler(-1),
+                (KeyCode::Char(']'), _) => app.move_spoiler(1),
+                (KeyCode::Char('s'), _) => app.toggle_step_mode(width),
+                (KeyCode::Char('+' | '='), _) => app.scale_servings(1.0),
+                (KeyCode::Char('-'), _) => app.scale_servings(-1.0),
+                (KeyCode::Char('u'), _) => app.cycle_units(),
+                (KeyCode::Tab, _) if app.show_pane => app.pane_focus = !app.pane_focus,
                 (KeyCode::Esc, _) => {
                     app.query = None;
                     app.matches.clear();
@@ -430,18 +1303,32 @@ fn handle_key(app: &mut App, key: KeyEvent, viewport: u16, width: u16) -> bool {
 }
 
 /// Run the interactive viewer until the user quits with `q` (or Ctrl-C).
-pub fn run_tui(path: PathBuf, theme: ThemeChoice) -> Result<()> {
+pub fn run_tui(
+    path: PathBuf,
+    theme: ThemeChoice,
+    markdown: MarkdownOptions,
+    emoji: EmojiResolver,
+    extras: AnalysisExtras,
+) -> Result<()> {
     install_panic_hook();
     // Start watching before taking over the screen so no early change is lost.
     let changes = mdpeek_watcher::watch_events(&path);
     let mut guard = TerminalGuard::new()?;
 
-    let mut app = App::new(load(&path, theme));
+    let (text, anchors) = load(&path, theme, &markdown, &emoji);
+    let mut app = App::new(text, anchors, load_analysis(&path, &markdown, &extras));
+    app.progress = extras.progress.clone().map(|store| (path.clone(), store));
 
     loop {
         let size = guard.terminal.size()?;
-        // Effective text area after reserving the status row and the padding.
-        let width = size.width.saturating_sub(PAD_X * 2);
+        let pane_width = if app.show_pane {
+            PANE_WIDTH.min(size.width / 2)
+        } else {
+            0
+        };
+        // Effective text area after reserving the status row, the side pane
+        // and the padding.
+        let width = size.width.saturating_sub(pane_width + PAD_X * 2);
         let content_height = size.height.saturating_sub(1 + PAD_TOP);
         let total = total_wrapped(&app.plain, width);
         app.scroll = clamp_scroll(app.scroll, total, content_height);
@@ -450,8 +1337,13 @@ pub fn run_tui(path: PathBuf, theme: ThemeChoice) -> Result<()> {
         guard.terminal.draw(|frame| {
             let area = frame.area();
             let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(area);
-            let content = chunks[0];
             let status = chunks[1];
+            let columns = Layout::horizontal([Constraint::Min(0), Constraint::Length(pane_width)])
+                .split(chunks[0]);
+            let content = columns[0];
+            if app.show_pane {
+                frame.render_widget(pane_widget(&app, columns[1].height), columns[1]);
+            }
 
             let paragraph = Paragraph::new(display)
                 .wrap(Wrap { trim: false })
@@ -491,7 +1383,10 @@ pub fn run_tui(path: PathBuf, theme: ThemeChoice) -> Result<()> {
                 changed = true;
             }
             if changed {
-                app.reload(load(&path, theme));
+                app.reload(
+                    load(&path, theme, &markdown, &emoji),
+                    load_analysis(&path, &markdown, &extras),
+                );
             }
         }
     }
@@ -590,4 +1485,339 @@ mod tests {
                 .any(|s| s.style.bg == Some(Color::LightRed))
         );
     }
+
+    fn analysis_of(md: &str) -> Analysis {
+        mdpeek_analyzer::analyze(md, None)
+    }
+
+    #[test]
+    fn rendered_line_is_the_last_anchor_at_or_before() {
+        let anchors = vec![(1, 0), (3, 2), (5, 4), (7, 6)];
+        // A line inside a block resolves to the block's anchor.
+        assert_eq!(rendered_line(&anchors, 6), Some(4));
+        assert_eq!(rendered_line(&anchors, 7), Some(6));
+        assert_eq!(rendered_line(&anchors, 0), None);
+    }
+
+    #[test]
+    fn blocks_are_anchored_where_the_renderer_wrote_them() {
+        let md = "---\ntitle: x\n---\n# Plan\n\n:rocket: launch\n\n\
+                  | a | b |\n|---|---|\n| 1 | 2 |\n\n\
+                  - [ ] add tests\n- [x] write docs\n\nTODO: benchmark\n";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.md");
+        std::fs::write(&path, md).unwrap();
+        let (text, anchors) = load(
+            &path,
+            ThemeChoice::Mono,
+            &MarkdownOptions::default(),
+            &EmojiResolver::new(),
+        );
+        let app = App::new(text, anchors, Some(analysis_of(md)));
+        let rendered = |source| app.plain[rendered_line(&app.anchors, source).unwrap()].as_str();
+        assert_eq!(rendered(4), " Plan ");
+        assert_eq!(rendered(6), "🚀 launch");
+        assert!(rendered(8).contains("a │ b"), "{:?}", app.plain);
+        // Every TODO entry jumps to its own rendered line.
+        let todos: Vec<(&str, &str)> = app
+            .pane
+            .iter()
+            .filter(|r| r.line.is_some() && r.text.starts_with('['))
+            .map(|r| (r.text.as_str(), rendered(r.line.unwrap())))
+            .collect();
+        assert_eq!(
+            todos,
+            vec![
+                ("[ ] add tests", "  [ ] add tests"),
+                ("[x] write docs", "  [✓] write docs"),
+                ("[ ] benchmark", "TODO: benchmark"),
+            ]
+        );
+    }
+
+    #[test]
+    fn pane_rows_list_sections_with_links() {
+        let md = "# Plan\n\n## Risks\n\nIt may overheat.\n\n- [ ] add tests\n";
+        let rows = pane_rows(&analysis_of(md), None, Scale::default());
+        let texts: Vec<&str> = rows.iter().map(|r| r.text.as_str()).collect();
+        assert!(
+            texts[0].contains('%'),
+            "doc type with confidence: {texts:?}"
+        );
+        assert_eq!(texts[1], "via fallback");
+        assert!(texts.contains(&"Outline"));
+        assert!(texts.contains(&"  Risks"));
+        assert!(texts.contains(&"[ ] add tests"));
+        let todo = rows.iter().find(|r| r.text == "[ ] add tests").unwrap();
+        assert_eq!(todo.line, Some(7));
+    }
+
+    #[test]
+    fn pane_rows_list_lint_diagnostics() {
+        use mdpeek_analyzer::lint::{LintConfig, Linter};
+        let md = "# Plan\n\nRun the the tests.\n";
+        let mut analysis = analysis_of(md);
+        let extras = AnalysisExtras {
+            issues: None,
+            linter: Some(Linter::new(&LintConfig::default()).unwrap()),
+            ..AnalysisExtras::default()
+        };
+        extras.apply(md, None, &MarkdownOptions::default(), &mut analysis);
+        let rows = pane_rows(&analysis, None, Scale::default());
+        let row = rows
+            .iter()
+            .find(|r| r.text == "Repeated word 'the' → the")
+            .expect("diagnostic row");
+        assert_eq!(row.line, Some(3));
+        assert_eq!(row.style.fg, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn status_line_shows_document_size() {
+        let md = format!("# Plan\n\n{}\n", "word ".repeat(399));
+        let app = App::new(Text::from("Plan"), Vec::new(), Some(analysis_of(&md)));
+        let status = status_line(&app).to_string();
+        assert!(
+            status.starts_with("400 words · ~2 min │ q quit"),
+            "{status}"
+        );
+    }
+
+    #[test]
+    fn pane_rows_show_referenced_issue_state() {
+        let issues =
+            mdpeek_analyzer::issues::IssueList::parse(r#"[{"number": 4, "state": "closed"}]"#)
+                .unwrap();
+        let mut analysis = analysis_of("# Plan\n\nTODO(#4): retry\n\nTODO(#5): later\n");
+        analysis.panel.link_issues(&issues);
+        let texts: Vec<String> = pane_rows(&analysis, None, Scale::default())
+            .into_iter()
+            .map(|r| r.text)
+            .collect();
+        assert!(
+            texts.contains(&"[ ] retry (#4 closed)".to_string()),
+            "{texts:?}"
+        );
+        assert!(
+            texts.contains(&"[ ] later (#5 missing)".to_string()),
+            "{texts:?}"
+        );
+    }
+
+    #[test]
+    fn selecting_an_entry_scrolls_to_its_block() {
+        let md = "# Plan\n\n## Risks\n\nIt may overheat.\n\n- [ ] add tests\n";
+        let mut lines: Vec<Line> = vec![Line::from(" Plan "), Line::from("")];
+        lines.extend((0..20).map(|i| Line::from(format!("filler {i}"))));
+        lines.extend(["## Risks", "", "It may overheat.", "", "  [ ] add tests"].map(Line::from));
+        let anchors = vec![(1, 0), (3, 22), (5, 24), (7, 26)];
+        let mut app = App::new(Text::from(lines), anchors, Some(analysis_of(md)));
+        app.show_pane = true;
+        app.pane_focus = true;
+        // Outline: Plan, Risks; then the TODO entry.
+        app.move_selection(2);
+        app.jump_to_selected(80);
+        // "  [ ] add tests" is rendered line 26; scroll keeps two lines of context.
+        assert_eq!(app.scroll, 24);
+        app.move_selection(-1);
+        app.jump_to_selected(80);
+        assert_eq!(app.scroll, 20);
+    }
+
+    #[test]
+    fn spoiler_mode_blurs_later_chapters_and_hides_new_characters() {
+        let md = "---\ntype: novel\ncharacters: [Mole, Toad]\n---\n\
+                  # Chapter 1\n\nMole woke.\n\n# Chapter 2\n\nToad arrived.\n";
+        let lines = [
+            "Chapter 1",
+            "",
+            "Mole woke.",
+            "",
+            "Chapter 2",
+            "",
+            "Toad arrived.",
+        ];
+        let mut app = App::new(
+            Text::from(lines.map(Line::from).to_vec()),
+            vec![(5, 0), (7, 2), (9, 4), (11, 6)],
+            Some(analysis_of(md)),
+        );
+        let texts = |app: &App| app.pane.iter().map(|r| r.text.clone()).collect::<Vec<_>>();
+        assert!(texts(&app).contains(&"Toad ×1 (ch. 2)".to_string()));
+
+        app.move_spoiler(-1);
+        assert_eq!(app.upto, Some(1));
+        let rows = texts(&app);
+        assert!(rows.contains(&"Mole ×1 (ch. 1)".to_string()), "{rows:?}");
+        assert!(!rows.iter().any(|r| r.contains("Toad")), "{rows:?}");
+        assert!(
+            rows.contains(&" 2. ░░░░░░░ ░ ░░ ░░░░░░".to_string()),
+            "{rows:?}"
+        );
+        let shown = app.display();
+        assert_eq!(shown.lines[2].to_string(), "Mole woke.");
+        assert_eq!(shown.lines[4].to_string(), "░░░░░░░ ░");
+        assert_eq!(shown.lines[6].to_string(), "░░░░ ░░░░░░░░");
+        assert!(
+            status_line(&app)
+                .to_string()
+                .starts_with("read to ch. 1/2 │")
+        );
+
+        // Reading past the last chapter turns the mode off.
+        app.move_spoiler(1);
+        assert_eq!(app.upto, None);
+        assert_eq!(app.display().lines[6].to_string(), "Toad arrived.");
+    }
+
+    #[test]
+    fn pane_rows_group_contract_obligations_by_party() {
+        let md = "---\ntype: contract\n---\n# Agreement\n\n## 4. Delivery\n\n\
+                  The Supplier shall deliver the Goods. The Supplier must not subcontract.\n\n\
+                  ## 5. Notices\n\nNotices must be given in writing.\n";
+        let rows = pane_rows(&analysis_of(md), None, Scale::default());
+        let start = rows.iter().position(|r| r.text == "Obligations").unwrap();
+        let texts: Vec<&str> = rows[start + 1..start + 6]
+            .iter()
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Supplier",
+                "  §4 must deliver the Goods",
+                "  §4 must not subcontract",
+                "(no party)",
+                "  §5 must be given in writing",
+            ]
+        );
+        assert_eq!(rows[start + 3].style.fg, Some(Color::Red));
+        assert_eq!(rows[start + 5].line, Some(12));
+    }
+
+    #[test]
+    fn pane_rows_list_releases_and_their_problems() {
+        let md = "---\ntype: changelog\n---\n# Changelog\n\n\
+                  ## [1.1.0] - 2024-03-01\n\n### Fixed\n\n- Crash\n\n\
+                  ## [1.2.0] - 2024-02-01 [YANKED]\n";
+        let rows = pane_rows(&analysis_of(md), None, Scale::default());
+        let start = rows.iter().position(|r| r.text == "Releases").unwrap();
+        assert_eq!(rows[start + 1].text, "1.1.0 (2024-03-01)  Fixed 1");
+        assert_eq!(rows[start + 1].line, Some(6));
+        assert_eq!(rows[start + 2].style.fg, Some(Color::DarkGray));
+        let problems = rows
+            .iter()
+            .position(|r| r.text == "Release problems")
+            .unwrap();
+        assert_eq!(
+            rows[problems + 1].text,
+            "1.2.0 is listed below the older 1.1.0"
+        );
+    }
+
+    #[test]
+    fn pane_rows_group_action_items_by_owner() {
+        let md = "# Sync 2024-10-16\n\n## Action items\n\n\
+                  - [ ] @alice send the notes by Friday\n- [x] Book a room\n\n\
+                  ## Decisions\n\n- Ship on Monday\n";
+        let rows = pane_rows(&analysis_of(md), None, Scale::default());
+        let start = rows.iter().position(|r| r.text == "Action items").unwrap();
+        let texts: Vec<&str> = rows[start + 1..start + 5]
+            .iter()
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "alice",
+                "  [ ] @alice send the notes by Friday (due 2024-10-18)",
+                "(no owner)",
+                "  [x] Book a room",
+            ]
+        );
+        let decisions = rows.iter().position(|r| r.text == "Decisions").unwrap();
+        assert_eq!(rows[decisions + 1].text, "Ship on Monday");
+    }
+
+    #[test]
+    fn step_mode_ticks_steps_off_and_saves_them() {
+        let md = "---\ntype: runbook\n---\n# Deploy\n\n## Steps\n\n\
+                  1. Build\n\n   ```sh\n   make\n   ```\n\n2. Ship\n";
+        let dir = tempfile::tempdir().unwrap();
+        let doc = dir.path().join("deploy.md");
+        let store = ProgressStore::new(dir.path().join("progress.json"));
+        let mut app = App::new(Text::from("Deploy"), Vec::new(), Some(analysis_of(md)));
+        app.progress = Some((doc.clone(), store.clone()));
+        let steps = app.pane.iter().position(|r| r.text == "Steps").unwrap();
+        assert_eq!(app.pane[steps + 1].text, "[ ] 1. Build");
+
+        app.toggle_step_mode(80);
+        assert!(app.step_mode && app.show_pane && app.pane_focus);
+        let texts: Vec<&str> = app.pane.iter().map(|r| r.text.as_str()).collect();
+        assert!(texts.contains(&"    $ make"), "{texts:?}");
+        let selected = app.selected_row().unwrap();
+        assert_eq!(app.pane[selected].text, "[ ] 1. Build");
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char(' ')), 20, 80);
+        let selected = app.selected_row().unwrap();
+        assert_eq!(app.pane[selected].text, "[ ] 2. Ship");
+        assert_eq!(app.pane[0].text, "Step by step (1/2 done)");
+        assert!(status_line(&app).to_string().starts_with("steps 1/2 │"));
+        assert!(store.load(&doc).contains("Build"));
+
+        app.toggle_step_mode(80);
+        assert!(!app.step_mode);
+    }
+
+    #[test]
+    fn recipe_scaler_rewrites_quantities_in_place() {
+        let md = "# Pancakes\n\nServes 2.\n\n## Ingredients\n\n- 1 cup flour\n- 2 eggs\n\n\
+                  ## Instructions\n\n1. Mix\n";
+        let lines = [
+            "Pancakes",
+            "",
+            "Serves 2.",
+            "",
+            "Ingredients",
+            "",
+            "  • 1 cup flour",
+            "  • 2 eggs",
+        ];
+        let mut app = App::new(
+            Text::from(lines.map(Line::from).to_vec()),
+            vec![(1, 0), (3, 2), (5, 4), (7, 6), (7, 6), (8, 7)],
+            Some(analysis_of(md)),
+        );
+        let start = app
+            .pane
+            .iter()
+            .position(|r| r.text == "Ingredients")
+            .unwrap();
+        assert_eq!(app.pane[start + 1].text, "1 cup flour");
+
+        app.scale_servings(1.0);
+        assert_eq!(app.scale.servings, Some(3.0));
+        let shown = app.display();
+        assert_eq!(shown.lines[6].to_string(), "  • 1 1/2 cups flour");
+        assert_eq!(shown.lines[7].to_string(), "  • 3 eggs");
+        assert_eq!(app.pane[start + 2].text, "3 eggs");
+        assert!(
+            status_line(&app)
+                .to_string()
+                .starts_with("serves 3 (×1.5) │")
+        );
+
+        app.cycle_units();
+        assert_eq!(app.display().lines[6].to_string(), "  • 355 ml flour");
+        assert!(
+            status_line(&app)
+                .to_string()
+                .starts_with("serves 3 (×1.5) · metric │")
+        );
+        // Never below one serving.
+        for _ in 0..5 {
+            app.scale_servings(-1.0);
+        }
+        assert_eq!(app.scale.servings, Some(1.0));
+    }
 }
@@ -30,6 +30,7 @@ const MDPEEK_THEME_KEY = "mdpeek-theme";
 // live DOM updates (#16), which rebuild these panels without a page reload.
 let outlineCollapsed = false;
 let frontmatterCollapsed = false;
+let analysisCollapsed = false;
 
 // Lucide icons (https://lucide.dev) embedded inline so we don't depend on a CDN.
 const LUCIDE_MOON =
@@ -355,6 +356,833 @@ function initializeFrontmatter() {
     buildFrontmatterPanel(source ? source.textContent : "");
 }
 
+// ---------------------------------------------------------------------------
+// Semantic side panel: outline / TODO / risks / open questions from the Layer 2
+// analysis (`GET /api/analysis`, refreshed by every `update` message). Each
+// entry links to a block by `block_id`; clicking scrolls to it and flashes it.
+// ---------------------------------------------------------------------------
+
+// Last analysis payload, so panel links resolve against the matching tree.
+let lastAnalysis = null;
+
+// Novel spoiler mode: the last chapter read (0 = only what precedes chapter 1),
+// or null to show everything. Seeded from `?upto=N` and kept in the URL so a
+// reload keeps the reader's place.
+let novelUpto = (function () {
+    const n = parseInt(new URLSearchParams(location.search).get("upto"), 10);
+    return n >= 0 ? n : null;
+})();
+
+// Recipe scaler: target servings (the multiplier when the recipe does not say
+// how many it serves) and units ("" = as written, "metric", "us"). Seeded from
+// and kept in `?servings=N&units=…`, like the spoiler mode.
+let recipeServings = (function () {
+    const n = parseFloat(new URLSearchParams(location.search).get("servings"));
+    return n > 0 ? n : null;
+})();
+let recipeUnits = new URLSearchParams(location.search).get("units") || "";
+
+// Rendered element(s) a block kind becomes, for locating a block in the DOM.
+const BLOCK_SELECTORS = {
+    heading: "h1, h2, h3, h4, h5, h6",
+    paragraph: "p",
+    item: "li",
+    code_block: "pre",
+    block_quote: "blockquote",
+    list: "ul, ol",
+    table: "table",
+};
+
+function findBlock(blocks, id) {
+    for (let i = 0; i < blocks.length; i++) {
+        if (blocks[i].id === id) {
+            return blocks[i];
+        }
+        const hit = findBlock(blocks[i].children || [], id);
+        if (hit) {
+            return hit;
+        }
+    }
+    return null;
+}
+
+// The rendered element for `link.block_id`: the element of the block's kind
+// the renderer stamped with the block's first source line (`data-source-line`).
+function elementForLink(link) {
+    const article = document.querySelector(".markdown-body");
+    if (!article || !lastAnalysis) {
+        return null;
+    }
+    const block = findBlock(lastAnalysis.tree.blocks, link.block_id);
+    if (!block) {
+        return null;
+    }
+    const line = String(block.range.start_line);
+    const candidates = article.querySelectorAll(BLOCK_SELECTORS[block.kind.kind] || "[data-source-line]");
+    return Array.from(candidates).find(function (el) {
+        return el.dataset.sourceLine === line;
+    }) || null;
+}
+
+function revealLink(link) {
+    const el = elementForLink(link);
+    if (!el) {
+        console.log("no rendered block for", link);
+        return;
+    }
+    el.scrollIntoView({ behavior: "smooth", block: "center" });
+    flashChanged([el]);
+}
+
+function analysisSection(label, rows) {
+    if (!rows.length) {
+        return null;
+    }
+    const section = document.createElement("div");
+    section.className = "mdpeek-analysis-section";
+    const heading = document.createElement("div");
+    heading.className = "mdpeek-analysis-heading";
+    heading.textContent = label + " (" + rows.length + ")";
+    section.appendChild(heading);
+    const ul = document.createElement("ul");
+    rows.forEach(function (row) {
+        const li = document.createElement("li");
+        const a = document.createElement("a");
+        a.href = "#";
+        a.textContent = row.text;
+        if (row.className) {
+            a.className = row.className;
+        }
+        if (row.indent) {
+            a.style.paddingLeft = row.indent * 10 + "px";
+        }
+        a.addEventListener("click", function (event) {
+            event.preventDefault();
+            if (row.link) {
+                revealLink(row.link);
+            }
+        });
+        if (row.link) {
+            a.title = "Line " + row.link.range.start_line;
+        }
+        li.appendChild(a);
+        ul.appendChild(li);
+    });
+    section.appendChild(ul);
+    return section;
+}
+
+// Rebuild the panel from an `Analysis` payload; a null/empty analysis removes
+// it. Safe to call repeatedly, like the front matter panel.
+function buildAnalysisPanel(analysis) {
+    const existing = document.getElementById("mdpeek-analysis");
+    if (existing) {
+        existing.remove();
+    }
+    lastAnalysis = analysis || null;
+    markSnippetErrors(analysis ? analysis.snippet_errors : []);
+    buildHttpCards(analysis ? analysis.http : []);
+    if (!analysis) {
+        applySpoiler(null);
+        return;
+    }
+    const p = analysis.panel;
+    const novel = analysis.novel;
+    const spoilerLine = spoilerStartLine(novel);
+    const spoiled = function (link) {
+        return spoilerLine !== null && link.range.start_line >= spoilerLine;
+    };
+    const sections = [
+        analysisSection("Outline", p.outline.map(function (o) {
+            return {
+                text: o.title,
+                link: o.link,
+                indent: o.level - 1,
+                className: spoiled(o.link) ? "mdpeek-spoiler" : "",
+            };
+        })),
+        novel && analysisSection("Chapters", novel.chapters.map(function (c) {
+            return {
+                text: c.number + ". " + c.title + " — " + c.words + " words",
+                link: c.link,
+                className: spoiled(c.link) ? "mdpeek-spoiler" : "",
+            };
+        })),
+        // Characters the reader has not met yet are left out entirely.
+        novel && analysisSection("Characters", novel.characters.filter(function (c) {
+            return novelUpto === null || c.first_chapter === null || c.first_chapter <= novelUpto;
+        }).map(function (c) {
+            return {
+                text: c.first_seen
+                    ? c.name + " ×" + c.mentions + " (ch. " + c.first_chapter + ")"
+                    : c.name + " (not mentioned)",
+                link: c.first_seen,
+            };
+        })),
+        analysis.contract && obligationTable(analysis.contract),
+        analysis.procedure && analysisSection("Prerequisites",
+            analysis.procedure.prerequisites.map(function (pre) {
+                return { text: "• " + pre.text, link: pre.link };
+            })),
+        analysis.recipe && analysisSection("Ingredients", analysis.recipe.ingredients.map(function (i) {
+            return { text: i.text, link: i.link, className: "mdpeek-ingredient" };
+        })),
+        analysis.procedure && stepChecklist("Steps", analysis.procedure.steps),
+        analysis.procedure && stepChecklist("Rollback", analysis.procedure.rollback),
+        analysis.changelog && analysisSection("Releases", analysis.changelog.releases.map(function (r) {
+            const counts = r.sections.filter(function (s) {
+                return s.entries.length;
+            }).map(function (s) {
+                return s.title + " " + s.entries.length;
+            });
+            return {
+                text: r.version + (r.date !== null ? " (" + r.date + ")" : "") +
+                    (counts.length ? " — " + counts.join(", ") : ""),
+                link: r.link,
+                className: r.yanked ? "mdpeek-release-yanked" : "",
+            };
+        })),
+        analysis.minutes && actionItems(analysis.minutes),
+        analysis.minutes && analysisSection("Decisions", analysis.minutes.decisions),
+        // Version order, dates, duplicates and unknown change types.
+        analysis.changelog && analysisSection("Release problems",
+            analysis.changelog.problems.map(function (pr) {
+                return { text: pr.message, link: pr.link, className: "mdpeek-lint-warning" };
+            })),
+        analysisSection("TODO", p.todos.map(function (t) {
+            // `TODO(#12)` entries carry the issue's state once joined against
+            // the configured issue export.
+            const issue = t.issue && t.issue.state
+                ? " (#" + t.issue.number + " " + t.issue.state + ")"
+                : "";
+            return {
+                text: (t.done ? "☑ " : "☐ ") + t.text + issue,
+                link: t.link,
+                className: t.done ? "mdpeek-analysis-done" : "",
+            };
+        })),
+        // JSON/YAML/TOML code blocks that do not parse.
+        analysisSection("Snippet errors", analysis.snippet_errors.map(function (e) {
+            return { text: snippetErrorText(e), link: e.link, className: "mdpeek-lint-error" };
+        })),
+        analysisSection("Risks", p.risks),
+        analysisSection("Open questions", p.open_questions),
+        // Prose linter findings (`[lint]` in config.toml).
+        analysisSection("Diagnostics", (analysis.diagnostics || []).filter(function (d) {
+            return d.block_id !== null;
+        }).map(function (d) {
+            return {
+                text: d.message + (d.fix !== null ? " → " + d.fix : ""),
+                link: { block_id: d.block_id, range: d.range },
+                className: "mdpeek-lint-" + d.severity,
+            };
+        })),
+        // Per-section sizes; oversized sections are highlighted.
+        analysisSection("Sections", analysis.stats.sections.filter(function (s) {
+            return s.link;
+        }).map(function (s) {
+            return {
+                text: s.title + " — " + s.words + " words",
+                link: s.link,
+                indent: s.level - 1,
+                className: s.bloated ? "mdpeek-analysis-bloated" : "",
+            };
+        })),
+    ].filter(Boolean);
+    if (!sections.length) {
+        return;
+    }
+
+    const panel = document.createElement("aside");
+    panel.id = "mdpeek-analysis";
+
+    const title = document.createElement("div");
+    title.id = "mdpeek-analysis-title";
+    const docType = analysis.model.doc_type;
+    title.innerHTML = "<span>Analysis · " + escapeText(docType.value) +
+        "</span><span aria-hidden=\"true\">▾</span>";
+    if (docType.rule) {
+        // Which classification rule fired, e.g. `analyzer.doctypes[0] (…)`.
+        title.firstElementChild.title = "via " + docType.rule;
+    }
+    title.addEventListener("click", function () {
+        analysisCollapsed = panel.classList.toggle("mdpeek-collapsed");
+        title.lastElementChild.textContent = analysisCollapsed ? "▸" : "▾";
+    });
+    panel.appendChild(title);
+
+    const body = document.createElement("div");
+    body.id = "mdpeek-analysis-body";
+    const st = analysis.stats;
+    const summary = document.createElement("div");
+    summary.className = "mdpeek-analysis-stats";
+    summary.textContent = st.words + " words · ~" + st.reading_minutes + " min · " +
+        Math.round(st.code_ratio * 100) + "% code · " + st.links + " links · " +
+        st.images + " images · depth " + st.max_heading_depth;
+    body.appendChild(summary);
+    if (novel && novel.chapters.length) {
+        body.appendChild(spoilerControl(novel));
+    }
+    if (analysis.recipe && analysis.recipe.ingredients.length) {
+        body.appendChild(recipeControl(analysis.recipe));
+    }
+    if (analysis.minutes && analysis.minutes.actions.length) {
+        const ics = document.createElement("a");
+        ics.className = "mdpeek-ics";
+        ics.href = "/api/minutes.ics";
+        ics.download = "";
+        ics.textContent = "Export action items (.ics)";
+        body.appendChild(ics);
+    }
+    sections.forEach(function (s) {
+        body.appendChild(s);
+    });
+    panel.appendChild(body);
+
+    // Restore collapse state carried over from a previous render.
+    if (analysisCollapsed) {
+        panel.classList.add("mdpeek-collapsed");
+        title.lastElementChild.textContent = "▸";
+    }
+
+    document.body.appendChild(panel);
+    applySpoiler(spoilerLine === null ? null : novel.chapters[novelUpto].link);
+    if (analysis.recipe) {
+        applyRecipeScale();
+    }
+}
+
+const MODALITY_LABELS = { prohibition: "must not", permission: "may" };
+
+// Contract obligation matrix: one table row per statement, grouped by party,
+// each linking back to its clause.
+function obligationTable(contract) {
+    const rows = [];
+    contract.parties.forEach(function (party) {
+        party.obligations.forEach(function (i) {
+            rows.push([party.name, contract.obligations[i]]);
+        });
+    });
+    contract.obligations.forEach(function (o) {
+        if (o.party === null) {
+            rows.push(["—", o]);
+        }
+    });
+    if (!rows.length) {
+        return null;
+    }
+    const section = document.createElement("div");
+    section.className = "mdpeek-analysis-section";
+    const heading = document.createElement("div");
+    heading.className = "mdpeek-analysis-heading";
+    heading.textContent = "Obligations (" + rows.length + ")";
+    section.appendChild(heading);
+    const table = document.createElement("table");
+    table.className = "mdpeek-obligations";
+    table.innerHTML = "<thead><tr><th>Party</th><th>§</th><th>Obligation</th><th>Deadline</th></tr></thead>";
+    const tbody = document.createElement("tbody");
+    let previous = null;
+    rows.forEach(function (row) {
+        const o = row[1];
+        const tr = document.createElement("tr");
+        tr.className = "mdpeek-obligation-" + o.modality;
+        tr.title = o.text;
+        [
+            row[0] === previous ? "" : row[0],
+            o.clause || "",
+            o.modality === "obligation" ? o.action : MODALITY_LABELS[o.modality] + " " + o.action,
+            o.deadline || "",
+        ].forEach(function (text) {
+            const td = document.createElement("td");
+            td.textContent = text;
+            tr.appendChild(td);
+        });
+        tr.addEventListener("click", function () {
+            revealLink(o.link);
+        });
+        tbody.appendChild(tr);
+        previous = row[0];
+    });
+    table.appendChild(tbody);
+    section.appendChild(table);
+    return section;
+}
+
+// Action items of meeting minutes, one section per owner (unassigned last);
+// an item with several owners is listed under each.
+function actionItems(minutes) {
+    const groups = [];
+    minutes.actions.forEach(function (item) {
+        (item.owners.length ? item.owners : [null]).forEach(function (owner) {
+            let group = groups.find(function (g) { return g.owner === owner; });
+            if (!group) {
+                group = { owner: owner, items: [] };
+                groups.push(group);
+            }
+            group.items.push(item);
+        });
+    });
+    groups.sort(function (a, b) { return (a.owner === null) - (b.owner === null); });
+    if (!groups.length) {
+        return null;
+    }
+    const wrapper = document.createElement("div");
+    groups.forEach(function (g) {
+        wrapper.appendChild(analysisSection("Actions · " + (g.owner || "no owner"), g.items.map(function (item) {
+            const due = item.due ? " (due " + (item.due.date || item.due.text) + ")" : "";
+            return {
+                text: (item.done ? "☑ " : "☐ ") + item.text + due,
+                link: item.link,
+                className: item.done ? "mdpeek-analysis-done" : "",
+            };
+        })));
+    });
+    return wrapper;
+}
+
+// Step-by-step checklist of a runbook/procedure: a checkbox per step (saved
+// through `/api/progress`), its commands and expected output, and for the
+// main steps a "Next step" button revealing the first one not yet done.
+function stepChecklist(label, steps) {
+    if (!steps.length) {
+        return null;
+    }
+    const done = steps.filter(function (step) { return step.done; }).length;
+    const section = document.createElement("div");
+    section.className = "mdpeek-analysis-section mdpeek-steps";
+    const heading = document.createElement("div");
+    heading.className = "mdpeek-analysis-heading";
+    heading.textContent = label + " (" + done + "/" + steps.length + " done)";
+    section.appendChild(heading);
+    const next = steps.find(function (step) { return !step.done; });
+    if (label === "Steps" && next) {
+        const button = document.createElement("button");
+        button.type = "button";
+        button.className = "mdpeek-next-step";
+        button.textContent = "Next step: " + next.number + ". " + next.title;
+        button.addEventListener("click", function () {
+            revealLink(next.link);
+        });
+        section.appendChild(button);
+    }
+    const ul = document.createElement("ul");
+    steps.forEach(function (step) {
+        const li = document.createElement("li");
+        li.className = step === next ? "mdpeek-step-next" : "";
+        const box = document.createElement("input");
+        box.type = "checkbox";
+        box.checked = step.done;
+        box.addEventListener("change", function () {
+            saveStep(step, box.checked);
+        });
+        li.appendChild(box);
+        const a = document.createElement("a");
+        a.href = "#";
+        a.textContent = step.number + ". " + step.title;
+        a.title = "Line " + step.link.range.start_line;
+        a.className = step.done ? "mdpeek-analysis-done" : "";
+        a.addEventListener("click", function (event) {
+            event.preventDefault();
+            revealLink(step.link);
+        });
+        li.appendChild(a);
+        step.commands.concat(step.expected).forEach(function (text, i) {
+            const pre = document.createElement("code");
+            pre.className = i < step.commands.length ? "mdpeek-step-command" : "mdpeek-step-expected";
+            pre.textContent = (i < step.commands.length ? "$ " : "→ ") + text.replace(/^\$ /, "");
+            li.appendChild(pre);
+        });
+        ul.appendChild(li);
+    });
+    section.appendChild(ul);
+    return section;
+}
+
+// Record a step as done (or not) and redraw the panel; the checkbox is put
+// back when the server cannot save it.
+function saveStep(step, done) {
+    fetch("/api/progress", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ path: lastAnalysis.path, key: step.key, done: done }),
+    })
+        .then(function (r) {
+            if (!r.ok) {
+                return Promise.reject(r.status);
+            }
+            step.done = done;
+        })
+        .catch(function (e) { console.log("saving step progress failed", e); })
+        .then(function () { buildAnalysisPanel(lastAnalysis); });
+}
+
+// Servings stepper and units picker of the recipe scaler.
+function recipeControl(recipe) {
+    const control = document.createElement("div");
+    control.className = "mdpeek-recipe-control";
+    const base = recipe.servings;
+    const current = recipeServings !== null ? recipeServings : (base !== null ? base : 1);
+    // Whole servings, or half steps of the multiplier.
+    const step = base !== null ? 1 : 0.5;
+    const value = document.createElement("span");
+    value.textContent = base !== null ? "Serves " + current : "×" + current;
+    const button = function (text, delta) {
+        const b = document.createElement("button");
+        b.type = "button";
+        b.textContent = text;
+        b.addEventListener("click", function () {
+            const next = Math.max(step, current + delta);
+            setRecipeScale(next === (base !== null ? base : 1) ? null : next, recipeUnits);
+        });
+        return b;
+    };
+    const units = document.createElement("select");
+    [["", "as written"], ["metric", "metric"], ["us", "US units"]].forEach(function (o) {
+        const option = document.createElement("option");
+        option.value = o[0];
+        option.textContent = o[1];
+        units.appendChild(option);
+    });
+    units.value = recipeUnits;
+    units.addEventListener("change", function () {
+        setRecipeScale(recipeServings, units.value);
+    });
+    control.append(button("−", -step), value, button("+", step), units);
+    return control;
+}
+
+function setRecipeScale(servings, units) {
+    recipeServings = servings;
+    recipeUnits = units;
+    const params = new URLSearchParams(location.search);
+    if (servings === null) {
+        params.delete("servings");
+    } else {
+        params.set("servings", servings);
+    }
+    if (units) {
+        params.set("units", units);
+    } else {
+        params.delete("units");
+    }
+    const query = params.toString();
+    history.replaceState(null, "", location.pathname + (query ? "?" + query : ""));
+    buildAnalysisPanel(lastAnalysis);
+}
+
+// Rewrite the recipe's quantities in the document and the panel for the
+// current scaler setting (`/api/recipe` does the arithmetic), or put the
+// written ones back.
+function applyRecipeScale() {
+    if (recipeServings === null && !recipeUnits) {
+        document.querySelectorAll("span.mdpeek-quantity").forEach(function (span) {
+            span.textContent = span.dataset.original;
+        });
+        return;
+    }
+    const params = new URLSearchParams();
+    if (recipeServings !== null) {
+        params.set("servings", recipeServings);
+    }
+    if (recipeUnits) {
+        params.set("units", recipeUnits);
+    }
+    fetch("/api/recipe?" + params.toString())
+        .then(function (r) { return r.ok ? r.json() : Promise.reject(r.status); })
+        .then(function (scaled) {
+            const entries = document.querySelectorAll("#mdpeek-analysis a.mdpeek-ingredient");
+            scaled.ingredients.forEach(function (i, n) {
+                if (entries[n]) {
+                    entries[n].textContent = i.text;
+                }
+                const el = i.measure !== null ? elementForLink(i.link) : null;
+                if (el) {
+                    replaceQuantity(el, i.measure, i.scaled);
+                }
+            });
+        })
+        .catch(function (e) { console.log("recipe scaling failed", e); });
+}
+
+// Show `scaled` in place of the quantity `original` within list item `el`
+// (not its nested lists), wrapping it in a span on first use.
+function replaceQuantity(el, original, scaled) {
+    const existing = Array.from(el.querySelectorAll("span.mdpeek-quantity")).find(function (span) {
+        return span.closest("li") === el;
+    });
+    if (existing) {
+        existing.textContent = scaled;
+        return;
+    }
+    const walker = document.createTreeWalker(el, NodeFilter.SHOW_TEXT);
+    for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+        const at = node.textContent.indexOf(original);
+        if (at < 0 || node.parentElement.closest("li") !== el) {
+            continue;
+        }
+        const rest = node.splitText(at);
+        rest.textContent = rest.textContent.slice(original.length);
+        const span = document.createElement("span");
+        span.className = "mdpeek-quantity";
+        span.dataset.original = original;
+        span.textContent = scaled;
+        rest.parentNode.insertBefore(span, rest);
+        return;
+    }
+}
+
+// Source line where the unread part of the novel starts, or null when the
+// spoiler mode is off or covers every chapter.
+function spoilerStartLine(novel) {
+    if (!novel || novelUpto === null || novelUpto >= novel.chapters.length) {
+        return null;
+    }
+    return novel.chapters[novelUpto].link.range.start_line;
+}
+
+// "Read up to" chapter picker for the spoiler mode.
+function spoilerControl(novel) {
+    const label = document.createElement("label");
+    label.className = "mdpeek-spoiler-control";
+    label.textContent = "Read up to ";
+    const select = document.createElement("select");
+    const options = [["", "all chapters"], ["0", "before chapter 1"]].concat(
+        novel.chapters.map(function (c) {
+            return [String(c.number), c.number + ". " + c.title];
+        })
+    );
+    options.forEach(function (o) {
+        const option = document.createElement("option");
+        option.value = o[0];
+        option.textContent = o[1];
+        select.appendChild(option);
+    });
+    select.value = novelUpto === null || novelUpto >= novel.chapters.length
+        ? ""
+        : String(novelUpto);
+    select.addEventListener("change", function () {
+        novelUpto = select.value === "" ? null : parseInt(select.value, 10);
+        const params = new URLSearchParams(location.search);
+        if (novelUpto === null) {
+            params.delete("upto");
+        } else {
+            params.set("upto", novelUpto);
+        }
+        const query = params.toString();
+        history.replaceState(null, "", location.pathname + (query ? "?" + query : ""));
+        buildAnalysisPanel(lastAnalysis);
+    });
+    label.appendChild(select);
+    return label;
+}
+
+function snippetErrorText(e) {
+    return e.format + " snippet does not parse: line " + e.range.start_line + ": " + e.message;
+}
+
+// Flag the rendered code blocks whose JSON/YAML/TOML does not parse; the
+// message is shown under the block (see `pre.mdpeek-snippet-error`).
+function markSnippetErrors(errors) {
+    document.querySelectorAll(".markdown-body pre.mdpeek-snippet-error").forEach(function (el) {
+        el.classList.remove("mdpeek-snippet-error");
+        delete el.dataset.snippetError;
+    });
+    errors.forEach(function (e) {
+        const el = elementForLink(e.link);
+        if (el) {
+            el.classList.add("mdpeek-snippet-error");
+            el.dataset.snippetError = "⚠ " + snippetErrorText(e);
+        }
+    });
+}
+
+// Show each HTTP code block (`analysis.http`) as a request card. The card and
+// the code block share a wrapper in the block's place, so the article keeps
+// its block count for live patching; "Source" shows the block again.
+function buildHttpCards(exchanges) {
+    document.querySelectorAll(".markdown-body .mdpeek-http-card").forEach(function (card) {
+        card.remove();
+    });
+    exchanges.forEach(function (exchange, index) {
+        const pre = elementForLink(exchange.link);
+        if (!pre) {
+            return;
+        }
+        let wrapper = pre.parentElement;
+        if (!wrapper.classList.contains("mdpeek-http")) {
+            wrapper = document.createElement("div");
+            wrapper.className = "mdpeek-http";
+            pre.replaceWith(wrapper);
+            wrapper.appendChild(pre);
+        }
+        wrapper.insertBefore(httpCard(exchange, index, wrapper), pre);
+    });
+}
+
+function httpCard(exchange, index, wrapper) {
+    const card = document.createElement("div");
+    card.className = "mdpeek-http-card";
+    const line = document.createElement("div");
+    line.className = "mdpeek-http-line";
+    card.appendChild(line);
+    const result = document.createElement("div");
+    result.className = "mdpeek-http-result";
+    const request = exchange.request;
+    if (request) {
+        const method = document.createElement("span");
+        method.className = "mdpeek-http-method mdpeek-http-" + request.method.toLowerCase();
+        method.textContent = request.method;
+        const url = document.createElement("code");
+        url.textContent = request.url;
+        line.append(method, url);
+        const send = document.createElement("button");
+        send.textContent = "Send";
+        send.title = "Send through the mdpeek API explorer proxy";
+        send.addEventListener("click", function () {
+            sendHttp(index, send, result);
+        });
+        line.appendChild(send);
+        appendHttpMessage(card, request.headers, request.body);
+    }
+    if (exchange.response) {
+        const r = exchange.response;
+        if (request) {
+            const expected = document.createElement("div");
+            expected.className = "mdpeek-http-expected";
+            expected.appendChild(httpStatus(r.status, r.reason, "Expected "));
+            appendHttpMessage(expected, r.headers, r.body);
+            card.appendChild(expected);
+        } else {
+            line.appendChild(httpStatus(r.status, r.reason, ""));
+            appendHttpMessage(card, r.headers, r.body);
+        }
+    }
+    const source = document.createElement("button");
+    source.className = "mdpeek-http-source";
+    source.textContent = "Source";
+    source.addEventListener("click", function () {
+        wrapper.classList.toggle("mdpeek-http-show-source");
+    });
+    line.appendChild(source);
+    card.appendChild(result);
+    return card;
+}
+
+function httpStatus(status, reason, prefix) {
+    const span = document.createElement("span");
+    span.className = "mdpeek-http-status mdpeek-http-status-" + String(status).charAt(0) + "xx";
+    span.textContent = prefix + status + (reason ? " " + reason : "");
+    return span;
+}
+
+// Header table and body of a request or response.
+function appendHttpMessage(parent, headers, body) {
+    if (headers.length) {
+        const table = document.createElement("table");
+        table.className = "mdpeek-http-headers";
+        headers.forEach(function (h) {
+            const row = table.insertRow();
+            const name = document.createElement("th");
+            name.textContent = h.name;
+            row.appendChild(name);
+            row.insertCell().textContent = h.value;
+        });
+        parent.appendChild(table);
+    }
+    if (body) {
+        const div = document.createElement("div");
+        div.className = "mdpeek-http-body";
+        div.textContent = body;
+        parent.appendChild(div);
+    }
+}
+
+// Replay the `index`th HTTP block through `POST /api/http` and show what came
+// back (or why nothing did) under the card.
+function sendHttp(index, button, result) {
+    button.disabled = true;
+    result.replaceChildren();
+    result.classList.remove("mdpeek-http-failed");
+    fetch("/api/http", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ index: index }),
+    })
+        .then(function (r) {
+            if (!r.ok) {
+                return r.text().then(function (text) {
+                    return Promise.reject(text || "HTTP " + r.status);
+                });
+            }
+            return r.json();
+        })
+        .then(function (res) {
+            const status = httpStatus(res.status, res.reason, "");
+            const meta = document.createElement("span");
+            meta.className = "mdpeek-http-meta";
+            meta.textContent = res.url + " · " + res.elapsed_ms + " ms";
+            result.append(status, meta);
+            appendHttpMessage(result, res.headers, res.body);
+        })
+        .catch(function (e) {
+            result.classList.add("mdpeek-http-failed");
+            result.textContent = String(e);
+        })
+        .then(function () { button.disabled = false; });
+}
+
+// Blur the rendered document from the heading `link` points at (the first
+// unread chapter) to the end, and the outline entries that lead there. A null
+// link clears the blur.
+function applySpoiler(link) {
+    document.querySelectorAll(".mdpeek-spoiler").forEach(function (el) {
+        if (!el.closest("#mdpeek-analysis")) {
+            el.classList.remove("mdpeek-spoiler");
+        }
+    });
+    const article = document.querySelector(".markdown-body");
+    let el = link && article ? elementForLink(link) : null;
+    while (el && el.parentElement !== article) {
+        el = el.parentElement;
+    }
+    for (; el; el = el.nextElementSibling) {
+        el.classList.add("mdpeek-spoiler");
+    }
+    document.querySelectorAll("a[data-toc-id]").forEach(function (a) {
+        const target = document.getElementById(a.dataset.tocId);
+        if (target && target.closest(".mdpeek-spoiler")) {
+            a.classList.add("mdpeek-spoiler");
+        }
+    });
+}
+
+// The innermost block whose source range covers `line` (ranges end exclusive).
+function blockAtLine(blocks, line) {
+    for (let i = 0; i < blocks.length; i++) {
+        const r = blocks[i].range;
+        if (r.start_line <= line && (line < r.end_line || line === r.start_line)) {
+            return blockAtLine(blocks[i].children || [], line) || blocks[i];
+        }
+    }
+    return null;
+}
+
+function initializeAnalysis() {
+    fetch("/api/analysis")
+        .then(function (r) { return r.ok ? r.json() : Promise.reject(r.status); })
+        .then(function (analysis) {
+            buildAnalysisPanel(analysis);
+            // `/?line=N` (dashboard links, after `openRequestedFile`): reveal that
+            // line's block.
+            const line = parseInt(new URLSearchParams(location.search).get("line"), 10);
+            const block = line ? blockAtLine(analysis.tree.blocks, line) : null;
+            if (block) {
+                revealLink({ block_id: block.id, range: block.range });
+            }
+        })
+        .catch(function (e) { console.log("analysis fetch failed", e); });
+}
+
 // ---------------------------------------------------------------------------
 // Live update (#16): patch changed blocks in place instead of full-reloading.
 // ---------------------------------------------------------------------------
@@ -445,14 +1273,36 @@ function flashChanged(nodes) {
 
 // Diff the new body against the last clean snapshot and patch only the changed
 // top-level blocks into the live article. Returns the newly inserted nodes.
-function patchArticle(article, newHTML) {
-    const oldClean = Array.from(parseFragment(lastBodyHTML).children).map(function (e) {
-        return e.outerHTML;
+// The element and its descendants that carry a `data-source-line`.
+function sourceLineElements(el) {
+    const els = Array.from(el.querySelectorAll("[data-source-line]"));
+    return el.hasAttribute("data-source-line") ? [el].concat(els) : els;
+}
+
+// A block's HTML without source lines, so a block that only moved (lines
+// added or removed above it) does not count as changed.
+function withoutSourceLines(el) {
+    const copy = el.cloneNode(true);
+    sourceLineElements(copy).forEach(function (e) {
+        e.removeAttribute("data-source-line");
     });
-    const newNodes = Array.from(parseFragment(newHTML).children);
-    const newClean = newNodes.map(function (e) {
-        return e.outerHTML;
+    return copy.outerHTML;
+}
+
+// Carry the new source lines over to an unchanged (kept) live block.
+function copySourceLines(from, to) {
+    const dst = sourceLineElements(to);
+    sourceLineElements(from).forEach(function (src, i) {
+        if (dst[i]) {
+            dst[i].setAttribute("data-source-line", src.getAttribute("data-source-line"));
+        }
     });
+}
+
+function patchArticle(article, newHTML) {
+    const oldClean = Array.from(parseFragment(lastBodyHTML).children).map(withoutSourceLines);
+    const newNodes = Array.from(parseFragment(newHTML).children);
+    const newClean = newNodes.map(withoutSourceLines);
 
     // If the live block count drifted from our snapshot (unexpected external
     // mutation), fall back to a full replace to stay correct.
@@ -475,6 +1325,12 @@ function patchArticle(article, newHTML) {
     }
 
     const live = Array.from(article.children);
+    for (let i = 0; i < a; i++) {
+        copySourceLines(newNodes[i], live[i]);
+    }
+    for (let i = bOld + 1; i < n; i++) {
+        copySourceLines(newNodes[i - bOld + bNew], live[i]);
+    }
     const ref = live[bOld + 1] || null;
     for (let i = a; i <= bOld; i++) {
         article.removeChild(live[i]);
@@ -647,6 +1503,33 @@ function selectFile(path) {
     });
 }
 
+// `/?open=…&line=N` (TODO / ADR dashboard links). A link must not switch the
+// active file by itself, so the page asks for it with `POST /api/select` and
+// reloads onto the remaining query (`?line=N`). Returns whether it did.
+function openRequestedFile() {
+    const params = new URLSearchParams(location.search);
+    const path = params.get("open");
+    if (!path) {
+        return false;
+    }
+    params.delete("open");
+    fetch("/api/select", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ path: path }),
+    }).then(function (r) {
+        if (!r.ok) {
+            console.log("select rejected", r.status);
+        }
+    }).catch(function (e) {
+        console.log("select error", e);
+    }).finally(function () {
+        const query = params.toString();
+        location.replace("/" + (query ? "?" + query : ""));
+    });
+    return true;
+}
+
 function buildSidebar(data) {
     const tree = data && data.tree;
     const btn = document.getElementById("mdpeek-sidebar-toggle");
@@ -1231,6 +2114,9 @@ function closeDiff() {
 }
 
 (function() {
+    if (openRequestedFile()) {
+        return;
+    }
     const article = document.querySelector(".markdown-body");
     // Snapshot the clean server HTML before highlight/mermaid mutate the DOM.
     lastBodyHTML = article ? article.innerHTML : "";
@@ -1240,6 +2126,7 @@ function closeDiff() {
     initializeHighlight();
     initializeOutline();
     initializeFrontmatter();
+    initializeAnalysis();
     initializeTocToggle();
     initializeAutoScrollToggle();
     initializeBreadcrumb();
@@ -1276,6 +2163,10 @@ function closeDiff() {
             }
             if (msg && msg.type === "update") {
                 applyUpdate(msg.html, msg.frontmatter);
+            } else if (msg && msg.type === "analysis") {
+                // Follows each update once the (possibly LLM-refined)
+                // analysis of the new content is ready.
+                buildAnalysisPanel(msg.analysis);
             } else if (msg && msg.type === "diff-update") {
                 // Live re-diff (#15): refresh the diff view if it is open, and
                 // flash the rows/blocks that changed (#16).
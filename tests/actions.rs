@@ -0,0 +1,81 @@
+// `mdpeek actions` の統合テスト
+// 一時ディレクトリに議事録を置き、担当者ごとのアクションアイテムと決定事項の
+// テキスト出力、JSON 出力、iCalendar (VTODO) 出力を検証する。
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// 2024-10-16（水）の定例の議事録
+fn minutes_doc() -> TempDir {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("sync.md"),
+        "---\ndate: 2024-10-16\n---\n# 定例\n\n## 宿題\n\n\
+         - [ ] @alice 議事録を共有する 金曜まで\n\
+         - [x] 会議室を予約する 担当：田中\n\
+         - Review the roadmap by next Monday\n\n\
+         ## 決定事項\n\n- 月曜にリリースする\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn actions(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.current_dir(dir.path()).args(["actions", "sync.md"]);
+    cmd
+}
+
+/// 担当者ごとにまとめ、期日を解決して表示する
+#[test]
+fn actions_are_grouped_by_owner() {
+    let dir = minutes_doc();
+    actions(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "alice\n  [ ] @alice 議事録を共有する 金曜まで  (due 2024-10-18)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "田中\n  [x] 会議室を予約する 担当：田中\n",
+        ))
+        .stdout(predicate::str::contains(
+            "(no owner)\n  [ ] Review the roadmap by next Monday  (due 2024-10-21)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "decisions\n  - 月曜にリリースする\n",
+        ));
+}
+
+/// JSON には会議日・担当者・期日（記述と解決後の日付）が含まれる
+#[test]
+fn actions_as_json() {
+    let dir = minutes_doc();
+    let out = actions(&dir).args(["--format", "json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON 出力");
+    assert_eq!(json["date"], "2024-10-16");
+    assert_eq!(json["actions"][0]["owners"][0], "alice");
+    assert_eq!(json["actions"][0]["due"]["text"], "金曜");
+    assert_eq!(json["actions"][0]["due"]["date"], "2024-10-18");
+    assert_eq!(json["decisions"][0]["text"], "月曜にリリースする");
+}
+
+/// iCalendar 出力はアクションアイテムごとに VTODO を持つ
+#[test]
+fn actions_as_ical() {
+    let dir = minutes_doc();
+    actions(&dir)
+        .args(["--format", "ical"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("BEGIN:VCALENDAR\r\n"))
+        .stdout(predicate::str::contains(
+            "SUMMARY:@alice 議事録を共有する 金曜まで\r\n",
+        ))
+        .stdout(predicate::str::contains("DUE;VALUE=DATE:20241018\r\n"))
+        .stdout(predicate::str::contains("STATUS:COMPLETED\r\n"))
+        .stdout(predicate::function(|out: &str| {
+            out.matches("BEGIN:VTODO").count() == 3
+        }));
+}
@@ -0,0 +1,65 @@
+// `mdpeek adrs` の統合テスト
+// 一時ディレクトリ（git 管理外）に ADR を置き、索引の表示と
+// 置き換え（supersession）チェーンの診断を検証する。
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// ADR 1 を ADR 2 が置き換え、ADR 2 は存在しない ADR 8 も置き換えると主張する
+fn sample_repo() -> TempDir {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    let adr = dir.path().join("docs/adr");
+    std::fs::create_dir_all(&adr).unwrap();
+    std::fs::write(
+        adr.join("0001-use-mysql.md"),
+        "# 1. Use MySQL\n\nDate: 2023-01-01\n\n## Status\n\nSuperseded by [2. Use PostgreSQL](0002-use-postgresql.md)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        adr.join("0002-use-postgresql.md"),
+        "# 2. Use PostgreSQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nSupersedes ADR-1\n\nSupersedes ADR-8\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("README.md"), "# Project\n").unwrap();
+    dir
+}
+
+fn adrs(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.arg("adrs").arg(dir.path());
+    cmd
+}
+
+/// テキスト出力は番号・状態・日付・タイトルの表で、診断は stderr に出る
+#[test]
+fn adrs_prints_an_index_table() {
+    let dir = sample_repo();
+    adrs(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "   1  superseded  2023-01-01  Use MySQL (superseded by 2)  docs/adr/0001-use-mysql.md",
+        ))
+        .stdout(predicate::str::contains(
+            "   2  accepted    2024-01-01  Use PostgreSQL (supersedes 1)",
+        ))
+        .stdout(predicate::str::contains("README.md").not())
+        .stderr(predicate::str::contains(
+            "docs/adr/0002-use-postgresql.md:11: dangling: supersedes ADR 8, which does not exist",
+        ));
+}
+
+/// `--format json` では ADR と診断がまとめて出力される
+#[test]
+fn adrs_prints_json() {
+    let dir = sample_repo();
+    let output = adrs(&dir).args(["--format", "json"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON 出力");
+    let records = json["adrs"].as_array().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["status"], "superseded");
+    assert_eq!(records[0]["superseded_by"], serde_json::json!([2]));
+    assert_eq!(json["diagnostics"][0]["issue"], "dangling");
+}
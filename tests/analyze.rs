@@ -0,0 +1,311 @@
+// `mdpeek analyze` の統合テスト
+// 一時ディレクトリに文書を置き、語数・読了時間・セクションごとの分量が
+// テキスト / JSON の両形式で出力されることを検証する。
+// 小説では章と登場人物の一覧、契約書では義務マトリクスも出力される。
+// config.toml の `[analyzer]` に書いた分類ルールが使われ、どのルールで
+// 文書種別が決まったかが表示されることも確かめる。
+// 英語以外の文書は言語が判定され、その言語の見出しキーワードで分類される。
+// HTTP のコードブロックはリクエストとレスポンスに分解される。
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// 短い節と 600 語の長い節を持つ文書
+fn sample_doc() -> TempDir {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    let long = "word ".repeat(600);
+    std::fs::write(
+        dir.path().join("guide.md"),
+        format!(
+            "# Guide\n\nSee [docs](https://example.com).\n\n## Setup\n\n```sh\nmake\n```\n\n## Details\n\n{long}\n"
+        ),
+    )
+    .unwrap();
+    dir
+}
+
+fn analyze(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.current_dir(dir.path()).args(["analyze", "guide.md"]);
+    cmd
+}
+
+/// テキスト出力に合計値と節ごとの語数が並び、肥大化した節に印が付く
+#[test]
+fn analyze_prints_totals_and_section_sizes() {
+    let dir = sample_doc();
+    analyze(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("words 605"))
+        .stdout(predicate::str::contains("reading ~4 min"))
+        .stdout(predicate::str::contains("code 1 blocks, 1 lines"))
+        .stdout(predicate::str::contains(
+            "links 1  images 0  headings 3 (max depth 2)",
+        ))
+        .stdout(predicate::str::contains("     1      1  ## Setup\n"))
+        .stdout(predicate::str::contains(
+            "   601      0  ## Details  [bloated]",
+        ));
+}
+
+/// JSON 出力は文書種別と統計を含む
+#[test]
+fn analyze_outputs_json() {
+    let dir = sample_doc();
+    let output = analyze(&dir).args(["--format", "json"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file"], "guide.md");
+    assert_eq!(json["stats"]["words"], 605);
+    assert_eq!(json["stats"]["sections"].as_array().unwrap().len(), 3);
+    assert_eq!(json["stats"]["sections"][2]["bloated"], true);
+    assert!(json["doc_type"]["value"].is_string());
+}
+
+/// 小説は章ごとの語数と、初登場の章つきの登場人物一覧を出力する
+#[test]
+fn analyze_lists_novel_chapters_and_characters() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("book.md"),
+        "---\ntype: novel\ncharacters: [Mole]\n---\n# Chapter 1\n\nMole woke. He met Ratty.\n\n\
+         # Chapter 2\n\nMole and Ratty rowed. Later Toad waved at Ratty, and Toad sang. \
+         Then Toad left.\n",
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.current_dir(dir.path())
+        .args(["analyze", "book.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("     7  1. Chapter 1\n"))
+        .stdout(predicate::str::contains("       2  ch. 1  Mole\n"))
+        .stdout(predicate::str::contains("       3  ch. 1  Ratty\n"))
+        .stdout(predicate::str::contains("       3  ch. 2  Toad\n"));
+}
+
+/// 契約書は当事者ごとの義務を条項番号・期限つきの表と JSON で出力する
+#[test]
+fn analyze_prints_contract_obligation_matrix() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("contract.md"),
+        "---\ntype: contract\nparties: [Supplier, Customer]\n---\n# Supply Agreement\n\n\
+         ## 4. Delivery\n\nThe Supplier shall deliver the Goods within 30 days of the Order.\n\n\
+         ## 5. Payment\n\nThe Customer must not withhold payment.\n",
+    )
+    .unwrap();
+    let run = |format: &str| {
+        let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+        cmd.current_dir(dir.path())
+            .args(["analyze", "contract.md", "--format", format])
+            .output()
+            .unwrap()
+    };
+
+    let text = String::from_utf8(run("text").stdout).unwrap();
+    assert!(
+        text.contains(
+            "Supplier  4       must      within 30 days of the Order  \
+             deliver the Goods within 30 days of the Order\n"
+        ),
+        "{text}"
+    );
+    assert!(
+        text.contains(
+            "Customer  5       must not  -                            withhold payment\n"
+        ),
+        "{text}"
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&run("json").stdout).unwrap();
+    let contract = &json["contract"];
+    assert_eq!(contract["parties"][1]["name"], "Customer");
+    assert_eq!(contract["parties"][1]["obligations"][0], 1);
+    let obligation = &contract["obligations"][1];
+    assert_eq!(obligation["modality"], "prohibition");
+    assert_eq!(obligation["clause"], "5");
+    assert_eq!(obligation["link"]["range"]["start_line"], 13);
+}
+
+/// 手順書は前提条件・手順（コマンドと期待される出力）・切り戻しを一覧表示する
+#[test]
+fn analyze_lists_procedure_steps() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("runbook.md"),
+        "# Restart the API\n\n## Prerequisites\n\n- SSH access\n\n\
+         ## Procedure\n\n1. Stop the service\n\n   ```sh\n   $ systemctl stop api\n   ```\n\n\
+         2. Start it again\n\n   ```sh\n   systemctl start api\n   ```\n\n   Expected: active (running)\n\n\
+         ## Rollback\n\n1. Restore the previous release\n",
+    )
+    .unwrap();
+    let run = |format: &str| {
+        let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+        cmd.current_dir(dir.path())
+            .args(["analyze", "runbook.md", "--format", format])
+            .output()
+            .unwrap()
+    };
+
+    let text = String::from_utf8(run("text").stdout).unwrap();
+    assert!(text.contains("prerequisites\n  - SSH access\n"), "{text}");
+    assert!(
+        text.contains(
+            "steps\n  1. Stop the service\n       $ systemctl stop api\n  \
+             2. Start it again\n       $ systemctl start api\n       → active (running)\n"
+        ),
+        "{text}"
+    );
+    assert!(
+        text.contains("rollback\n  1. Restore the previous release\n"),
+        "{text}"
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&run("json").stdout).unwrap();
+    let steps = &json["procedure"]["steps"];
+    assert_eq!(steps[1]["key"], "Start it again");
+    assert_eq!(steps[1]["link"]["range"]["start_line"], 15);
+    assert_eq!(
+        json["procedure"]["rollback"][0]["key"],
+        "rollback:Restore the previous release"
+    );
+}
+
+/// レシピは材料ごとの分量と材料名を列に分けて出力する
+#[test]
+fn analyze_lists_recipe_ingredients() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("pancakes.md"),
+        "# Pancakes\n\nServes 4.\n\n## Ingredients\n\n- 1 1/2 cups flour\n- 2 eggs\n- Salt to taste\n\n\
+         ## Instructions\n\n1. Mix\n",
+    )
+    .unwrap();
+    let run = |format: &str| {
+        let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+        cmd.current_dir(dir.path())
+            .args(["analyze", "pancakes.md", "--format", format])
+            .output()
+            .unwrap()
+    };
+
+    let text = String::from_utf8(run("text").stdout).unwrap();
+    assert!(
+        text.contains(
+            "ingredients (serves 4)\n  1 1/2 cups  flour\n  2           eggs\n  -           Salt to taste\n"
+        ),
+        "{text}"
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&run("json").stdout).unwrap();
+    let recipe = &json["recipe"];
+    assert_eq!(recipe["servings"], 4.0);
+    assert_eq!(recipe["ingredients"][0]["measure"]["amount"], 1.5);
+    assert_eq!(recipe["ingredients"][0]["measure"]["unit"], "cup");
+    assert_eq!(recipe["ingredients"][0]["item"], "flour");
+}
+
+/// `[analyzer]` のファイル名ルールと見出しキーワードで手順書と判定され、
+/// 判定に使ったルールが表示される
+#[test]
+fn analyze_uses_configured_classification_rules() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    let config = dir.path().join("config").join("mdpeek");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("config.toml"),
+        "[analyzer.sections]\n\"作業\" = \"step\"\n\n\
+         [[analyzer.doctypes]]\ntype = \"procedure\"\nfilename = \"sop/*.md\"\nconfidence = 0.95\n",
+    )
+    .unwrap();
+    std::fs::create_dir(dir.path().join("sop")).unwrap();
+    std::fs::write(
+        dir.path().join("sop").join("stocktake.md"),
+        "# 棚卸し\n\n## 作業\n\n1. 在庫を数える\n2. 記録する\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("mdpeek")
+        .expect("mdpeek バイナリが見つからない")
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join("config"))
+        .args(["analyze", "sop/stocktake.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "sop/stocktake.md: Procedure (95%) via analyzer.doctypes[0] (filename \"sop/*.md\")\n",
+        ))
+        .stdout(predicate::str::contains("在庫を数える"));
+}
+
+/// ドイツ語の議事録は言語が de と判定され、ドイツ語の見出しで議事録と分類される
+#[test]
+fn analyze_detects_the_language_and_uses_its_keywords() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("sitzung.md"),
+        "# Sitzung des Vorstands\n\n## Tagesordnung\n\n1. Haushalt\n\n\
+         ## Teilnehmer\n\nAnna, Bernd und die Kassenwartin\n\n\
+         ## Beschlüsse\n\nDer Vorstand hat den Haushalt für das Jahr beschlossen.\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("mdpeek")
+        .expect("mdpeek バイナリが見つからない")
+        .current_dir(dir.path())
+        .args(["analyze", "sitzung.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "sitzung.md: Minutes (80%) via heading \"tagesordnung\"\nlanguage de\n",
+        ));
+
+    let output = Command::cargo_bin("mdpeek")
+        .expect("mdpeek バイナリが見つからない")
+        .current_dir(dir.path())
+        .args(["analyze", "sitzung.md", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["locale"], "de");
+}
+
+/// HTTP のコードブロックはリクエストとレスポンスに分解され、テキストでは 1 行ずつ、
+/// JSON では `http` としてメソッド・URL・ヘッダ・ボディ・ステータスが出力される
+#[test]
+fn analyze_parses_http_code_blocks() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("api.md"),
+        "# Users API\n\n```http\nPOST /users HTTP/1.1\nContent-Type: application/json\n\n\
+         {\"name\": \"alice\"}\n\nHTTP/1.1 201 Created\nLocation: /users/7\n```\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("mdpeek")
+        .expect("mdpeek バイナリが見つからない")
+        .current_dir(dir.path())
+        .args(["analyze", "api.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "line 3: POST /users -> 201 Created\n",
+        ));
+
+    let output = Command::cargo_bin("mdpeek")
+        .expect("mdpeek バイナリが見つからない")
+        .current_dir(dir.path())
+        .args(["analyze", "api.md", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let request = &json["http"][0]["request"];
+    assert_eq!(request["method"], "POST");
+    assert_eq!(request["url"], "/users");
+    assert_eq!(request["headers"][0]["name"], "Content-Type");
+    assert_eq!(request["body"], "{\"name\": \"alice\"}");
+    assert_eq!(json["http"][0]["response"]["status"], 201);
+}
@@ -0,0 +1,113 @@
+// `mdpeek changelog` の統合テスト
+// 一時ディレクトリに git リポジトリと CHANGELOG.md を作り、
+// リリース一覧・検証結果（タグの無いリリース、順序、日付）と
+// `--latest` による最新リリースのノート出力を検証する。
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const CHANGELOG: &str = "# Changelog\n\n\
+                         ## [Unreleased]\n\n### Added\n\n- Dark mode\n\n\
+                         ## [1.1.0] - 2024-03-01\n\n### Fixed\n\n- Crash on start\n\n\
+                         ## [1.0.0] - 2024-01-15\n\n### Added\n\n- First release\n\n\
+                         [1.1.0]: https://example.com/v1.1.0\n";
+
+fn git(dir: &TempDir, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .current_dir(dir.path())
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("git の実行に失敗");
+    assert!(status.success(), "git {args:?} が失敗");
+}
+
+/// CHANGELOG.md をコミットし、`tags` を付けたリポジトリ
+fn repo(changelog: &str, tags: &[&str]) -> TempDir {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(dir.path().join("CHANGELOG.md"), changelog).unwrap();
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "init"]);
+    for tag in tags {
+        git(&dir, &["tag", tag]);
+    }
+    dir
+}
+
+fn changelog(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.current_dir(dir.path()).arg("changelog");
+    cmd
+}
+
+/// すべてのリリースにタグがあれば一覧を表示して成功する
+#[test]
+fn changelog_lists_releases_when_all_are_tagged() {
+    let dir = repo(CHANGELOG, &["v1.1.0", "v1.0.0"]);
+    changelog(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1.1.0           2024-03-01  Fixed 1",
+        ))
+        .stdout(predicate::str::contains("Unreleased"))
+        .stderr(predicate::str::is_empty());
+}
+
+/// タグの無いリリースは位置付きで報告され、終了コードが 1 になる
+#[test]
+fn changelog_flags_untagged_releases() {
+    let dir = repo(CHANGELOG, &["v1.0.0"]);
+    changelog(&dir)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "CHANGELOG.md:9:1: untagged: 1.1.0 has no git tag",
+        ));
+}
+
+/// 順序の逆転・不正な日付・重複したバージョンを検出する
+#[test]
+fn changelog_validates_order_dates_and_duplicates() {
+    let md = "# Changelog\n\n## [1.0.0] - 2024-13-01\n\n## [1.1.0] - 2024-03-01\n\n## [1.0.0] - 2024-01-01\n";
+    let dir = repo(md, &["1.0.0", "1.1.0"]);
+    changelog(&dir)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "3:1: invalid-date: 1.0.0: `2024-13-01` is not a YYYY-MM-DD date",
+        ))
+        .stderr(predicate::str::contains(
+            "5:1: unordered: 1.1.0 is listed below the older 1.0.0",
+        ))
+        .stderr(predicate::str::contains(
+            "7:1: duplicate-version: 1.0.0 is listed more than once",
+        ));
+}
+
+/// `--latest` は最新リリースのノートだけを出力する（Unreleased は含まない）
+#[test]
+fn changelog_latest_prints_the_newest_release_notes() {
+    let dir = repo(CHANGELOG, &[]);
+    changelog(&dir)
+        .arg("--latest")
+        .assert()
+        .success()
+        .stdout("### Fixed\n\n- Crash on start\n");
+}
+
+/// `--latest --format json` はバージョン・日付・ノートを JSON で出力する
+#[test]
+fn changelog_latest_as_json() {
+    let dir = repo(CHANGELOG, &[]);
+    let out = changelog(&dir)
+        .args(["--latest", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON 出力");
+    assert_eq!(json["version"], "1.1.0");
+    assert_eq!(json["date"], "2024-03-01");
+    assert_eq!(json["notes"], "### Fixed\n\n- Crash on start");
+}
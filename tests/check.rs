@@ -0,0 +1,147 @@
+// `mdpeek check` の統合テスト
+// 一時ディレクトリに Cargo.toml・clap 定義・README を置き、
+// パス / バイナリ / フラグ / 設定スニペットの不一致が位置付きで報告されることを検証する。
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// `mdpeek` バイナリ（`--port` フラグ付き）と docs/ を持つリポジトリ
+fn sample_repo(readme: &str) -> TempDir {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    let root = dir.path();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"markdown-peek\"\n\n[[bin]]\nname = \"mdpeek\"\npath = \"src/main.rs\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("src/main.rs"),
+        "#[arg(short = 'p', long = \"port\")]\npub port: Option<String>,\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    std::fs::write(root.join("docs/guide.md"), "# Guide\n").unwrap();
+    std::fs::write(root.join("README.md"), readme).unwrap();
+    dir
+}
+
+fn check(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.current_dir(dir.path()).arg("check");
+    cmd
+}
+
+/// 整合している README は何も出力せず成功する
+#[test]
+fn check_passes_a_consistent_readme() {
+    let dir = sample_repo(
+        "# Usage\n\nSee [the guide](docs/guide.md) and `src/main.rs`.\n\n\
+         ```sh\ncargo run --bin mdpeek -- --port 8080\n```\n\n\
+         ## Configuration\n\n```toml\n[server]\nport = \"8080\"\n```\n",
+    );
+    check(&dir).assert().success().stdout("");
+}
+
+/// 不一致は `file:line:col: kind: message` 形式で報告され、終了コードは 1
+#[test]
+fn check_reports_mismatches_with_positions() {
+    let dir = sample_repo(
+        "# Usage\n\nSee `docs/missing.md`.\n\n\
+         ```sh\ncargo run --bin peek\nmdpeek --prot 8080\n```\n\n\
+         ## Configuration\n\n```toml\n[server]\nprot = \"8080\"\n```\n",
+    );
+    check(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "README.md:3:5: missing-path: `docs/missing.md` does not exist",
+        ))
+        .stdout(predicate::str::contains(
+            "README.md:6:17: unknown-binary: `peek` is not a binary of this repository",
+        ))
+        .stdout(predicate::str::contains(
+            "README.md:7:8: unknown-flag: `--prot` is not a known flag",
+        ))
+        .stdout(predicate::str::contains(
+            "README.md:12:1: invalid-config: config snippet does not parse: unknown field `prot`",
+        ));
+}
+
+/// 設定の見出しの外でも、パースできない JSON / YAML / TOML のコードブロックは `invalid-snippet` として報告される
+#[test]
+fn check_reports_snippets_that_do_not_parse() {
+    let dir = sample_repo(
+        "# Example\n\n```yaml\nname: demo\n  key: 1\n```\n\n```json\n{\"a\": 1,}\n```\n",
+    );
+    check(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "README.md:5:6: invalid-snippet: yaml snippet does not parse: mapping values are not allowed in this context",
+        ))
+        .stdout(predicate::str::contains(
+            "README.md:9:9: invalid-snippet: json snippet does not parse: key must be a string",
+        ));
+}
+
+/// `--format json` では各指摘が SourceRange 付きで出力される
+#[test]
+fn check_prints_findings_as_json() {
+    let dir = sample_repo("# Docs\n\n[old](docs/old.md)\n");
+    let output = check(&dir).args(["--format", "json"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON 出力");
+    let entries = json.as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["file"], "README.md");
+    assert_eq!(entries[0]["kind"], "missing_path");
+    assert_eq!(entries[0]["range"]["start_line"], 3);
+    assert_eq!(entries[0]["range"]["start_column"], 1);
+}
+
+/// `--issues` のエクスポートと照合し、クローズ済み / 存在しない issue を指す TODO を報告する
+#[test]
+fn check_flags_todos_for_closed_and_unknown_issues() {
+    let dir = sample_repo("# Usage\n\nTODO(#1): retry\n\nTODO(#2): crash\n\nTODO(#9): ghost\n");
+    std::fs::write(
+        dir.path().join("issues.json"),
+        r#"[{"number": 1, "title": "Retries", "state": "OPEN"},
+            {"number": 2, "title": "Crash", "state": "CLOSED"}]"#,
+    )
+    .unwrap();
+    check(&dir)
+        .args(["--issues", "issues.json"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "README.md:5:1: closed-issue: TODO references closed issue #2 (Crash)",
+        ))
+        .stdout(predicate::str::contains(
+            "README.md:7:1: missing-issue: TODO references issue #9, which is not in the issue export",
+        ))
+        .stdout(predicate::str::contains("#1").not());
+}
+
+/// `[lint]` を有効にした設定では文章の警告が `prose` として報告され、提案（suggestion）は報告されない
+#[test]
+fn check_reports_prose_lint_warnings() {
+    let dir = sample_repo("# Usage\n\nThe guide was written to utilize the the tools.\n");
+    std::fs::write(
+        dir.path().join("lint.toml"),
+        "[lint]\nenabled = true\n\n[[lint.substitute]]\npattern = \"utilize\"\nreplacement = \"use\"\n",
+    )
+    .unwrap();
+    check(&dir)
+        .args(["--config", "lint.toml"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "README.md:3:26: prose: Use 'use' instead of 'utilize' (fix: 'use') [substitute, warning]",
+        ))
+        .stdout(predicate::str::contains(
+            "README.md:3:34: prose: Repeated word 'the' (fix: 'the') [repeated-word, warning]",
+        ))
+        .stdout(predicate::str::contains("passive-voice").not());
+}
@@ -114,3 +114,26 @@ fn term_paragraph_present() {
         .success()
         .stdout(predicate::str::contains("A paragraph of text."));
 }
+
+/// front matter の lang と smart_punctuation がターミナル出力にも効くこと
+#[test]
+fn term_smart_punctuation_follows_front_matter_lang() {
+    let md = "---\nlang: fr\nmarkdown:\n  smart_punctuation: true\n---\n\nIl a dit \"bonjour\" -- enfin...\n";
+    let (assert, _tmp) = run_term(md);
+    assert.success().stdout(predicate::str::contains(
+        "«\u{202F}bonjour\u{202F}» – enfin…",
+    ));
+}
+
+/// パースできない YAML のコードブロックはエラー行に ⚠ が付き、ブロックの下にメッセージが出ること
+#[test]
+fn term_flags_snippet_syntax_errors() {
+    let md = "```yaml\nname: demo\n  key: 1\n```\n";
+    let (assert, _tmp) = run_term(md);
+    assert
+        .success()
+        .stdout(predicate::str::contains("⚠"))
+        .stdout(predicate::str::contains(
+            "line 2: yaml snippet does not parse: mapping values are not allowed in this context",
+        ));
+}
@@ -0,0 +1,99 @@
+// `mdpeek todos` の統合テスト
+// 一時ディレクトリ（git 管理外なのでディレクトリ走査にフォールバック）に
+// Markdown とソースを置き、テキスト / JSON 出力とフィルタを検証する。
+// config.toml の `[analyzer] lang` で選んだ言語のマーカーも拾うことを確かめる。
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// README（タスク 2 件 + インライン TODO）と FIXME コメント付きソースを持つディレクトリ
+fn sample_repo() -> TempDir {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    std::fs::write(
+        dir.path().join("README.md"),
+        "# Project\n\n- [ ] write docs\n- [x] ship it\n\nTODO: add benchmarks\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("main.rs"),
+        "fn main() {} // FIXME: exit code\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn todos(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mdpeek").expect("mdpeek バイナリが見つからない");
+    cmd.arg("todos").arg(dir.path());
+    cmd
+}
+
+/// テキスト出力は `path:line: ラベル` 形式で、既定ではコードを走査しない
+#[test]
+fn todos_lists_markdown_entries_as_text() {
+    let dir = sample_repo();
+    todos(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("README.md:3: [ ] write docs"))
+        .stdout(predicate::str::contains("README.md:4: [x] ship it"))
+        .stdout(predicate::str::contains(
+            "README.md:6: TODO: add benchmarks",
+        ))
+        .stdout(predicate::str::contains("main.rs").not());
+}
+
+/// `--open` と `--marker` で絞り込める
+#[test]
+fn todos_filters_by_state_and_marker() {
+    let dir = sample_repo();
+    todos(&dir)
+        .args(["--open", "--marker", "task"])
+        .assert()
+        .success()
+        .stdout("README.md:3: [ ] write docs\n");
+}
+
+/// `--code` でコメント内のマーカーも拾い、JSON で出力できる
+#[test]
+fn todos_scans_code_comments_as_json() {
+    let dir = sample_repo();
+    let output = todos(&dir)
+        .args(["--code", "--marker", "fixme", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON 出力");
+    let entries = json.as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["rel"], "main.rs");
+    assert_eq!(entries[0]["line"], 1);
+    assert_eq!(entries[0]["text"], "exit code");
+}
+
+/// front matter に `lang` がなくても、config の `[analyzer] lang` の言語で
+/// マーカー（ドイツ語の `OFFEN:`）を拾う
+#[test]
+fn todos_use_the_configured_language() {
+    let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗");
+    let config = dir.path().join("config").join("mdpeek");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(config.join("config.toml"), "[analyzer]\nlang = \"de\"\n").unwrap();
+    let docs = dir.path().join("docs");
+    std::fs::create_dir(&docs).unwrap();
+    std::fs::write(
+        docs.join("plan.md"),
+        "# Plan\n\nOFFEN: check the release plan\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("mdpeek")
+        .expect("mdpeek バイナリが見つからない")
+        .env("XDG_CONFIG_HOME", dir.path().join("config"))
+        .arg("todos")
+        .arg(&docs)
+        .assert()
+        .success()
+        .stdout("plan.md:3: TODO: check the release plan\n");
+}